@@ -1,4 +1,4 @@
-//! An example of displaying an image in a window using the display_image function.
+//! An example of displaying an image in a window using the display_dynamic function.
 //! Run this example from your root directory, enabled the display_image feature and
 //! provide a path to an image file as an argument.
 //!
@@ -6,7 +6,7 @@
 
 #[cfg(feature = "display-window")]
 fn main() {
-    use imageproc::window::display_image;
+    use imageproc::window::display_dynamic;
     use std::env;
 
     let image_path = match env::args().nth(1) {
@@ -17,11 +17,9 @@ fn main() {
         }
     };
 
-    let image = image::open(image_path)
-        .expect("No image found at provided path")
-        .to_rgba8();
+    let image = image::open(image_path).expect("No image found at provided path");
 
-    display_image("", &image, 500, 500);
+    display_dynamic("", &image, 500, 500);
 }
 
 #[cfg(not(feature = "display-window"))]
@@ -3,9 +3,13 @@
 ///
 /// [Calonder, et. al. (2010)]: https://www.cs.ubc.ca/~lowe/525/papers/calonder_eccv10.pdf
 use image::{GenericImageView, GrayImage, Luma};
+use rand::{rngs::StdRng, SeedableRng};
 use rand_distr::{Distribution, Normal};
 
-use crate::{corners::Corner, definitions::Image, integral_image::integral_image, point::Point};
+use crate::{
+    corners::Corner, definitions::Image, filter::gaussian_blur_f32, integral_image::integral_image,
+    map::map_subpixels, point::Point,
+};
 
 use super::{
     constants::{BRIEF_PATCH_DIAMETER, BRIEF_PATCH_RADIUS},
@@ -71,8 +75,8 @@ fn local_pixel_average(integral_image: &Image<Luma<u32>>, x: u32, y: u32, radius
     if radius == 0 {
         return 0;
     }
-    let y_min = if y < radius { 0 } else { y - radius };
-    let x_min = if x < radius { 0 } else { x - radius };
+    let y_min = y.saturating_sub(radius);
+    let x_min = x.saturating_sub(radius);
     let y_max = u32::min(y + radius + 1, integral_image.height() - 1);
     let x_max = u32::min(x + radius + 1, integral_image.width() - 1);
 
@@ -262,6 +266,116 @@ pub fn brief(
     Ok((descriptors, test_pairs))
 }
 
+/// The standard deviation of the Gaussian used by [`compute_brief`] to smooth images before
+/// comparing pixel intensities. This is the value suggested by Calonder et al.
+const COMPUTE_BRIEF_SIGMA: f32 = 2.0;
+
+/// Computes a BRIEF descriptor of length `num_bits` for the patch of size `patch_size`
+/// centred on each keypoint, as described in [Calonder, et. al. (2010)].
+///
+/// The image is first smoothed by a Gaussian with standard deviation 2. Each bit of a
+/// descriptor is then the result of comparing the smoothed intensities at a pair of
+/// offsets from the keypoint: the bit is set if the intensity at the first offset is less
+/// than the intensity at the second. The offset pairs are sampled from an isotropic
+/// Gaussian with standard deviation `patch_size / 5`, restricted to the patch, using a
+/// random number generator seeded with `seed`. Descriptors are only comparable if they
+/// were computed with the same `patch_size`, `num_bits` and `seed`.
+///
+/// Keypoints are rounded to the nearest pixel. Unlike [`brief`], keypoints may lie
+/// anywhere in the image, with the image padded by continuity where patches extend
+/// beyond its edges, and a descriptor is returned for every keypoint.
+///
+/// See also [`hamming_distance`](super::hamming_distance) and
+/// [`match_binary_descriptors_brute_force`](super::match_binary_descriptors_brute_force).
+///
+/// # Panics
+///
+/// If `patch_size < 3`, if `num_bits` is zero or not a multiple of 128, or if any
+/// keypoint lies outside the image.
+///
+/// # Examples
+/// ```
+/// use imageproc::binary_descriptors::{brief::compute_brief, hamming_distance};
+/// use imageproc::point::Point;
+/// use imageproc::utils::gray_bench_image;
+///
+/// let image = gray_bench_image(60, 60);
+/// let keypoints = [Point::new(20.0, 20.0), Point::new(41.0, 38.0)];
+/// let descriptors = compute_brief(&image, &keypoints, 31, 256, 7);
+///
+/// assert_eq!(descriptors.len(), 2);
+/// assert_eq!(hamming_distance(&descriptors[0], &descriptors[0]), 0);
+/// ```
+///
+/// [Calonder, et. al. (2010)]: https://www.cs.ubc.ca/~lowe/525/papers/calonder_eccv10.pdf
+pub fn compute_brief(
+    image: &GrayImage,
+    keypoints: &[Point<f32>],
+    patch_size: u32,
+    num_bits: usize,
+    seed: u64,
+) -> Vec<BriefDescriptor> {
+    assert!(patch_size >= 3, "patch_size must be >= 3");
+    assert!(
+        num_bits > 0 && num_bits % 128 == 0,
+        "num_bits must be a positive multiple of 128 (found {})",
+        num_bits
+    );
+
+    let radius = (patch_size / 2) as i32;
+    let distribution = Normal::new(0.0, patch_size as f32 / 5.0).unwrap();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut sample_offset = || loop {
+        let (dx, dy) = (
+            distribution.sample(&mut rng).round() as i32,
+            distribution.sample(&mut rng).round() as i32,
+        );
+        if dx.abs() <= radius && dy.abs() <= radius {
+            return (dx, dy);
+        }
+    };
+    let mut offset_pairs = Vec::with_capacity(num_bits);
+    while offset_pairs.len() < num_bits {
+        let (p0, p1) = (sample_offset(), sample_offset());
+        if p0 != p1 {
+            offset_pairs.push((p0, p1));
+        }
+    }
+
+    let smoothed = gaussian_blur_f32(&map_subpixels(image, f32::from), COMPUTE_BRIEF_SIGMA);
+    let (width, height) = image.dimensions();
+    let at = |x: i32, y: i32| {
+        let x = x.clamp(0, width as i32 - 1) as u32;
+        let y = y.clamp(0, height as i32 - 1) as u32;
+        smoothed.get_pixel(x, y)[0]
+    };
+
+    keypoints
+        .iter()
+        .map(|keypoint| {
+            let (x, y) = (keypoint.x.round(), keypoint.y.round());
+            assert!(
+                x >= 0.0 && x < width as f32 && y >= 0.0 && y < height as f32,
+                "keypoint ({}, {}) lies outside the image",
+                keypoint.x,
+                keypoint.y
+            );
+            let (x, y) = (x as i32, y as i32);
+
+            let mut bits = vec![0u128; num_bits / 128];
+            for (i, ((x0, y0), (x1, y1))) in offset_pairs.iter().enumerate() {
+                if at(x + x0, y + y0) < at(x + x1, y + y1) {
+                    bits[i / 128] |= 1 << (i % 128);
+                }
+            }
+            BriefDescriptor {
+                bits,
+                corner: Corner::new(x as u32, y as u32, 0.0),
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -316,6 +430,85 @@ mod tests {
         let integral_image: Image<Luma<u32>> = integral_image(&image);
         assert_eq!(local_pixel_average(&integral_image, 3, 3, 2), 117);
     }
+
+    fn texture(width: u32, height: u32, offset_x: u32, offset_y: u32) -> GrayImage {
+        // A smooth, non-repeating pattern, so that patches at different locations differ
+        GrayImage::from_fn(width, height, |x, y| {
+            let (x, y) = ((x + offset_x) as f32, (y + offset_y) as f32);
+            let value = 128.0
+                + 50.0 * (0.31 * x + 0.005 * y * y).sin()
+                + 50.0 * (0.23 * y - 0.002 * x * y).cos();
+            Luma([value as u8])
+        })
+    }
+
+    #[test]
+    fn test_compute_brief_matches_shifted_features() {
+        let image = texture(100, 100, 0, 0);
+        // Pixel (x, y) of shifted shows the same content as pixel (x + 7, y + 4) of image
+        let shifted = crate::noise::gaussian_noise(&texture(100, 100, 7, 4), 0.0, 3.0, 1);
+
+        let keypoints: Vec<Point<f32>> = [(25, 30), (50, 50), (70, 35), (40, 75), (75, 70)]
+            .iter()
+            .map(|&(x, y)| Point::new(x as f32, y as f32))
+            .collect();
+        let shifted_keypoints: Vec<Point<f32>> = keypoints
+            .iter()
+            .map(|p| Point::new(p.x - 7.0, p.y - 4.0))
+            .collect();
+
+        let descriptors = compute_brief(&image, &keypoints, 31, 256, 3);
+        let shifted_descriptors = compute_brief(&shifted, &shifted_keypoints, 31, 256, 3);
+
+        for (i, d) in descriptors.iter().enumerate() {
+            let same = d.hamming_distance(&shifted_descriptors[i]);
+            for (j, other) in shifted_descriptors.iter().enumerate() {
+                if i != j {
+                    let different = d.hamming_distance(other);
+                    assert!(
+                        same < different,
+                        "keypoint {}: distance {} to its match, {} to keypoint {}",
+                        i,
+                        same,
+                        different,
+                        j
+                    );
+                }
+            }
+            assert!(same < 256 / 8, "keypoint {}: distance {}", i, same);
+        }
+    }
+
+    #[test]
+    fn test_compute_brief_is_deterministic_and_pads_edges() {
+        let image = texture(40, 30, 0, 0);
+        let keypoints = [
+            Point::new(0.0, 0.0),
+            Point::new(39.4, 29.2),
+            Point::new(20.0, 15.0),
+        ];
+        let first = compute_brief(&image, &keypoints, 15, 128, 11);
+        let second = compute_brief(&image, &keypoints, 15, 128, 11);
+        assert!(first == second);
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[1].position(), Point::new(39, 29));
+        assert_eq!(first[0].get_size(), 128);
+
+        let other_seed = compute_brief(&image, &keypoints, 15, 128, 12);
+        assert!(first[2].hamming_distance(&other_seed[2]) > 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_compute_brief_rejects_invalid_length() {
+        let _ = compute_brief(&GrayImage::new(10, 10), &[], 9, 100, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_compute_brief_rejects_keypoints_outside_image() {
+        let _ = compute_brief(&GrayImage::new(10, 10), &[Point::new(10.0, 2.0)], 9, 128, 0);
+    }
 }
 
 #[cfg(not(miri))]
@@ -3,6 +3,7 @@
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::collections::HashMap;
 
+use crate::matching::match_descriptors_bruteforce;
 use crate::point::Point;
 
 pub mod brief;
@@ -29,6 +30,44 @@ pub trait BinaryDescriptor {
     fn position(&self) -> Point<u32>;
 }
 
+/// Returns the number of bits that are different between two descriptors.
+///
+/// Equivalent to [`BinaryDescriptor::hamming_distance`].
+///
+/// # Panics
+///
+/// If the two descriptors have unequal lengths.
+pub fn hamming_distance<T: BinaryDescriptor>(a: &T, b: &T) -> u32 {
+    a.hamming_distance(b)
+}
+
+/// For each descriptor in `d1`, find the descriptor in `d2` with the minimum
+/// Hamming distance below `threshold`. If no such descriptor exists in `d2`,
+/// the descriptor in `d1` is left unmatched. If several descriptors in `d2`
+/// have the minimum distance, the first of them is chosen.
+///
+/// Descriptors in `d2` may be matched with more than one descriptor in `d1`.
+///
+/// Compares every pair of descriptors, so always finds the best match, but takes time
+/// proportional to `d1.len() * d2.len()`. See [`match_binary_descriptors`] for a faster
+/// approximate alternative, and [`match_descriptors_bruteforce`] for matching using
+/// other distances.
+///
+/// Returns a vector of references describing the matched pairs. The first
+/// reference is to a descriptor in `d1`, and the second reference is to a
+/// descriptor in `d2`.
+pub fn match_binary_descriptors_brute_force<'a, T: BinaryDescriptor>(
+    d1: &'a [T],
+    d2: &'a [T],
+    threshold: u32,
+) -> Vec<(&'a T, &'a T)> {
+    match_descriptors_bruteforce(d1, d2, |a, b| a.hamming_distance(b) as f32)
+        .into_iter()
+        .filter(|m| m.distance < threshold as f32)
+        .map(|m| (&d1[m.query_idx], &d2[m.train_idx]))
+        .collect()
+}
+
 /// For each descriptor in `d1`, find the descriptor in `d2` with the minimum
 /// Hamming distance below `threshold`. If no such descriptor exists in `d2`,
 /// the descriptor in `d1` is left unmatched.
@@ -133,6 +172,48 @@ pub fn match_binary_descriptors<'a, T: BinaryDescriptor>(
     matches
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_descriptors::brief::BriefDescriptor;
+    use crate::corners::Corner;
+
+    fn descriptor(bits: u128, x: u32) -> BriefDescriptor {
+        BriefDescriptor {
+            bits: vec![bits],
+            corner: Corner::new(x, 0, 0.0),
+        }
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(
+            hamming_distance(&descriptor(0b1011, 0), &descriptor(0b0110, 0)),
+            3
+        );
+    }
+
+    #[test]
+    fn test_match_binary_descriptors_brute_force() {
+        let d1 = [
+            descriptor(0b1111_0000, 0),
+            descriptor(0b0000_1111, 1),
+            descriptor(u128::MAX, 2),
+        ];
+        let d2 = [
+            descriptor(0b0000_1110, 10),
+            descriptor(0b1111_0001, 11),
+            descriptor(0b1111_0011, 12),
+        ];
+        let matches = match_binary_descriptors_brute_force(&d1, &d2, 4);
+        let positions: Vec<(u32, u32)> = matches
+            .iter()
+            .map(|(a, b)| (a.position().x, b.position().x))
+            .collect();
+        assert_eq!(positions, vec![(0, 11), (1, 10)]);
+    }
+}
+
 #[cfg(not(miri))]
 #[cfg(test)]
 mod benches {
@@ -0,0 +1,571 @@
+//! Functions for detecting blobs using the Laplacian of Gaussian, and scale-space keypoints
+//! using the difference of Gaussians.
+
+use crate::definitions::{Image, Position, Score};
+use crate::filter::separable_filter_equal;
+use crate::map::map_subpixels;
+use image::{GrayImage, Luma};
+
+/// A blob detected by [`detect_blobs`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Blob {
+    /// x-coordinate of the blob centre.
+    pub x: u32,
+    /// y-coordinate of the blob centre.
+    pub y: u32,
+    /// The scale at which the blob was detected. A disk of radius `r`
+    /// is detected at scale approximately `r / sqrt(2)`.
+    pub sigma: f32,
+    /// The scale-normalised Laplacian of Gaussian at the blob centre. This
+    /// is negative for bright blobs on a dark background and positive for dark
+    /// blobs on a bright background.
+    pub response: f32,
+}
+
+impl Blob {
+    /// The approximate radius of the blob, `sqrt(2) * sigma`.
+    pub fn radius(&self) -> f32 {
+        std::f32::consts::SQRT_2 * self.sigma
+    }
+}
+
+impl Position for Blob {
+    fn x(&self) -> u32 {
+        self.x
+    }
+
+    fn y(&self) -> u32 {
+        self.y
+    }
+}
+
+impl Score for Blob {
+    fn score(&self) -> f32 {
+        self.response.abs()
+    }
+}
+
+/// Computes the Laplacian of an image smoothed by a Gaussian with standard deviation `sigma`.
+///
+/// The Gaussian kernel is truncated at `4 * sigma`, and the Laplacian is approximated using the
+/// 4-connectivity discrete Laplacian, padding by continuity. The result is not scale-normalised.
+///
+/// # Panics
+///
+/// If `sigma <= 0.0`.
+#[must_use = "the function does not modify the original image"]
+pub fn laplacian_of_gaussian(image: &GrayImage, sigma: f32) -> Image<Luma<f32>> {
+    assert!(sigma > 0.0, "sigma must be > 0.0");
+    let smoothed =
+        separable_filter_equal(&map_subpixels(image, f32::from), &gaussian_kernel(sigma));
+    let (width, height) = image.dimensions();
+
+    Image::from_fn(width, height, |x, y| {
+        let at = |x: u32, y: u32| smoothed.get_pixel(x, y)[0];
+        let left = at(x.saturating_sub(1), y);
+        let right = at((x + 1).min(width - 1), y);
+        let up = at(x, y.saturating_sub(1));
+        let down = at(x, (y + 1).min(height - 1));
+        Luma([left + right + up + down - 4.0 * at(x, y)])
+    })
+}
+
+// A normalised Gaussian kernel, truncated at 4 * sigma. The kernel used by
+// gaussian_blur_f32 is truncated at 2 * sigma, which visibly distorts the Laplacian.
+fn gaussian_kernel(sigma: f32) -> Vec<f32> {
+    let radius = (4.0 * sigma).ceil() as i32;
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|k| *k /= sum);
+    kernel
+}
+
+/// Detects blobs using a scale-normalised Laplacian of Gaussian.
+///
+/// The image is filtered by [`laplacian_of_gaussian`] at `num_scales` values of sigma
+/// evenly spaced between `min_sigma` and `max_sigma` inclusive, and each response is
+/// multiplied by `sigma²` so that responses are comparable between scales. Blobs are
+/// the points whose absolute normalised response is at least `threshold` and is a local
+/// maximum among their neighbours in both position and scale.
+///
+/// Blobs whose centres lie within the radius of a blob with a larger absolute response
+/// are then discarded. Returned blobs are sorted by decreasing absolute response.
+///
+/// Bright blobs on dark backgrounds and dark blobs on bright backgrounds are both
+/// detected, and can be distinguished by the sign of [`Blob::response`]. For a disk of
+/// intensity contrast `c` the absolute response at the detected scale is roughly `0.74 * c`.
+/// The edges of a disk also produce weaker responses of the opposite sign just outside
+/// the disk, of roughly `0.2 * c`, so `threshold` should usually exceed this.
+///
+/// # Panics
+///
+/// If `min_sigma <= 0.0`, `max_sigma < min_sigma` or `num_scales == 0`.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::blobs::detect_blobs;
+/// use imageproc::drawing::draw_filled_circle_mut;
+///
+/// let mut image = GrayImage::new(60, 60);
+/// draw_filled_circle_mut(&mut image, (30, 28), 8, Luma([255]));
+///
+/// let blobs = detect_blobs(&image, 2.0, 8.0, 7, 100.0);
+/// assert_eq!(blobs.len(), 1);
+/// assert_eq!((blobs[0].x, blobs[0].y), (30, 28));
+/// assert!((blobs[0].radius() - 8.0).abs() < 1.5);
+/// ```
+pub fn detect_blobs(
+    image: &GrayImage,
+    min_sigma: f32,
+    max_sigma: f32,
+    num_scales: usize,
+    threshold: f32,
+) -> Vec<Blob> {
+    assert!(min_sigma > 0.0, "min_sigma must be > 0.0");
+    assert!(max_sigma >= min_sigma, "max_sigma must be >= min_sigma");
+    assert!(num_scales > 0, "num_scales must be > 0");
+
+    let (width, height) = image.dimensions();
+    let sigmas: Vec<f32> = (0..num_scales)
+        .map(|i| {
+            if num_scales == 1 {
+                min_sigma
+            } else {
+                min_sigma + (max_sigma - min_sigma) * i as f32 / (num_scales - 1) as f32
+            }
+        })
+        .collect();
+
+    let scale_space: Vec<Image<Luma<f32>>> = sigmas
+        .iter()
+        .map(|&sigma| {
+            let mut log = laplacian_of_gaussian(image, sigma);
+            log.iter_mut().for_each(|v| *v *= sigma * sigma);
+            log
+        })
+        .collect();
+
+    let mut candidates = Vec::new();
+    for (s, layer) in scale_space.iter().enumerate() {
+        let scales = s.saturating_sub(1)..(s + 2).min(num_scales);
+        for y in 0..height {
+            for x in 0..width {
+                let response = layer.get_pixel(x, y)[0];
+                let magnitude = response.abs();
+                if magnitude < threshold {
+                    continue;
+                }
+                let is_maximum = scales.clone().all(|t| {
+                    let other = &scale_space[t];
+                    (y.saturating_sub(1)..(y + 2).min(height)).all(|ny| {
+                        (x.saturating_sub(1)..(x + 2).min(width))
+                            .all(|nx| other.get_pixel(nx, ny)[0].abs() <= magnitude)
+                    })
+                });
+                if is_maximum {
+                    candidates.push(Blob {
+                        x,
+                        y,
+                        sigma: sigmas[s],
+                        response,
+                    });
+                }
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| b.response.abs().total_cmp(&a.response.abs()));
+
+    let mut blobs: Vec<Blob> = Vec::new();
+    for candidate in candidates {
+        let overlaps_stronger = blobs.iter().any(|blob| {
+            let dx = blob.x as f32 - candidate.x as f32;
+            let dy = blob.y as f32 - candidate.y as f32;
+            (dx * dx + dy * dy).sqrt() <= blob.radius()
+        });
+        if !overlaps_stronger {
+            blobs.push(candidate);
+        }
+    }
+    blobs
+}
+
+/// Computes the difference between an image smoothed by a Gaussian with standard deviation
+/// `sigma2` and the same image smoothed by a Gaussian with standard deviation `sigma1`.
+///
+/// If `sigma2 = k * sigma1` for some `k` slightly larger than one, then this approximates
+/// `(k - 1) * sigma1²` times the [`laplacian_of_gaussian`] at scale `sigma1`, and so is negative
+/// at the centres of bright blobs on a dark background.
+///
+/// The Gaussian kernels are truncated at `4 * sigma`, padding by continuity.
+///
+/// # Panics
+///
+/// If `sigma1 <= 0.0` or `sigma2 <= 0.0`.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::blobs::difference_of_gaussians;
+///
+/// let mut image = GrayImage::new(21, 21);
+/// image.put_pixel(10, 10, Luma([255]));
+/// let dog = difference_of_gaussians(&image, 1.0, 1.6);
+///
+/// assert!(dog.get_pixel(10, 10)[0] < 0.0);
+/// assert!(dog.get_pixel(10, 13)[0] > 0.0);
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn difference_of_gaussians(image: &GrayImage, sigma1: f32, sigma2: f32) -> Image<Luma<f32>> {
+    assert!(sigma1 > 0.0, "sigma1 must be > 0.0");
+    assert!(sigma2 > 0.0, "sigma2 must be > 0.0");
+    let input = map_subpixels(image, f32::from);
+    let mut dog = separable_filter_equal(&input, &gaussian_kernel(sigma2));
+    let smoothed = separable_filter_equal(&input, &gaussian_kernel(sigma1));
+    for (d, s) in dog.iter_mut().zip(smoothed.iter()) {
+        *d -= s;
+    }
+    dog
+}
+
+/// A keypoint detected by [`detect_dog_keypoints`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DogKeypoint {
+    /// x-coordinate of the keypoint.
+    pub x: u32,
+    /// y-coordinate of the keypoint.
+    pub y: u32,
+    /// The scale at which the keypoint was detected. A disk of radius `r`
+    /// is detected at scale approximately `r / sqrt(2)`.
+    pub sigma: f32,
+    /// The difference of Gaussians at the keypoint, divided by `k - 1` where `k` is the
+    /// ratio between adjacent scales. This is comparable to the scale-normalised
+    /// [`Blob::response`], and is negative for bright blobs on a dark background.
+    pub response: f32,
+}
+
+impl Position for DogKeypoint {
+    fn x(&self) -> u32 {
+        self.x
+    }
+
+    fn y(&self) -> u32 {
+        self.y
+    }
+}
+
+impl Score for DogKeypoint {
+    fn score(&self) -> f32 {
+        self.response.abs()
+    }
+}
+
+/// Keypoints whose difference of Gaussians has a ratio of principal curvatures larger
+/// than this lie on edges rather than blobs, and are rejected. This is the value used by Lowe.
+const DOG_EDGE_RATIO: f32 = 10.0;
+
+/// Detects keypoints as extrema of a difference of Gaussians scale space, as in the first
+/// stage of Lowe's SIFT detector.
+///
+/// The image is smoothed by Gaussians with standard deviations `base_sigma * k^i` for
+/// `k = 2^(1 / scales_per_octave)` and `i` in `0..num_octaves * scales_per_octave + 3`, and
+/// adjacent smoothed images are subtracted to give a stack of differences of Gaussians.
+/// Keypoints are the points in the stack, excluding its first and last layers, whose
+/// normalised response (see [`DogKeypoint::response`]) has absolute value at least
+/// `threshold` and is at least as large (for maxima) or as small (for minima) as the
+/// responses of all of their neighbours in both position and scale. Each keypoint
+/// is assigned the geometric mean of the two scales whose difference it was detected in.
+///
+/// Keypoints lying along edges rather than at blobs are rejected, as their ratio of
+/// principal curvatures is greater than 10. Unlike SIFT, all scales are computed at the
+/// full resolution of the image and keypoint locations are not refined to subpixel accuracy.
+///
+/// Returned keypoints are sorted by decreasing absolute response. As for [`detect_blobs`],
+/// the absolute response at the centre of a disk of contrast `c` is roughly `0.74 * c`.
+///
+/// # Panics
+///
+/// If `base_sigma <= 0.0`, `num_octaves == 0` or `scales_per_octave == 0`.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::blobs::detect_dog_keypoints;
+/// use imageproc::drawing::draw_filled_circle_mut;
+///
+/// let mut image = GrayImage::new(60, 60);
+/// draw_filled_circle_mut(&mut image, (30, 28), 8, Luma([255]));
+///
+/// let keypoints = detect_dog_keypoints(&image, 1.6, 3, 3, 100.0);
+/// assert_eq!(keypoints.len(), 1);
+/// assert_eq!((keypoints[0].x, keypoints[0].y), (30, 28));
+/// assert!((std::f32::consts::SQRT_2 * keypoints[0].sigma - 8.0).abs() < 1.5);
+/// ```
+pub fn detect_dog_keypoints(
+    image: &GrayImage,
+    base_sigma: f32,
+    num_octaves: usize,
+    scales_per_octave: usize,
+    threshold: f32,
+) -> Vec<DogKeypoint> {
+    assert!(base_sigma > 0.0, "base_sigma must be > 0.0");
+    assert!(num_octaves > 0, "num_octaves must be > 0");
+    assert!(scales_per_octave > 0, "scales_per_octave must be > 0");
+
+    let (width, height) = image.dimensions();
+    let k = 2f32.powf(1.0 / scales_per_octave as f32);
+    let sigmas: Vec<f32> = (0..num_octaves * scales_per_octave + 3)
+        .map(|i| base_sigma * k.powi(i as i32))
+        .collect();
+
+    let input = map_subpixels(image, f32::from);
+    let smoothed: Vec<Image<Luma<f32>>> = sigmas
+        .iter()
+        .map(|&sigma| separable_filter_equal(&input, &gaussian_kernel(sigma)))
+        .collect();
+    let scale_space: Vec<Image<Luma<f32>>> = smoothed
+        .windows(2)
+        .map(|pair| {
+            let mut dog = pair[1].clone();
+            for (d, s) in dog.iter_mut().zip(pair[0].iter()) {
+                *d = (*d - s) / (k - 1.0);
+            }
+            dog
+        })
+        .collect();
+
+    let mut keypoints = Vec::new();
+    for s in 1..scale_space.len() - 1 {
+        let layer = &scale_space[s];
+        for y in 0..height {
+            for x in 0..width {
+                let response = layer.get_pixel(x, y)[0];
+                if response.abs() < threshold {
+                    continue;
+                }
+                let is_extremum = scale_space[s - 1..s + 2].iter().all(|other| {
+                    (y.saturating_sub(1)..(y + 2).min(height)).all(|ny| {
+                        (x.saturating_sub(1)..(x + 2).min(width)).all(|nx| {
+                            let value = other.get_pixel(nx, ny)[0];
+                            if response > 0.0 {
+                                value <= response
+                            } else {
+                                value >= response
+                            }
+                        })
+                    })
+                });
+                if is_extremum && !is_edge_like(layer, x, y) {
+                    keypoints.push(DogKeypoint {
+                        x,
+                        y,
+                        sigma: (sigmas[s] * sigmas[s + 1]).sqrt(),
+                        response,
+                    });
+                }
+            }
+        }
+    }
+
+    keypoints.sort_by(|a, b| b.response.abs().total_cmp(&a.response.abs()));
+    keypoints
+}
+
+// Returns true if the ratio of the principal curvatures of layer at (x, y) exceeds
+// DOG_EDGE_RATIO, or if the curvatures have different signs.
+fn is_edge_like(layer: &Image<Luma<f32>>, x: u32, y: u32) -> bool {
+    let (width, height) = layer.dimensions();
+    let at = |dx: i32, dy: i32| {
+        let nx = (x as i32 + dx).clamp(0, width as i32 - 1) as u32;
+        let ny = (y as i32 + dy).clamp(0, height as i32 - 1) as u32;
+        layer.get_pixel(nx, ny)[0]
+    };
+    let centre = at(0, 0);
+    let dxx = at(1, 0) + at(-1, 0) - 2.0 * centre;
+    let dyy = at(0, 1) + at(0, -1) - 2.0 * centre;
+    let dxy = 0.25 * (at(1, 1) - at(1, -1) - at(-1, 1) + at(-1, -1));
+
+    let trace = dxx + dyy;
+    let det = dxx * dyy - dxy * dxy;
+    let r = DOG_EDGE_RATIO;
+    det <= 0.0 || trace * trace * r >= (r + 1.0) * (r + 1.0) * det
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::draw_filled_circle_mut;
+
+    #[test]
+    fn test_laplacian_of_gaussian_constant_image() {
+        let image = GrayImage::from_pixel(10, 10, Luma([90]));
+        let log = laplacian_of_gaussian(&image, 2.0);
+        assert!(log.iter().all(|v| v.abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_laplacian_of_gaussian_bright_spot_is_negative_at_centre() {
+        let mut image = GrayImage::new(21, 21);
+        image.put_pixel(10, 10, Luma([255]));
+        let log = laplacian_of_gaussian(&image, 1.5);
+        let centre = log.get_pixel(10, 10)[0];
+        assert!(centre < 0.0);
+        assert!(log.iter().all(|&v| v >= centre));
+    }
+
+    #[test]
+    fn test_detect_blobs_recovers_disk_centres_and_sizes() {
+        let disks = [((25, 25), 4), ((70, 30), 7), ((40, 75), 11)];
+        let mut image = GrayImage::new(110, 110);
+        for &(centre, radius) in &disks {
+            draw_filled_circle_mut(&mut image, centre, radius, Luma([220]));
+        }
+
+        let blobs = detect_blobs(&image, 2.0, 10.0, 17, 80.0);
+        assert_eq!(blobs.len(), disks.len(), "{:?}", blobs);
+
+        for &((cx, cy), radius) in &disks {
+            let blob = blobs
+                .iter()
+                .find(|b| (b.x as i32 - cx).abs() <= 1 && (b.y as i32 - cy).abs() <= 1)
+                .unwrap_or_else(|| panic!("no blob found at ({}, {}): {:?}", cx, cy, blobs));
+            assert!(blob.response < 0.0);
+            assert!(
+                (blob.radius() - radius as f32).abs() <= 0.2 * radius as f32 + 0.5,
+                "radius {} detected as {}",
+                radius,
+                blob.radius()
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_blobs_finds_dark_blobs() {
+        let mut image = GrayImage::from_pixel(50, 50, Luma([200]));
+        draw_filled_circle_mut(&mut image, (20, 28), 6, Luma([20]));
+        let blobs = detect_blobs(&image, 2.0, 8.0, 13, 80.0);
+        assert_eq!(blobs.len(), 1);
+        assert_eq!((blobs[0].x, blobs[0].y), (20, 28));
+        assert!(blobs[0].response > 0.0);
+    }
+
+    #[test]
+    fn test_detect_blobs_ignores_weak_responses() {
+        let mut image = GrayImage::from_pixel(40, 40, Luma([100]));
+        draw_filled_circle_mut(&mut image, (20, 20), 5, Luma([110]));
+        assert!(detect_blobs(&image, 2.0, 6.0, 5, 40.0).is_empty());
+    }
+
+    #[test]
+    fn test_difference_of_gaussians_constant_image() {
+        let image = GrayImage::from_pixel(10, 10, Luma([90]));
+        let dog = difference_of_gaussians(&image, 1.0, 2.0);
+        assert!(dog.iter().all(|v| v.abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_difference_of_gaussians_peaks_at_blob_scale() {
+        let radius = 6;
+        let mut image = GrayImage::new(80, 80);
+        draw_filled_circle_mut(&mut image, (40, 40), radius, Luma([200]));
+
+        let k = 1.6f32;
+        let sigmas: Vec<f32> = (0..30).map(|i| 1.0 + 0.25 * i as f32).collect();
+        let responses: Vec<f32> = sigmas
+            .iter()
+            .map(|&sigma| difference_of_gaussians(&image, sigma, k * sigma).get_pixel(40, 40)[0])
+            .collect();
+        assert!(responses.iter().all(|&r| r < 0.0), "{:?}", responses);
+
+        let strongest = (0..sigmas.len())
+            .min_by(|&i, &j| responses[i].total_cmp(&responses[j]))
+            .unwrap();
+        // The response of a disk of radius r peaks at scale r / sqrt(2), measured
+        // as the geometric mean of the two scales
+        let scale = sigmas[strongest] * k.sqrt();
+        let expected = radius as f32 / std::f32::consts::SQRT_2;
+        assert!(
+            (scale - expected).abs() < 0.15 * expected,
+            "strongest response at scale {}, expected {}",
+            scale,
+            expected
+        );
+        assert!(strongest > 0 && strongest < sigmas.len() - 1);
+    }
+
+    #[test]
+    fn test_detect_dog_keypoints_recovers_disk_centres_and_sizes() {
+        let disks = [((25, 25), 4), ((70, 30), 7), ((40, 75), 11)];
+        let mut image = GrayImage::new(110, 110);
+        for &(centre, radius) in &disks {
+            draw_filled_circle_mut(&mut image, centre, radius, Luma([220]));
+        }
+
+        let keypoints = detect_dog_keypoints(&image, 1.6, 3, 4, 80.0);
+        assert_eq!(keypoints.len(), disks.len(), "{:?}", keypoints);
+
+        for &((cx, cy), radius) in &disks {
+            let keypoint = keypoints
+                .iter()
+                .find(|k| (k.x as i32 - cx).abs() <= 1 && (k.y as i32 - cy).abs() <= 1)
+                .unwrap_or_else(|| panic!("no keypoint at ({}, {}): {:?}", cx, cy, keypoints));
+            assert!(keypoint.response < 0.0);
+            let detected_radius = std::f32::consts::SQRT_2 * keypoint.sigma;
+            assert!(
+                (detected_radius - radius as f32).abs() <= 0.2 * radius as f32 + 0.5,
+                "radius {} detected as {}",
+                radius,
+                detected_radius
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_dog_keypoints_rejects_edges() {
+        let image = GrayImage::from_fn(60, 60, |x, _| Luma([if x < 30 { 20 } else { 220 }]));
+        assert!(detect_dog_keypoints(&image, 1.6, 2, 3, 10.0).is_empty());
+
+        let mut bar = GrayImage::new(60, 60);
+        for y in 10..50 {
+            for x in 28..32 {
+                bar.put_pixel(x, y, Luma([220]));
+            }
+        }
+        let keypoints = detect_dog_keypoints(&bar, 1.6, 2, 3, 40.0);
+        // Only the ends of the bar, not the points along it, are blob-like
+        assert!(
+            keypoints.iter().all(|k| k.y < 18 || k.y > 41),
+            "{:?}",
+            keypoints
+        );
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::gray_bench_image;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_detect_blobs(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let blobs = detect_blobs(&image, 2.0, 8.0, 7, 20.0);
+            black_box(blobs);
+        });
+    }
+
+    #[bench]
+    fn bench_detect_dog_keypoints(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let keypoints = detect_dog_keypoints(&image, 1.6, 2, 3, 20.0);
+            black_box(keypoints);
+        });
+    }
+}
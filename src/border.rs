@@ -0,0 +1,268 @@
+//! Functions for extending images beyond their borders.
+
+use crate::definitions::Image;
+use image::GenericImageView;
+
+/// How the values of pixels outside an image are chosen.
+///
+/// The examples for each variant show how the row `a b c d` is extended by three pixels
+/// on each side.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BorderMode<P> {
+    /// All pixels outside the image take the given value: `x x x | a b c d | x x x`.
+    Constant(P),
+    /// Pixels take the value of the nearest pixel in the image: `a a a | a b c d | d d d`.
+    Replicate,
+    /// The image is mirrored about its edges, repeating the edge pixels:
+    /// `c b a | a b c d | d c b`.
+    Reflect,
+    /// The image is mirrored about its edge pixels, without repeating them:
+    /// `d c b | a b c d | c b a`.
+    Reflect101,
+    /// The image is tiled: `b c d | a b c d | a b c`.
+    Wrap,
+}
+
+impl<P> BorderMode<P> {
+    /// Returns the index in `0..len` of the pixel whose value is used for index `index`
+    /// along a dimension of length `len`, or `None` if the mode is [`BorderMode::Constant`]
+    /// and `index` is outside `0..len`.
+    ///
+    /// # Panics
+    ///
+    /// If `len` is 0 and a pixel value would be needed from the image.
+    pub(crate) fn source_index(&self, index: i64, len: u32) -> Option<u32> {
+        let n = len as i64;
+        if (0..n).contains(&index) {
+            return Some(index as u32);
+        }
+        if let BorderMode::Constant(_) = self {
+            return None;
+        }
+        assert!(
+            len > 0,
+            "only BorderMode::Constant can be used to extend an empty image"
+        );
+        let source = match self {
+            BorderMode::Constant(_) => unreachable!(),
+            BorderMode::Replicate => index.clamp(0, n - 1),
+            BorderMode::Reflect => {
+                let m = index.rem_euclid(2 * n);
+                if m < n {
+                    m
+                } else {
+                    2 * n - 1 - m
+                }
+            }
+            BorderMode::Reflect101 => {
+                if n == 1 {
+                    0
+                } else {
+                    let period = 2 * (n - 1);
+                    let m = index.rem_euclid(period);
+                    if m < n {
+                        m
+                    } else {
+                        period - m
+                    }
+                }
+            }
+            BorderMode::Wrap => index.rem_euclid(n),
+        };
+        Some(source as u32)
+    }
+}
+
+/// Returns a copy of `image` extended by `top`, `bottom`, `left` and `right` pixels on each
+/// side, with the new pixels chosen according to `mode`.
+///
+/// Padding may be larger than the image itself, in which case reflection and wrapping are
+/// repeated as many times as necessary.
+///
+/// # Panics
+///
+/// If `image` is empty, `mode` is not [`BorderMode::Constant`] and the output is non-empty.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::border::{pad, BorderMode};
+///
+/// let image = gray_image!(
+///     1, 2, 3;
+///     4, 5, 6);
+///
+/// let reflected = gray_image!(
+///     2, 1, 1, 2, 3, 3, 2;
+///     5, 4, 4, 5, 6, 6, 5);
+/// assert_pixels_eq!(pad(&image, 0, 0, 2, 2, BorderMode::Reflect), reflected);
+///
+/// let reflected_101 = gray_image!(
+///     3, 2, 1, 2, 3, 2, 1;
+///     6, 5, 4, 5, 6, 5, 4);
+/// assert_pixels_eq!(pad(&image, 0, 0, 2, 2, BorderMode::Reflect101), reflected_101);
+/// # }
+/// ```
+pub fn pad<I>(
+    image: &I,
+    top: u32,
+    bottom: u32,
+    left: u32,
+    right: u32,
+    mode: BorderMode<I::Pixel>,
+) -> Image<I::Pixel>
+where
+    I: GenericImageView,
+{
+    let (width, height) = image.dimensions();
+    let out_width = left + width + right;
+    let out_height = top + height + bottom;
+
+    let columns: Vec<Option<u32>> = (0..out_width)
+        .map(|x| mode.source_index(x as i64 - left as i64, width))
+        .collect();
+    let rows: Vec<Option<u32>> = (0..out_height)
+        .map(|y| mode.source_index(y as i64 - top as i64, height))
+        .collect();
+
+    let mut out = Image::new(out_width, out_height);
+    for (y, source_y) in rows.iter().enumerate() {
+        for (x, source_x) in columns.iter().enumerate() {
+            let pixel = match (source_x, source_y) {
+                (Some(sx), Some(sy)) => image.get_pixel(*sx, *sy),
+                _ => match mode {
+                    BorderMode::Constant(p) => p,
+                    _ => unreachable!(),
+                },
+            };
+            out.put_pixel(x as u32, y as u32, pixel);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, Rgb, RgbImage};
+
+    fn row(values: &[u8]) -> GrayImage {
+        GrayImage::from_raw(values.len() as u32, 1, values.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_pad_constant() {
+        let image = gray_image!(
+            1, 2;
+            3, 4);
+        let expected = gray_image!(
+            9, 9, 9, 9, 9;
+            9, 1, 2, 9, 9;
+            9, 3, 4, 9, 9;
+            9, 9, 9, 9, 9;
+            9, 9, 9, 9, 9);
+        let padded = pad(&image, 1, 2, 1, 2, BorderMode::Constant(Luma([9])));
+        assert_pixels_eq!(padded, expected);
+    }
+
+    #[test]
+    fn test_pad_replicate() {
+        let image = gray_image!(
+            1, 2;
+            3, 4);
+        let expected = gray_image!(
+            1, 1, 1, 2, 2;
+            1, 1, 1, 2, 2;
+            3, 3, 3, 4, 4);
+        let padded = pad(&image, 1, 0, 2, 1, BorderMode::Replicate);
+        assert_pixels_eq!(padded, expected);
+    }
+
+    #[test]
+    fn test_pad_reflect_repeats_edge_pixels() {
+        let padded = pad(&row(&[1, 2, 3, 4]), 0, 0, 3, 3, BorderMode::Reflect);
+        assert_pixels_eq!(padded, row(&[3, 2, 1, 1, 2, 3, 4, 4, 3, 2]));
+    }
+
+    #[test]
+    fn test_pad_reflect_101_does_not_repeat_edge_pixels() {
+        let padded = pad(&row(&[1, 2, 3, 4]), 0, 0, 3, 3, BorderMode::Reflect101);
+        assert_pixels_eq!(padded, row(&[4, 3, 2, 1, 2, 3, 4, 3, 2, 1]));
+    }
+
+    #[test]
+    fn test_pad_wrap() {
+        let padded = pad(&row(&[1, 2, 3, 4]), 0, 0, 3, 3, BorderMode::Wrap);
+        assert_pixels_eq!(padded, row(&[2, 3, 4, 1, 2, 3, 4, 1, 2, 3]));
+    }
+
+    #[test]
+    fn test_pad_reflects_vertically() {
+        let image = gray_image!(
+            1;
+            2;
+            3);
+        let reflect = pad(&image, 2, 2, 0, 0, BorderMode::Reflect);
+        let reflect_101 = pad(&image, 2, 2, 0, 0, BorderMode::Reflect101);
+        assert_eq!(reflect.into_raw(), vec![2, 1, 1, 2, 3, 3, 2]);
+        assert_eq!(reflect_101.into_raw(), vec![3, 2, 1, 2, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_pad_larger_than_image() {
+        let image = row(&[1, 2]);
+        assert_pixels_eq!(
+            pad(&image, 0, 0, 5, 5, BorderMode::Reflect),
+            row(&[1, 1, 2, 2, 1, 1, 2, 2, 1, 1, 2, 2])
+        );
+        assert_pixels_eq!(
+            pad(&image, 0, 0, 5, 5, BorderMode::Reflect101),
+            row(&[2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1])
+        );
+        assert_pixels_eq!(
+            pad(&image, 0, 0, 5, 5, BorderMode::Wrap),
+            row(&[2, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 1])
+        );
+        assert_pixels_eq!(
+            pad(&row(&[7]), 0, 0, 2, 2, BorderMode::Reflect101),
+            row(&[7, 7, 7, 7, 7])
+        );
+    }
+
+    #[test]
+    fn test_pad_multichannel_image() {
+        let image = RgbImage::from_fn(2, 2, |x, y| Rgb([x as u8, y as u8, 10]));
+        let padded = pad(&image, 1, 1, 1, 1, BorderMode::Wrap);
+        assert_eq!(padded.dimensions(), (4, 4));
+        assert_eq!(padded.get_pixel(0, 0), &Rgb([1, 1, 10]));
+        assert_eq!(padded.get_pixel(3, 3), &Rgb([0, 0, 10]));
+    }
+
+    #[test]
+    fn test_pad_without_padding_copies_image() {
+        let image = gray_image!(
+            1, 2, 3;
+            4, 5, 6);
+        assert_pixels_eq!(pad(&image, 0, 0, 0, 0, BorderMode::Wrap), image);
+    }
+
+    #[test]
+    fn test_pad_empty_image() {
+        let image = GrayImage::new(0, 0);
+        let padded = pad(&image, 1, 1, 1, 1, BorderMode::Constant(Luma([3])));
+        assert_pixels_eq!(padded, GrayImage::from_pixel(2, 2, Luma([3])));
+        assert_eq!(
+            pad(&image, 0, 0, 0, 0, BorderMode::Replicate).dimensions(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pad_empty_image_by_reflection() {
+        let _ = pad(&GrayImage::new(0, 3), 0, 0, 1, 1, BorderMode::Reflect);
+    }
+}
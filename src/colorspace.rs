@@ -0,0 +1,891 @@
+//! Conversions between RGB and other color spaces, and color adjustments.
+
+use crate::definitions::Image;
+use crate::stats::cumulative_histogram;
+use image::{Luma, Rgb, RgbImage};
+
+/// A color in the HSV (hue, saturation, value) color space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hsv {
+    /// Hue in degrees, in `[0, 360)`.
+    pub hue: f32,
+    /// Saturation, in `[0, 1]`.
+    pub saturation: f32,
+    /// Value, i.e. the largest of the RGB channels, in `[0, 1]`.
+    pub value: f32,
+}
+
+/// A color in the HSL (hue, saturation, lightness) color space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hsl {
+    /// Hue in degrees, in `[0, 360)`.
+    pub hue: f32,
+    /// Saturation, in `[0, 1]`.
+    pub saturation: f32,
+    /// Lightness, i.e. the mean of the largest and smallest RGB channels, in `[0, 1]`.
+    pub lightness: f32,
+}
+
+/// Converts an RGB color to HSV.
+///
+/// The hue of a gray is defined to be 0.
+///
+/// # Examples
+/// ```
+/// use image::Rgb;
+/// use imageproc::colorspace::{rgb_to_hsv, Hsv};
+///
+/// let sky_blue = rgb_to_hsv(Rgb([0, 128, 255]));
+/// assert!((sky_blue.hue - 209.88).abs() < 0.01);
+/// assert_eq!(sky_blue.saturation, 1.0);
+/// assert_eq!(sky_blue.value, 1.0);
+/// ```
+pub fn rgb_to_hsv(rgb: Rgb<u8>) -> Hsv {
+    let (hue, max, min) = hue_max_min(rgb);
+    let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+    Hsv {
+        hue,
+        saturation,
+        value: max,
+    }
+}
+
+/// Converts an HSV color to RGB, rounding each channel to the nearest integer.
+///
+/// Hues outside `[0, 360)` are wrapped into this range, and saturations and values outside
+/// `[0, 1]` are clamped to it.
+pub fn hsv_to_rgb(hsv: Hsv) -> Rgb<u8> {
+    let saturation = hsv.saturation.clamp(0.0, 1.0);
+    let value = hsv.value.clamp(0.0, 1.0);
+    let chroma = value * saturation;
+    rgb_from_hue_chroma(hsv.hue, chroma, value - chroma)
+}
+
+/// Converts an RGB color to HSL.
+///
+/// The hue of a gray is defined to be 0.
+pub fn rgb_to_hsl(rgb: Rgb<u8>) -> Hsl {
+    let (hue, max, min) = hue_max_min(rgb);
+    let lightness = (max + min) / 2.0;
+    let denominator = 1.0 - (2.0 * lightness - 1.0).abs();
+    let saturation = if denominator > 0.0 {
+        ((max - min) / denominator).min(1.0)
+    } else {
+        0.0
+    };
+    Hsl {
+        hue,
+        saturation,
+        lightness,
+    }
+}
+
+/// Converts an HSL color to RGB, rounding each channel to the nearest integer.
+///
+/// Hues outside `[0, 360)` are wrapped into this range, and saturations and lightnesses
+/// outside `[0, 1]` are clamped to it.
+pub fn hsl_to_rgb(hsl: Hsl) -> Rgb<u8> {
+    let saturation = hsl.saturation.clamp(0.0, 1.0);
+    let lightness = hsl.lightness.clamp(0.0, 1.0);
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    rgb_from_hue_chroma(hsl.hue, chroma, lightness - chroma / 2.0)
+}
+
+// Returns the hue in degrees and the largest and smallest channels of an RGB color,
+// with channels scaled to [0, 1].
+fn hue_max_min(rgb: Rgb<u8>) -> (f32, f32, f32) {
+    let [r, g, b] = rgb.0.map(|c| c as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, max, min)
+}
+
+// Builds an RGB color from its hue in degrees, its chroma, and the offset to add
+// to every channel, with chroma and offset in [0, 1].
+fn rgb_from_hue_chroma(hue: f32, chroma: f32, offset: f32) -> Rgb<u8> {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |c: f32| (255.0 * (c + offset)).round().clamp(0.0, 255.0) as u8;
+    Rgb([channel(r), channel(g), channel(b)])
+}
+
+/// Converts every pixel of an RGB image to HSV, storing the hue, saturation and
+/// value in the first, second and third channels of the output.
+pub fn rgb_image_to_hsv(image: &RgbImage) -> Image<Rgb<f32>> {
+    Image::from_fn(image.width(), image.height(), |x, y| {
+        let hsv = rgb_to_hsv(*image.get_pixel(x, y));
+        Rgb([hsv.hue, hsv.saturation, hsv.value])
+    })
+}
+
+/// Converts an image whose channels contain hue, saturation and value, as produced
+/// by [`rgb_image_to_hsv`], to RGB.
+pub fn hsv_image_to_rgb(image: &Image<Rgb<f32>>) -> RgbImage {
+    RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let [hue, saturation, value] = image.get_pixel(x, y).0;
+        hsv_to_rgb(Hsv {
+            hue,
+            saturation,
+            value,
+        })
+    })
+}
+
+/// Converts every pixel of an RGB image to HSL, storing the hue, saturation and
+/// lightness in the first, second and third channels of the output.
+pub fn rgb_image_to_hsl(image: &RgbImage) -> Image<Rgb<f32>> {
+    Image::from_fn(image.width(), image.height(), |x, y| {
+        let hsl = rgb_to_hsl(*image.get_pixel(x, y));
+        Rgb([hsl.hue, hsl.saturation, hsl.lightness])
+    })
+}
+
+/// Converts an image whose channels contain hue, saturation and lightness, as produced
+/// by [`rgb_image_to_hsl`], to RGB.
+pub fn hsl_image_to_rgb(image: &Image<Rgb<f32>>) -> RgbImage {
+    RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let [hue, saturation, lightness] = image.get_pixel(x, y).0;
+        hsl_to_rgb(Hsl {
+            hue,
+            saturation,
+            lightness,
+        })
+    })
+}
+
+/// Renders a field of 2D vectors as a color image, with the hue of each pixel giving the
+/// direction of its vector and the value giving its magnitude.
+///
+/// Vectors pointing right are red, with hue increasing clockwise in image coordinates, so
+/// that vectors pointing down are yellow-green and vectors pointing up are purple.
+/// Magnitudes are scaled so that the longest vector in the field has value 1. Pixels with
+/// zero vectors are black.
+///
+/// # Panics
+///
+/// If `x` and `y` have different dimensions.
+///
+/// # Examples
+/// ```
+/// use image::{Luma, Rgb};
+/// use imageproc::colorspace::vector_field_to_rgb;
+/// use imageproc::definitions::Image;
+///
+/// let x = Image::from_fn(3, 1, |x, _| Luma([x as f32]));
+/// let y = Image::from_fn(3, 1, |x, _| Luma([if x == 2 { 2.0 } else { 0.0 }]));
+/// let colors = vector_field_to_rgb(&x, &y);
+///
+/// assert_eq!(colors.get_pixel(0, 0), &Rgb([0, 0, 0]));
+/// assert_eq!(colors.get_pixel(1, 0), &Rgb([90, 0, 0]));
+/// ```
+pub fn vector_field_to_rgb(x: &Image<Luma<f32>>, y: &Image<Luma<f32>>) -> RgbImage {
+    assert_eq!(
+        x.dimensions(),
+        y.dimensions(),
+        "vector components must have the same dimensions"
+    );
+
+    let components = |px: u32, py: u32| (x.get_pixel(px, py)[0], y.get_pixel(px, py)[0]);
+    let magnitude = |(dx, dy): (f32, f32)| (dx * dx + dy * dy).sqrt();
+    let (width, height) = x.dimensions();
+    let max_magnitude = (0..height)
+        .flat_map(|py| (0..width).map(move |px| (px, py)))
+        .map(|(px, py)| magnitude(components(px, py)))
+        .fold(0.0f32, f32::max);
+
+    RgbImage::from_fn(width, height, |px, py| {
+        if max_magnitude == 0.0 {
+            return Rgb([0, 0, 0]);
+        }
+        let (dx, dy) = components(px, py);
+        hsv_to_rgb(Hsv {
+            hue: dy.atan2(dx).to_degrees().rem_euclid(360.0),
+            saturation: 1.0,
+            value: magnitude((dx, dy)) / max_magnitude,
+        })
+    })
+}
+
+/// Scales the HSV saturation of every pixel of an image by `factor`, clamping the result
+/// to `[0, 1]`.
+///
+/// A `factor` of 0 produces a grayscale image, and factors greater than 1 make colors
+/// more vivid.
+///
+/// # Panics
+///
+/// If `factor` is negative.
+///
+/// # Examples
+/// ```
+/// use image::{Rgb, RgbImage};
+/// use imageproc::colorspace::adjust_saturation;
+///
+/// let image = RgbImage::from_pixel(1, 1, Rgb([200, 100, 50]));
+/// let gray = adjust_saturation(&image, 0.0);
+/// assert_eq!(gray.get_pixel(0, 0), &Rgb([200, 200, 200]));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn adjust_saturation(image: &RgbImage, factor: f32) -> RgbImage {
+    assert!(factor >= 0.0, "factor must be non-negative");
+    adjust_hsv(image, |hsv| Hsv {
+        saturation: (hsv.saturation * factor).min(1.0),
+        ..hsv
+    })
+}
+
+/// Rotates the hue of every pixel of an image by `degrees`, wrapping around the color wheel.
+///
+/// # Examples
+/// ```
+/// use image::{Rgb, RgbImage};
+/// use imageproc::colorspace::adjust_hue;
+///
+/// let red = RgbImage::from_pixel(1, 1, Rgb([255, 0, 0]));
+/// assert_eq!(adjust_hue(&red, 120.0).get_pixel(0, 0), &Rgb([0, 255, 0]));
+/// assert_eq!(adjust_hue(&red, -120.0).get_pixel(0, 0), &Rgb([0, 0, 255]));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn adjust_hue(image: &RgbImage, degrees: f32) -> RgbImage {
+    adjust_hsv(image, |hsv| Hsv {
+        hue: (hsv.hue + degrees).rem_euclid(360.0),
+        ..hsv
+    })
+}
+
+fn adjust_hsv(image: &RgbImage, f: impl Fn(Hsv) -> Hsv) -> RgbImage {
+    RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        hsv_to_rgb(f(rgb_to_hsv(*image.get_pixel(x, y))))
+    })
+}
+
+/// A color in the CIELAB color space, relative to the D65 white point.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Lab {
+    /// Lightness, in `[0, 100]` for colors in the sRGB gamut.
+    pub l: f32,
+    /// Position on the green–red axis. Negative values are green and positive values red.
+    pub a: f32,
+    /// Position on the blue–yellow axis. Negative values are blue and positive values yellow.
+    pub b: f32,
+}
+
+// The XYZ coordinates of the D65 white point, i.e. of sRGB white.
+const D65_WHITE: [f32; 3] = [0.950_47, 1.0, 1.088_83];
+
+/// Converts an sRGB color to CIELAB.
+///
+/// The color is linearised using the sRGB transfer function and converted to CIE XYZ
+/// before being mapped to CIELAB, using D65 as the reference white.
+///
+/// # Examples
+/// ```
+/// use image::Rgb;
+/// use imageproc::colorspace::rgb_to_lab;
+///
+/// let white = rgb_to_lab(Rgb([255, 255, 255]));
+/// assert!((white.l - 100.0).abs() < 1e-3);
+/// assert!(white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+/// ```
+pub fn rgb_to_lab(rgb: Rgb<u8>) -> Lab {
+    let [r, g, b] = rgb.0.map(|c| srgb_to_linear(c as f32 / 255.0));
+    let xyz = [
+        0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b,
+        0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b,
+        0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b,
+    ];
+    let [fx, fy, fz] = [0, 1, 2].map(|i| lab_f(xyz[i] / D65_WHITE[i]));
+    Lab {
+        l: 116.0 * fy - 16.0,
+        a: 500.0 * (fx - fy),
+        b: 200.0 * (fy - fz),
+    }
+}
+
+/// Converts a CIELAB color to sRGB, rounding each channel to the nearest integer.
+///
+/// Colors outside the sRGB gamut are clamped channel-wise to it.
+pub fn lab_to_rgb(lab: Lab) -> Rgb<u8> {
+    let fy = (lab.l + 16.0) / 116.0;
+    let fx = fy + lab.a / 500.0;
+    let fz = fy - lab.b / 200.0;
+    let [x, y, z] = [0, 1, 2].map(|i| lab_f_inverse([fx, fy, fz][i]) * D65_WHITE[i]);
+    let linear = [
+        3.240_454_2 * x - 1.537_138_5 * y - 0.498_531_4 * z,
+        -0.969_266 * x + 1.876_010_8 * y + 0.041_556 * z,
+        0.055_643_4 * x - 0.204_025_9 * y + 1.057_225_2 * z,
+    ];
+    Rgb(linear.map(|c| (255.0 * linear_to_srgb(c.clamp(0.0, 1.0))).round() as u8))
+}
+
+pub(crate) fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.040_45 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub(crate) fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+const LAB_DELTA: f32 = 6.0 / 29.0;
+
+fn lab_f(t: f32) -> f32 {
+    if t > LAB_DELTA * LAB_DELTA * LAB_DELTA {
+        t.cbrt()
+    } else {
+        t / (3.0 * LAB_DELTA * LAB_DELTA) + 4.0 / 29.0
+    }
+}
+
+fn lab_f_inverse(t: f32) -> f32 {
+    if t > LAB_DELTA {
+        t * t * t
+    } else {
+        3.0 * LAB_DELTA * LAB_DELTA * (t - 4.0 / 29.0)
+    }
+}
+
+/// The CIE76 color difference between two colors, i.e. their Euclidean distance in CIELAB.
+///
+/// A difference of around 2.3 is commonly quoted as the smallest that is noticeable.
+///
+/// # Examples
+/// ```
+/// use image::Rgb;
+/// use imageproc::colorspace::{delta_e_cie76, rgb_to_lab};
+///
+/// let black = rgb_to_lab(Rgb([0, 0, 0]));
+/// let white = rgb_to_lab(Rgb([255, 255, 255]));
+/// assert!((delta_e_cie76(black, white) - 100.0).abs() < 1e-3);
+/// ```
+pub fn delta_e_cie76(x: Lab, y: Lab) -> f32 {
+    let (dl, da, db) = (x.l - y.l, x.a - y.a, x.b - y.b);
+    (dl * dl + da * da + db * db).sqrt()
+}
+
+/// The CIEDE2000 color difference between two colors.
+///
+/// This corrects the non-uniformity of CIE76 distances by weighting differences in
+/// lightness, chroma and hue separately, and agrees better with perceived differences,
+/// particularly between saturated blues. The parametric weighting factors are all 1.
+pub fn delta_e_ciede2000(x: Lab, y: Lab) -> f32 {
+    const POW25_7: f32 = 6_103_515_625.0;
+
+    let chroma_mean = (x.a.hypot(x.b) + y.a.hypot(y.b)) / 2.0;
+    let chroma_mean_7 = chroma_mean.powi(7);
+    let g = 0.5 * (1.0 - (chroma_mean_7 / (chroma_mean_7 + POW25_7)).sqrt());
+
+    let (a1, a2) = ((1.0 + g) * x.a, (1.0 + g) * y.a);
+    let (c1, c2) = (a1.hypot(x.b), a2.hypot(y.b));
+    let (h1, h2) = (hue_degrees(a1, x.b), hue_degrees(a2, y.b));
+    let chromatic = c1 * c2 != 0.0;
+
+    let delta_l = y.l - x.l;
+    let delta_c = c2 - c1;
+    let delta_h = if !chromatic {
+        0.0
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else if h2 - h1 < -180.0 {
+        h2 - h1 + 360.0
+    } else {
+        h2 - h1
+    };
+    let delta_big_h = 2.0 * (c1 * c2).sqrt() * (delta_h.to_radians() / 2.0).sin();
+
+    let l_mean = (x.l + y.l) / 2.0;
+    let c_mean = (c1 + c2) / 2.0;
+    let h_mean = if !chromatic {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let cos_deg = |d: f32| d.to_radians().cos();
+    let t = 1.0 - 0.17 * cos_deg(h_mean - 30.0)
+        + 0.24 * cos_deg(2.0 * h_mean)
+        + 0.32 * cos_deg(3.0 * h_mean + 6.0)
+        - 0.20 * cos_deg(4.0 * h_mean - 63.0);
+    let delta_theta = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let c_mean_7 = c_mean.powi(7);
+    let r_c = 2.0 * (c_mean_7 / (c_mean_7 + POW25_7)).sqrt();
+    let l_offset = (l_mean - 50.0).powi(2);
+    let s_l = 1.0 + 0.015 * l_offset / (20.0 + l_offset).sqrt();
+    let s_c = 1.0 + 0.045 * c_mean;
+    let s_h = 1.0 + 0.015 * c_mean * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let (dl, dc, dh) = (delta_l / s_l, delta_c / s_c, delta_big_h / s_h);
+    (dl * dl + dc * dc + dh * dh + r_t * dc * dh).sqrt()
+}
+
+// The hue angle in degrees of a CIELAB color, in [0, 360).
+fn hue_degrees(a: f32, b: f32) -> f32 {
+    if a == 0.0 && b == 0.0 {
+        0.0
+    } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    }
+}
+
+/// Computes the CIE76 color difference between corresponding pixels of two images.
+///
+/// See [`delta_e_cie76`].
+///
+/// # Panics
+///
+/// If `a` and `b` have different dimensions.
+pub fn color_difference_image(a: &RgbImage, b: &RgbImage) -> Image<Luma<f32>> {
+    assert_eq!(
+        a.dimensions(),
+        b.dimensions(),
+        "images must have the same dimensions"
+    );
+    Image::from_fn(a.width(), a.height(), |x, y| {
+        Luma([delta_e_cie76(
+            rgb_to_lab(*a.get_pixel(x, y)),
+            rgb_to_lab(*b.get_pixel(x, y)),
+        )])
+    })
+}
+
+/// Corrects the white balance of an image using the gray-world assumption.
+///
+/// Each channel is scaled so that its mean equals the mean over all channels, i.e. the
+/// image is assumed to be gray on average. Results are rounded and clamped to `[0, 255]`.
+/// Channels that are zero everywhere are left unchanged.
+///
+/// # Examples
+/// ```
+/// use image::{Rgb, RgbImage};
+/// use imageproc::colorspace::white_balance_grayworld;
+///
+/// let mut image = RgbImage::from_pixel(2, 1, Rgb([120, 100, 80]));
+/// image.put_pixel(1, 0, Rgb([60, 50, 40]));
+///
+/// let balanced = white_balance_grayworld(&image);
+/// assert_eq!(balanced.get_pixel(0, 0), &Rgb([100, 100, 100]));
+/// assert_eq!(balanced.get_pixel(1, 0), &Rgb([50, 50, 50]));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn white_balance_grayworld(image: &RgbImage) -> RgbImage {
+    let mut sums = [0u64; 3];
+    for p in image.pixels() {
+        for (sum, &c) in sums.iter_mut().zip(p.0.iter()) {
+            *sum += c as u64;
+        }
+    }
+    let mean = sums.iter().sum::<u64>() as f32 / 3.0;
+    scale_channels(
+        image,
+        sums.map(|s| if s > 0 { mean / s as f32 } else { 1.0 }),
+    )
+}
+
+/// Corrects the white balance of an image using the white-patch assumption.
+///
+/// Each channel is scaled so that its `percentile`th percentile intensity maps to 255,
+/// i.e. the brightest parts of the image are assumed to be white. Using a percentile
+/// slightly below 100 makes the result robust to a few overexposed or noisy pixels.
+/// Results are rounded and clamped to `[0, 255]`. Channels that are zero at the given
+/// percentile are left unchanged.
+///
+/// The `p`th percentile intensity of a channel is the least `x` such that at least `p`%
+/// of pixels have an intensity less than or equal to `x` in that channel.
+///
+/// # Panics
+///
+/// If `percentile` is not in `[0, 100]`.
+///
+/// # Examples
+/// ```
+/// use image::{Rgb, RgbImage};
+/// use imageproc::colorspace::white_balance_whitepatch;
+///
+/// let mut image = RgbImage::from_pixel(2, 1, Rgb([200, 250, 100]));
+/// image.put_pixel(1, 0, Rgb([100, 125, 50]));
+///
+/// let balanced = white_balance_whitepatch(&image, 100.0);
+/// assert_eq!(balanced.get_pixel(0, 0), &Rgb([255, 255, 255]));
+/// assert_eq!(balanced.get_pixel(1, 0), &Rgb([128, 128, 128]));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn white_balance_whitepatch(image: &RgbImage, percentile: f32) -> RgbImage {
+    assert!(
+        (0.0..=100.0).contains(&percentile),
+        "percentile must be in [0, 100]"
+    );
+    let histogram = cumulative_histogram(image);
+    let required = percentile as f64 / 100.0 * image.pixels().len() as f64;
+    let scales = [0, 1, 2].map(|c| {
+        let level = histogram.channels[c]
+            .iter()
+            .position(|&count| count as f64 >= required)
+            .unwrap_or(255);
+        if level > 0 {
+            255.0 / level as f32
+        } else {
+            1.0
+        }
+    });
+    scale_channels(image, scales)
+}
+
+fn scale_channels(image: &RgbImage, scales: [f32; 3]) -> RgbImage {
+    RgbImage::from_fn(image.width(), image.height(), |x, y| {
+        let p = image.get_pixel(x, y).0;
+        Rgb([0, 1, 2].map(|c| (p[c] as f32 * scales[c]).round().clamp(0.0, 255.0) as u8))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_field_to_rgb_hue_follows_direction() {
+        let directions = [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)];
+        let x = Image::from_fn(4, 1, |px, _| Luma([directions[px as usize].0]));
+        let y = Image::from_fn(4, 1, |px, _| Luma([directions[px as usize].1]));
+        let colors = vector_field_to_rgb(&x, &y);
+        for (px, hue) in [0.0f32, 90.0, 180.0, 270.0].into_iter().enumerate() {
+            let color = rgb_to_hsv(*colors.get_pixel(px as u32, 0));
+            assert!((color.hue - hue).abs() < 1.0, "{:?} vs {}", color, hue);
+            assert_eq!(color.value, 1.0);
+        }
+    }
+
+    fn test_colors() -> Vec<Rgb<u8>> {
+        let mut colors = vec![
+            Rgb([255, 0, 0]),
+            Rgb([0, 255, 0]),
+            Rgb([0, 0, 255]),
+            Rgb([255, 255, 0]),
+            Rgb([0, 255, 255]),
+            Rgb([255, 0, 255]),
+            Rgb([0, 0, 0]),
+            Rgb([255, 255, 255]),
+            Rgb([128, 128, 128]),
+            Rgb([37, 37, 37]),
+        ];
+        for r in (0..=255).step_by(51) {
+            for g in (0..=255).step_by(51) {
+                for b in (0..=255).step_by(51) {
+                    colors.push(Rgb([r as u8, g as u8, b as u8]));
+                }
+            }
+        }
+        colors
+    }
+
+    #[test]
+    fn test_rgb_to_hsv_primaries_and_grays() {
+        let red = rgb_to_hsv(Rgb([255, 0, 0]));
+        assert_eq!((red.hue, red.saturation, red.value), (0.0, 1.0, 1.0));
+        let green = rgb_to_hsv(Rgb([0, 255, 0]));
+        assert_eq!(
+            (green.hue, green.saturation, green.value),
+            (120.0, 1.0, 1.0)
+        );
+        let blue = rgb_to_hsv(Rgb([0, 0, 255]));
+        assert_eq!((blue.hue, blue.saturation, blue.value), (240.0, 1.0, 1.0));
+
+        let gray = rgb_to_hsv(Rgb([51, 51, 51]));
+        assert_eq!((gray.hue, gray.saturation, gray.value), (0.0, 0.0, 0.2));
+        assert_eq!(rgb_to_hsv(Rgb([0, 0, 0])).saturation, 0.0);
+    }
+
+    #[test]
+    fn test_rgb_to_hsl_primaries_and_grays() {
+        let red = rgb_to_hsl(Rgb([255, 0, 0]));
+        assert_eq!((red.hue, red.saturation, red.lightness), (0.0, 1.0, 0.5));
+        let white = rgb_to_hsl(Rgb([255, 255, 255]));
+        assert_eq!((white.saturation, white.lightness), (0.0, 1.0));
+        let magenta = rgb_to_hsl(Rgb([255, 0, 255]));
+        assert_eq!(magenta.hue, 300.0);
+    }
+
+    #[test]
+    fn test_hsv_round_trip() {
+        for color in test_colors() {
+            assert_eq!(hsv_to_rgb(rgb_to_hsv(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        for color in test_colors() {
+            assert_eq!(hsl_to_rgb(rgb_to_hsl(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_image_round_trips() {
+        let image = RgbImage::from_fn(12, 9, |x, y| {
+            Rgb([(x * 21) as u8, (y * 28) as u8, ((x * y * 7) % 256) as u8])
+        });
+        assert_pixels_eq!(hsv_image_to_rgb(&rgb_image_to_hsv(&image)), image);
+        assert_pixels_eq!(hsl_image_to_rgb(&rgb_image_to_hsl(&image)), image);
+    }
+
+    #[test]
+    fn test_adjust_saturation_zero_gives_grayscale() {
+        let image = RgbImage::from_fn(12, 9, |x, y| {
+            Rgb([(x * 21) as u8, (y * 28) as u8, ((x * y * 7) % 256) as u8])
+        });
+        let gray = adjust_saturation(&image, 0.0);
+        for (p, q) in image.pixels().zip(gray.pixels()) {
+            assert!(q[0] == q[1] && q[1] == q[2]);
+            assert_eq!(q[0], *p.0.iter().max().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_adjust_saturation_clamps() {
+        let image = RgbImage::from_pixel(1, 1, Rgb([200, 150, 100]));
+        let vivid = adjust_saturation(&image, 10.0);
+        assert_eq!(vivid.get_pixel(0, 0), &Rgb([200, 100, 0]));
+        assert_pixels_eq!(adjust_saturation(&image, 1.0), image);
+    }
+
+    #[test]
+    fn test_adjust_hue_wraps_around() {
+        let image = RgbImage::from_fn(6, 1, |x, _| {
+            hsv_to_rgb(Hsv {
+                hue: 60.0 * x as f32,
+                saturation: 1.0,
+                value: 1.0,
+            })
+        });
+        let rotated = adjust_hue(&image, 420.0);
+        for x in 0..6 {
+            assert_eq!(rotated.get_pixel(x, 0), image.get_pixel((x + 1) % 6, 0));
+        }
+        assert_pixels_eq!(adjust_hue(&image, 360.0), image);
+    }
+
+    #[test]
+    fn test_rgb_to_lab_reference_values() {
+        let expected = [
+            (Rgb([255, 0, 0]), (53.24, 80.09, 67.20)),
+            (Rgb([0, 255, 0]), (87.73, -86.18, 83.18)),
+            (Rgb([0, 0, 255]), (32.30, 79.19, -107.86)),
+            (Rgb([128, 128, 128]), (53.59, 0.0, 0.0)),
+        ];
+        for (rgb, (l, a, b)) in expected {
+            let lab = rgb_to_lab(rgb);
+            assert!(
+                (lab.l - l).abs() < 0.01 && (lab.a - a).abs() < 0.01 && (lab.b - b).abs() < 0.01,
+                "{:?} -> {:?}",
+                rgb,
+                lab
+            );
+        }
+    }
+
+    #[test]
+    fn test_lab_round_trip() {
+        for color in test_colors() {
+            assert_eq!(lab_to_rgb(rgb_to_lab(color)), color);
+        }
+    }
+
+    #[test]
+    fn test_lab_to_rgb_clamps_out_of_gamut_colors() {
+        let lab = Lab {
+            l: 50.0,
+            a: 0.0,
+            b: -200.0,
+        };
+        let rgb = lab_to_rgb(lab);
+        assert_eq!(rgb[0], 0);
+        assert_eq!(rgb[2], 255);
+    }
+
+    #[test]
+    fn test_delta_e_identical_and_black_white() {
+        for color in test_colors() {
+            let lab = rgb_to_lab(color);
+            assert_eq!(delta_e_cie76(lab, lab), 0.0);
+            assert_eq!(delta_e_ciede2000(lab, lab), 0.0);
+        }
+        let black = rgb_to_lab(Rgb([0, 0, 0]));
+        let white = rgb_to_lab(Rgb([255, 255, 255]));
+        assert!((delta_e_cie76(black, white) - 100.0).abs() < 1e-3);
+        assert!((delta_e_ciede2000(black, white) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_delta_e_ciede2000_reference_values() {
+        // Test data from Sharma, Wu and Dalal, "The CIEDE2000 Color-Difference Formula:
+        // Implementation Notes, Supplementary Test Data, and Mathematical Observations".
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669),
+            ((50.0, 2.49, -0.001), (50.0, -2.49, 0.0009), 7.1792),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            (
+                (60.2574, -34.0099, 36.2677),
+                (60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+            ((2.0776, 0.0795, -1.135), (0.9033, -0.0636, -0.5514), 0.9082),
+        ];
+        for ((l1, a1, b1), (l2, a2, b2), expected) in pairs {
+            let x = Lab {
+                l: l1,
+                a: a1,
+                b: b1,
+            };
+            let y = Lab {
+                l: l2,
+                a: a2,
+                b: b2,
+            };
+            let de = delta_e_ciede2000(x, y);
+            assert!((de - expected).abs() < 1e-3, "{} != {}", de, expected);
+            assert!((delta_e_ciede2000(y, x) - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_color_difference_image() {
+        let a = RgbImage::from_fn(4, 3, |x, y| Rgb([(x * 60) as u8, (y * 100) as u8, 30]));
+        let mut b = a.clone();
+        b.put_pixel(2, 1, Rgb([255, 255, 255]));
+        let diff = color_difference_image(&a, &b);
+        assert_eq!(diff.dimensions(), (4, 3));
+        for (x, y, p) in diff.enumerate_pixels() {
+            let expected = delta_e_cie76(
+                rgb_to_lab(*a.get_pixel(x, y)),
+                rgb_to_lab(*b.get_pixel(x, y)),
+            );
+            assert_eq!(p[0], expected);
+            assert_eq!(p[0] > 0.0, (x, y) == (2, 1));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_color_difference_image_rejects_mismatched_dimensions() {
+        let _ = color_difference_image(&RgbImage::new(2, 2), &RgbImage::new(2, 3));
+    }
+
+    fn channel_means(image: &RgbImage) -> [f32; 3] {
+        let mut sums = [0f32; 3];
+        for p in image.pixels() {
+            for c in 0..3 {
+                sums[c] += p[c] as f32;
+            }
+        }
+        sums.map(|s| s / image.pixels().len() as f32)
+    }
+
+    fn mean_spread(image: &RgbImage) -> f32 {
+        let means = channel_means(image);
+        let max = means.iter().cloned().fold(f32::MIN, f32::max);
+        let min = means.iter().cloned().fold(f32::MAX, f32::min);
+        max - min
+    }
+
+    fn color_cast_image() -> RgbImage {
+        // A textured gray scene under a warm illuminant.
+        RgbImage::from_fn(20, 16, |x, y| {
+            let gray = (40 + (x * 37 + y * 91) % 180) as f32;
+            Rgb([
+                (gray * 1.3).min(255.0) as u8,
+                gray as u8,
+                (gray * 0.6) as u8,
+            ])
+        })
+    }
+
+    fn gray_ramp() -> RgbImage {
+        RgbImage::from_fn(256, 4, |x, _| Rgb([x as u8, x as u8, x as u8]))
+    }
+
+    #[test]
+    fn test_white_balance_grayworld_removes_color_cast() {
+        let image = color_cast_image();
+        let balanced = white_balance_grayworld(&image);
+        assert!(mean_spread(&image) > 50.0);
+        assert!(
+            mean_spread(&balanced) < 2.0,
+            "{:?}",
+            channel_means(&balanced)
+        );
+    }
+
+    #[test]
+    fn test_white_balance_whitepatch_removes_color_cast() {
+        let image = color_cast_image();
+        let balanced = white_balance_whitepatch(&image, 99.0);
+        assert!(
+            mean_spread(&balanced) < mean_spread(&image) / 4.0,
+            "{:?}",
+            channel_means(&balanced)
+        );
+    }
+
+    #[test]
+    fn test_white_balance_leaves_gray_ramp_unchanged() {
+        let ramp = gray_ramp();
+        assert_pixels_eq!(white_balance_grayworld(&ramp), ramp);
+        assert_pixels_eq!(white_balance_whitepatch(&ramp, 100.0), ramp);
+        assert_pixels_eq_within!(white_balance_whitepatch(&ramp, 99.0), ramp, 3);
+    }
+
+    #[test]
+    fn test_white_balance_clamps_to_valid_range() {
+        let mut image = RgbImage::from_pixel(10, 10, Rgb([100, 100, 100]));
+        image.put_pixel(0, 0, Rgb([250, 100, 100]));
+        let balanced = white_balance_whitepatch(&image, 50.0);
+        assert_eq!(balanced.get_pixel(0, 0), &Rgb([255, 255, 255]));
+        assert_eq!(balanced.get_pixel(1, 1), &Rgb([255, 255, 255]));
+    }
+
+    #[test]
+    fn test_white_balance_leaves_empty_channels_unchanged() {
+        let image = RgbImage::from_fn(5, 5, |x, y| Rgb([(x * 40) as u8, (y * 30) as u8, 0]));
+        assert!(white_balance_grayworld(&image).pixels().all(|p| p[2] == 0));
+        assert!(white_balance_whitepatch(&image, 90.0)
+            .pixels()
+            .all(|p| p[2] == 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_white_balance_whitepatch_rejects_invalid_percentile() {
+        let _ = white_balance_whitepatch(&RgbImage::new(2, 2), 101.0);
+    }
+}
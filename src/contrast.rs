@@ -2,17 +2,20 @@
 
 use std::cmp::{max, min};
 
-use image::{GrayImage, Luma};
+use image::{GrayImage, Luma, Pixel, RgbImage};
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 
-use crate::definitions::{HasBlack, HasWhite};
-use crate::integral_image::{integral_image, sum_image_pixels};
-use crate::map::map_subpixels_mut;
+use crate::definitions::{HasBlack, HasWhite, Image};
+use crate::filter::{gaussian_blur_f32, separable_filter_equal};
+use crate::integral_image::{integral_image, integral_squared_image, sum_image_pixels};
+use crate::map::{map_subpixels, map_subpixels_mut};
 use crate::stats::{cumulative_histogram, histogram};
 
 /// Applies an adaptive threshold to an image.
 ///
+/// See [`adaptive_threshold_with_method`] for a Gaussian-weighted variant.
+///
 /// This algorithm compares each pixel's brightness with the average brightness of the pixels
 /// in the (2 * `block_radius` + 1) square block centered on it minus delta. If the pixel is at least as bright
 /// as the threshold then it will have a value of 255 in the output image, otherwise 0.
@@ -46,6 +49,189 @@ pub fn adaptive_threshold(image: &GrayImage, block_radius: u32, delta: i32) -> G
     out
 }
 
+/// How the local threshold is computed by [`adaptive_threshold_with_method`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum AdaptiveMethod {
+    /// The unweighted mean of the block, as used by [`adaptive_threshold`].
+    #[default]
+    Mean,
+    /// A Gaussian-weighted mean of the block, with standard deviation
+    /// `0.3 * (block_radius - 1) + 0.8`.
+    Gaussian,
+}
+
+/// Applies an adaptive threshold to an image, using the given method to compute the local
+/// mean brightness around each pixel.
+///
+/// Each pixel's brightness is compared with the mean brightness of the (2 * `block_radius` + 1)
+/// square block centered on it minus `delta`, where the mean is computed according to
+/// `method`. If the pixel is at least as bright as the threshold then it will have a value
+/// of 255 in the output image, otherwise 0.
+///
+/// [`AdaptiveMethod::Mean`] gives the same result as [`adaptive_threshold`], computing the mean
+/// using an integral image over the block clipped to the image bounds.
+/// [`AdaptiveMethod::Gaussian`] gives more weight to pixels nearer the block centre, and pads
+/// the image by continuity.
+///
+/// # Panics
+///
+/// If `block_radius` is zero.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::contrast::{adaptive_threshold_with_method, AdaptiveMethod};
+///
+/// // A dark dot on a background whose brightness increases from left to right
+/// let image = gray_image!(
+///      40,  60,  80, 100, 120;
+///      40,  60,  10, 100, 120;
+///      40,  60,  80, 100, 120);
+///
+/// let expected = gray_image!(
+///     255, 255, 255, 255, 255;
+///     255, 255,   0, 255, 255;
+///     255, 255, 255, 255, 255);
+///
+/// let binary = adaptive_threshold_with_method(&image, 1, 10, AdaptiveMethod::Gaussian);
+/// assert_pixels_eq!(binary, expected);
+/// # }
+/// ```
+pub fn adaptive_threshold_with_method(
+    image: &GrayImage,
+    block_radius: u32,
+    delta: i32,
+    method: AdaptiveMethod,
+) -> GrayImage {
+    assert!(block_radius > 0, "block_radius must be > 0");
+    match method {
+        AdaptiveMethod::Mean => adaptive_threshold(image, block_radius, delta),
+        AdaptiveMethod::Gaussian => {
+            let sigma = 0.3 * (block_radius as f32 - 1.0) + 0.8;
+            let kernel: Vec<f32> = (-(block_radius as i32)..=block_radius as i32)
+                .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+                .collect();
+            let sum: f32 = kernel.iter().sum();
+            let kernel: Vec<f32> = kernel.iter().map(|k| k / sum).collect();
+
+            let means = separable_filter_equal(&map_subpixels(image, f32::from), &kernel);
+            let mut out = GrayImage::from_pixel(image.width(), image.height(), Luma::black());
+            for ((p, mean), o) in image.iter().zip(means.iter()).zip(out.iter_mut()) {
+                if *p as f32 >= mean - delta as f32 {
+                    *o = 255;
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Binarizes an image using Niblack's method.
+///
+/// The threshold for each pixel is `mean + k * std_dev`, where `mean` and `std_dev` are the mean
+/// and standard deviation of the pixels in the (2 * `block_radius` + 1) square block centered
+/// on it, clipped to the image bounds. If the pixel is at least as bright as the threshold then
+/// it will have a value of 255 in the output image, otherwise 0.
+///
+/// Negative values of `k` are used to find dark text on a light background, with `k = -0.2`
+/// being typical. This method tends to produce noise in regions of the image containing no
+/// text; [`sauvola_threshold`] is usually preferable for binarizing documents.
+///
+/// # Panics
+///
+/// If `block_radius` is zero.
+#[must_use = "the function does not modify the original image"]
+pub fn niblack_threshold(image: &GrayImage, block_radius: u32, k: f32) -> GrayImage {
+    let k = k as f64;
+    local_statistics_threshold(image, block_radius, |mean, std_dev| mean + k * std_dev)
+}
+
+/// Binarizes an image using Sauvola's method.
+///
+/// The threshold for each pixel is `mean * (1 + k * (std_dev / r - 1))`, where `mean` and
+/// `std_dev` are the mean and standard deviation of the pixels in the (2 * `block_radius` + 1)
+/// square block centered on it, clipped to the image bounds. If the pixel is at least as bright
+/// as the threshold then it will have a value of 255 in the output image, otherwise 0.
+///
+/// `r` is the dynamic range of the standard deviation, and is usually 128 for 8bpp images.
+/// Typical values of `k` lie between 0.2 and 0.5. In regions of low contrast the threshold
+/// falls below the local mean, so that uniform background is classified as white even where
+/// the illumination is uneven.
+///
+/// The means and standard deviations are computed using integral images, so the running time
+/// is independent of `block_radius`.
+///
+/// # Panics
+///
+/// If `block_radius` is zero or `r` is not positive.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::contrast::sauvola_threshold;
+///
+/// // A dark stroke on a background whose brightness increases from left to right
+/// let image = gray_image!(
+///     100, 120,  20, 160, 180;
+///     100, 120,  20, 160, 180;
+///     100, 120,  20, 160, 180);
+///
+/// let expected = gray_image!(
+///     255, 255,   0, 255, 255;
+///     255, 255,   0, 255, 255;
+///     255, 255,   0, 255, 255);
+///
+/// assert_pixels_eq!(sauvola_threshold(&image, 2, 0.3, 128.0), expected);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn sauvola_threshold(image: &GrayImage, block_radius: u32, k: f32, r: f32) -> GrayImage {
+    assert!(r > 0.0, "r must be > 0.0");
+    let (k, r) = (k as f64, r as f64);
+    local_statistics_threshold(image, block_radius, |mean, std_dev| {
+        mean * (1.0 + k * (std_dev / r - 1.0))
+    })
+}
+
+// Thresholds each pixel at threshold(mean, std_dev), where mean and std_dev are computed
+// over the (2 * block_radius + 1) square block centered on it, clipped to the image bounds.
+fn local_statistics_threshold<F>(image: &GrayImage, block_radius: u32, threshold: F) -> GrayImage
+where
+    F: Fn(f64, f64) -> f64,
+{
+    assert!(block_radius > 0, "block_radius must be > 0");
+    let (width, height) = image.dimensions();
+    let sum = integral_image::<_, u64>(image);
+    let sum_sq = integral_squared_image::<_, u64>(image);
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let (left, right) = (
+            x.saturating_sub(block_radius),
+            min(width - 1, x + block_radius),
+        );
+        let (top, bottom) = (
+            y.saturating_sub(block_radius),
+            min(height - 1, y + block_radius),
+        );
+        let n = ((right - left + 1) * (bottom - top + 1)) as f64;
+        let mean = sum_image_pixels(&sum, left, top, right, bottom)[0] as f64 / n;
+        let mean_sq = sum_image_pixels(&sum_sq, left, top, right, bottom)[0] as f64 / n;
+        let std_dev = (mean_sq - mean * mean).max(0.0).sqrt();
+
+        if image.get_pixel(x, y)[0] as f64 >= threshold(mean, std_dev) {
+            Luma::white()
+        } else {
+            Luma::black()
+        }
+    })
+}
+
 /// Returns the [Otsu threshold level] of an 8bpp image.
 ///
 /// [Otsu threshold level]: https://en.wikipedia.org/wiki/Otsu%27s_method
@@ -102,6 +288,127 @@ pub fn otsu_level(image: &GrayImage) -> u8 {
     best_threshold
 }
 
+/// Returns `levels - 1` thresholds which partition the intensities of an 8bpp image into
+/// `levels` classes, chosen to maximise the between-class variance.
+///
+/// This generalises [`otsu_level`] to more than two classes. The thresholds are returned in
+/// increasing order, and a pixel with intensity `p` belongs to the lowest class whose
+/// threshold is at least `p`, or to the highest class if `p` exceeds every threshold.
+/// The result can be applied to an image using [`apply_multilevel_threshold`].
+///
+/// The optimal partition is found using dynamic programming over the histogram of the image,
+/// taking `O(levels * 256^2)` operations in addition to computing the histogram.
+///
+/// # Panics
+///
+/// If `levels` is zero or greater than 256.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::contrast::{apply_multilevel_threshold, otsu_multilevel};
+///
+/// let image = gray_image!(
+///      10,  12, 120, 125, 230;
+///      11, 118, 122, 235, 240);
+///
+/// let thresholds = otsu_multilevel(&image, 3);
+/// assert_eq!(thresholds, vec![12, 125]);
+///
+/// let expected = gray_image!(
+///       0,   0, 127, 127, 255;
+///       0, 127, 127, 255, 255);
+/// assert_pixels_eq!(apply_multilevel_threshold(&image, &thresholds), expected);
+/// # }
+/// ```
+pub fn otsu_multilevel(image: &GrayImage, levels: usize) -> Vec<u8> {
+    assert!(
+        (1..=256).contains(&levels),
+        "levels must be between 1 and 256"
+    );
+    let hist = &histogram(image).channels[0];
+
+    // Cumulative pixel counts and intensity sums, with counts[i] and sums[i] covering
+    // the histogram bins below i.
+    let mut counts = [0f64; 257];
+    let mut sums = [0f64; 257];
+    for (i, &h) in hist.iter().enumerate() {
+        counts[i + 1] = counts[i] + h as f64;
+        sums[i + 1] = sums[i] + (i as f64) * h as f64;
+    }
+
+    // Maximising the between-class variance is equivalent to maximising the sum over
+    // classes of (class intensity sum)^2 / (class size). This returns the term for a class
+    // containing bins lower..=upper.
+    let class_score = |lower: usize, upper: usize| {
+        let count = counts[upper + 1] - counts[lower];
+        if count > 0.0 {
+            (sums[upper + 1] - sums[lower]).powi(2) / count
+        } else {
+            0.0
+        }
+    };
+
+    // scores[k][j] is the best score for splitting bins 0..=j into k + 1 classes, and
+    // starts[k][j] is the first bin of the last class in the split achieving it.
+    let mut scores = vec![[f64::NEG_INFINITY; 256]; levels];
+    let mut starts = vec![[0usize; 256]; levels];
+    for j in 0..256 {
+        scores[0][j] = class_score(0, j);
+    }
+    for k in 1..levels {
+        for j in k..256 {
+            for start in k..=j {
+                let score = scores[k - 1][start - 1] + class_score(start, j);
+                if score > scores[k][j] {
+                    scores[k][j] = score;
+                    starts[k][j] = start;
+                }
+            }
+        }
+    }
+
+    let mut thresholds = vec![0u8; levels - 1];
+    let mut upper = 255;
+    for k in (1..levels).rev() {
+        let start = starts[k][upper];
+        thresholds[k - 1] = (start - 1) as u8;
+        upper = start - 1;
+    }
+    thresholds
+}
+
+/// Assigns each pixel of an image to a class using the given thresholds, and sets it to an
+/// intensity representing its class.
+///
+/// A pixel with intensity `p` belongs to class `i` if `i` is the smallest index such that
+/// `p <= thresholds[i]`, or to class `thresholds.len()` if `p` exceeds every threshold. The
+/// classes are represented by evenly spaced intensities, with the lowest class mapped to 0
+/// and the highest to 255. If `thresholds` is empty every pixel is mapped to 0.
+///
+/// See [`otsu_multilevel`] for an example.
+///
+/// # Panics
+///
+/// If `thresholds` is not strictly increasing.
+#[must_use = "the function does not modify the original image"]
+pub fn apply_multilevel_threshold(image: &GrayImage, thresholds: &[u8]) -> GrayImage {
+    assert!(
+        thresholds.windows(2).all(|w| w[0] < w[1]),
+        "thresholds must be strictly increasing"
+    );
+    let num_classes = thresholds.len();
+    let mut lut = [0u8; 256];
+    for (p, value) in lut.iter_mut().enumerate() {
+        let class = thresholds.iter().filter(|&&t| (p as u8) > t).count();
+        *value = (class * 255).checked_div(num_classes).unwrap_or(0) as u8;
+    }
+    apply_lut(image, &lut)
+}
+
 /// Options for how to treat the threshold value in [`threshold`] and [`threshold_mut`].
 pub enum ThresholdType {
     /// `dst(x,y) = if src(x,y) > threshold { 255 } else { 0 }`
@@ -242,6 +549,126 @@ pub fn equalize_histogram_mut(image: &mut GrayImage) {
     });
 }
 
+/// Applies contrast limited adaptive histogram equalization (CLAHE) to an 8bpp grayscale image.
+///
+/// The image is divided into a grid of `tiles_x` by `tiles_y` tiles, and an equalizing
+/// intensity mapping is computed from the histogram of each tile. Before computing the
+/// mapping, each histogram bin is clipped to `clip_limit` times the average bin count and
+/// the clipped counts are redistributed evenly across all bins. This limits the slope of
+/// the mapping, and so prevents noise in nearly uniform regions from being amplified.
+///
+/// Each output pixel is computed by bilinearly interpolating between the mappings of the
+/// four tiles whose centers are nearest to it, to avoid visible seams between tiles.
+/// Pixels between the image border and the outermost tile centers use the mappings of
+/// the nearest tiles.
+///
+/// Larger values of `clip_limit` give more contrast enhancement. Values of 2 to 4
+/// are typical, and the result approaches per-tile histogram equalization as `clip_limit`
+/// increases.
+///
+/// # Panics
+///
+/// If `tiles_x` or `tiles_y` is zero or larger than the corresponding image dimension,
+/// or if `clip_limit` is not strictly positive.
+#[must_use = "the function does not modify the original image"]
+pub fn clahe(image: &GrayImage, tiles_x: u32, tiles_y: u32, clip_limit: f32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    assert!(
+        tiles_x > 0 && tiles_x <= width,
+        "tiles_x must be between 1 and the image width"
+    );
+    assert!(
+        tiles_y > 0 && tiles_y <= height,
+        "tiles_y must be between 1 and the image height"
+    );
+    assert!(clip_limit > 0.0, "clip_limit must be > 0.0");
+
+    // Tile i along an axis of length n covers [i * n / tiles, (i + 1) * n / tiles)
+    let tile_start = |i: u32, n: u32, tiles: u32| (i as u64 * n as u64 / tiles as u64) as u32;
+
+    let mut luts = Vec::with_capacity((tiles_x * tiles_y) as usize);
+    for ty in 0..tiles_y {
+        let (y0, y1) = (
+            tile_start(ty, height, tiles_y),
+            tile_start(ty + 1, height, tiles_y),
+        );
+        for tx in 0..tiles_x {
+            let (x0, x1) = (
+                tile_start(tx, width, tiles_x),
+                tile_start(tx + 1, width, tiles_x),
+            );
+            let mut hist = [0u32; 256];
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    hist[image.get_pixel(x, y)[0] as usize] += 1;
+                }
+            }
+            luts.push(clipped_equalization_lut(
+                &mut hist,
+                (x1 - x0) * (y1 - y0),
+                clip_limit,
+            ));
+        }
+    }
+
+    // The location of a pixel relative to the tile centers along one axis, as the
+    // index of the tile center before it, the index of the one after, and the weight
+    // of the latter.
+    let neighbours = |p: u32, n: u32, tiles: u32| -> (usize, usize, f32) {
+        let t = (p as f32 + 0.5) * tiles as f32 / n as f32 - 0.5;
+        if t <= 0.0 {
+            (0, 0, 0.0)
+        } else if t >= (tiles - 1) as f32 {
+            let last = (tiles - 1) as usize;
+            (last, last, 0.0)
+        } else {
+            let t0 = t.floor();
+            (t0 as usize, t0 as usize + 1, t - t0)
+        }
+    };
+
+    let mut out = GrayImage::new(width, height);
+    for y in 0..height {
+        let (ty0, ty1, wy) = neighbours(y, height, tiles_y);
+        for x in 0..width {
+            let (tx0, tx1, wx) = neighbours(x, width, tiles_x);
+            let v = image.get_pixel(x, y)[0] as usize;
+            let lut = |tx: usize, ty: usize| luts[ty * tiles_x as usize + tx][v];
+            let top = (1.0 - wx) * lut(tx0, ty0) + wx * lut(tx1, ty0);
+            let bottom = (1.0 - wx) * lut(tx0, ty1) + wx * lut(tx1, ty1);
+            let value = (1.0 - wy) * top + wy * bottom;
+            out.put_pixel(x, y, Luma([value.round().clamp(0.0, 255.0) as u8]));
+        }
+    }
+    out
+}
+
+// Clips a histogram of `count` pixels at `clip_limit` times its mean bin count,
+// redistributes the excess evenly and returns the resulting equalization mapping.
+fn clipped_equalization_lut(hist: &mut [u32; 256], count: u32, clip_limit: f32) -> [f32; 256] {
+    let limit = ((clip_limit * count as f32 / 256.0) as u32).max(1);
+    let mut excess = 0;
+    for h in hist.iter_mut() {
+        if *h > limit {
+            excess += *h - limit;
+            *h = limit;
+        }
+    }
+    let (share, remainder) = (excess / 256, excess % 256);
+    for (i, h) in hist.iter_mut().enumerate() {
+        // Spread the remainder across the whole range rather than the lowest bins
+        *h += share + u32::from((i as u32 * remainder) % 256 < remainder);
+    }
+
+    let mut lut = [0f32; 256];
+    let mut sum = 0;
+    for (l, h) in lut.iter_mut().zip(hist.iter()) {
+        sum += h;
+        *l = 255.0 * sum as f32 / count as f32;
+    }
+    lut
+}
+
 /// Stretches the contrast in an image, linearly mapping intensities in `(input_lower, input_upper)` to `(output_lower, output_upper)` and saturating
 /// values outside this input range.
 ///
@@ -335,6 +762,68 @@ pub fn stretch_contrast_mut(
     map_subpixels_mut(image, f);
 }
 
+/// Stretches the contrast in an image so that the intensity at percentile `low_pct` maps
+/// to 0 and the intensity at percentile `high_pct` maps to 255.
+///
+/// The intensity at percentile `p` is the smallest intensity `v` such that at least `p`
+/// percent of pixels, and at least one pixel, have intensity at most `v`. Intensities
+/// between the two percentiles are mapped linearly, as by [`stretch_contrast`], and
+/// intensities outside them saturate. Unlike stretching between the minimum and maximum
+/// intensities, which is the special case `low_pct = 0.0` and `high_pct = 100.0`, this
+/// ignores a small number of outlying pixels. Values of around 1 and 99 are typical.
+///
+/// If the intensities at both percentiles are equal, as for a constant image, the image is
+/// returned unchanged.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::contrast::stretch_contrast_percentile;
+///
+/// let image = gray_image!(
+///     0,  60,  70,  80,  90;
+///     60, 70, 80,  90, 255);
+///
+/// // 10% of pixels are darker than 60, and 10% are brighter than 90
+/// let stretched = stretch_contrast_percentile(&image, 20.0, 80.0);
+///
+/// let expected = gray_image!(
+///     0,   0,  85, 170, 255;
+///     0,  85, 170, 255, 255);
+///
+/// assert_pixels_eq!(stretched, expected);
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// If `low_pct < 0.0`, `high_pct > 100.0` or `low_pct >= high_pct`.
+#[must_use = "the function does not modify the original image"]
+pub fn stretch_contrast_percentile(image: &GrayImage, low_pct: f32, high_pct: f32) -> GrayImage {
+    assert!(low_pct >= 0.0, "low_pct must be >= 0.0");
+    assert!(high_pct <= 100.0, "high_pct must be <= 100.0");
+    assert!(low_pct < high_pct, "low_pct must be smaller than high_pct");
+
+    let hist = cumulative_histogram(image).channels[0];
+    let total = hist[255] as f64;
+    if total == 0.0 {
+        return image.clone();
+    }
+    let intensity_at = |pct: f32| {
+        let count = (pct as f64 / 100.0 * total).max(1.0);
+        hist.iter().position(|&h| h as f64 >= count).unwrap_or(255) as u8
+    };
+
+    let (low, high) = (intensity_at(low_pct), intensity_at(high_pct));
+    if low >= high {
+        return image.clone();
+    }
+    stretch_contrast(image, low, high, 0, 255)
+}
+
 /// Adjusts contrast of an 8bpp grayscale image so that its
 /// histogram is as close as possible to that of the target image.
 pub fn match_histogram(image: &GrayImage, target: &GrayImage) -> GrayImage {
@@ -353,6 +842,162 @@ pub fn match_histogram_mut(image: &mut GrayImage, target: &GrayImage) {
     }
 }
 
+/// Adjusts the contrast of each channel of an RGB image so that its histogram is as close as
+/// possible to that of the corresponding channel of the target image.
+///
+/// Channels are matched independently, using the same method as [`match_histogram`].
+pub fn match_histogram_rgb(image: &RgbImage, target: &RgbImage) -> RgbImage {
+    let mut out = image.clone();
+    match_histogram_rgb_mut(&mut out, target);
+    out
+}
+#[doc=generate_mut_doc_comment!("match_histogram_rgb")]
+pub fn match_histogram_rgb_mut(image: &mut RgbImage, target: &RgbImage) {
+    let image_histc = cumulative_histogram(image).channels;
+    let target_histc = cumulative_histogram(target).channels;
+    let luts: Vec<[usize; 256]> = (0..3)
+        .map(|c| histogram_lut(&image_histc[c], &target_histc[c]))
+        .collect();
+
+    for p in image.pixels_mut() {
+        for (c, lut) in p.0.iter_mut().zip(&luts) {
+            *c = lut[*c as usize] as u8;
+        }
+    }
+}
+
+/// Maps each color channel of an 8bpp image through a lookup table, leaving any alpha channel
+/// unchanged. This can be used to apply arbitrary tone curves.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use image::{Rgba, RgbaImage};
+/// use imageproc::contrast::apply_lut;
+///
+/// // Invert intensities
+/// let mut lut = [0u8; 256];
+/// for (i, l) in lut.iter_mut().enumerate() {
+///     *l = 255 - i as u8;
+/// }
+///
+/// let image = RgbaImage::from_pixel(1, 1, Rgba([10, 100, 200, 50]));
+/// let inverted = apply_lut(&image, &lut);
+/// assert_eq!(inverted.get_pixel(0, 0), &Rgba([245, 155, 55, 50]));
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn apply_lut<P>(image: &Image<P>, lut: &[u8; 256]) -> Image<P>
+where
+    P: Pixel<Subpixel = u8>,
+{
+    let mut out = image.clone();
+    apply_lut_mut(&mut out, lut);
+    out
+}
+#[doc=generate_mut_doc_comment!("apply_lut")]
+pub fn apply_lut_mut<P>(image: &mut Image<P>, lut: &[u8; 256])
+where
+    P: Pixel<Subpixel = u8>,
+{
+    for p in image.pixels_mut() {
+        p.apply_with_alpha(|c| lut[c as usize], |a| a);
+    }
+}
+
+/// Applies gamma correction to each color channel of an 8bpp image, leaving any alpha
+/// channel unchanged.
+///
+/// Each intensity is mapped to `255 * (intensity / 255)^gamma`, rounded to the nearest
+/// integer. Values of `gamma` less than 1 brighten the midtones of an image, and values
+/// greater than 1 darken them. Black and white are unchanged.
+///
+/// # Panics
+///
+/// If `gamma` is not strictly positive.
+#[must_use = "the function does not modify the original image"]
+pub fn gamma_correction<P>(image: &Image<P>, gamma: f32) -> Image<P>
+where
+    P: Pixel<Subpixel = u8>,
+{
+    let mut out = image.clone();
+    gamma_correction_mut(&mut out, gamma);
+    out
+}
+#[doc=generate_mut_doc_comment!("gamma_correction")]
+pub fn gamma_correction_mut<P>(image: &mut Image<P>, gamma: f32)
+where
+    P: Pixel<Subpixel = u8>,
+{
+    assert!(gamma > 0.0, "gamma must be > 0.0");
+    let mut lut = [0u8; 256];
+    for (i, l) in lut.iter_mut().enumerate() {
+        *l = (255.0 * (i as f32 / 255.0).powf(gamma)).round() as u8;
+    }
+    apply_lut_mut(image, &lut);
+}
+
+/// Applies single-scale retinex to an 8bpp grayscale image, compressing its dynamic range
+/// by removing slowly varying illumination.
+///
+/// Equivalent to [`multi_scale_retinex`] with the single scale `sigma`.
+///
+/// # Panics
+///
+/// If `sigma <= 0.0`.
+#[must_use = "the function does not modify the original image"]
+pub fn single_scale_retinex(image: &GrayImage, sigma: f32) -> GrayImage {
+    multi_scale_retinex(image, &[sigma])
+}
+
+/// Applies multi-scale retinex to an 8bpp grayscale image, compressing its dynamic range
+/// by removing slowly varying illumination.
+///
+/// For each `sigma` in `sigmas` the illumination of the image is estimated by smoothing it
+/// with a Gaussian of standard deviation `sigma`, and the log of the illumination is
+/// subtracted from the log of the image. The results are summed over all scales and
+/// linearly rescaled so that the smallest value maps to 0 and the largest to 255. If all
+/// values are equal, as for a constant image, the result is black.
+///
+/// Intensities are offset by one before taking logs, so that black pixels are handled.
+/// Small scales enhance fine detail and large scales preserve the overall tonal balance of
+/// the image, so `sigmas` usually span a wide range, e.g. `[15.0, 80.0, 250.0]` for
+/// photographs.
+///
+/// # Panics
+///
+/// If `sigmas` is empty or contains a value that is not strictly positive.
+#[must_use = "the function does not modify the original image"]
+pub fn multi_scale_retinex(image: &GrayImage, sigmas: &[f32]) -> GrayImage {
+    assert!(!sigmas.is_empty(), "sigmas must not be empty");
+    assert!(sigmas.iter().all(|&s| s > 0.0), "sigmas must be > 0.0");
+
+    let input = map_subpixels(image, |p| f32::from(p) + 1.0);
+    let log_input = map_subpixels(&input, f32::ln);
+    let mut retinex: Image<Luma<f32>> = Image::new(image.width(), image.height());
+    for &sigma in sigmas {
+        let illumination = gaussian_blur_f32(&input, sigma);
+        for ((r, l), i) in retinex
+            .iter_mut()
+            .zip(log_input.iter())
+            .zip(illumination.iter())
+        {
+            *r += l - i.ln();
+        }
+    }
+
+    let (min, max) = retinex
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &r| {
+            (lo.min(r), hi.max(r))
+        });
+    let scale = if max > min { 255.0 / (max - min) } else { 0.0 };
+    map_subpixels(&retinex, |r| ((r - min) * scale).round() as u8)
+}
+
 /// `l = histogram_lut(s, t)` is chosen so that `target_histc[l[i]] / sum(target_histc)`
 /// is as close as possible to `source_histc[i] / sum(source_histc)`.
 fn histogram_lut(source_histc: &[u32; 256], target_histc: &[u32; 256]) -> [usize; 256] {
@@ -393,6 +1038,7 @@ fn histogram_lut(source_histc: &[u32; 256], target_histc: &[u32; 256]) -> [usize
 mod tests {
     use super::*;
     use crate::definitions::{HasBlack, HasWhite};
+    use crate::utils::gray_bench_image;
     use image::{GrayImage, Luma};
 
     #[test]
@@ -449,6 +1095,242 @@ mod tests {
         }
     }
 
+    // Dark text drawn on a background whose brightness increases from left to right,
+    // along with a mask which is 255 for text pixels, 0 for background pixels and
+    // 127 for pixels on the antialiased boundary of the text.
+    fn text_with_illumination_gradient() -> (GrayImage, GrayImage) {
+        use crate::drawing::draw_text_mut;
+        use ab_glyph::FontRef;
+
+        let font =
+            FontRef::try_from_slice(include_bytes!("../tests/data/fonts/DejaVuSans.ttf")).unwrap();
+        let mut coverage = GrayImage::new(240, 50);
+        draw_text_mut(&mut coverage, Luma([255]), 5, 8, 30.0, &font, "Adaptive");
+
+        let mut image = GrayImage::new(240, 50);
+        let mut mask = GrayImage::new(240, 50);
+        for (x, y, c) in coverage.enumerate_pixels() {
+            let background = 30.0 + 200.0 * x as f32 / 239.0;
+            let value = background - 25.0 * c[0] as f32 / 255.0;
+            image.put_pixel(x, y, Luma([value.round() as u8]));
+            let label = match c[0] {
+                0 => 0,
+                c if c > 200 => 255,
+                _ => 127,
+            };
+            mask.put_pixel(x, y, Luma([label]));
+        }
+        (image, mask)
+    }
+
+    // The number of text pixels classified as background plus the number of background
+    // pixels classified as text, ignoring the antialiased boundary of the text.
+    fn misclassified(binary: &GrayImage, mask: &GrayImage) -> usize {
+        binary
+            .iter()
+            .zip(mask.iter())
+            .filter(|(&b, &m)| (m == 255 && b != 0) || (m == 0 && b != 255))
+            .count()
+    }
+
+    #[test]
+    fn test_adaptive_threshold_separates_text_under_illumination_gradient() {
+        let (image, mask) = text_with_illumination_gradient();
+        let text_pixels = mask.iter().filter(|&&m| m == 255).count();
+        assert!(text_pixels > 300);
+
+        let global = threshold(&image, otsu_level(&image), ThresholdType::Binary);
+        let global_errors = misclassified(&global, &mask);
+
+        for method in [AdaptiveMethod::Mean, AdaptiveMethod::Gaussian] {
+            let binary = adaptive_threshold_with_method(&image, 6, 5, method);
+            let errors = misclassified(&binary, &mask);
+            assert!(
+                errors * 20 < text_pixels && errors * 50 < global_errors,
+                "{:?}: {} errors, global threshold: {} errors",
+                method,
+                errors,
+                global_errors
+            );
+        }
+    }
+
+    // Dark text drawn on a scanned-document-like background which is shaded unevenly, along
+    // with a mask labelling text and background pixels as for text_with_illumination_gradient.
+    // The ink is proportionally darker than the background, so that text on the lightest parts
+    // of the page is brighter than the background on the darkest parts.
+    fn document_with_uneven_background() -> (GrayImage, GrayImage) {
+        use crate::drawing::draw_text_mut;
+        use ab_glyph::FontRef;
+
+        let font =
+            FontRef::try_from_slice(include_bytes!("../tests/data/fonts/DejaVuSans.ttf")).unwrap();
+        let mut coverage = GrayImage::new(260, 90);
+        draw_text_mut(&mut coverage, Luma([255]), 6, 4, 32.0, &font, "Scanned");
+        draw_text_mut(&mut coverage, Luma([255]), 6, 46, 32.0, &font, "document");
+
+        let mut image = GrayImage::new(260, 90);
+        let mut mask = GrayImage::new(260, 90);
+        for (x, y, c) in coverage.enumerate_pixels() {
+            let (u, v) = (x as f32 / 259.0, y as f32 / 89.0);
+            let background = 60.0 + 150.0 * u + 30.0 * (1.0 - (2.0 * v - 1.0).powi(2));
+            let ink = c[0] as f32 / 255.0;
+            let value = background * (1.0 - 0.6 * ink);
+            image.put_pixel(x, y, Luma([value.round() as u8]));
+            let label = match c[0] {
+                0 => 0,
+                c if c > 200 => 255,
+                _ => 127,
+            };
+            mask.put_pixel(x, y, Luma([label]));
+        }
+        (image, mask)
+    }
+
+    #[test]
+    fn test_sauvola_threshold_binarizes_document_better_than_otsu() {
+        let (image, mask) = document_with_uneven_background();
+        let text_pixels = mask.iter().filter(|&&m| m == 255).count();
+        assert!(text_pixels > 500);
+
+        let global = threshold(&image, otsu_level(&image), ThresholdType::Binary);
+        let global_errors = misclassified(&global, &mask);
+        let sauvola = sauvola_threshold(&image, 12, 0.2, 128.0);
+        let sauvola_errors = misclassified(&sauvola, &mask);
+
+        assert!(
+            sauvola_errors * 20 < text_pixels && sauvola_errors * 10 < global_errors,
+            "sauvola: {} errors, otsu: {} errors",
+            sauvola_errors,
+            global_errors
+        );
+    }
+
+    #[test]
+    fn test_niblack_threshold_finds_text_in_document() {
+        let (image, mask) = document_with_uneven_background();
+        let niblack = niblack_threshold(&image, 12, -0.2);
+
+        // Niblack's method finds the text, but admits noise in background regions
+        // containing no text, so we only check the text pixels.
+        let (text, found) = mask
+            .iter()
+            .zip(niblack.iter())
+            .filter(|(&m, _)| m == 255)
+            .fold((0, 0), |(text, found), (_, &b)| {
+                (text + 1, found + (b == 0) as usize)
+            });
+        assert!(
+            found * 20 > text * 19,
+            "{} of {} text pixels found",
+            found,
+            text
+        );
+    }
+
+    #[test]
+    fn test_local_thresholds_of_constant_image_are_white() {
+        let image = GrayImage::from_pixel(7, 5, Luma([140u8]));
+        let white = GrayImage::from_pixel(7, 5, Luma::white());
+        assert_pixels_eq!(sauvola_threshold(&image, 2, 0.3, 128.0), white);
+        assert_pixels_eq!(niblack_threshold(&image, 2, -0.2), white);
+    }
+
+    #[test]
+    fn test_adaptive_threshold_with_mean_method_matches_adaptive_threshold() {
+        let image = gray_bench_image(30, 20);
+        assert_pixels_eq!(
+            adaptive_threshold_with_method(&image, 3, 4, AdaptiveMethod::Mean),
+            adaptive_threshold(&image, 3, 4)
+        );
+    }
+
+    #[test]
+    fn test_adaptive_threshold_with_gaussian_method_constant() {
+        let image = GrayImage::from_pixel(6, 4, Luma([90u8]));
+        assert_pixels_eq!(
+            adaptive_threshold_with_method(&image, 2, 0, AdaptiveMethod::Gaussian),
+            GrayImage::from_pixel(6, 4, Luma::white())
+        );
+    }
+
+    #[test]
+    fn test_otsu_multilevel_separates_three_plateaus() {
+        use crate::noise::gaussian_noise;
+
+        // Three horizontal bands of intensity 40, 120 and 200, with added noise
+        let bands = GrayImage::from_fn(60, 60, |_, y| Luma([40 + 80 * (y / 20) as u8]));
+        let image = gaussian_noise(&bands, 0.0, 8.0, 7);
+        let thresholds = otsu_multilevel(&image, 3);
+
+        assert_eq!(thresholds.len(), 2);
+        let band_range = |band: u32| {
+            let values = image.rows().skip(20 * band as usize).take(20).flatten();
+            let values: Vec<u8> = values.map(|p| p[0]).collect();
+            (*values.iter().min().unwrap(), *values.iter().max().unwrap())
+        };
+        let (low, middle, high) = (band_range(0), band_range(1), band_range(2));
+        assert!(
+            low.1 <= thresholds[0] && thresholds[0] < middle.0,
+            "{:?}",
+            thresholds
+        );
+        assert!(
+            middle.1 <= thresholds[1] && thresholds[1] < high.0,
+            "{:?}",
+            thresholds
+        );
+
+        let classes = apply_multilevel_threshold(&image, &thresholds);
+        for (x, y, p) in classes.enumerate_pixels() {
+            assert_eq!(
+                p[0] as u32,
+                [0, 127, 255][(y / 20) as usize],
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn test_otsu_multilevel_with_two_levels_matches_otsu_level() {
+        for image in [gray_bench_image(50, 40), gray_bench_image(17, 90)] {
+            assert_eq!(otsu_multilevel(&image, 2), vec![otsu_level(&image)]);
+        }
+    }
+
+    #[test]
+    fn test_otsu_multilevel_four_levels() {
+        let image =
+            GrayImage::from_fn(40, 10, |x, _| Luma([[10, 70, 150, 220][(x / 10) as usize]]));
+        let thresholds = otsu_multilevel(&image, 4);
+        assert_eq!(thresholds.len(), 3);
+        assert!((10..70).contains(&thresholds[0]));
+        assert!((70..150).contains(&thresholds[1]));
+        assert!((150..220).contains(&thresholds[2]));
+        assert!(otsu_multilevel(&image, 1).is_empty());
+    }
+
+    #[test]
+    fn test_apply_multilevel_threshold() {
+        let image = gray_image!(0, 50, 51, 100, 101, 255);
+        assert_pixels_eq!(
+            apply_multilevel_threshold(&image, &[50, 100]),
+            gray_image!(0, 0, 127, 127, 255, 255)
+        );
+        assert_pixels_eq!(
+            apply_multilevel_threshold(&image, &[]),
+            GrayImage::new(6, 1)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_apply_multilevel_threshold_rejects_unsorted_thresholds() {
+        let _ = apply_multilevel_threshold(&GrayImage::new(2, 2), &[100, 50]);
+    }
+
     #[test]
     fn test_adaptive_thesholding_with_delta() {
         let mut image = GrayImage::from_pixel(3, 3, Luma([100u8]));
@@ -578,6 +1460,304 @@ mod tests {
         let expected = gray_image!(10u8, 10, 10, 11, 11, 12, 12, 13, 13, 13, 52, 120);
         assert_pixels_eq!(stretch_contrast(&input, 1, 255, 10, 120), expected);
     }
+
+    #[test]
+    fn test_stretch_contrast_percentile_ignores_outliers() {
+        // Most pixels lie in [100, 150], but a few are black or white
+        let mut image = GrayImage::from_fn(50, 20, |x, _| Luma([100 + x as u8]));
+        for &(x, y) in &[(3, 4), (17, 9), (40, 15)] {
+            image.put_pixel(x, y, Luma([0]));
+        }
+        for &(x, y) in &[(8, 1), (30, 12)] {
+            image.put_pixel(x, y, Luma([255]));
+        }
+
+        // Naive min-max normalisation changes nothing, as the outliers span the full range
+        let (min, max) = (*image.iter().min().unwrap(), *image.iter().max().unwrap());
+        let naive = stretch_contrast(&image, min, max, 0, 255);
+        assert_eq!(naive.get_pixel(0, 0)[0], 100);
+        assert_eq!(naive.get_pixel(49, 0)[0], 149);
+
+        let stretched = stretch_contrast_percentile(&image, 1.0, 99.0);
+        assert!(stretched.get_pixel(0, 0)[0] <= 2);
+        assert!(stretched.get_pixel(49, 0)[0] >= 253);
+        // Intensity ordering is preserved
+        for x in 1..50 {
+            assert!(stretched.get_pixel(x, 0)[0] >= stretched.get_pixel(x - 1, 0)[0]);
+        }
+    }
+
+    #[test]
+    fn test_stretch_contrast_percentile_full_range_is_min_max() {
+        let image = gray_image!(10u8, 20, 30, 40, 50);
+        assert_pixels_eq!(
+            stretch_contrast_percentile(&image, 0.0, 100.0),
+            stretch_contrast(&image, 10, 50, 0, 255)
+        );
+    }
+
+    #[test]
+    fn test_stretch_contrast_percentile_constant_image_is_unchanged() {
+        let image = GrayImage::from_pixel(4, 4, Luma([77]));
+        assert_pixels_eq!(stretch_contrast_percentile(&image, 2.0, 98.0), image);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stretch_contrast_percentile_rejects_inverted_percentiles() {
+        let _ = stretch_contrast_percentile(&GrayImage::new(4, 4), 60.0, 40.0);
+    }
+
+    fn range(image: &GrayImage) -> u8 {
+        image.iter().max().unwrap() - image.iter().min().unwrap()
+    }
+
+    #[test]
+    fn test_clipped_equalization_lut_preserves_total() {
+        let mut hist = [0u32; 256];
+        hist[10] = 900;
+        hist[200] = 100;
+        let lut = clipped_equalization_lut(&mut hist, 1000, 2.0);
+        assert_eq!(hist.iter().sum::<u32>(), 1000);
+        assert!(hist.iter().all(|&h| h <= 7 + 4));
+        assert_eq!(lut[255], 255.0);
+        assert!(lut.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_clahe_boosts_local_low_contrast_without_blowing_out() {
+        use image::GenericImageView;
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        // The left half is a low contrast texture, and the right half a high
+        // contrast texture whose intensities already cover the full range evenly
+        let mut rng = StdRng::seed_from_u64(1);
+        let image = GrayImage::from_fn(128, 64, |x, y| {
+            if x < 64 {
+                Luma([100 + ((x * 7 + y * 3) % 11) as u8])
+            } else {
+                Luma([rng.gen()])
+            }
+        });
+        let result = clahe(&image, 4, 2, 3.0);
+
+        let left = |image: &GrayImage| image.view(8, 8, 40, 48).to_image();
+        let right = |image: &GrayImage| image.view(80, 8, 40, 48).to_image();
+        assert!(range(&left(&result)) > 3 * range(&left(&image)));
+
+        // The well contrasted half changes little, and is not pushed into saturation
+        let (right_before, right_after) = (right(&image), right(&result));
+        let mean_change = right_before
+            .iter()
+            .zip(right_after.iter())
+            .map(|(&a, &b)| (a as f32 - b as f32).abs())
+            .sum::<f32>()
+            / right_before.len() as f32;
+        assert!(mean_change < 10.0, "mean change {}", mean_change);
+        let saturated = |image: &GrayImage| image.iter().filter(|&&p| p == 0 || p == 255).count();
+        assert!(saturated(&right_after) <= 2 * saturated(&right_before) + 10);
+    }
+
+    #[test]
+    fn test_clahe_with_tight_clip_limit_changes_little() {
+        let image = GrayImage::from_fn(64, 64, |x, y| Luma([90 + ((x + 2 * y) % 20) as u8]));
+        let loose = clahe(&image, 2, 2, 40.0);
+        let tight = clahe(&image, 2, 2, 1.0);
+        assert!(range(&tight) < range(&loose));
+        assert!(range(&loose) > 200);
+    }
+
+    #[test]
+    fn test_clahe_has_no_seams_between_tiles() {
+        let image = GrayImage::from_fn(80, 80, |x, y| Luma([((x + y) / 2 + (x * y) % 5) as u8]));
+        let result = clahe(&image, 4, 4, 2.0);
+        // Neighbouring pixels differ by at most a few intensity levels in the input,
+        // and the interpolated mapping keeps them close in the output
+        for y in 0..80 {
+            for x in 1..80 {
+                let (a, b) = (result.get_pixel(x - 1, y)[0], result.get_pixel(x, y)[0]);
+                assert!((a as i32 - b as i32).abs() <= 16, "({}, {})", x, y);
+            }
+        }
+    }
+
+    fn mean<'a>(values: impl Iterator<Item = &'a u8>) -> f32 {
+        let (sum, count) = values.fold((0u32, 0u32), |(s, c), &v| (s + v as u32, c + 1));
+        sum as f32 / count as f32
+    }
+
+    #[test]
+    fn test_match_histogram_to_self_is_identity() {
+        let image = GrayImage::from_fn(40, 30, |x, y| Luma([((x * 13 + y * 7) % 97 + 50) as u8]));
+        assert_pixels_eq!(match_histogram(&image, &image), image);
+
+        let image = RgbImage::from_fn(20, 20, |x, y| {
+            image::Rgb([(x * 12) as u8, (y * 5 + 30) as u8, ((x * y) % 200) as u8])
+        });
+        assert_pixels_eq!(match_histogram_rgb(&image, &image), image);
+    }
+
+    #[test]
+    fn test_match_histogram_dark_to_bright_shifts_mean_towards_target() {
+        let dark = GrayImage::from_fn(32, 32, |x, y| Luma([((x + y) % 40 + 10) as u8]));
+        let bright = GrayImage::from_fn(32, 32, |x, y| Luma([((x * 3 + y) % 60 + 180) as u8]));
+        let matched = match_histogram(&dark, &bright);
+
+        let target_mean = mean(bright.iter());
+        assert!(mean(dark.iter()) < target_mean - 100.0);
+        assert!((mean(matched.iter()) - target_mean).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_match_histogram_rgb_matches_channels_independently() {
+        let image = RgbImage::from_fn(16, 16, |x, y| {
+            image::Rgb([(x * 4) as u8, (y * 4) as u8, 100])
+        });
+        let target = RgbImage::from_fn(16, 16, |x, y| {
+            image::Rgb([(x * 4 + 150) as u8, (y * 2) as u8, (x * y) as u8])
+        });
+        let matched = match_histogram_rgb(&image, &target);
+        for c in 0..2 {
+            let channel = |image: &RgbImage| -> Vec<u8> { image.pixels().map(|p| p[c]).collect() };
+            let matched_mean = mean(channel(&matched).iter());
+            let target_mean = mean(channel(&target).iter());
+            assert!((matched_mean - target_mean).abs() < 5.0, "channel {}", c);
+        }
+        // A constant channel is mapped to a single value
+        assert!(matched.pixels().all(|p| p[2] == matched.get_pixel(0, 0)[2]));
+        // Channels keep their own orderings
+        assert!(matched.get_pixel(15, 0)[0] > matched.get_pixel(0, 0)[0]);
+        assert_eq!(matched.get_pixel(15, 0)[1], matched.get_pixel(0, 0)[1]);
+    }
+
+    #[test]
+    fn test_gamma_correction_of_one_is_identity() {
+        let image = GrayImage::from_fn(16, 16, |x, y| Luma([(x * 16 + y) as u8]));
+        assert_pixels_eq!(gamma_correction(&image, 1.0), image);
+    }
+
+    #[test]
+    fn test_gamma_correction_below_one_brightens_midtones() {
+        let image = gray_image!(0, 64, 128, 192, 255);
+        let corrected = gamma_correction(&image, 0.5);
+        assert_eq!(corrected.get_pixel(0, 0)[0], 0);
+        assert_eq!(corrected.get_pixel(4, 0)[0], 255);
+        for x in 1..4 {
+            assert!(corrected.get_pixel(x, 0)[0] > image.get_pixel(x, 0)[0]);
+        }
+        // 255 * sqrt(128 / 255) = 180.66
+        assert_eq!(corrected.get_pixel(2, 0)[0], 181);
+
+        let darkened = gamma_correction(&image, 2.0);
+        assert!(darkened.get_pixel(2, 0)[0] < 128);
+    }
+
+    #[test]
+    fn test_apply_lut_matches_per_pixel_remap_and_keeps_alpha() {
+        use image::{Rgba, RgbaImage};
+
+        let mut lut = [0u8; 256];
+        for (i, l) in lut.iter_mut().enumerate() {
+            *l = ((i * 7 + 3) % 256) as u8;
+        }
+        let image = RgbaImage::from_fn(10, 10, |x, y| {
+            Rgba([(x * 25) as u8, (y * 25) as u8, (x * y) as u8, (x + y) as u8])
+        });
+        let mapped = apply_lut(&image, &lut);
+        for (p, q) in image.pixels().zip(mapped.pixels()) {
+            let expected = Rgba([
+                lut[p[0] as usize],
+                lut[p[1] as usize],
+                lut[p[2] as usize],
+                p[3],
+            ]);
+            assert_eq!(*q, expected);
+        }
+
+        let gray = GrayImage::from_fn(10, 10, |x, y| Luma([(x * 20 + y) as u8]));
+        let mapped = apply_lut(&gray, &lut);
+        for (p, q) in gray.pixels().zip(mapped.pixels()) {
+            assert_eq!(q[0], lut[p[0] as usize]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_clahe_rejects_zero_tiles() {
+        let _ = clahe(&GrayImage::new(10, 10), 0, 2, 2.0);
+    }
+
+    // A pattern with local detail under illumination which increases from left to right
+    fn unevenly_lit_image() -> GrayImage {
+        GrayImage::from_fn(128, 64, |x, y| {
+            let reflectance = if (x / 4 + y / 4) % 2 == 0 { 0.5 } else { 1.0 };
+            let illumination = 0.1 + 0.9 * x as f32 / 127.0;
+            Luma([(250.0 * illumination * reflectance) as u8])
+        })
+    }
+
+    fn region_mean_and_stddev(image: &GrayImage, x0: u32, x1: u32) -> (f32, f32) {
+        let values: Vec<f32> = (0..image.height())
+            .flat_map(|y| (x0..x1).map(move |x| (x, y)))
+            .map(|(x, y)| image.get_pixel(x, y)[0] as f32)
+            .collect();
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32;
+        (mean, variance.sqrt())
+    }
+
+    #[test]
+    fn test_multi_scale_retinex_flattens_illumination_and_keeps_detail() {
+        let image = unevenly_lit_image();
+        let retinex = multi_scale_retinex(&image, &[5.0, 15.0, 40.0]);
+
+        // Regions away from the borders, in the dark and bright halves of the image
+        let (dark_in, dark_in_stddev) = region_mean_and_stddev(&image, 16, 32);
+        let (bright_in, _) = region_mean_and_stddev(&image, 96, 112);
+        let (dark_out, dark_out_stddev) = region_mean_and_stddev(&retinex, 16, 32);
+        let (bright_out, bright_out_stddev) = region_mean_and_stddev(&retinex, 96, 112);
+
+        assert!(bright_in - dark_in > 100.0);
+        assert!(
+            (bright_out - dark_out).abs() < 0.25 * (bright_in - dark_in),
+            "means {} and {}",
+            dark_out,
+            bright_out
+        );
+        // The checkerboard is still visible, and more so in the shadows than before
+        assert!(
+            dark_out_stddev > 2.0 * dark_in_stddev,
+            "{} vs {}",
+            dark_out_stddev,
+            dark_in_stddev
+        );
+        assert!(bright_out_stddev > 20.0, "{}", bright_out_stddev);
+    }
+
+    #[test]
+    fn test_single_scale_retinex_matches_multi_scale_with_one_scale() {
+        let image = unevenly_lit_image();
+        assert_pixels_eq!(
+            single_scale_retinex(&image, 10.0),
+            multi_scale_retinex(&image, &[10.0])
+        );
+    }
+
+    #[test]
+    fn test_retinex_output_spans_full_range() {
+        let retinex = single_scale_retinex(&unevenly_lit_image(), 8.0);
+        assert_eq!(retinex.iter().min(), Some(&0));
+        assert_eq!(retinex.iter().max(), Some(&255));
+
+        let constant = GrayImage::from_pixel(10, 10, Luma([40]));
+        assert!(single_scale_retinex(&constant, 3.0).iter().all(|&p| p == 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_multi_scale_retinex_rejects_empty_scales() {
+        let _ = multi_scale_retinex(&GrayImage::new(5, 5), &[]);
+    }
 }
 
 #[cfg(not(miri))]
@@ -598,6 +1778,43 @@ mod benches {
         });
     }
 
+    #[bench]
+    fn bench_adaptive_threshold_gaussian(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let thresholded =
+                adaptive_threshold_with_method(&image, 10, 0, AdaptiveMethod::Gaussian);
+            black_box(thresholded);
+        });
+    }
+
+    #[bench]
+    fn bench_sauvola_threshold(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let thresholded = sauvola_threshold(&image, 10, 0.3, 128.0);
+            black_box(thresholded);
+        });
+    }
+
+    #[bench]
+    fn bench_otsu_multilevel(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let thresholds = otsu_multilevel(&image, 4);
+            black_box(thresholds);
+        });
+    }
+
+    #[bench]
+    fn bench_clahe(b: &mut Bencher) {
+        let image = gray_bench_image(500, 500);
+        b.iter(|| {
+            let equalized = clahe(&image, 8, 8, 2.0);
+            black_box(equalized);
+        });
+    }
+
     #[bench]
     fn bench_match_histogram(b: &mut Bencher) {
         let target = GrayImage::from_pixel(200, 200, Luma([150]));
@@ -679,4 +1896,13 @@ mod benches {
             black_box(());
         });
     }
+
+    #[bench]
+    fn bench_multi_scale_retinex(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let retinex = multi_scale_retinex(&image, &[5.0, 20.0, 60.0]);
+            black_box(retinex);
+        });
+    }
 }
@@ -1,10 +1,14 @@
 //! Functions for detecting corners, also known as interest points.
 
 use crate::{
-    definitions::{Position, Score},
+    definitions::{Image, Position, Score},
+    filter::{box_mean, filter},
+    gradients::sobel_kernels,
+    kernel::Kernel,
     point::Point,
+    suppress::local_maxima,
 };
-use image::{GenericImageView, GrayImage};
+use image::{GenericImageView, GrayImage, Luma};
 use rand::{rngs::StdRng, SeedableRng};
 use rand_distr::Distribution;
 
@@ -101,6 +105,274 @@ pub fn corners_fast9(image: &GrayImage, threshold: u8) -> Vec<Corner> {
     corners
 }
 
+/// Finds corners using the [Harris corner detector].
+///
+/// The structure tensor at each pixel is computed from Sobel gradients with the given
+/// `aperture` (3, 5 or 7), averaged over the `block_size x block_size` window centred on
+/// the pixel. The corner response is `det(M) - k * trace(M)²` for structure tensor `M`,
+/// and corners are the pixels whose response is at least `threshold` and is maximal
+/// within the square of side `2 * (block_size / 2 + aperture / 2) + 1` centred on them,
+/// i.e. the region of the image which contributes to their response. Typical values of
+/// `k` are between `0.04` and `0.06`.
+///
+/// Gradients are normalised to estimate the rate of change of intensity per pixel for
+/// intensities scaled to `[0, 1]`. With `block_size` and `aperture` both 3, a right-angled
+/// corner between regions of intensity 0 and 255 has a response of roughly `0.006`.
+/// Responses scale with the fourth power of the contrast, and are smaller for larger
+/// values of `block_size` and `aperture`, as these spread gradients over larger windows.
+///
+/// The score of each returned corner is its response.
+///
+/// # Panics
+///
+/// If `block_size` is not odd, or `aperture` is not 3, 5 or 7.
+///
+/// [Harris corner detector]: https://en.wikipedia.org/wiki/Harris_corner_detector
+pub fn harris_corners(
+    image: &GrayImage,
+    block_size: u32,
+    aperture: u32,
+    k: f32,
+    threshold: f32,
+) -> Vec<Corner> {
+    let k = k as f64;
+    let response = structure_tensor_response(image, block_size, aperture, |xx, yy, xy| {
+        let det = xx * yy - xy * xy;
+        let trace = xx + yy;
+        det - k * trace * trace
+    });
+    corners_from_response(
+        &response,
+        image.width(),
+        threshold,
+        block_size / 2 + aperture / 2,
+    )
+}
+
+/// Finds corners using the Shi-Tomasi "good features to track" detector.
+///
+/// The score of each pixel is the smaller eigenvalue of its structure tensor, computed
+/// from Sobel gradients with the given `aperture` (3, 5 or 7) averaged over the
+/// `block_size x block_size` window centred on the pixel, normalised as described in
+/// [`harris_corners`].
+///
+/// Candidate corners are the pixels whose score is maximal in their 3x3 neighbourhood
+/// and is at least `quality_level` times the maximum score in the image. Candidates are
+/// visited in order of decreasing score, and each is kept if it is at least
+/// `min_distance` from every corner kept so far. At most `max_corners` corners are
+/// returned, sorted by decreasing score.
+///
+/// # Panics
+///
+/// If `block_size` is not odd, or `aperture` is not 3, 5 or 7.
+pub fn shi_tomasi_corners(
+    image: &GrayImage,
+    block_size: u32,
+    aperture: u32,
+    quality_level: f32,
+    min_distance: f32,
+    max_corners: usize,
+) -> Vec<Corner> {
+    let response = structure_tensor_response(image, block_size, aperture, |xx, yy, xy| {
+        let mean = (xx + yy) / 2.0;
+        let half_difference = (xx - yy) / 2.0;
+        mean - (half_difference * half_difference + xy * xy).sqrt()
+    });
+
+    let max_score = response.iter().cloned().fold(0.0, f64::max);
+    if max_score <= 0.0 {
+        return vec![];
+    }
+    let threshold = (quality_level as f64 * max_score).max(f64::MIN_POSITIVE);
+
+    let mut candidates = corners_from_response(&response, image.width(), threshold as f32, 1);
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let min_distance_squared = min_distance * min_distance;
+    let mut corners: Vec<Corner> = Vec::new();
+    for candidate in candidates {
+        if corners.len() >= max_corners {
+            break;
+        }
+        let too_close = corners.iter().any(|c| {
+            let dx = c.x as f32 - candidate.x as f32;
+            let dy = c.y as f32 - candidate.y as f32;
+            dx * dx + dy * dy < min_distance_squared
+        });
+        if !too_close {
+            corners.push(candidate);
+        }
+    }
+    corners
+}
+
+/// Refines the locations of corners to subpixel accuracy.
+///
+/// This is the iterative method used by OpenCV's `cornerSubPix`. For a corner at `q`
+/// and any point `p` near it, the image gradient at `p` is orthogonal to `p - q`:
+/// either `p` lies in a flat region and the gradient is zero, or `p` lies on an edge
+/// running through `q` and the gradient is perpendicular to the edge. Each iteration
+/// moves `q` to the least squares solution of these constraints over the
+/// `(2 * window + 1) x (2 * window + 1)` patch centred on the current estimate,
+/// weighted by a Gaussian centred on the patch.
+///
+/// Iteration stops for each corner after `max_iters` iterations, or once an
+/// iteration moves the corner by less than `epsilon` pixels. A corner is left unchanged
+/// if its constraints are degenerate (e.g. it lies in a flat region or on a straight
+/// edge) or if refinement moves it further than `window` pixels from its initial location.
+///
+/// The method is most accurate for saddle points such as the corners of a checkerboard.
+/// The tip of a blurred L-shaped corner is rounded, so refined L-shaped corners are
+/// biased slightly towards their interior.
+///
+/// Pixel coordinates refer to pixel centres and the image is sampled using bilinear
+/// interpolation, clamping to the image boundary. Corners are returned unchanged if the
+/// image is empty.
+pub fn refine_corners_subpixel(
+    image: &GrayImage,
+    corners: &[Point<f32>],
+    window: u32,
+    max_iters: u32,
+    epsilon: f32,
+) -> Vec<Point<f32>> {
+    if image.width() == 0 || image.height() == 0 {
+        return corners.to_vec();
+    }
+    corners
+        .iter()
+        .map(|&corner| refine_corner_subpixel(image, corner, window, max_iters, epsilon))
+        .collect()
+}
+
+fn refine_corner_subpixel(
+    image: &GrayImage,
+    initial: Point<f32>,
+    window: u32,
+    max_iters: u32,
+    epsilon: f32,
+) -> Point<f32> {
+    let radius = window as i32;
+    // Patch samples extend one pixel beyond the window so that central differences
+    // are defined at every point of the window
+    let side = (2 * radius + 3) as usize;
+    let mut patch = vec![0f32; side * side];
+    let weight = |i: i32, j: i32| {
+        let s = (radius.max(1) * radius.max(1)) as f32;
+        (-((i * i + j * j) as f32) / s).exp()
+    };
+
+    let (mut qx, mut qy) = (initial.x, initial.y);
+    for _ in 0..max_iters {
+        for (k, value) in patch.iter_mut().enumerate() {
+            let i = (k % side) as i32 - radius - 1;
+            let j = (k / side) as i32 - radius - 1;
+            *value = sample_bilinear(image, qx + i as f32, qy + j as f32);
+        }
+
+        let (mut a, mut b, mut c, mut bx, mut by) = (0f64, 0f64, 0f64, 0f64, 0f64);
+        for j in -radius..=radius {
+            for i in -radius..=radius {
+                let k = (j + radius + 1) as usize * side + (i + radius + 1) as usize;
+                let gx = ((patch[k + 1] - patch[k - 1]) / 2.0) as f64;
+                let gy = ((patch[k + side] - patch[k - side]) / 2.0) as f64;
+                let w = weight(i, j) as f64;
+                let (gxx, gxy, gyy) = (w * gx * gx, w * gx * gy, w * gy * gy);
+                // Offsets relative to the current estimate
+                let (px, py) = (i as f64, j as f64);
+                a += gxx;
+                b += gxy;
+                c += gyy;
+                bx += gxx * px + gxy * py;
+                by += gxy * px + gyy * py;
+            }
+        }
+
+        let det = a * c - b * b;
+        if det.abs() <= f64::EPSILON * (a * c).abs().max(1.0) {
+            return initial;
+        }
+        let dx = ((c * bx - b * by) / det) as f32;
+        let dy = ((a * by - b * bx) / det) as f32;
+        qx += dx;
+        qy += dy;
+
+        if (qx - initial.x).abs() > window as f32 || (qy - initial.y).abs() > window as f32 {
+            return initial;
+        }
+        if dx * dx + dy * dy < epsilon * epsilon {
+            break;
+        }
+    }
+    Point::new(qx, qy)
+}
+
+// Samples an image at a subpixel location using bilinear interpolation,
+// clamping coordinates to the image.
+fn sample_bilinear(image: &GrayImage, x: f32, y: f32) -> f32 {
+    let (width, height) = image.dimensions();
+    let x = x.clamp(0.0, (width - 1) as f32);
+    let y = y.clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+    let at = |x, y| image.get_pixel(x, y)[0] as f32;
+    let top = at(x0, y0) * (1.0 - fx) + at(x1, y0) * fx;
+    let bottom = at(x0, y1) * (1.0 - fx) + at(x1, y1) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+// Computes the structure tensor of an image as described in the documentation of
+// `harris_corners`, and maps each pixel's tensor entries (xx, yy, xy) to a corner response.
+fn structure_tensor_response<F>(image: &GrayImage, block_size: u32, aperture: u32, f: F) -> Vec<f64>
+where
+    F: Fn(f64, f64, f64) -> f64,
+{
+    assert!(block_size % 2 == 1, "block_size must be odd");
+    let (horizontal, vertical) = sobel_kernels(aperture);
+
+    // The response of the horizontal kernel to an image with intensity x at column x
+    let center = (aperture / 2) as i32;
+    let ramp_response: i32 = horizontal
+        .iter()
+        .enumerate()
+        .map(|(i, k)| k * ((i as u32 % aperture) as i32 - center))
+        .sum();
+    let scale = 1.0 / (255.0 * ramp_response as f64);
+
+    let gx: Image<Luma<f64>> = filter(image, Kernel::new(&horizontal, aperture, aperture), |v| {
+        v as f64 * scale
+    });
+    let gy: Image<Luma<f64>> = filter(image, Kernel::new(&vertical, aperture, aperture), |v| {
+        v as f64 * scale
+    });
+
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let xx: Vec<f64> = gx.iter().map(|g| g * g).collect();
+    let yy: Vec<f64> = gy.iter().map(|g| g * g).collect();
+    let xy: Vec<f64> = gx.iter().zip(gy.iter()).map(|(x, y)| x * y).collect();
+
+    let radius = (block_size / 2) as usize;
+    let xx = box_mean(&xx, width, height, radius);
+    let yy = box_mean(&yy, width, height, radius);
+    let xy = box_mean(&xy, width, height, radius);
+
+    (0..width * height)
+        .map(|i| f(xx[i], yy[i], xy[i]))
+        .collect()
+}
+
+// Returns the pixels whose response is at least threshold and is maximal in the
+// (2 * radius + 1) square block centred on them.
+fn corners_from_response(response: &[f64], width: u32, threshold: f32, radius: u32) -> Vec<Corner> {
+    let candidates: Vec<Corner> = response
+        .iter()
+        .enumerate()
+        .filter(|(_, &r)| r >= threshold as f64)
+        .map(|(i, &r)| Corner::new(i as u32 % width, i as u32 / width, r as f32))
+        .collect();
+    local_maxima(&candidates, radius.max(1))
+}
+
 /// A FAST corner with associated orientation as described in [Rublee, et. al.
 /// (2012)][rublee].
 ///
@@ -114,20 +386,37 @@ pub struct OrientedFastCorner {
     pub orientation: f32,
 }
 
-fn intensity_centroid(image: &GrayImage, x: u32, y: u32, radius: u32) -> f32 {
+// The region around a corner used to compute its intensity centroid.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum CentroidPatch {
+    // The square of side 2 * radius + 1 centred on the corner.
+    Square,
+    // The pixels within radius of the corner.
+    Disk,
+}
+
+// The angle from (x, y) to the intensity centroid of the patch of the given radius
+// centred on it, in pixel coordinates. The patch is clipped to the image.
+fn intensity_centroid(image: &GrayImage, x: u32, y: u32, radius: u32, patch: CentroidPatch) -> f32 {
     let mut y_centroid: i32 = 0;
     let mut x_centroid: i32 = 0;
 
     let (width, height) = image.dimensions();
-    let x_min = if x < radius { 0 } else { x - radius };
-    let y_min = if y < radius { 0 } else { y - radius };
+    let x_min = x.saturating_sub(radius);
+    let y_min = y.saturating_sub(radius);
     let y_max = u32::min(y + radius + 1, height);
     let x_max = u32::min(x + radius + 1, width);
-
-    let (mut x_count, mut y_count) = (-(radius as i32), (radius as i32));
-
-    for y in y_min..y_max {
-        for x in x_min..x_max {
+    let radius_squared = (radius as i64).pow(2);
+
+    for py in y_min..y_max {
+        let dy = py as i32 - y as i32;
+        for px in x_min..x_max {
+            let dx = px as i32 - x as i32;
+            if patch == CentroidPatch::Disk
+                && (dx as i64).pow(2) + (dy as i64).pow(2) > radius_squared
+            {
+                continue;
+            }
             // UNSAFETY JUSTIFICATION
             //
             // Benefit
@@ -137,26 +426,15 @@ fn intensity_centroid(image: &GrayImage, x: u32, y: u32, radius: u32) -> f32 {
             //
             // Correctness
             //
-            // x will always be greater than or equal to x_min and strictly less
+            // px will always be greater than or equal to x_min and strictly less
             // than x_max due to the range in this for loop. x_min will never be
             // less than zero, and x_max will never be greater than the image
             // width, both due to the checks earlier in this function. The same
-            // logic applies to y, y_min, and y_max.
-            let pixel = unsafe { image.unsafe_get_pixel(x, y).0[0] };
-            x_centroid += x_count * (pixel as i32);
-            x_count += 1;
-        }
-        x_count = -(radius as i32);
-    }
-
-    for x in x_min..x_max {
-        for y in y_min..y_max {
-            // See UNSAFETY JUSTIFICATION above.
-            let pixel = unsafe { image.unsafe_get_pixel(x, y).0[0] };
-            y_centroid += y_count * (pixel as i32);
-            y_count -= 1;
+            // logic applies to py, y_min, and y_max.
+            let pixel = unsafe { image.unsafe_get_pixel(px, py).0[0] };
+            x_centroid += dx * (pixel as i32);
+            y_centroid -= dy * (pixel as i32);
         }
-        y_count = radius as i32;
     }
 
     // Important note: we flip the sign here because there are two coordinate
@@ -180,7 +458,6 @@ pub fn oriented_fast(
     let (width, height) = image.dimensions();
     let (min_x, max_x) = (edge_radius, width - edge_radius);
     let (min_y, max_y) = (edge_radius, height - edge_radius);
-    let mut corners = vec![];
 
     let local_threshold = if let Some(t) = threshold {
         t
@@ -210,15 +487,11 @@ pub fn oriented_fast(
         fast_scores[fraction_idx]
     };
 
-    // Iterate over every pixel in the image and find potential corners.
-    for y in edge_radius..height - edge_radius {
-        for x in edge_radius..width - edge_radius {
-            if is_corner_fast9(image, local_threshold, x, y) {
-                let score = fast_corner_score(image, local_threshold, x, y, Fast::Nine);
-                corners.push(Corner::new(x, y, score as f32));
-            }
-        }
-    }
+    // Find potential corners away from the edges of the image.
+    let mut corners: Vec<Corner> = corners_fast9(image, local_threshold)
+        .into_iter()
+        .filter(|c| (min_x..max_x).contains(&c.x) && (min_y..max_y).contains(&c.y))
+        .collect();
 
     // Sort descending by Harris corner measure.
     corners.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
@@ -235,7 +508,49 @@ pub fn oriented_fast(
         .iter()
         .map(|c| OrientedFastCorner {
             corner: *c,
-            orientation: intensity_centroid(image, c.x, c.y, 15),
+            orientation: intensity_centroid(image, c.x, c.y, 15, CentroidPatch::Square),
+        })
+        .collect()
+}
+
+/// Finds FAST-9 corners with orientations, as in the ORB detector of
+/// [Rublee et. al. (2012)][rublee].
+///
+/// Corners are found using [`corners_fast9`] with the given `threshold`. Clusters of
+/// adjacent corners are then reduced to single corners by keeping only those whose
+/// score is maximal in the 3x3 block centred on them.
+///
+/// The orientation of each corner is the angle of the vector from the corner to the
+/// intensity centroid of the disk of diameter `patch_size` centred on it, i.e.
+/// `atan2(m01, m10)` where `m10` and `m01` are the first order moments of the intensities
+/// in the disk about the corner. The disk is clipped to the image. Angles are in radians,
+/// measured in pixel coordinates from the positive x axis towards the positive y axis,
+/// so rotating an image clockwise about a corner through an angle `theta` increases
+/// the orientation of the corner by `theta`. Rotating patches by minus their orientation
+/// before comparing them makes the comparison invariant to rotation.
+///
+/// Unlike [`oriented_fast`], which orients corners using a square patch and returns only
+/// the corners with the highest scores, all corners are returned, in raster order.
+///
+/// # Panics
+///
+/// If `patch_size < 3`.
+///
+/// [rublee]: http://www.gwylab.com/download/ORB_2012.pdf
+pub fn oriented_fast_corners(
+    image: &GrayImage,
+    threshold: u8,
+    patch_size: u32,
+) -> Vec<OrientedFastCorner> {
+    assert!(patch_size >= 3, "patch_size must be >= 3");
+    let radius = patch_size / 2;
+    let mut corners = local_maxima(&corners_fast9(image, threshold), 1);
+    corners.sort_by_key(|c| (c.y, c.x));
+    corners
+        .into_iter()
+        .map(|corner| OrientedFastCorner {
+            corner,
+            orientation: intensity_centroid(image, corner.x, corner.y, radius, CentroidPatch::Disk),
         })
         .collect()
 }
@@ -488,6 +803,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filter::gaussian_blur_f32;
 
     #[test]
     fn test_is_corner_fast12_12_contiguous_darker_pixels() {
@@ -605,6 +921,209 @@ mod tests {
         assert!(is_corner_fast9(&image, 8, 3, 3));
     }
 
+    fn checkerboard(squares: u32, square_size: u32) -> GrayImage {
+        let side = squares * square_size;
+        GrayImage::from_fn(side, side, |x, y| {
+            if (x / square_size + y / square_size) % 2 == 0 {
+                Luma([230])
+            } else {
+                Luma([20])
+            }
+        })
+    }
+
+    // Checks that corners are found at exactly the interior grid intersections of a
+    // checkerboard, allowing for the corner lying between pixels
+    fn assert_checkerboard_corners(
+        corners: &[Corner],
+        squares: u32,
+        square_size: u32,
+        tolerance: u32,
+    ) {
+        let expected = (squares - 1) * (squares - 1);
+        assert_eq!(corners.len() as u32, expected, "{:?}", corners);
+        for corner in corners {
+            let near_grid = |v: u32| {
+                let rem = v % square_size;
+                rem <= tolerance || rem + tolerance >= square_size
+            };
+            assert!(near_grid(corner.x) && near_grid(corner.y), "{:?}", corner);
+        }
+    }
+
+    #[test]
+    fn test_harris_corners_checkerboard() {
+        let image = checkerboard(6, 10);
+        let corners = harris_corners(&image, 3, 3, 0.04, 1e-3);
+        assert_checkerboard_corners(&corners, 6, 10, 1);
+    }
+
+    #[test]
+    fn test_harris_corners_larger_aperture_and_block() {
+        let image = checkerboard(5, 12);
+        let corners = harris_corners(&image, 5, 5, 0.05, 2e-4);
+        assert_checkerboard_corners(&corners, 5, 12, 2);
+    }
+
+    #[test]
+    fn test_harris_corners_ignores_edges_and_flat_regions() {
+        let image = GrayImage::from_fn(30, 30, |x, _| if x < 15 { Luma([0]) } else { Luma([255]) });
+        assert!(harris_corners(&image, 3, 3, 0.04, 1e-4).is_empty());
+        let flat = GrayImage::from_pixel(20, 20, Luma([100]));
+        assert!(harris_corners(&flat, 3, 3, 0.04, 1e-6).is_empty());
+    }
+
+    #[test]
+    fn test_harris_corners_single_corner_response() {
+        let image = GrayImage::from_fn(40, 40, |x, y| {
+            if x >= 20 && y >= 20 {
+                Luma([255])
+            } else {
+                Luma([0])
+            }
+        });
+        let corners = harris_corners(&image, 3, 3, 0.04, 1e-4);
+        assert_eq!(corners.len(), 1);
+        let corner = corners[0];
+        assert!((19..=20).contains(&corner.x) && (19..=20).contains(&corner.y));
+        assert!((corner.score - 0.006).abs() < 0.001, "{}", corner.score);
+    }
+
+    fn grid_of_dots(rows: u32, cols: u32, spacing: u32) -> GrayImage {
+        GrayImage::from_fn(cols * spacing, rows * spacing, |x, y| {
+            let (dx, dy) = (x % spacing, y % spacing);
+            if (spacing / 2 - 2..=spacing / 2 + 2).contains(&dx)
+                && (spacing / 2 - 2..=spacing / 2 + 2).contains(&dy)
+            {
+                Luma([255])
+            } else {
+                Luma([0])
+            }
+        })
+    }
+
+    fn assert_min_spacing(corners: &[Corner], min_distance: f32) {
+        for (i, a) in corners.iter().enumerate() {
+            for b in &corners[i + 1..] {
+                let dx = a.x as f32 - b.x as f32;
+                let dy = a.y as f32 - b.y as f32;
+                assert!(
+                    (dx * dx + dy * dy).sqrt() >= min_distance,
+                    "{:?} {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_shi_tomasi_corners_respects_spacing_and_count() {
+        let image = grid_of_dots(4, 5, 14);
+
+        // Each 5x5 dot has four corners
+        let dense = shi_tomasi_corners(&image, 3, 3, 0.1, 0.0, 1000);
+        assert_eq!(dense.len(), 80);
+
+        // Corners of the same dot are at most 6 pixels apart and corners of different
+        // dots are at least 9 pixels apart, so requiring a spacing of 7 leaves one
+        // corner per dot
+        let sparse = shi_tomasi_corners(&image, 3, 3, 0.1, 7.0, 1000);
+        assert_eq!(sparse.len(), 20);
+        assert_min_spacing(&sparse, 7.0);
+
+        let limited = shi_tomasi_corners(&image, 3, 3, 0.1, 7.0, 7);
+        assert_eq!(limited.len(), 7);
+        assert_min_spacing(&limited, 7.0);
+        assert!(limited.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_shi_tomasi_corners_quality_level() {
+        // A high contrast corner and a low contrast corner
+        let image = GrayImage::from_fn(60, 30, |x, y| {
+            if (10..20).contains(&x) && (10..20).contains(&y) {
+                Luma([255])
+            } else if (40..50).contains(&x) && (10..20).contains(&y) {
+                Luma([40])
+            } else {
+                Luma([0])
+            }
+        });
+        let all = shi_tomasi_corners(&image, 3, 3, 0.001, 3.0, 100);
+        assert_eq!(all.len(), 8);
+        let strong = shi_tomasi_corners(&image, 3, 3, 0.5, 3.0, 100);
+        assert_eq!(strong.len(), 4);
+        assert!(strong.iter().all(|c| c.x < 30));
+    }
+
+    #[test]
+    fn test_shi_tomasi_corners_flat_image() {
+        let image = GrayImage::from_pixel(20, 20, Luma([50]));
+        assert!(shi_tomasi_corners(&image, 3, 3, 0.01, 1.0, 10).is_empty());
+    }
+
+    // An anti-aliased checkerboard corner at subpixel location (cx, cy), with edges along
+    // directions theta and theta + 90 degrees, blurred slightly as if by a camera's optics.
+    fn checkerboard_corner(size: u32, cx: f32, cy: f32, theta: f32) -> GrayImage {
+        let (sin, cos) = theta.sin_cos();
+        let samples = 16;
+        let sharp = GrayImage::from_fn(size, size, |x, y| {
+            let mut inside = 0;
+            for sy in 0..samples {
+                for sx in 0..samples {
+                    let px = x as f32 - 0.5 + (sx as f32 + 0.5) / samples as f32 - cx;
+                    let py = y as f32 - 0.5 + (sy as f32 + 0.5) / samples as f32 - cy;
+                    if (px * cos + py * sin > 0.0) == (-px * sin + py * cos > 0.0) {
+                        inside += 1;
+                    }
+                }
+            }
+            Luma([(20 + 200 * inside / (samples * samples)) as u8])
+        });
+        gaussian_blur_f32(&sharp, 1.0)
+    }
+
+    #[test]
+    fn test_refine_corners_subpixel_recovers_subpixel_location() {
+        for &(cx, cy, theta) in &[(15.3, 14.7, 0.0), (14.62, 15.41, 0.35), (15.85, 15.1, -0.6)] {
+            let image = checkerboard_corner(31, cx, cy, theta);
+            let initial = Point::new(cx.round(), cy.round() + 1.0);
+            let refined = refine_corners_subpixel(&image, &[initial], 5, 20, 1e-3);
+            assert_eq!(refined.len(), 1);
+            let error = ((refined[0].x - cx).powi(2) + (refined[0].y - cy).powi(2)).sqrt();
+            assert!(
+                error < 0.1,
+                "expected ({}, {}), got {:?}",
+                cx,
+                cy,
+                refined[0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_refine_corners_subpixel_leaves_flat_regions_unchanged() {
+        let image = GrayImage::from_pixel(20, 20, Luma([90]));
+        let corners = [Point::new(10.0, 10.0), Point::new(3.5, 7.25)];
+        assert_eq!(
+            refine_corners_subpixel(&image, &corners, 3, 10, 1e-3),
+            corners
+        );
+    }
+
+    #[test]
+    fn test_refine_corners_subpixel_empty_image() {
+        let corners = [Point::new(1.0, 2.0)];
+        for (width, height) in [(0, 0), (0, 5), (5, 0)] {
+            let image = GrayImage::new(width, height);
+            assert_eq!(
+                refine_corners_subpixel(&image, &corners, 3, 10, 1e-3),
+                corners
+            );
+        }
+    }
+
     #[test]
     fn test_intensity_centroid() {
         let image = gray_image!(
@@ -617,11 +1136,112 @@ mod tests {
             00, 00, 00, 10, 10, 00, 00);
 
         assert_eq!(
-            intensity_centroid(&image, 3, 3, 3),
+            intensity_centroid(&image, 3, 3, 3, CentroidPatch::Square),
             -std::f32::consts::FRAC_PI_4
         );
     }
 
+    #[test]
+    fn test_disk_intensity_centroid_matches_square() {
+        let image = gray_image!(
+            00, 00, 10, 10, 10, 00, 00;
+            00, 10, 00, 00, 00, 10, 00;
+            10, 00, 00, 00, 00, 00, 10;
+            10, 00, 00, 00, 00, 00, 10;
+            00, 00, 00, 00, 00, 00, 10;
+            00, 00, 00, 00, 00, 10, 00;
+            00, 00, 00, 10, 10, 00, 00);
+        assert_eq!(
+            intensity_centroid(&image, 3, 3, 3, CentroidPatch::Disk),
+            intensity_centroid(&image, 3, 3, 3, CentroidPatch::Square)
+        );
+    }
+
+    fn angle_difference(a: f32, b: f32) -> f32 {
+        let d = (a - b).rem_euclid(2.0 * std::f32::consts::PI);
+        d.min(2.0 * std::f32::consts::PI - d)
+    }
+
+    #[test]
+    fn test_oriented_fast_corners_rotate_with_image() {
+        use crate::geometric_transformations::{rotate_about_center, Interpolation};
+
+        // A bright square whose top left corner is at (30, 30)
+        let mut image = GrayImage::from_pixel(101, 101, Luma([20]));
+        for y in 30..71 {
+            for x in 30..71 {
+                image.put_pixel(x, y, Luma([220]));
+            }
+        }
+        let find = |corners: &[OrientedFastCorner], x: f32, y: f32| {
+            let distance = |c: &OrientedFastCorner| {
+                (c.corner.x as f32 - x).powi(2) + (c.corner.y as f32 - y).powi(2)
+            };
+            let nearest = *corners
+                .iter()
+                .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+                .unwrap();
+            assert!(distance(&nearest) <= 4.0, "no corner near ({}, {})", x, y);
+            nearest
+        };
+
+        let corners = oriented_fast_corners(&image, 50, 31);
+        let original = find(&corners, 30.0, 30.0);
+        // The centroid of the patch lies inside the square, along the diagonal
+        assert!(angle_difference(original.orientation, std::f32::consts::FRAC_PI_4) < 0.05);
+
+        for theta in [0.3f32, 1.0, 2.5] {
+            let rotated = rotate_about_center(&image, theta, Interpolation::Bilinear, Luma([20]));
+            // Rotating clockwise about the centre (50, 50) moves (30, 30) here
+            let (x, y) = (
+                50.0 - 20.0 * theta.cos() + 20.0 * theta.sin(),
+                50.0 - 20.0 * theta.sin() - 20.0 * theta.cos(),
+            );
+            let corner = find(&oriented_fast_corners(&rotated, 50, 31), x, y);
+            let difference = angle_difference(corner.orientation, original.orientation + theta);
+            assert!(
+                difference < 0.15,
+                "rotation {}: orientation {} at ({}, {}) vs {}",
+                theta,
+                corner.orientation,
+                corner.corner.x,
+                corner.corner.y,
+                original.orientation
+            );
+        }
+    }
+
+    #[test]
+    fn test_oriented_fast_corners_suppresses_clusters() {
+        // Blurring a corner produces FAST responses at several adjacent pixels
+        let mut image = GrayImage::from_pixel(40, 40, Luma([0]));
+        for y in 15..40 {
+            for x in 15..40 {
+                image.put_pixel(x, y, Luma([255]));
+            }
+        }
+        let image = gaussian_blur_f32(&image, 1.0);
+
+        let all = corners_fast9(&image, 20);
+        let has_adjacent = |corners: &[Corner]| {
+            corners.iter().any(|a| {
+                corners
+                    .iter()
+                    .any(|b| a != b && a.x.abs_diff(b.x) <= 1 && a.y.abs_diff(b.y) <= 1)
+            })
+        };
+        assert!(has_adjacent(&all), "{:?}", all);
+
+        let suppressed: Vec<Corner> = oriented_fast_corners(&image, 20, 9)
+            .iter()
+            .map(|c| c.corner)
+            .collect();
+        assert!(!suppressed.is_empty());
+        assert!(suppressed.len() < all.len());
+        assert!(!has_adjacent(&suppressed), "{:?}", suppressed);
+        assert!(suppressed.iter().all(|c| all.contains(c)));
+    }
+
     #[test]
     fn test_is_corner_fast9_12_noncontiguous() {
         let image = gray_image!(
@@ -660,6 +1280,7 @@ mod tests {
 #[cfg(test)]
 mod benches {
     use super::*;
+    use crate::utils::gray_bench_image;
     use test::{black_box, Bencher};
 
     #[bench]
@@ -676,6 +1297,24 @@ mod benches {
         b.iter(|| black_box(is_corner_fast12(&image, 8, 3, 3)));
     }
 
+    #[bench]
+    fn bench_harris_corners(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let corners = harris_corners(&image, 3, 3, 0.04, 1e-4);
+            black_box(corners);
+        });
+    }
+
+    #[bench]
+    fn bench_shi_tomasi_corners(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let corners = shi_tomasi_corners(&image, 3, 3, 0.01, 5.0, 500);
+            black_box(corners);
+        });
+    }
+
     #[bench]
     fn bench_intensity_centroid(b: &mut Bencher) {
         let image = gray_image!(
@@ -687,7 +1326,7 @@ mod benches {
             00, 00, 00, 00, 00, 10, 00;
             00, 00, 00, 10, 10, 00, 00);
 
-        b.iter(|| black_box(intensity_centroid(&image, 3, 3, 3)));
+        b.iter(|| black_box(intensity_centroid(&image, 3, 3, 3, CentroidPatch::Square)));
     }
 
     #[bench]
@@ -704,6 +1343,12 @@ mod benches {
         b.iter(|| black_box(oriented_fast(&image, Some(0), 1, 0, Some(0xc0))));
     }
 
+    #[bench]
+    fn bench_oriented_fast_corners(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| black_box(oriented_fast_corners(&image, 3, 31)));
+    }
+
     #[bench]
     fn bench_oriented_fast_non_corner(b: &mut Bencher) {
         let image = gray_image!(
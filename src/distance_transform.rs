@@ -252,6 +252,176 @@ pub fn euclidean_squared_distance_transform(image: &Image<Luma<u8>>) -> Image<Lu
     result
 }
 
+/// Computes the exact `L2` (Euclidean) distance transform of `image`. Distances are to the
+/// nearest foreground pixel, where a pixel is counted as foreground if it has non-zero value.
+///
+/// This is the square root of [`euclidean_squared_distance_transform`], and so also runs in
+/// time linear in the size of the image. If `image` has no foreground pixels then every
+/// distance is infinite.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::distance_transform::euclidean_distance_transform;
+///
+/// let image = gray_image!(
+///     0, 0, 0, 0;
+///     0, 1, 0, 0;
+///     0, 0, 0, 0
+/// );
+///
+/// let distances = euclidean_distance_transform(&image);
+/// assert_eq!(distances.get_pixel(1, 1)[0], 0.0);
+/// assert_eq!(distances.get_pixel(3, 1)[0], 2.0);
+/// assert_eq!(distances.get_pixel(3, 2)[0], 5f32.sqrt());
+/// # }
+/// ```
+pub fn euclidean_distance_transform(image: &Image<Luma<u8>>) -> Image<Luma<f32>> {
+    let squared = euclidean_squared_distance_transform(image);
+    let (width, height) = squared.dimensions();
+    Image::from_fn(width, height, |x, y| {
+        Luma([squared.get_pixel(x, y)[0].sqrt() as f32])
+    })
+}
+
+/// Computes the signed Euclidean distance transform of `image`, where a pixel is counted as
+/// foreground if it has non-zero value.
+///
+/// Distances are negative inside the foreground and positive in the background. Each
+/// background pixel has the distance to the nearest foreground pixel, and each foreground
+/// pixel has minus the distance to the nearest background pixel, so the boundary of the
+/// foreground lies where values change from `-1` to `1`.
+///
+/// This is the difference between the [`euclidean_distance_transform`]s of `image` and of
+/// its complement. If `image` has no background pixels then every distance is negative
+/// infinity, and if it has no foreground pixels then every distance is positive infinity.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::distance_transform::signed_distance_transform;
+///
+/// let image = gray_image!(
+///     0, 0, 0, 0, 0, 0;
+///     0, 1, 1, 1, 1, 0;
+///     0, 1, 1, 1, 1, 0;
+///     0, 1, 1, 1, 1, 0;
+///     0, 0, 0, 0, 0, 0
+/// );
+///
+/// let d = 2f32.sqrt();
+/// let expected = gray_image!(type: f32,
+///       d,  1.0,  1.0,  1.0,  1.0,   d;
+///     1.0, -1.0, -1.0, -1.0, -1.0, 1.0;
+///     1.0, -1.0, -2.0, -2.0, -1.0, 1.0;
+///     1.0, -1.0, -1.0, -1.0, -1.0, 1.0;
+///       d,  1.0,  1.0,  1.0,  1.0,   d
+/// );
+///
+/// assert_pixels_eq!(signed_distance_transform(&image), expected);
+/// # }
+/// ```
+pub fn signed_distance_transform(image: &Image<Luma<u8>>) -> Image<Luma<f32>> {
+    let mut complement = image.clone();
+    for p in complement.iter_mut() {
+        *p = if *p == 0 { 1 } else { 0 };
+    }
+    let outside = euclidean_distance_transform(image);
+    let inside = euclidean_distance_transform(&complement);
+
+    let (width, height) = image.dimensions();
+    Image::from_fn(width, height, |x, y| {
+        if image.get_pixel(x, y)[0] == 0 {
+            *outside.get_pixel(x, y)
+        } else {
+            Luma([-inside.get_pixel(x, y)[0]])
+        }
+    })
+}
+
+/// Computes the feature transform of `image`: the index of the nearest foreground pixel
+/// to each pixel, where a pixel is counted as foreground if it has non-zero value.
+///
+/// The pixel at `(x, y)` has index `y * width + x`. Each output pixel therefore labels the
+/// cell of the Voronoi diagram of the foreground pixels that it belongs to. Where several
+/// foreground pixels are equally near, any of them may be chosen. If `image` has no foreground
+/// pixels then every output pixel is [`u32::MAX`].
+///
+/// Distances are Euclidean, and the transform is computed in time linear in the size of the
+/// image using the same algorithm as [`euclidean_squared_distance_transform`].
+///
+/// # Panics
+///
+/// If `image` has `u32::MAX` or more pixels.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::distance_transform::feature_transform;
+///
+/// let image = gray_image!(
+///     1, 0, 0, 0, 0, 0;
+///     0, 0, 0, 0, 0, 0;
+///     0, 0, 0, 0, 0, 1
+/// );
+///
+/// // The foreground pixels have indices 0 and 17
+/// let nearest = gray_image!(type: u32,
+///     0, 0, 0, 17, 17, 17;
+///     0, 0, 0, 17, 17, 17;
+///     0, 0, 0, 17, 17, 17
+/// );
+///
+/// assert_pixels_eq!(feature_transform(&image), nearest);
+/// # }
+/// ```
+pub fn feature_transform(image: &Image<Luma<u8>>) -> Image<Luma<u32>> {
+    let (width, height) = image.dimensions();
+    let (w, h) = (width as usize, height as usize);
+    assert!(
+        (w * h) < u32::MAX as usize,
+        "images with u32::MAX or more pixels are not supported"
+    );
+
+    // Squared distances to, and rows of, the nearest foreground pixel in each column
+    let mut column_distances = vec![0f64; w * h];
+    let mut nearest_rows = vec![0usize; w * h];
+    let mut column_envelope = LowerEnvelope::new(h);
+    for x in 0..width {
+        let source = Column { image, column: x };
+        let x = x as usize;
+        distance_transform_1d_with_locations(&source, &mut column_envelope, |y, value, row| {
+            column_distances[y * w + x] = value;
+            nearest_rows[y * w + x] = row;
+        });
+    }
+
+    let mut result = Image::new(width, height);
+    let mut row_envelope = LowerEnvelope::new(w);
+    for y in 0..h {
+        let row = &column_distances[y * w..(y + 1) * w];
+        distance_transform_1d_with_locations(row, &mut row_envelope, |x, value, column| {
+            let index = if value.is_finite() {
+                (nearest_rows[y * w + column] * w + column) as u32
+            } else {
+                u32::MAX
+            };
+            result.put_pixel(x as u32, y as u32, Luma([index]));
+        });
+    }
+
+    result
+}
+
 struct LowerEnvelope {
     // Indices of the parabolas in the lower envelope.
     locations: Vec<usize>,
@@ -358,6 +528,16 @@ where
     T: Sink,
 {
     assert!(result.len() == f.len());
+    distance_transform_1d_with_locations(f, envelope, |q, value, _| result.put(q, value));
+}
+
+// Computes the 1d distance transform of f, calling put(q, value, location) for each index q,
+// where location is the index minimising f[location] + (q - location)^2.
+fn distance_transform_1d_with_locations<S, F>(f: &S, envelope: &mut LowerEnvelope, mut put: F)
+where
+    S: Source + ?Sized,
+    F: FnMut(usize, f64, usize),
+{
     assert!(envelope.boundaries.len() == f.len() + 1);
     assert!(envelope.locations.len() == f.len());
 
@@ -417,8 +597,9 @@ where
         while envelope.boundaries[k + 1] < q as f64 {
             k += 1;
         }
-        let dist = q as f64 - envelope.locations[k] as f64;
-        result.put(q, dist * dist + f.get(envelope.locations[k]));
+        let location = envelope.locations[k];
+        let dist = q as f64 - location as f64;
+        put(q, dist * dist + f.get(location), location);
     }
 }
 
@@ -443,6 +624,7 @@ fn intersection<S: Source + ?Sized>(f: &S, p: usize, q: usize) -> f64 {
 mod tests {
     use super::*;
     use crate::definitions::Image;
+    use crate::drawing::draw_filled_circle_mut;
     use crate::property_testing::GrayTestImage;
     use crate::utils::pixel_diff_summary;
     use image::{GrayImage, Luma};
@@ -600,6 +782,144 @@ mod tests {
         quickcheck(prop as fn(GrayTestImage) -> TestResult);
     }
 
+    #[test]
+    fn test_euclidean_distance_transform_single_pixel() {
+        let mut image = GrayImage::new(17, 11);
+        image.put_pixel(6, 4, Luma([255]));
+        let distances = euclidean_distance_transform(&image);
+        for (x, y, p) in distances.enumerate_pixels() {
+            let expected = ((x as f32 - 6.0).powi(2) + (y as f32 - 4.0).powi(2)).sqrt();
+            assert!((p[0] - expected).abs() < 1e-5, "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_euclidean_distance_transform_of_empty_image_is_infinite() {
+        let distances = euclidean_distance_transform(&GrayImage::new(3, 2));
+        assert!(distances.iter().all(|d| *d == f32::INFINITY));
+    }
+
+    #[cfg_attr(miri, ignore = "slow")]
+    #[test]
+    fn test_euclidean_distance_transform_matches_reference_implementation() {
+        fn prop(image: GrayTestImage) -> TestResult {
+            let squared = euclidean_squared_distance_transform_reference(&image.0);
+            let expected: Image<Luma<f32>> =
+                Image::from_fn(squared.width(), squared.height(), |x, y| {
+                    Luma([squared.get_pixel(x, y)[0].sqrt() as f32])
+                });
+            let actual = euclidean_distance_transform(&image.0);
+            match pixel_diff_summary(&actual, &expected) {
+                None => TestResult::passed(),
+                Some(err) => TestResult::error(err),
+            }
+        }
+        quickcheck(prop as fn(GrayTestImage) -> TestResult);
+    }
+
+    #[test]
+    fn test_signed_distance_transform_of_filled_circle() {
+        let (centre, radius) = ((25, 25), 12);
+        let mut image = GrayImage::new(51, 51);
+        draw_filled_circle_mut(&mut image, centre, radius, Luma([255]));
+        let distances = signed_distance_transform(&image);
+
+        // The centre is roughly one radius from the boundary
+        let centre_distance = distances.get_pixel(25, 25)[0];
+        assert!(
+            (centre_distance + radius as f32).abs() <= 1.0,
+            "{}",
+            centre_distance
+        );
+
+        // Distances are negative exactly inside the circle
+        for (x, y, p) in distances.enumerate_pixels() {
+            assert_eq!(p[0] < 0.0, image.get_pixel(x, y)[0] > 0);
+        }
+
+        // Zero crossings lie on the circle
+        for y in 0..51 {
+            for x in 0..50 {
+                let (left, right) = (
+                    distances.get_pixel(x, y)[0],
+                    distances.get_pixel(x + 1, y)[0],
+                );
+                if (left < 0.0) != (right < 0.0) {
+                    let (dx, dy) = (x as f32 + 0.5 - 25.0, y as f32 - 25.0);
+                    let r = (dx * dx + dy * dy).sqrt();
+                    assert!((r - radius as f32).abs() <= 1.0, "({}, {}): {}", x, y, r);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_distance_transform_without_background() {
+        let image = GrayImage::from_pixel(3, 3, Luma([1]));
+        let distances = signed_distance_transform(&image);
+        assert!(distances.iter().all(|d| *d == f32::NEG_INFINITY));
+    }
+
+    #[test]
+    fn test_feature_transform_of_two_seeds_splits_at_perpendicular_bisector() {
+        let (width, height) = (40, 30);
+        let seeds = [(8u32, 5u32), (29u32, 22u32)];
+        let mut image = GrayImage::new(width, height);
+        for &(x, y) in &seeds {
+            image.put_pixel(x, y, Luma([255]));
+        }
+        let labels = feature_transform(&image);
+
+        let index = |(x, y): (u32, u32)| y * width + x;
+        let squared_distance = |(sx, sy): (u32, u32), x: u32, y: u32| {
+            (sx as i64 - x as i64).pow(2) + (sy as i64 - y as i64).pow(2)
+        };
+        for (x, y, p) in labels.enumerate_pixels() {
+            let d0 = squared_distance(seeds[0], x, y);
+            let d1 = squared_distance(seeds[1], x, y);
+            if d0 < d1 {
+                assert_eq!(p[0], index(seeds[0]), "({}, {})", x, y);
+            } else if d1 < d0 {
+                assert_eq!(p[0], index(seeds[1]), "({}, {})", x, y);
+            } else {
+                assert!(p[0] == index(seeds[0]) || p[0] == index(seeds[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_feature_transform_of_empty_image() {
+        let labels = feature_transform(&GrayImage::new(4, 3));
+        assert!(labels.iter().all(|&l| l == u32::MAX));
+    }
+
+    #[cfg_attr(miri, ignore = "slow")]
+    #[test]
+    fn test_feature_transform_matches_reference_distances() {
+        fn prop(image: GrayTestImage) -> TestResult {
+            let image = image.0;
+            let width = image.width();
+            let expected = euclidean_squared_distance_transform_reference(&image);
+            let labels = feature_transform(&image);
+            for (x, y, p) in labels.enumerate_pixels() {
+                let expected = expected.get_pixel(x, y)[0];
+                if expected.is_infinite() {
+                    if p[0] != u32::MAX {
+                        return TestResult::error(format!("({}, {}): {}", x, y, p[0]));
+                    }
+                    continue;
+                }
+                let (fx, fy) = (p[0] % width, p[0] / width);
+                let actual = (fx as f64 - x as f64).powi(2) + (fy as f64 - y as f64).powi(2);
+                if image.get_pixel(fx, fy)[0] == 0 || actual != expected {
+                    return TestResult::error(format!("({}, {}): ({}, {})", x, y, fx, fy));
+                }
+            }
+            TestResult::passed()
+        }
+        quickcheck(prop as fn(GrayTestImage) -> TestResult);
+    }
+
     #[test]
     fn test_euclidean_squared_distance_transform_example() {
         let image = gray_image!(
@@ -647,6 +967,15 @@ mod benches {
     bench_euclidean_squared_distance_transform!(bench_euclidean_squared_distance_transform_100, side: 100);
     bench_euclidean_squared_distance_transform!(bench_euclidean_squared_distance_transform_200, side: 200);
 
+    #[bench]
+    fn bench_euclidean_distance_transform_200(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let distance = euclidean_distance_transform(&image);
+            black_box(distance);
+        })
+    }
+
     macro_rules! bench_distance_transform {
         ($name:ident, $norm:expr, side: $s:expr) => {
             #[bench]
@@ -0,0 +1,255 @@
+use crate::definitions::Image;
+use crate::drawing::line::draw_line_segment_mut;
+use crate::drawing::Canvas;
+use image::{GenericImage, Luma};
+
+/// Draws an arrow from `start` to `end` on an image.
+///
+/// The shaft is drawn as a line segment, and the head as two line segments of length
+/// `head_length` starting at `end`, each making an angle of `head_angle` radians with
+/// the shaft. If `start` and `end` are equal only a single point is drawn.
+///
+/// Endpoints are rounded to the nearest pixel. Draws as much of the arrow as lies inside
+/// the image bounds.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_arrow;
+///
+/// let image = GrayImage::new(12, 12);
+/// let arrow = draw_arrow(&image, (1.0, 6.0), (10.0, 6.0), Luma([255]), 3.0, std::f32::consts::FRAC_PI_4);
+///
+/// // The head ends about 2.1 pixels behind and to either side of the tip.
+/// assert_eq!(arrow.get_pixel(10, 6), &Luma([255]));
+/// assert_eq!(arrow.get_pixel(8, 4), &Luma([255]));
+/// assert_eq!(arrow.get_pixel(8, 8), &Luma([255]));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn draw_arrow<I>(
+    image: &I,
+    start: (f32, f32),
+    end: (f32, f32),
+    color: I::Pixel,
+    head_length: f32,
+    head_angle: f32,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_arrow_mut(&mut out, start, end, color, head_length, head_angle);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_arrow")]
+pub fn draw_arrow_mut<C>(
+    canvas: &mut C,
+    start: (f32, f32),
+    end: (f32, f32),
+    color: C::Pixel,
+    head_length: f32,
+    head_angle: f32,
+) where
+    C: Canvas,
+{
+    let round = |p: (f32, f32)| (p.0.round(), p.1.round());
+    draw_line_segment_mut(canvas, round(start), round(end), color);
+    if let Some(barbs) = arrow_head(start, end, head_length, head_angle) {
+        for barb in barbs {
+            draw_line_segment_mut(canvas, round(end), round(barb), color);
+        }
+    }
+}
+
+// The endpoints of the two segments forming the head of an arrow, or None if the arrow
+// has no direction.
+fn arrow_head(
+    start: (f32, f32),
+    end: (f32, f32),
+    head_length: f32,
+    head_angle: f32,
+) -> Option<[(f32, f32); 2]> {
+    let (dx, dy) = (start.0 - end.0, start.1 - end.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return None;
+    }
+    let (ux, uy) = (dx / length, dy / length);
+    let barb = |angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        (
+            end.0 + head_length * (ux * cos - uy * sin),
+            end.1 + head_length * (ux * sin + uy * cos),
+        )
+    };
+    Some([barb(head_angle), barb(-head_angle)])
+}
+
+/// Draws a grid of arrows visualising a vector field, such as the output of an optical
+/// flow computation or the gradients of an image.
+///
+/// The field is given by its horizontal and vertical components `flow_x` and `flow_y`.
+/// Arrows are drawn every `step` pixels in each direction, starting `step / 2` pixels from
+/// the top left corner, from each sampled pixel `(x, y)` to
+/// `(x + scale * flow_x(x, y), y + scale * flow_y(x, y))`. Each arrow head is a third of
+/// the arrow's length, at an angle of 30 degrees to its shaft. Vectors of length zero are
+/// drawn as single points, so that the sampling grid is visible.
+///
+/// # Panics
+///
+/// If `flow_x` and `flow_y` have different dimensions, or `step` is 0.
+#[must_use = "the function does not modify the original image"]
+pub fn draw_flow_field<I>(
+    image: &I,
+    flow_x: &Image<Luma<f32>>,
+    flow_y: &Image<Luma<f32>>,
+    step: u32,
+    scale: f32,
+    color: I::Pixel,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_flow_field_mut(&mut out, flow_x, flow_y, step, scale, color);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_flow_field")]
+pub fn draw_flow_field_mut<C>(
+    canvas: &mut C,
+    flow_x: &Image<Luma<f32>>,
+    flow_y: &Image<Luma<f32>>,
+    step: u32,
+    scale: f32,
+    color: C::Pixel,
+) where
+    C: Canvas,
+{
+    assert_eq!(
+        flow_x.dimensions(),
+        flow_y.dimensions(),
+        "flow components must have the same dimensions"
+    );
+    assert!(step > 0, "step must be > 0");
+
+    let (width, height) = flow_x.dimensions();
+    for y in (step / 2..height).step_by(step as usize) {
+        for x in (step / 2..width).step_by(step as usize) {
+            let dx = scale * flow_x.get_pixel(x, y)[0];
+            let dy = scale * flow_y.get_pixel(x, y)[0];
+            let start = (x as f32, y as f32);
+            let end = (start.0 + dx, start.1 + dy);
+            let head_length = (dx * dx + dy * dy).sqrt() / 3.0;
+            draw_arrow_mut(
+                canvas,
+                start,
+                end,
+                color,
+                head_length,
+                std::f32::consts::FRAC_PI_6,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::draw_line_segment;
+    use image::GrayImage;
+
+    fn drawn_pixels(image: &GrayImage) -> Vec<(u32, u32)> {
+        image
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p[0] > 0)
+            .map(|(x, y, _)| (x, y))
+            .collect()
+    }
+
+    #[test]
+    fn test_zero_length_arrow_draws_a_point() {
+        let image = GrayImage::new(10, 10);
+        let arrow = draw_arrow(&image, (4.0, 6.0), (4.0, 6.0), Luma([255]), 3.0, 0.5);
+        assert_eq!(drawn_pixels(&arrow), vec![(4, 6)]);
+    }
+
+    #[test]
+    fn test_arrow_head_geometry() {
+        let (start, end) = ((3.0, 4.0), (20.0, 15.0));
+        for &angle in &[0.2f32, 0.5, 1.0] {
+            let barbs = arrow_head(start, end, 5.0, angle).unwrap();
+            let shaft_direction = (start.1 - end.1).atan2(start.0 - end.0);
+            let mut offsets = Vec::new();
+            for barb in barbs {
+                let (dx, dy) = (barb.0 - end.0, barb.1 - end.1);
+                assert!(((dx * dx + dy * dy).sqrt() - 5.0).abs() < 1e-4);
+                let mut offset = dy.atan2(dx) - shaft_direction;
+                if offset > std::f32::consts::PI {
+                    offset -= 2.0 * std::f32::consts::PI;
+                } else if offset < -std::f32::consts::PI {
+                    offset += 2.0 * std::f32::consts::PI;
+                }
+                offsets.push(offset);
+            }
+            assert!((offsets[0] - angle).abs() < 1e-4, "{:?}", offsets);
+            assert!((offsets[1] + angle).abs() < 1e-4, "{:?}", offsets);
+        }
+        assert!(arrow_head(start, start, 5.0, 0.5).is_none());
+    }
+
+    #[test]
+    fn test_arrow_draws_shaft_and_head() {
+        let image = GrayImage::new(20, 20);
+        let white = Luma([255u8]);
+        let arrow = draw_arrow(
+            &image,
+            (2.0, 10.0),
+            (15.0, 10.0),
+            white,
+            4.0,
+            std::f32::consts::FRAC_PI_2,
+        );
+        let mut expected = draw_line_segment(&image, (2.0, 10.0), (15.0, 10.0), white);
+        draw_line_segment_mut(&mut expected, (15.0, 6.0), (15.0, 14.0), white);
+        assert_pixels_eq!(arrow, expected);
+    }
+
+    #[test]
+    fn test_draw_flow_field_samples_grid() {
+        let image = GrayImage::new(20, 20);
+        let flow_x = Image::from_pixel(20, 20, Luma([0.0f32]));
+        let flow_y = Image::from_pixel(20, 20, Luma([0.0f32]));
+        let field = draw_flow_field(&image, &flow_x, &flow_y, 5, 1.0, Luma([255]));
+        let expected: Vec<(u32, u32)> = [2, 7, 12, 17]
+            .iter()
+            .flat_map(|&y| [2, 7, 12, 17].iter().map(move |&x| (x, y)))
+            .collect();
+        let mut drawn = drawn_pixels(&field);
+        drawn.sort_by_key(|&(x, y)| (y, x));
+        assert_eq!(drawn, expected);
+    }
+
+    #[test]
+    fn test_draw_flow_field_scales_vectors() {
+        let image = GrayImage::new(30, 10);
+        let flow_x = Image::from_pixel(30, 10, Luma([2.0f32]));
+        let flow_y = Image::from_pixel(30, 10, Luma([0.0f32]));
+        let field = draw_flow_field(&image, &flow_x, &flow_y, 10, 3.0, Luma([255]));
+        // Arrows start at x = 5, 15 and 25 and extend 6 pixels to the right.
+        for &x in &[5, 11, 15, 21, 25, 29] {
+            assert_eq!(field.get_pixel(x, 5)[0], 255, "x = {}", x);
+        }
+        assert_eq!(field.get_pixel(4, 5)[0], 0);
+        assert_eq!(field.get_pixel(12, 5)[0], 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_draw_flow_field_rejects_mismatched_components() {
+        let flow_x = Image::new(5, 5);
+        let flow_y = Image::new(5, 6);
+        let _ = draw_flow_field(&GrayImage::new(5, 5), &flow_x, &flow_y, 2, 1.0, Luma([1u8]));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::definitions::Image;
 use crate::drawing::line::draw_line_segment_mut;
+use crate::drawing::stroke::{draw_thick_polyline_mut, LineCap, LineJoin};
 use crate::drawing::Canvas;
 use image::GenericImage;
 
@@ -78,6 +79,311 @@ pub fn draw_cubic_bezier_curve_mut<C>(
     }
 }
 
+/// Draws a cubic Bézier curve of the given `thickness` on an image.
+///
+/// Unlike [`draw_cubic_bezier_curve`], which samples the curve at evenly spaced parameter
+/// values, the curve is recursively subdivided until each piece is within a quarter of a
+/// pixel of a straight line, so sharp bends are followed closely while flat sections are
+/// drawn with few segments. A curve whose control points lie on the segment between its
+/// endpoints is therefore drawn exactly as that line segment.
+///
+/// The resulting line segments are drawn as by
+/// [`draw_thick_polyline`](crate::drawing::draw_thick_polyline), with round caps and joins.
+///
+/// Draws as much of the curve as lies within image bounds.
+///
+/// # Panics
+///
+/// If `thickness` is not positive.
+#[must_use = "the function does not modify the original image"]
+pub fn draw_thick_cubic_bezier_curve<I>(
+    image: &I,
+    start: (f32, f32),
+    end: (f32, f32),
+    control_a: (f32, f32),
+    control_b: (f32, f32),
+    thickness: f32,
+    color: I::Pixel,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_thick_cubic_bezier_curve_mut(&mut out, start, end, control_a, control_b, thickness, color);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_thick_cubic_bezier_curve")]
+pub fn draw_thick_cubic_bezier_curve_mut<C>(
+    canvas: &mut C,
+    start: (f32, f32),
+    end: (f32, f32),
+    control_a: (f32, f32),
+    control_b: (f32, f32),
+    thickness: f32,
+    color: C::Pixel,
+) where
+    C: Canvas,
+{
+    let mut points = vec![start];
+    flatten_cubic_bezier([start, control_a, control_b, end], 0, &mut points);
+    draw_thick_polyline_mut(
+        canvas,
+        &points,
+        color,
+        thickness,
+        LineCap::Round,
+        LineJoin::Round,
+    );
+}
+
+/// Draws a Catmull-Rom spline of the given `thickness` through `points` on an image.
+///
+/// The spline passes through every point, and its tangent at each interior point is
+/// parallel to the line joining the neighbouring points. The first and last points are
+/// repeated to define the tangents at the ends. Each piece of the spline is converted to
+/// a cubic Bézier curve and drawn as by [`draw_thick_cubic_bezier_curve`].
+///
+/// Draws as much of the spline as lies within image bounds.
+///
+/// # Panics
+///
+/// If `thickness` is not positive.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_catmull_rom_spline;
+///
+/// let points = [(2.0, 10.0), (10.0, 3.0), (18.0, 10.0), (26.0, 3.0)];
+/// let image = draw_catmull_rom_spline(&GrayImage::new(30, 15), &points, 1.0, Luma([255]));
+///
+/// for (x, y) in points {
+///     assert_eq!(image.get_pixel(x as u32, y as u32), &Luma([255]));
+/// }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn draw_catmull_rom_spline<I>(
+    image: &I,
+    points: &[(f32, f32)],
+    thickness: f32,
+    color: I::Pixel,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_catmull_rom_spline_mut(&mut out, points, thickness, color);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_catmull_rom_spline")]
+pub fn draw_catmull_rom_spline_mut<C>(
+    canvas: &mut C,
+    points: &[(f32, f32)],
+    thickness: f32,
+    color: C::Pixel,
+) where
+    C: Canvas,
+{
+    assert!(thickness > 0.0, "thickness must be positive");
+    if points.is_empty() {
+        return;
+    }
+
+    let at = |i: isize| points[i.clamp(0, points.len() as isize - 1) as usize];
+    let mut flattened = vec![points[0]];
+    for i in 0..points.len() as isize - 1 {
+        let (previous, start, end, next) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+        let control_a = (
+            start.0 + (end.0 - previous.0) / 6.0,
+            start.1 + (end.1 - previous.1) / 6.0,
+        );
+        let control_b = (
+            end.0 - (next.0 - start.0) / 6.0,
+            end.1 - (next.1 - start.1) / 6.0,
+        );
+        flatten_cubic_bezier([start, control_a, control_b, end], 0, &mut flattened);
+    }
+    draw_thick_polyline_mut(
+        canvas,
+        &flattened,
+        color,
+        thickness,
+        LineCap::Round,
+        LineJoin::Round,
+    );
+}
+
+// Appends to `points` the end points of a sequence of line segments approximating the given
+// cubic Bézier curve, excluding its start, by recursive subdivision at t = 0.5.
+fn flatten_cubic_bezier(curve: [(f32, f32); 4], depth: u32, points: &mut Vec<(f32, f32)>) {
+    const TOLERANCE: f32 = 0.25;
+    const MAX_DEPTH: u32 = 16;
+
+    let [p0, p1, p2, p3] = curve;
+    let is_flat = distance_to_segment(p1, p0, p3).max(distance_to_segment(p2, p0, p3)) <= TOLERANCE;
+    if is_flat || depth >= MAX_DEPTH {
+        points.push(p3);
+        return;
+    }
+
+    let mid = |a: (f32, f32), b: (f32, f32)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let (p01, p12, p23) = (mid(p0, p1), mid(p1, p2), mid(p2, p3));
+    let (p012, p123) = (mid(p01, p12), mid(p12, p23));
+    let split = mid(p012, p123);
+    flatten_cubic_bezier([p0, p01, p012, split], depth + 1, points);
+    flatten_cubic_bezier([split, p123, p23, p3], depth + 1, points);
+}
+
+fn distance_to_segment(p: (f32, f32), start: (f32, f32), end: (f32, f32)) -> f32 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared > 0.0 {
+        (((p.0 - start.0) * dx + (p.1 - start.1) * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    ((p.0 - start.0 - t * dx).powi(2) + (p.1 - start.1 - t * dy).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::draw_thick_line_segment;
+    use image::{GrayImage, Luma};
+
+    #[test]
+    fn test_collinear_bezier_matches_line_segment() {
+        let image = GrayImage::new(40, 30);
+        let white = Luma([255u8]);
+        let (start, end) = ((3.0, 4.0), (35.0, 22.0));
+        for (a, b) in [(0.2, 0.9), (0.5, 0.5), (0.8, 0.1)] {
+            let control_a = (
+                start.0 + a * (end.0 - start.0),
+                start.1 + a * (end.1 - start.1),
+            );
+            let control_b = (
+                start.0 + b * (end.0 - start.0),
+                start.1 + b * (end.1 - start.1),
+            );
+            for thickness in [1.0, 2.5] {
+                let curve = draw_thick_cubic_bezier_curve(
+                    &image, start, end, control_a, control_b, thickness, white,
+                );
+                let line =
+                    draw_thick_line_segment(&image, start, end, white, thickness, LineCap::Round);
+                assert_pixels_eq!(curve, line);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bezier_passes_through_endpoints() {
+        let image = GrayImage::new(60, 60);
+        for thickness in [1.0, 2.0, 5.0] {
+            let curve = draw_thick_cubic_bezier_curve(
+                &image,
+                (5.0, 50.0),
+                (52.0, 45.0),
+                (10.0, -20.0),
+                (70.0, 10.0),
+                thickness,
+                Luma([255]),
+            );
+            assert_eq!(curve.get_pixel(5, 50)[0], 255);
+            assert_eq!(curve.get_pixel(52, 45)[0], 255);
+            assert_eq!(curve.get_pixel(30, 30)[0], 0);
+        }
+    }
+
+    #[test]
+    fn test_bezier_is_connected() {
+        let image = GrayImage::new(60, 60);
+        let curve = draw_thick_cubic_bezier_curve(
+            &image,
+            (5.0, 50.0),
+            (52.0, 45.0),
+            (10.0, -20.0),
+            (70.0, 10.0),
+            1.0,
+            Luma([255]),
+        );
+        // Each column crossed by the curve contains at least one of its pixels.
+        for x in 5..53 {
+            assert!(
+                (0..60).any(|y| curve.get_pixel(x, y)[0] == 255),
+                "gap at x = {}",
+                x
+            );
+        }
+    }
+
+    #[test]
+    fn test_thickness_widens_curve() {
+        let image = GrayImage::new(40, 40);
+        let column_extent = |thickness: f32| {
+            let curve = draw_thick_cubic_bezier_curve(
+                &image,
+                (5.0, 20.0),
+                (35.0, 20.0),
+                (15.0, 20.0),
+                (25.0, 20.0),
+                thickness,
+                Luma([255]),
+            );
+            (0..40)
+                .filter(|&y| curve.get_pixel(20, y)[0] == 255)
+                .count()
+        };
+        assert_eq!(column_extent(1.0), 1);
+        assert_eq!(column_extent(3.0), 3);
+        assert_eq!(column_extent(6.0), 6);
+        assert_eq!(column_extent(4.5), 5);
+    }
+
+    #[test]
+    fn test_catmull_rom_spline_passes_through_points() {
+        let points = [
+            (5.0, 30.0),
+            (15.0, 8.0),
+            (30.0, 25.0),
+            (45.0, 5.0),
+            (55.0, 30.0),
+        ];
+        for thickness in [1.0, 3.0] {
+            let spline =
+                draw_catmull_rom_spline(&GrayImage::new(60, 40), &points, thickness, Luma([255]));
+            for &(x, y) in &points {
+                assert_eq!(spline.get_pixel(x as u32, y as u32)[0], 255);
+            }
+        }
+    }
+
+    #[test]
+    fn test_catmull_rom_spline_with_few_points() {
+        let image = GrayImage::new(10, 10);
+        assert_pixels_eq!(
+            draw_catmull_rom_spline(&image, &[], 1.0, Luma([255])),
+            image
+        );
+        let dot = draw_catmull_rom_spline(&image, &[(4.0, 6.0)], 1.0, Luma([255]));
+        assert_eq!(dot.iter().filter(|&&p| p == 255).count(), 1);
+        assert_eq!(dot.get_pixel(4, 6)[0], 255);
+
+        let line = draw_catmull_rom_spline(&image, &[(1.0, 1.0), (8.0, 5.0)], 1.0, Luma([255]));
+        let expected = draw_thick_line_segment(
+            &image,
+            (1.0, 1.0),
+            (8.0, 5.0),
+            Luma([255]),
+            1.0,
+            LineCap::Round,
+        );
+        assert_pixels_eq!(line, expected);
+    }
+}
+
 #[cfg(not(miri))]
 #[cfg(test)]
 mod benches {
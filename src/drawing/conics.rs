@@ -3,6 +3,7 @@ use crate::drawing::draw_if_in_bounds;
 use crate::drawing::line::draw_line_segment_mut;
 use crate::drawing::Canvas;
 use image::GenericImage;
+use std::f32::consts::PI;
 
 /// Draws the outline of an ellipse on an image.
 ///
@@ -14,6 +15,8 @@ use image::GenericImage;
 /// The ellipse is axis-aligned and satisfies the following equation:
 ///
 /// (`x^2 / width_radius^2) + (y^2 / height_radius^2) = 1`
+///
+/// See [`draw_hollow_rotated_ellipse`] for ellipses which need not be axis-aligned.
 #[must_use = "the function does not modify the original image"]
 pub fn draw_hollow_ellipse<I>(
     image: &I,
@@ -66,6 +69,8 @@ pub fn draw_hollow_ellipse_mut<C>(
 /// The ellipse is axis-aligned and satisfies the following equation:
 ///
 /// `(x^2 / width_radius^2) + (y^2 / height_radius^2) <= 1`
+///
+/// See [`draw_filled_rotated_ellipse`] for ellipses which need not be axis-aligned.
 #[must_use = "the function does not modify the original image"]
 pub fn draw_filled_ellipse<I>(
     image: &I,
@@ -280,6 +285,245 @@ where
     }
 }
 
+/// Draws a rotated ellipse and its contents on an image.
+///
+/// The ellipse has semi-axes of length `radii.0` and `radii.1`, where the first semi-axis
+/// makes an angle of `angle` radians with the positive x-axis, measured clockwise in image
+/// coordinates (i.e. with the y-axis pointing down). Pixels whose centres lie inside or on the
+/// ellipse are drawn.
+///
+/// Draws as much of the ellipse and its contents as lies inside the image bounds.
+///
+/// # Panics
+///
+/// If either radius is not positive.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_filled_rotated_ellipse;
+///
+/// let image = GrayImage::new(21, 21);
+/// let quarter_turn = std::f32::consts::FRAC_PI_2;
+/// let ellipse = draw_filled_rotated_ellipse(&image, (10.0, 10.0), (8.4, 3.4), quarter_turn, Luma([255]));
+///
+/// // The first semi-axis now points down the image
+/// assert_eq!(ellipse.get_pixel(10, 18), &Luma([255]));
+/// assert_eq!(ellipse.get_pixel(18, 10), &Luma([0]));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn draw_filled_rotated_ellipse<I>(
+    image: &I,
+    center: (f32, f32),
+    radii: (f32, f32),
+    angle: f32,
+    color: I::Pixel,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_filled_rotated_ellipse_mut(&mut out, center, radii, angle, color);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_filled_rotated_ellipse")]
+pub fn draw_filled_rotated_ellipse_mut<C>(
+    canvas: &mut C,
+    center: (f32, f32),
+    radii: (f32, f32),
+    angle: f32,
+    color: C::Pixel,
+) where
+    C: Canvas,
+{
+    let ellipse = RotatedEllipse::new(center, radii, angle);
+    let (width, height) = (canvas.width(), canvas.height());
+    for (x, y) in ellipse.bounding_pixels(width, height, 0.0) {
+        if ellipse.normalized_distance(x as f32, y as f32) <= 1.0 {
+            canvas.draw_pixel(x, y, color);
+        }
+    }
+}
+
+/// Draws the outline of a rotated ellipse on an image.
+///
+/// The ellipse is specified as for [`draw_filled_rotated_ellipse`]. The outline is
+/// approximated by line segments between points on the ellipse at most one pixel apart.
+///
+/// Draws as much of the ellipse as lies inside the image bounds.
+///
+/// # Panics
+///
+/// If either radius is not positive.
+#[must_use = "the function does not modify the original image"]
+pub fn draw_hollow_rotated_ellipse<I>(
+    image: &I,
+    center: (f32, f32),
+    radii: (f32, f32),
+    angle: f32,
+    color: I::Pixel,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_hollow_rotated_ellipse_mut(&mut out, center, radii, angle, color);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_hollow_rotated_ellipse")]
+pub fn draw_hollow_rotated_ellipse_mut<C>(
+    canvas: &mut C,
+    center: (f32, f32),
+    radii: (f32, f32),
+    angle: f32,
+    color: C::Pixel,
+) where
+    C: Canvas,
+{
+    let ellipse = RotatedEllipse::new(center, radii, angle);
+    let num_points = ((2.0 * PI * radii.0.max(radii.1)).ceil() as usize).max(16);
+    let round = |(x, y): (f32, f32)| (x.round(), y.round());
+
+    let mut previous = round(ellipse.point_at(0.0));
+    for i in 1..=num_points {
+        let next = round(ellipse.point_at(2.0 * PI * i as f32 / num_points as f32));
+        draw_line_segment_mut(canvas, previous, next, color);
+        previous = next;
+    }
+}
+
+/// Draws an antialiased outline of a rotated ellipse on an image.
+///
+/// The ellipse is specified as for [`draw_filled_rotated_ellipse`]. Each pixel within one
+/// pixel of the ellipse is blended with `color`, with weight decreasing linearly from one on
+/// the ellipse to zero at a distance of one pixel.
+///
+/// The parameters of blend are (line color, original color, line weight).
+/// Consider using [`interpolate`](crate::pixelops::interpolate) for blend.
+///
+/// # Panics
+///
+/// If either radius is not positive.
+#[must_use = "the function does not modify the original image"]
+pub fn draw_hollow_rotated_ellipse_antialiased<I, B>(
+    image: &I,
+    center: (f32, f32),
+    radii: (f32, f32),
+    angle: f32,
+    color: I::Pixel,
+    blend: B,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+    B: Fn(I::Pixel, I::Pixel, f32) -> I::Pixel,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_hollow_rotated_ellipse_antialiased_mut(&mut out, center, radii, angle, color, blend);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_hollow_rotated_ellipse_antialiased")]
+pub fn draw_hollow_rotated_ellipse_antialiased_mut<I, B>(
+    image: &mut I,
+    center: (f32, f32),
+    radii: (f32, f32),
+    angle: f32,
+    color: I::Pixel,
+    blend: B,
+) where
+    I: GenericImage,
+    B: Fn(I::Pixel, I::Pixel, f32) -> I::Pixel,
+{
+    let ellipse = RotatedEllipse::new(center, radii, angle);
+    let (width, height) = image.dimensions();
+    for (x, y) in ellipse.bounding_pixels(width, height, 1.0) {
+        let weight = 1.0 - ellipse.approximate_distance(x as f32, y as f32);
+        if weight > 0.0 {
+            let original = image.get_pixel(x, y);
+            image.put_pixel(x, y, blend(color, original, weight.min(1.0)));
+        }
+    }
+}
+
+// An ellipse with semi-axes radii.0 and radii.1, the first at the given angle to the x-axis.
+struct RotatedEllipse {
+    center: (f32, f32),
+    radii: (f32, f32),
+    sin: f32,
+    cos: f32,
+}
+
+impl RotatedEllipse {
+    fn new(center: (f32, f32), radii: (f32, f32), angle: f32) -> Self {
+        assert!(
+            radii.0 > 0.0 && radii.1 > 0.0,
+            "ellipse radii must be positive"
+        );
+        let (sin, cos) = angle.sin_cos();
+        RotatedEllipse {
+            center,
+            radii,
+            sin,
+            cos,
+        }
+    }
+
+    // Coordinates of (x, y) along the ellipse's axes, relative to its centre.
+    fn to_ellipse_axes(&self, x: f32, y: f32) -> (f32, f32) {
+        let (dx, dy) = (x - self.center.0, y - self.center.1);
+        (dx * self.cos + dy * self.sin, dy * self.cos - dx * self.sin)
+    }
+
+    // Equal to 1 on the ellipse, less than 1 inside it and greater than 1 outside.
+    fn normalized_distance(&self, x: f32, y: f32) -> f32 {
+        let (u, v) = self.to_ellipse_axes(x, y);
+        (u / self.radii.0).powi(2) + (v / self.radii.1).powi(2)
+    }
+
+    // First order approximation to the distance from (x, y) to the ellipse.
+    fn approximate_distance(&self, x: f32, y: f32) -> f32 {
+        let (u, v) = self.to_ellipse_axes(x, y);
+        let (a2, b2) = (self.radii.0 * self.radii.0, self.radii.1 * self.radii.1);
+        let value = u * u / a2 + v * v / b2 - 1.0;
+        let gradient = 2.0 * ((u / a2).powi(2) + (v / b2).powi(2)).sqrt();
+        if gradient > 0.0 {
+            value.abs() / gradient
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    fn point_at(&self, t: f32) -> (f32, f32) {
+        let (u, v) = (self.radii.0 * t.cos(), self.radii.1 * t.sin());
+        (
+            self.center.0 + u * self.cos - v * self.sin,
+            self.center.1 + u * self.sin + v * self.cos,
+        )
+    }
+
+    // All pixels in the image within margin of the ellipse's axis-aligned bounding box.
+    fn bounding_pixels(
+        &self,
+        width: u32,
+        height: u32,
+        margin: f32,
+    ) -> impl Iterator<Item = (u32, u32)> {
+        let (a, b) = self.radii;
+        let half_width = ((a * self.cos).powi(2) + (b * self.sin).powi(2)).sqrt() + margin;
+        let half_height = ((a * self.sin).powi(2) + (b * self.cos).powi(2)).sqrt() + margin;
+        let clamp_range = |centre: f32, half: f32, len: u32| {
+            let start = (centre - half).floor().max(0.0) as u32;
+            let end = ((centre + half).ceil() + 1.0).clamp(0.0, len as f32) as u32;
+            start..end.max(start)
+        };
+        let xs = clamp_range(self.center.0, half_width, width);
+        let ys = clamp_range(self.center.1, half_height, height);
+        ys.flat_map(move |y| xs.clone().map(move |x| (x, y)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::draw_filled_ellipse_mut;
@@ -351,6 +595,135 @@ mod tests {
         const EPS: f32 = 0.0019;
         check_filled_ellipse(&img, ellipse, inner_color, outer_color, EPS);
     }
+
+    #[test]
+    fn test_filled_rotated_ellipse_at_zero_angle_matches_axis_aligned_ellipse() {
+        use super::draw_filled_rotated_ellipse_mut;
+
+        let color = image::Luma([255u8]);
+        let mut expected = image::GrayImage::new(60, 40);
+        draw_filled_ellipse_mut(&mut expected, (30, 20), 25, 12, color);
+        let mut actual = image::GrayImage::new(60, 40);
+        draw_filled_rotated_ellipse_mut(&mut actual, (30.0, 20.0), (25.0, 12.0), 0.0, color);
+
+        let ellipse = Ellipse {
+            center: (30, 20),
+            width_radius: 25,
+            height_radius: 12,
+        };
+        for (x, y, p) in actual.enumerate_pixels() {
+            if !ellipse.is_boundary_point((x as i32, y as i32), 0.1) {
+                assert_eq!(p, expected.get_pixel(x, y), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotating_ellipse_by_quarter_turn_swaps_radii() {
+        use super::{draw_filled_rotated_ellipse, draw_hollow_rotated_ellipse_antialiased};
+        use crate::pixelops::interpolate;
+        use std::f32::consts::FRAC_PI_2;
+
+        let image = image::GrayImage::new(41, 41);
+        let color = image::Luma([255u8]);
+        let (center, radii) = ((20.0, 20.0), (15.4, 6.6));
+
+        let rotated = draw_filled_rotated_ellipse(&image, center, radii, FRAC_PI_2, color);
+        let swapped = draw_filled_rotated_ellipse(&image, center, (radii.1, radii.0), 0.0, color);
+        assert_pixels_eq!(rotated, swapped);
+        assert_eq!(rotated.get_pixel(20, 35)[0], 255);
+        assert_eq!(rotated.get_pixel(35, 20)[0], 0);
+
+        let rotated = draw_hollow_rotated_ellipse_antialiased(
+            &image,
+            center,
+            radii,
+            FRAC_PI_2,
+            color,
+            interpolate,
+        );
+        let swapped = draw_hollow_rotated_ellipse_antialiased(
+            &image,
+            center,
+            (radii.1, radii.0),
+            0.0,
+            color,
+            interpolate,
+        );
+        for (p, q) in rotated.iter().zip(swapped.iter()) {
+            assert!((*p as i32 - *q as i32).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_rotated_ellipses_are_symmetric_about_center() {
+        use super::{draw_filled_rotated_ellipse, draw_hollow_rotated_ellipse_antialiased};
+        use crate::pixelops::interpolate;
+
+        let image = image::GrayImage::new(40, 40);
+        let color = image::Luma([255u8]);
+        let (center, radii, angle) = ((19.5, 19.5), (16.0, 7.0), 0.6);
+
+        let filled = draw_filled_rotated_ellipse(&image, center, radii, angle, color);
+        let outline = draw_hollow_rotated_ellipse_antialiased(
+            &image,
+            center,
+            radii,
+            angle,
+            color,
+            interpolate,
+        );
+        assert!(filled.iter().any(|&p| p > 0));
+        for y in 0..40 {
+            for x in 0..40 {
+                assert_eq!(filled.get_pixel(x, y), filled.get_pixel(39 - x, 39 - y));
+                let (p, q) = (
+                    outline.get_pixel(x, y)[0],
+                    outline.get_pixel(39 - x, 39 - y)[0],
+                );
+                assert!((p as i32 - q as i32).abs() <= 1, "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotated_ellipse_outlines_lie_on_ellipse() {
+        use super::{draw_hollow_rotated_ellipse, draw_hollow_rotated_ellipse_antialiased};
+        use crate::pixelops::interpolate;
+
+        let image = image::GrayImage::new(50, 50);
+        let color = image::Luma([255u8]);
+        let (center, radii, angle) = ((25.0, 24.0), (20.0, 9.0), -0.4);
+        let (sin, cos) = f32::sin_cos(angle);
+        let normalized_distance = |x: u32, y: u32| {
+            let (dx, dy) = (x as f32 - center.0, y as f32 - center.1);
+            let (u, v) = (dx * cos + dy * sin, dy * cos - dx * sin);
+            ((u / radii.0).powi(2) + (v / radii.1).powi(2)).sqrt()
+        };
+
+        let hollow = draw_hollow_rotated_ellipse(&image, center, radii, angle, color);
+        let antialiased = draw_hollow_rotated_ellipse_antialiased(
+            &image,
+            center,
+            radii,
+            angle,
+            color,
+            interpolate,
+        );
+        assert!(hollow.iter().filter(|&&p| p > 0).count() > 60);
+        for (x, y, p) in hollow.enumerate_pixels() {
+            if p[0] > 0 {
+                assert!((normalized_distance(x, y) - 1.0).abs() < 0.15);
+            }
+        }
+        for (x, y, p) in antialiased.enumerate_pixels() {
+            if p[0] > 0 {
+                assert!((normalized_distance(x, y) - 1.0).abs() < 0.15);
+            }
+        }
+        assert_eq!(antialiased.get_pixel(25, 24)[0], 0);
+        assert!(antialiased.iter().any(|&p| p > 200));
+    }
 }
 
 #[cfg(not(miri))]
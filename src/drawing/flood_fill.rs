@@ -0,0 +1,235 @@
+use crate::definitions::Image;
+use crate::region_labelling::Connectivity;
+use image::{GenericImage, Pixel};
+
+/// Fills the connected region of an image containing `seed` with `color`.
+///
+/// A pixel belongs to the region if it is connected to `seed`, as determined by
+/// `connectivity`, through pixels whose channels all differ from the corresponding
+/// channels of the original color of `seed` by at most `tolerance`. With a `tolerance`
+/// of 0 only pixels with exactly the seed's color are filled.
+///
+/// Uses a scanline algorithm, which fills each horizontal run of pixels at once and keeps
+/// an explicit stack of runs to visit, so large regions do not cause deep recursion.
+///
+/// # Panics
+///
+/// If `seed` is outside the image or `tolerance` is negative.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use image::Luma;
+/// use imageproc::drawing::flood_fill;
+/// use imageproc::region_labelling::Connectivity;
+///
+/// let image = gray_image!(
+///     1, 1, 9, 1;
+///     1, 9, 1, 1;
+///     9, 1, 1, 1);
+///
+/// let four = flood_fill(&image, (0, 0), Luma([5]), 0.0, Connectivity::Four);
+/// assert_pixels_eq!(four, gray_image!(
+///     5, 5, 9, 1;
+///     5, 9, 1, 1;
+///     9, 1, 1, 1));
+///
+/// // With 8-connectivity the fill leaks through the diagonal gaps
+/// let eight = flood_fill(&image, (0, 0), Luma([5]), 0.0, Connectivity::Eight);
+/// assert_pixels_eq!(eight, gray_image!(
+///     5, 5, 9, 5;
+///     5, 9, 5, 5;
+///     9, 5, 5, 5));
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn flood_fill<I>(
+    image: &I,
+    seed: (u32, u32),
+    color: I::Pixel,
+    tolerance: f32,
+    connectivity: Connectivity,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+    <I::Pixel as Pixel>::Subpixel: Into<f32>,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    flood_fill_mut(&mut out, seed, color, tolerance, connectivity);
+    out
+}
+#[doc=generate_mut_doc_comment!("flood_fill")]
+pub fn flood_fill_mut<I>(
+    image: &mut I,
+    seed: (u32, u32),
+    color: I::Pixel,
+    tolerance: f32,
+    connectivity: Connectivity,
+) where
+    I: GenericImage,
+    <I::Pixel as Pixel>::Subpixel: Into<f32>,
+{
+    let (width, height) = image.dimensions();
+    assert!(
+        seed.0 < width && seed.1 < height,
+        "seed {:?} is outside the image of dimensions {:?}",
+        seed,
+        (width, height)
+    );
+    assert!(tolerance >= 0.0, "tolerance must be non-negative");
+
+    let target = image.get_pixel(seed.0, seed.1);
+    let similar = |p: I::Pixel| {
+        p.channels()
+            .iter()
+            .zip(target.channels())
+            .all(|(&a, &b)| (a.into() - b.into()).abs() <= tolerance)
+    };
+
+    // Filled pixels may still be similar to the target, so are tracked separately.
+    let mut filled = vec![false; width as usize * height as usize];
+    let index = |x: u32, y: u32| y as usize * width as usize + x as usize;
+    let mut stack = vec![seed];
+
+    while let Some((x, y)) = stack.pop() {
+        if filled[index(x, y)] || !similar(image.get_pixel(x, y)) {
+            continue;
+        }
+
+        let mut left = x;
+        while left > 0 && !filled[index(left - 1, y)] && similar(image.get_pixel(left - 1, y)) {
+            left -= 1;
+        }
+        let mut right = x;
+        while right + 1 < width
+            && !filled[index(right + 1, y)]
+            && similar(image.get_pixel(right + 1, y))
+        {
+            right += 1;
+        }
+        for fx in left..=right {
+            image.put_pixel(fx, y, color);
+            filled[index(fx, y)] = true;
+        }
+
+        let (from, to) = match connectivity {
+            Connectivity::Four => (left, right),
+            Connectivity::Eight => (left.saturating_sub(1), (right + 1).min(width - 1)),
+        };
+        let neighbouring_rows = [y.checked_sub(1), Some(y + 1).filter(|&ny| ny < height)];
+        for ny in neighbouring_rows.into_iter().flatten() {
+            // Push one seed for each run of fillable pixels in the neighbouring row.
+            let mut in_run = false;
+            for nx in from..=to {
+                let fillable = !filled[index(nx, ny)] && similar(image.get_pixel(nx, ny));
+                if fillable && !in_run {
+                    stack.push((nx, ny));
+                }
+                in_run = fillable;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, Rgb, RgbImage};
+
+    #[test]
+    fn test_flood_fill_quadrant_of_two_color_image() {
+        let image = GrayImage::from_fn(40, 30, |x, y| {
+            Luma([if (x < 20) == (y < 15) { 10 } else { 200 }])
+        });
+        let filled = flood_fill(&image, (25, 3), Luma([77]), 0.0, Connectivity::Four);
+        for (x, y, p) in filled.enumerate_pixels() {
+            let expected = if x >= 20 && y < 15 {
+                77
+            } else {
+                image.get_pixel(x, y)[0]
+            };
+            assert_eq!(p[0], expected, "({}, {})", x, y);
+        }
+
+        // The two quadrants with the seed's color touch only diagonally.
+        let eight = flood_fill(&image, (25, 3), Luma([77]), 0.0, Connectivity::Eight);
+        assert_eq!(eight.iter().filter(|&&p| p == 77).count(), 600);
+        assert_eq!(eight.get_pixel(5, 20)[0], 77);
+    }
+
+    #[test]
+    fn test_flood_fill_tolerance() {
+        let image = GrayImage::from_fn(10, 1, |x, _| Luma([100 + 3 * x as u8]));
+        let count = |tolerance: f32| {
+            flood_fill(&image, (0, 0), Luma([0]), tolerance, Connectivity::Four)
+                .iter()
+                .filter(|&&p| p == 0)
+                .count()
+        };
+        // Tolerance is measured from the seed's color, not between neighbours.
+        assert_eq!(count(0.0), 1);
+        assert_eq!(count(2.9), 1);
+        assert_eq!(count(3.0), 2);
+        assert_eq!(count(10.0), 4);
+        assert_eq!(count(27.0), 10);
+    }
+
+    #[test]
+    fn test_flood_fill_with_zero_tolerance_stops_at_color_boundary() {
+        let mut image = RgbImage::from_pixel(20, 20, Rgb([50, 60, 70]));
+        for i in 0..20 {
+            image.put_pixel(i, 8, Rgb([50, 60, 71]));
+            image.put_pixel(12, i, Rgb([51, 60, 70]));
+        }
+        let red = Rgb([255, 0, 0]);
+        let filled = flood_fill(&image, (3, 3), red, 0.0, Connectivity::Eight);
+        for (x, y, p) in filled.enumerate_pixels() {
+            assert_eq!(*p == red, x < 12 && y < 8, "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_flood_fill_with_similar_color_terminates() {
+        let image = GrayImage::from_pixel(50, 50, Luma([100]));
+        let filled = flood_fill(&image, (10, 10), Luma([101]), 5.0, Connectivity::Eight);
+        assert!(filled.iter().all(|&p| p == 101));
+    }
+
+    #[test]
+    fn test_flood_fill_large_spiral() {
+        // A long winding corridor which would overflow the stack of a recursive fill.
+        let size = 301;
+        let mut image = GrayImage::from_pixel(size, size, Luma([255]));
+        for i in (0..size).step_by(4) {
+            for j in 0..size - 2 {
+                let (x, y) = if (i / 4) % 2 == 0 {
+                    (j, i + 2)
+                } else {
+                    (j + 2, i + 2)
+                };
+                if y < size {
+                    image.put_pixel(x, y, Luma([0]));
+                }
+            }
+        }
+        let filled = flood_fill(&image, (0, 0), Luma([128]), 0.0, Connectivity::Four);
+        let white = image.iter().filter(|&&p| p == 255).count();
+        assert_eq!(filled.iter().filter(|&&p| p == 128).count(), white);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_flood_fill_rejects_seed_outside_image() {
+        let _ = flood_fill(
+            &GrayImage::new(4, 4),
+            (4, 0),
+            Luma([1]),
+            0.0,
+            Connectivity::Four,
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use crate::colorspace::{linear_to_srgb, srgb_to_linear};
+use crate::definitions::Image;
+use crate::drawing::Canvas;
+use image::{GenericImage, Pixel};
+
+/// Fills an image with a linear gradient from `color_start` at `start` to `color_end` at `end`.
+///
+/// Each pixel is coloured according to the position of its projection onto the line through
+/// `start` and `end`. Pixels projecting before `start` take `color_start` and pixels projecting
+/// after `end` take `color_end`.
+///
+/// Color channels are interpolated in linear light, treating subpixel values as sRGB encoded.
+/// Alpha channels are interpolated directly.
+///
+/// # Panics
+///
+/// If `start` and `end` are equal.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_linear_gradient;
+///
+/// let image = GrayImage::new(11, 3);
+/// let gradient = draw_linear_gradient(&image, (0.0, 0.0), (10.0, 0.0), Luma([0]), Luma([255]));
+///
+/// assert_eq!(gradient.get_pixel(0, 2), &Luma([0]));
+/// assert_eq!(gradient.get_pixel(10, 2), &Luma([255]));
+/// // Half of the maximum intensity in linear light
+/// assert_eq!(gradient.get_pixel(5, 1), &Luma([188]));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn draw_linear_gradient<I>(
+    image: &I,
+    start: (f32, f32),
+    end: (f32, f32),
+    color_start: I::Pixel,
+    color_end: I::Pixel,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+    I::Pixel: Pixel<Subpixel = u8>,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_linear_gradient_mut(&mut out, start, end, color_start, color_end);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_linear_gradient")]
+pub fn draw_linear_gradient_mut<C>(
+    canvas: &mut C,
+    start: (f32, f32),
+    end: (f32, f32),
+    color_start: C::Pixel,
+    color_end: C::Pixel,
+) where
+    C: Canvas,
+    C::Pixel: Pixel<Subpixel = u8>,
+{
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    assert!(length_squared > 0.0, "start and end must be distinct");
+
+    let blend = LinearBlend::new(color_start, color_end);
+    for y in 0..canvas.height() {
+        for x in 0..canvas.width() {
+            let t = ((x as f32 - start.0) * dx + (y as f32 - start.1) * dy) / length_squared;
+            canvas.draw_pixel(x, y, blend.at(t));
+        }
+    }
+}
+
+/// Fills an image with a radial gradient from `inner` at `center` to `outer` at distance
+/// `radius` from `center`.
+///
+/// Pixels at a distance of at least `radius` from `center` take `outer`.
+///
+/// Color channels are interpolated in linear light, treating subpixel values as sRGB encoded.
+/// Alpha channels are interpolated directly.
+///
+/// # Panics
+///
+/// If `radius <= 0.0`.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_radial_gradient;
+///
+/// let image = GrayImage::new(21, 21);
+/// let gradient = draw_radial_gradient(&image, (10.0, 10.0), 8.0, Luma([255]), Luma([0]));
+///
+/// assert_eq!(gradient.get_pixel(10, 10), &Luma([255]));
+/// assert_eq!(gradient.get_pixel(10, 2), &Luma([0]));
+/// assert_eq!(gradient.get_pixel(0, 0), &Luma([0]));
+/// assert_eq!(gradient.get_pixel(6, 10), gradient.get_pixel(14, 10));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn draw_radial_gradient<I>(
+    image: &I,
+    center: (f32, f32),
+    radius: f32,
+    inner: I::Pixel,
+    outer: I::Pixel,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+    I::Pixel: Pixel<Subpixel = u8>,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_radial_gradient_mut(&mut out, center, radius, inner, outer);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_radial_gradient")]
+pub fn draw_radial_gradient_mut<C>(
+    canvas: &mut C,
+    center: (f32, f32),
+    radius: f32,
+    inner: C::Pixel,
+    outer: C::Pixel,
+) where
+    C: Canvas,
+    C::Pixel: Pixel<Subpixel = u8>,
+{
+    assert!(radius > 0.0, "radius must be > 0.0");
+
+    let blend = LinearBlend::new(inner, outer);
+    for y in 0..canvas.height() {
+        for x in 0..canvas.width() {
+            let (dx, dy) = (x as f32 - center.0, y as f32 - center.1);
+            let t = (dx * dx + dy * dy).sqrt() / radius;
+            canvas.draw_pixel(x, y, blend.at(t));
+        }
+    }
+}
+
+// Interpolates between two sRGB encoded pixels in linear light.
+struct LinearBlend<P> {
+    start: P,
+    start_linear: Vec<f32>,
+    end_linear: Vec<f32>,
+}
+
+impl<P> LinearBlend<P>
+where
+    P: Pixel<Subpixel = u8>,
+{
+    fn new(start: P, end: P) -> Self {
+        LinearBlend {
+            start,
+            start_linear: Self::decode(&start),
+            end_linear: Self::decode(&end),
+        }
+    }
+
+    fn decode(pixel: &P) -> Vec<f32> {
+        let alpha_index = Self::alpha_index();
+        pixel
+            .channels()
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                let c = c as f32 / 255.0;
+                if Some(i) == alpha_index {
+                    c
+                } else {
+                    srgb_to_linear(c)
+                }
+            })
+            .collect()
+    }
+
+    fn alpha_index() -> Option<usize> {
+        if P::HAS_ALPHA {
+            Some(P::CHANNEL_COUNT as usize - 1)
+        } else {
+            None
+        }
+    }
+
+    // Returns the pixel a fraction t of the way from start to end, with t clamped to [0, 1].
+    fn at(&self, t: f32) -> P {
+        let t = t.clamp(0.0, 1.0);
+        let alpha_index = Self::alpha_index();
+        let mut pixel = self.start;
+        for (i, c) in pixel.channels_mut().iter_mut().enumerate() {
+            let value = self.start_linear[i] + t * (self.end_linear[i] - self.start_linear[i]);
+            let value = if Some(i) == alpha_index {
+                value
+            } else {
+                linear_to_srgb(value)
+            };
+            *c = (255.0 * value).round().clamp(0.0, 255.0) as u8;
+        }
+        pixel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+
+    fn linear_average(a: u8, b: u8) -> f32 {
+        let (a, b) = (
+            srgb_to_linear(a as f32 / 255.0),
+            srgb_to_linear(b as f32 / 255.0),
+        );
+        255.0 * linear_to_srgb((a + b) / 2.0)
+    }
+
+    #[test]
+    fn test_linear_gradient_midpoint_is_average_of_endpoints() {
+        let (start, end) = (Rgb([200u8, 10, 60]), Rgb([20u8, 240, 60]));
+        let image =
+            draw_linear_gradient(&RgbImage::new(21, 5), (0.0, 2.0), (20.0, 2.0), start, end);
+
+        assert_eq!(image.get_pixel(0, 2), &start);
+        assert_eq!(image.get_pixel(20, 2), &end);
+        let mid = image.get_pixel(10, 2);
+        for c in 0..3 {
+            let expected = linear_average(start[c], end[c]);
+            assert!(
+                (mid[c] as f32 - expected).abs() <= 0.5,
+                "channel {}: {} vs {}",
+                c,
+                mid[c],
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_linear_gradient_is_constant_perpendicular_to_axis() {
+        let image = draw_linear_gradient(
+            &GrayImage::new(20, 20),
+            (2.0, 2.0),
+            (17.0, 17.0),
+            Luma([30]),
+            Luma([220]),
+        );
+        for k in 0..20 {
+            assert_eq!(image.get_pixel(k, 19 - k), image.get_pixel(19 - k, k));
+        }
+        assert_eq!(image.get_pixel(0, 0), &Luma([30]));
+        assert_eq!(image.get_pixel(19, 19), &Luma([220]));
+    }
+
+    #[test]
+    fn test_linear_gradient_is_monotonic() {
+        let image = draw_linear_gradient(
+            &GrayImage::new(30, 1),
+            (30.0, 0.0),
+            (0.0, 0.0),
+            Luma([0]),
+            Luma([255]),
+        );
+        let values: Vec<u8> = image.iter().copied().collect();
+        assert!(values.windows(2).all(|w| w[0] >= w[1]), "{:?}", values);
+    }
+
+    #[test]
+    fn test_gradient_interpolates_alpha_directly() {
+        let image = draw_linear_gradient(
+            &RgbaImage::new(11, 1),
+            (0.0, 0.0),
+            (10.0, 0.0),
+            Rgba([255, 255, 255, 0]),
+            Rgba([255, 255, 255, 254]),
+        );
+        assert_eq!(image.get_pixel(5, 0), &Rgba([255, 255, 255, 127]));
+    }
+
+    #[test]
+    fn test_radial_gradient_is_symmetric() {
+        let image = draw_radial_gradient(
+            &RgbImage::new(21, 21),
+            (10.0, 10.0),
+            9.0,
+            Rgb([255, 0, 0]),
+            Rgb([0, 0, 255]),
+        );
+        assert_eq!(image.get_pixel(10, 10), &Rgb([255, 0, 0]));
+        assert_eq!(image.get_pixel(1, 10), &Rgb([0, 0, 255]));
+        for y in 0..21 {
+            for x in 0..21 {
+                assert_eq!(image.get_pixel(x, y), image.get_pixel(20 - x, y));
+                assert_eq!(image.get_pixel(x, y), image.get_pixel(y, x));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_linear_gradient_rejects_degenerate_axis() {
+        let _ = draw_linear_gradient(
+            &GrayImage::new(5, 5),
+            (2.0, 2.0),
+            (2.0, 2.0),
+            Luma([0]),
+            Luma([255]),
+        );
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use image::{Rgb, RgbImage};
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_draw_linear_gradient(b: &mut Bencher) {
+        let mut image = RgbImage::new(200, 200);
+        b.iter(|| {
+            draw_linear_gradient_mut(
+                &mut image,
+                (10.0, 20.0),
+                (180.0, 150.0),
+                Rgb([255, 128, 0]),
+                Rgb([0, 64, 255]),
+            );
+            black_box(&image);
+        });
+    }
+}
@@ -199,6 +199,116 @@ where
     }
 }
 
+/// Draws a dashed or dotted line segment on an image.
+///
+/// `pattern` lists alternating lengths in pixels of drawn and skipped sections, starting
+/// with a drawn section, and is repeated along the segment. For example `&[6.0, 3.0]`
+/// draws dashes of 6 pixels separated by gaps of 3, and `&[1.0, 2.0]` draws a dotted line.
+/// As in SVG, a pattern with an odd number of entries is repeated to give an even number,
+/// so `&[4.0]` alternates dashes and gaps of 4 pixels.
+///
+/// Each pixel of the Bresenham line between `start` and `end` is drawn if its distance
+/// from `start`, offset by `phase`, lies within a drawn section of the pattern. Draws as
+/// much of the line segment as lies inside the image bounds.
+///
+/// See [`draw_dashed_line_segment_mut`] for drawing connected segments with a continuous
+/// pattern.
+///
+/// # Panics
+///
+/// If `pattern` is empty, contains a negative length, or has total length 0.
+#[must_use = "the function does not modify the original image"]
+pub fn draw_dashed_line_segment<I>(
+    image: &I,
+    start: (f32, f32),
+    end: (f32, f32),
+    color: I::Pixel,
+    pattern: &[f32],
+    phase: f32,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_dashed_line_segment_mut(&mut out, start, end, color, pattern, phase);
+    out
+}
+
+/// Draws a dashed or dotted line segment on an image in place, as described in
+/// [`draw_dashed_line_segment`].
+///
+/// Returns the phase at `end`, i.e. `phase` plus the length of the segment, modulo the
+/// length of the pattern. Passing this as the `phase` of a segment starting at `end`
+/// continues the pattern seamlessly across the join.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_dashed_line_segment_mut;
+///
+/// let mut image = GrayImage::new(12, 12);
+/// let pattern = [3.0, 2.0];
+/// let white = Luma([255]);
+///
+/// let phase = draw_dashed_line_segment_mut(&mut image, (0.0, 0.0), (6.0, 0.0), white, &pattern, 0.0);
+/// assert_eq!(phase, 1.0);
+/// draw_dashed_line_segment_mut(&mut image, (6.0, 0.0), (6.0, 11.0), white, &pattern, phase);
+///
+/// let top: Vec<u8> = (0..7).map(|x| image.get_pixel(x, 0)[0]).collect();
+/// assert_eq!(top, [255, 255, 255, 0, 0, 255, 255]);
+/// let right: Vec<u8> = (0..5).map(|y| image.get_pixel(6, y)[0]).collect();
+/// assert_eq!(right, [255, 255, 0, 0, 255]);
+/// ```
+pub fn draw_dashed_line_segment_mut<C>(
+    canvas: &mut C,
+    start: (f32, f32),
+    end: (f32, f32),
+    color: C::Pixel,
+    pattern: &[f32],
+    phase: f32,
+) -> f32
+where
+    C: Canvas,
+{
+    assert!(!pattern.is_empty(), "pattern must not be empty");
+    assert!(
+        pattern.iter().all(|&l| l >= 0.0),
+        "pattern lengths must be non-negative"
+    );
+    let pattern: Vec<f32> = if pattern.len() % 2 == 1 {
+        pattern.iter().chain(pattern).cloned().collect()
+    } else {
+        pattern.to_vec()
+    };
+    let period: f32 = pattern.iter().sum();
+    assert!(period > 0.0, "pattern must have a positive total length");
+
+    let is_drawn = |distance: f32| {
+        let mut offset = (phase + distance).rem_euclid(period);
+        for (i, &length) in pattern.iter().enumerate() {
+            if offset < length {
+                return i % 2 == 0;
+            }
+            offset -= length;
+        }
+        false
+    };
+
+    let (width, height) = canvas.dimensions();
+    let in_bounds = |x, y| x >= 0 && x < width as i32 && y >= 0 && y < height as i32;
+
+    for (x, y) in BresenhamLineIter::new(start, end) {
+        let distance = ((x as f32 - start.0).powi(2) + (y as f32 - start.1).powi(2)).sqrt();
+        if in_bounds(x, y) && is_drawn(distance) {
+            canvas.draw_pixel(x as u32, y as u32, color);
+        }
+    }
+
+    let length = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
+    (phase + length).rem_euclid(period)
+}
+
 /// Draws an antialised line segment on an image.
 ///
 /// Draws as much of the line segment between `start` and `end` as lies inside the image bounds.
@@ -593,6 +703,101 @@ mod tests {
         }
         assert_pixels_eq!(left, expected);
     }
+
+    #[test]
+    fn test_draw_dashed_line_segment_matches_pattern_ratio() {
+        let image = GrayImage::new(100, 3);
+        let white = Luma([255u8]);
+        for (pattern, expected) in [
+            (vec![3.0, 2.0], 60),
+            (vec![1.0, 1.0], 50),
+            (vec![1.0, 3.0], 25),
+            (vec![4.0], 52),
+            (vec![6.0, 1.0, 2.0, 1.0], 80),
+        ] {
+            let dashed =
+                draw_dashed_line_segment(&image, (0.0, 1.0), (99.0, 1.0), white, &pattern, 0.0);
+            let drawn = (0..100)
+                .filter(|&x| dashed.get_pixel(x, 1)[0] == 255)
+                .count();
+            assert_eq!(drawn, expected, "pattern {:?}", pattern);
+            assert!(dashed
+                .enumerate_pixels()
+                .all(|(_, y, p)| y == 1 || p[0] == 0));
+        }
+    }
+
+    #[test]
+    fn test_draw_dashed_line_segment_layout() {
+        let image = GrayImage::new(10, 1);
+        let white = Luma([1u8]);
+        let dashed =
+            draw_dashed_line_segment(&image, (0.0, 0.0), (9.0, 0.0), white, &[3.0, 2.0], 0.0);
+        assert_pixels_eq!(dashed, gray_image!(1, 1, 1, 0, 0, 1, 1, 1, 0, 0));
+
+        // The pattern is measured from start, so reversing the line reverses the pattern.
+        let reversed =
+            draw_dashed_line_segment(&image, (9.0, 0.0), (0.0, 0.0), white, &[3.0, 2.0], 0.0);
+        assert_pixels_eq!(reversed, gray_image!(0, 0, 1, 1, 1, 0, 0, 1, 1, 1));
+
+        let shifted =
+            draw_dashed_line_segment(&image, (0.0, 0.0), (9.0, 0.0), white, &[3.0, 2.0], 2.0);
+        assert_pixels_eq!(shifted, gray_image!(1, 0, 0, 1, 1, 1, 0, 0, 1, 1));
+    }
+
+    #[test]
+    fn test_draw_dashed_line_segment_phase_continues_across_segments() {
+        let white = Luma([1u8]);
+        let pattern = [4.0, 3.0];
+        let joined = draw_dashed_line_segment(
+            &GrayImage::new(20, 1),
+            (0.0, 0.0),
+            (19.0, 0.0),
+            white,
+            &pattern,
+            0.0,
+        );
+
+        let mut pieces = GrayImage::new(20, 1);
+        let mut phase = 0.0;
+        for (from, to) in [(0.0, 5.0), (5.0, 11.0), (11.0, 19.0)] {
+            phase = draw_dashed_line_segment_mut(
+                &mut pieces,
+                (from, 0.0),
+                (to, 0.0),
+                white,
+                &pattern,
+                phase,
+            );
+        }
+        assert_eq!(phase, 19.0 % 7.0);
+        assert_pixels_eq!(pieces, joined);
+    }
+
+    #[test]
+    fn test_draw_dashed_line_segment_with_solid_pattern_matches_line() {
+        let image = GrayImage::new(20, 20);
+        let white = Luma([255u8]);
+        let dashed =
+            draw_dashed_line_segment(&image, (2.0, 3.0), (17.0, 12.0), white, &[5.0, 0.0], 0.0);
+        assert_pixels_eq!(
+            dashed,
+            draw_line_segment(&image, (2.0, 3.0), (17.0, 12.0), white)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_draw_dashed_line_segment_rejects_zero_length_pattern() {
+        let _ = draw_dashed_line_segment(
+            &GrayImage::new(5, 5),
+            (0.0, 0.0),
+            (4.0, 4.0),
+            Luma([1]),
+            &[0.0, 0.0],
+            0.0,
+        );
+    }
 }
 
 #[cfg(not(miri))]
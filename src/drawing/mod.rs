@@ -1,7 +1,13 @@
 //! Helpers for drawing basic shapes on images.
 
+mod arrow;
+pub use self::arrow::{draw_arrow, draw_arrow_mut, draw_flow_field, draw_flow_field_mut};
+
 mod bezier;
-pub use self::bezier::{draw_cubic_bezier_curve, draw_cubic_bezier_curve_mut};
+pub use self::bezier::{
+    draw_catmull_rom_spline, draw_catmull_rom_spline_mut, draw_cubic_bezier_curve,
+    draw_cubic_bezier_curve_mut, draw_thick_cubic_bezier_curve, draw_thick_cubic_bezier_curve_mut,
+};
 
 mod canvas;
 pub use self::canvas::{Blend, Canvas};
@@ -9,31 +15,56 @@ pub use self::canvas::{Blend, Canvas};
 mod conics;
 pub use self::conics::{
     draw_filled_circle, draw_filled_circle_mut, draw_filled_ellipse, draw_filled_ellipse_mut,
-    draw_hollow_circle, draw_hollow_circle_mut, draw_hollow_ellipse, draw_hollow_ellipse_mut,
+    draw_filled_rotated_ellipse, draw_filled_rotated_ellipse_mut, draw_hollow_circle,
+    draw_hollow_circle_mut, draw_hollow_ellipse, draw_hollow_ellipse_mut,
+    draw_hollow_rotated_ellipse, draw_hollow_rotated_ellipse_antialiased,
+    draw_hollow_rotated_ellipse_antialiased_mut, draw_hollow_rotated_ellipse_mut,
 };
 
 mod cross;
 pub use self::cross::{draw_cross, draw_cross_mut};
 
+mod flood_fill;
+pub use self::flood_fill::{flood_fill, flood_fill_mut};
+
+mod gradient;
+pub use self::gradient::{
+    draw_linear_gradient, draw_linear_gradient_mut, draw_radial_gradient, draw_radial_gradient_mut,
+};
+
 mod line;
 pub use self::line::{
-    draw_antialiased_line_segment, draw_antialiased_line_segment_mut, draw_line_segment,
-    draw_line_segment_mut, BresenhamLineIter, BresenhamLinePixelIter, BresenhamLinePixelIterMut,
+    draw_antialiased_line_segment, draw_antialiased_line_segment_mut, draw_dashed_line_segment,
+    draw_dashed_line_segment_mut, draw_line_segment, draw_line_segment_mut, BresenhamLineIter,
+    BresenhamLinePixelIter, BresenhamLinePixelIterMut,
 };
 
+mod overlay;
+pub use self::overlay::{overlay_mut, BlendMode};
+
 mod polygon;
 pub use self::polygon::{
     draw_antialiased_polygon, draw_antialiased_polygon_mut, draw_hollow_polygon,
-    draw_hollow_polygon_mut, draw_polygon, draw_polygon_mut,
+    draw_hollow_polygon_antialiased, draw_hollow_polygon_antialiased_mut, draw_hollow_polygon_mut,
+    draw_polygon, draw_polygon_antialiased, draw_polygon_antialiased_mut, draw_polygon_mut,
 };
 
 mod rect;
 pub use self::rect::{
-    draw_filled_rect, draw_filled_rect_mut, draw_hollow_rect, draw_hollow_rect_mut,
+    draw_filled_rect, draw_filled_rect_mut, draw_filled_rotated_rect, draw_filled_rotated_rect_mut,
+    draw_hollow_rect, draw_hollow_rect_mut, draw_hollow_rotated_rect, draw_hollow_rotated_rect_mut,
+};
+
+mod stroke;
+pub use self::stroke::{
+    draw_thick_line_segment, draw_thick_line_segment_mut, draw_thick_polyline,
+    draw_thick_polyline_mut, LineCap, LineJoin,
 };
 
 mod text;
-pub use self::text::{draw_text, draw_text_mut, text_size};
+pub use self::text::{
+    draw_text, draw_text_aligned, draw_text_aligned_mut, draw_text_mut, text_size, Alignment,
+};
 
 // Set pixel at (x, y) to color if this point lies within image bounds,
 // otherwise do nothing.
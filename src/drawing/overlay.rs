@@ -0,0 +1,212 @@
+use image::{Rgba, RgbaImage};
+
+/// How the colors of an overlay are combined with the colors of the image beneath it.
+///
+/// Each mode is described in terms of the normalised color channels `b` of the base image and
+/// `s` of the overlay. The result is then composited onto the base image using the alpha of
+/// both images, as described in [`overlay_mut`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BlendMode {
+    /// The overlay color is used: `s`.
+    Normal,
+    /// The colors are multiplied, which darkens the base image: `b * s`.
+    Multiply,
+    /// The inverted colors are multiplied, which lightens the base image:
+    /// `1 - (1 - b) * (1 - s)`.
+    Screen,
+    /// The colors are added, saturating at white: `min(1, b + s)`.
+    Add,
+}
+
+impl BlendMode {
+    fn blend(&self, b: f32, s: f32) -> f32 {
+        match self {
+            BlendMode::Normal => s,
+            BlendMode::Multiply => b * s,
+            BlendMode::Screen => b + s - b * s,
+            BlendMode::Add => (b + s).min(1.0),
+        }
+    }
+}
+
+/// Composites `overlay` onto `base` with its top left corner at `(x, y)`.
+///
+/// The colors of each pair of pixels are first combined according to `mode` and the result is
+/// then composited onto the base pixel using the source-over operator. Where the base pixel
+/// is transparent the overlay color is used unchanged, so that blend modes only take effect
+/// where there is something to blend with. For a base pixel with color `b` and alpha `ab`
+/// and an overlay pixel with color `s` and alpha `as`, the output alpha is
+/// `as + ab * (1 - as)` and the output color is
+///
+/// `(as * ((1 - ab) * s + ab * mode(b, s)) + ab * (1 - as) * b) / alpha`.
+///
+/// Channels are treated as straight, i.e. not premultiplied, alpha. Any part of the overlay
+/// which lies outside `base` is ignored.
+///
+/// # Examples
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use imageproc::drawing::{overlay_mut, BlendMode};
+///
+/// let mut base = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 255, 255]));
+/// let overlay = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255]));
+///
+/// // Only the bottom right pixel of the overlay lies within the base image
+/// overlay_mut(&mut base, &overlay, -1, -1, BlendMode::Normal);
+///
+/// assert_eq!(base.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+/// assert_eq!(base.get_pixel(1, 0), &Rgba([0, 0, 255, 255]));
+/// assert_eq!(base.get_pixel(0, 1), &Rgba([0, 0, 255, 255]));
+/// ```
+pub fn overlay_mut(base: &mut RgbaImage, overlay: &RgbaImage, x: i32, y: i32, mode: BlendMode) {
+    let (x, y) = (x as i64, y as i64);
+    let x_start = x.max(0);
+    let y_start = y.max(0);
+    let x_end = (x + overlay.width() as i64).min(base.width() as i64);
+    let y_end = (y + overlay.height() as i64).min(base.height() as i64);
+
+    for base_y in y_start..y_end {
+        for base_x in x_start..x_end {
+            let source = overlay.get_pixel((base_x - x) as u32, (base_y - y) as u32);
+            let target = base.get_pixel_mut(base_x as u32, base_y as u32);
+            *target = composite(*target, *source, mode);
+        }
+    }
+}
+
+fn composite(base: Rgba<u8>, source: Rgba<u8>, mode: BlendMode) -> Rgba<u8> {
+    let alpha_source = source[3] as f32 / 255.0;
+    let alpha_base = base[3] as f32 / 255.0;
+    let alpha = alpha_source + alpha_base * (1.0 - alpha_source);
+    if alpha == 0.0 {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    let mut out = Rgba([0, 0, 0, to_u8(alpha)]);
+    for c in 0..3 {
+        let b = base[c] as f32 / 255.0;
+        let s = source[c] as f32 / 255.0;
+        let blended = (1.0 - alpha_base) * s + alpha_base * mode.blend(b, s);
+        let color = (alpha_source * blended + alpha_base * (1.0 - alpha_source) * b) / alpha;
+        out[c] = to_u8(color);
+    }
+    out
+}
+
+fn to_u8(value: f32) -> u8 {
+    (255.0 * value).round().clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn composite_pixel(base: Rgba<u8>, source: Rgba<u8>, mode: BlendMode) -> Rgba<u8> {
+        let mut image = RgbaImage::from_pixel(1, 1, base);
+        overlay_mut(&mut image, &RgbaImage::from_pixel(1, 1, source), 0, 0, mode);
+        *image.get_pixel(0, 0)
+    }
+
+    #[test]
+    fn test_overlay_half_transparent_red_on_blue_is_purple() {
+        let mut base = RgbaImage::from_pixel(10, 10, Rgba([0, 0, 255, 255]));
+        let overlay = RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 128]));
+        overlay_mut(&mut base, &overlay, 3, 3, BlendMode::Normal);
+
+        for (x, y, p) in base.enumerate_pixels() {
+            if (3..7).contains(&x) && (3..7).contains(&y) {
+                assert_eq!(p, &Rgba([128, 0, 127, 255]), "({}, {})", x, y);
+            } else {
+                assert_eq!(p, &Rgba([0, 0, 255, 255]), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlay_partly_off_canvas_is_clipped() {
+        let background = Rgba([10, 20, 30, 255]);
+        let overlay = RgbaImage::from_fn(5, 4, |x, y| Rgba([x as u8, y as u8, 200, 255]));
+
+        for (x, y) in [(-3, -2), (8, 7), (-2, 8), (8, -3), (-10, 0), (0, 20)] {
+            let mut base = RgbaImage::from_pixel(10, 10, background);
+            overlay_mut(&mut base, &overlay, x, y, BlendMode::Normal);
+
+            for (bx, by, p) in base.enumerate_pixels() {
+                let (ox, oy) = (bx as i32 - x, by as i32 - y);
+                let expected = if (0..5).contains(&ox) && (0..4).contains(&oy) {
+                    Rgba([ox as u8, oy as u8, 200, 255])
+                } else {
+                    background
+                };
+                assert_eq!(p, &expected, "offset ({}, {}) at ({}, {})", x, y, bx, by);
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlay_blend_modes_on_opaque_pixels() {
+        let base = Rgba([200, 100, 0, 255]);
+        let source = Rgba([128, 255, 60, 255]);
+        assert_eq!(
+            composite_pixel(base, source, BlendMode::Normal),
+            Rgba([128, 255, 60, 255])
+        );
+        assert_eq!(
+            composite_pixel(base, source, BlendMode::Multiply),
+            Rgba([100, 100, 0, 255])
+        );
+        assert_eq!(
+            composite_pixel(base, source, BlendMode::Screen),
+            Rgba([228, 255, 60, 255])
+        );
+        assert_eq!(
+            composite_pixel(base, source, BlendMode::Add),
+            Rgba([255, 255, 60, 255])
+        );
+    }
+
+    #[test]
+    fn test_overlay_alpha_compositing() {
+        let base = Rgba([90, 160, 30, 200]);
+        for mode in [
+            BlendMode::Normal,
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Add,
+        ] {
+            // A transparent overlay leaves the base unchanged
+            assert_eq!(composite_pixel(base, Rgba([255, 0, 0, 0]), mode), base);
+            // An overlay on a transparent pixel is copied unchanged
+            let source = Rgba([40, 50, 60, 70]);
+            assert_eq!(composite_pixel(Rgba([1, 2, 3, 0]), source, mode), source);
+            // Alpha follows the source-over operator: 0.5 + 0.5 * 0.5 = 0.75
+            let p = composite_pixel(Rgba([0, 0, 0, 128]), Rgba([255, 255, 255, 128]), mode);
+            assert_eq!(p[3], 192);
+        }
+        assert_eq!(
+            composite_pixel(Rgba([0, 0, 0, 0]), Rgba([0, 0, 0, 0]), BlendMode::Normal),
+            Rgba([0, 0, 0, 0])
+        );
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_overlay_mut(b: &mut Bencher) {
+        let mut base = RgbaImage::from_fn(500, 500, |x, y| {
+            Rgba([(x % 256) as u8, (y % 256) as u8, 100, 255])
+        });
+        let overlay = RgbaImage::from_fn(300, 300, |x, y| {
+            Rgba([200, (x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8])
+        });
+        b.iter(|| {
+            overlay_mut(&mut base, &overlay, 150, 120, BlendMode::Screen);
+            black_box(&base);
+        });
+    }
+}
@@ -228,3 +228,481 @@ where
         plotter(canvas, start, end, color);
     }
 }
+
+/// Draws an anti-aliased filled polygon on an image, using the even-odd fill rule.
+///
+/// Unlike [`draw_antialiased_polygon`], which fills an aliased polygon and then draws
+/// anti-aliased lines along its edges, this computes the fraction of each pixel covered
+/// by the polygon and blends `color` with the existing pixel by that fraction. Vertices
+/// may therefore have sub-pixel positions. As elsewhere in this module, integer
+/// coordinates refer to pixel centres.
+///
+/// A point lies inside a self-intersecting polygon if a ray from it crosses the polygon's
+/// edges an odd number of times. Coverage is computed exactly in the horizontal direction
+/// and sampled at 16 evenly spaced sub-scanlines per pixel in the vertical direction.
+///
+/// The provided list of points should be an open path, i.e. the first and last points
+/// must not be equal. An implicit edge is added from the last to the first point in the
+/// slice.
+///
+/// The parameters of blend are (polygon color, original color, coverage).
+/// Consider using [`interpolate`](crate::pixelops::interpolate) for blend.
+pub fn draw_polygon_antialiased<I, B>(
+    image: &I,
+    poly: &[Point<f32>],
+    color: I::Pixel,
+    blend: B,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+    B: Fn(I::Pixel, I::Pixel, f32) -> I::Pixel,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_polygon_antialiased_mut(&mut out, poly, color, blend);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_polygon_antialiased")]
+pub fn draw_polygon_antialiased_mut<I, B>(
+    image: &mut I,
+    poly: &[Point<f32>],
+    color: I::Pixel,
+    blend: B,
+) where
+    I: GenericImage,
+    B: Fn(I::Pixel, I::Pixel, f32) -> I::Pixel,
+{
+    if poly.is_empty() {
+        return;
+    }
+    if poly[0] == poly[poly.len() - 1] {
+        panic!(
+            "First point {:?} == last point {:?}",
+            poly[0],
+            poly[poly.len() - 1]
+        );
+    }
+    let shape = Shape::Polygon(poly.iter().map(|p| (p.x, p.y)).collect());
+    let coverage = PolygonCoverage::new(image.width(), image.height(), &[shape]);
+    coverage.blend(image, color, &blend);
+}
+
+/// Draws an anti-aliased outline of a polygon on an image.
+///
+/// Each edge is drawn as a line of width 1 with round joins, and `color` is blended with
+/// the existing pixels by the fraction of each pixel covered by the outline, as in
+/// [`draw_polygon_antialiased`]. Pixels covered by more than one edge are only blended once.
+///
+/// The provided list of points should be in polygon order and be an open path, i.e. the
+/// first and last points must not be equal. An implicit edge is added from the last to the
+/// first point in the slice.
+///
+/// The parameters of blend are (outline color, original color, coverage).
+/// Consider using [`interpolate`](crate::pixelops::interpolate) for blend.
+pub fn draw_hollow_polygon_antialiased<I, B>(
+    image: &I,
+    poly: &[Point<f32>],
+    color: I::Pixel,
+    blend: B,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+    B: Fn(I::Pixel, I::Pixel, f32) -> I::Pixel,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_hollow_polygon_antialiased_mut(&mut out, poly, color, blend);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_hollow_polygon_antialiased")]
+pub fn draw_hollow_polygon_antialiased_mut<I, B>(
+    image: &mut I,
+    poly: &[Point<f32>],
+    color: I::Pixel,
+    blend: B,
+) where
+    I: GenericImage,
+    B: Fn(I::Pixel, I::Pixel, f32) -> I::Pixel,
+{
+    if poly.is_empty() {
+        return;
+    }
+    if poly.len() < 2 {
+        panic!(
+            "Polygon only has {} points, but at least two are needed.",
+            poly.len(),
+        );
+    }
+    if poly[0] == poly[poly.len() - 1] {
+        panic!(
+            "First point {:?} == last point {:?}",
+            poly[0],
+            poly[poly.len() - 1]
+        );
+    }
+
+    const HALF_WIDTH: f32 = 0.5;
+    const JOIN_SIDES: usize = 8;
+
+    let mut shapes = Vec::with_capacity(2 * poly.len());
+    for (i, &start) in poly.iter().enumerate() {
+        let end = poly[(i + 1) % poly.len()];
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length > 0.0 {
+            let (nx, ny) = (-dy / length * HALF_WIDTH, dx / length * HALF_WIDTH);
+            shapes.push(Shape::Polygon(vec![
+                (start.x + nx, start.y + ny),
+                (end.x + nx, end.y + ny),
+                (end.x - nx, end.y - ny),
+                (start.x - nx, start.y - ny),
+            ]));
+        }
+        shapes.push(Shape::Polygon(
+            (0..JOIN_SIDES)
+                .map(|k| {
+                    let angle = 2.0 * std::f32::consts::PI * k as f32 / JOIN_SIDES as f32;
+                    (
+                        start.x + HALF_WIDTH * angle.cos(),
+                        start.y + HALF_WIDTH * angle.sin(),
+                    )
+                })
+                .collect(),
+        ));
+    }
+
+    let coverage = PolygonCoverage::new(image.width(), image.height(), &shapes);
+    coverage.blend(image, color, &blend);
+}
+
+// A region of the plane, which can be rasterised by PolygonCoverage.
+pub(crate) enum Shape {
+    // A polygon filled using the even-odd rule.
+    Polygon(Vec<(f32, f32)>),
+    // A disk with the given centre and radius.
+    Disk((f32, f32), f32),
+}
+
+impl Shape {
+    // Appends to `spans` the intervals of x at which the horizontal line at `y` is inside
+    // this shape. Intervals are half-open, so that shapes sharing an edge do not overlap.
+    fn spans(&self, y: f32, spans: &mut Vec<(f32, f32)>) {
+        match self {
+            Shape::Polygon(points) => {
+                let mut crossings = Vec::new();
+                for (i, &(x0, y0)) in points.iter().enumerate() {
+                    let (x1, y1) = points[(i + 1) % points.len()];
+                    if (y0 <= y) != (y1 <= y) {
+                        crossings.push(x0 + (y - y0) / (y1 - y0) * (x1 - x0));
+                    }
+                }
+                crossings.sort_unstable_by(f32::total_cmp);
+                spans.extend(crossings.chunks_exact(2).map(|c| (c[0], c[1])));
+            }
+            Shape::Disk((cx, cy), radius) => {
+                let dy = y - cy;
+                if -radius <= dy && dy < *radius {
+                    let dx = (radius * radius - dy * dy).sqrt();
+                    spans.push((cx - dx, cx + dx));
+                }
+            }
+        }
+    }
+
+    fn y_range(&self) -> (f32, f32) {
+        match self {
+            Shape::Polygon(points) => points
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), p| {
+                    (lo.min(p.1), hi.max(p.1))
+                }),
+            Shape::Disk((_, cy), radius) => (cy - radius, cy + radius),
+        }
+    }
+}
+
+// Draws every pixel whose centre lies within the union of the given shapes. Each pixel
+// is drawn at most once, so this can be used with blending canvases.
+pub(crate) fn fill_shapes<C>(canvas: &mut C, shapes: &[Shape], color: C::Pixel)
+where
+    C: Canvas,
+{
+    let (width, height) = canvas.dimensions();
+    PolygonCoverage::of_pixel_centres(width, height, shapes).fill(canvas, color);
+}
+
+// The fraction of each pixel of an image covered by the union of a set of shapes.
+struct PolygonCoverage {
+    width: u32,
+    y_min: u32,
+    rows: Vec<Vec<f32>>,
+}
+
+impl PolygonCoverage {
+    const SUBSCANLINES: u32 = 16;
+
+    // Estimates the area of each pixel covered by the shapes, by sampling each pixel
+    // along several horizontal lines.
+    fn new(width: u32, height: u32, shapes: &[Shape]) -> PolygonCoverage {
+        Self::sample(width, height, shapes, Self::SUBSCANLINES, Self::add_span)
+    }
+
+    // Assigns a coverage of 1 to each pixel whose centre lies within the shapes, and 0 to
+    // all other pixels.
+    fn of_pixel_centres(width: u32, height: u32, shapes: &[Shape]) -> PolygonCoverage {
+        Self::sample(width, height, shapes, 1, |row, (start, end), _| {
+            // Pixel x has centre x + 0.5 in the shifted coordinates used by sample.
+            let width = row.len() as f32;
+            let first = (start - 0.5).ceil().clamp(0.0, width) as usize;
+            let last = (end - 0.5).ceil().clamp(0.0, width) as usize;
+            for coverage in &mut row[first..last.max(first)] {
+                *coverage = 1.0;
+            }
+        })
+    }
+
+    // Intersects the union of the shapes with `subscanlines` evenly spaced horizontal
+    // lines through each row of pixels, and passes the resulting spans to `add_span`.
+    fn sample<F>(
+        width: u32,
+        height: u32,
+        shapes: &[Shape],
+        subscanlines: u32,
+        add_span: F,
+    ) -> PolygonCoverage
+    where
+        F: Fn(&mut [f32], (f32, f32), u32),
+    {
+        // Coordinates are shifted by half a pixel so that pixel (x, y) covers
+        // [x, x + 1) x [y, y + 1).
+        let (y_lo, y_hi) = shapes
+            .iter()
+            .map(Shape::y_range)
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), (a, b)| {
+                (lo.min(a), hi.max(b))
+            });
+        let (y_min, y_max) = if y_lo > y_hi {
+            (0, 0)
+        } else {
+            (
+                (y_lo + 0.5).floor().clamp(0.0, height as f32) as u32,
+                (y_hi + 0.5).ceil().clamp(0.0, height as f32) as u32,
+            )
+        };
+        let mut rows = vec![vec![0f32; width as usize]; (y_max - y_min) as usize];
+
+        let mut spans: Vec<(f32, f32)> = Vec::new();
+        for (row, y) in rows.iter_mut().zip(y_min..y_max) {
+            for s in 0..subscanlines {
+                let sy = y as f32 + (s as f32 + 0.5) / subscanlines as f32;
+
+                spans.clear();
+                for shape in shapes {
+                    shape.spans(sy - 0.5, &mut spans);
+                }
+
+                // Merge overlapping spans so that their union is only counted once.
+                spans.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+                let mut current: Option<(f32, f32)> = None;
+                for &(start, end) in &spans {
+                    let (start, end) = (start + 0.5, end + 0.5);
+                    current = match current {
+                        Some((s, e)) if start <= e => Some((s, e.max(end))),
+                        Some(span) => {
+                            add_span(row, span, subscanlines);
+                            Some((start, end))
+                        }
+                        None => Some((start, end)),
+                    };
+                }
+                if let Some(span) = current {
+                    add_span(row, span, subscanlines);
+                }
+            }
+        }
+
+        PolygonCoverage { width, y_min, rows }
+    }
+
+    // Adds the length of the overlap of each pixel with the span [start, end), divided
+    // by the number of subscanlines.
+    fn add_span(row: &mut [f32], (start, end): (f32, f32), subscanlines: u32) {
+        let width = row.len() as f32;
+        let (start, end) = (start.clamp(0.0, width), end.clamp(0.0, width));
+        if start >= end {
+            return;
+        }
+        let first = start.floor() as usize;
+        let last = (end.ceil() as usize).min(row.len());
+        for (x, coverage) in row.iter_mut().enumerate().take(last).skip(first) {
+            let overlap = end.min(x as f32 + 1.0) - start.max(x as f32);
+            *coverage += overlap / subscanlines as f32;
+        }
+    }
+
+    // Draws each covered pixel. Partial coverage is ignored.
+    fn fill<C>(&self, canvas: &mut C, color: C::Pixel)
+    where
+        C: Canvas,
+    {
+        for (row, y) in self.rows.iter().zip(self.y_min..) {
+            for (coverage, x) in row.iter().zip(0..self.width) {
+                if *coverage > 0.0 {
+                    canvas.draw_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn blend<I, B>(&self, image: &mut I, color: I::Pixel, blend: &B)
+    where
+        I: GenericImage,
+        B: Fn(I::Pixel, I::Pixel, f32) -> I::Pixel,
+    {
+        for (row, y) in self.rows.iter().zip(self.y_min..) {
+            for (coverage, x) in row.iter().zip(0..self.width) {
+                let coverage = coverage.min(1.0);
+                if coverage > 0.0 {
+                    let original = image.get_pixel(x, y);
+                    image.put_pixel(x, y, blend(color, original, coverage));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixelops::interpolate;
+    use image::{GrayImage, Luma};
+
+    fn rotated_triangle() -> Vec<Point<f32>> {
+        let (cx, cy, r) = (20.0f32, 20.0f32, 15.0f32);
+        (0..3)
+            .map(|k| {
+                let angle = 0.3 + 2.0 * std::f32::consts::PI * k as f32 / 3.0;
+                Point::new(cx + r * angle.cos(), cy + r * angle.sin())
+            })
+            .collect()
+    }
+
+    fn count_intermediate(image: &GrayImage) -> usize {
+        image.iter().filter(|&&p| p > 0 && p < 255).count()
+    }
+
+    fn total_coverage(image: &GrayImage) -> f32 {
+        image.iter().map(|&p| p as f32 / 255.0).sum()
+    }
+
+    #[test]
+    fn test_draw_polygon_antialiased_smooths_edges() {
+        let image = GrayImage::new(40, 40);
+        let white = Luma([255u8]);
+        let triangle = rotated_triangle();
+
+        let rounded: Vec<Point<i32>> = triangle
+            .iter()
+            .map(|p| Point::new(p.x.round() as i32, p.y.round() as i32))
+            .collect();
+        let aliased = draw_polygon(&image, &rounded, white);
+        let smooth = draw_polygon_antialiased(&image, &triangle, white, interpolate);
+
+        assert_eq!(count_intermediate(&aliased), 0);
+        // Each of the three edges is roughly 26 pixels long and crosses at least one
+        // partially covered pixel per row or column.
+        assert!(
+            count_intermediate(&smooth) > 50,
+            "{}",
+            count_intermediate(&smooth)
+        );
+
+        // The covered area matches the area of the triangle.
+        let area = 3.0 * 3f32.sqrt() / 4.0 * 15.0 * 15.0;
+        assert!((total_coverage(&smooth) - area).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_draw_polygon_antialiased_axis_aligned_square() {
+        let image = GrayImage::new(6, 6);
+        let square = [
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(3.0, 3.0),
+            Point::new(0.0, 3.0),
+        ];
+        // Pixel centres are at integer coordinates, so edges pass through the middle of pixels.
+        let drawn = draw_polygon_antialiased(&image, &square, Luma([200]), interpolate);
+        let expected = gray_image!(
+            50, 100, 100, 50, 0, 0;
+            100, 200, 200, 100, 0, 0;
+            100, 200, 200, 100, 0, 0;
+            50, 100, 100, 50, 0, 0;
+            0, 0, 0, 0, 0, 0;
+            0, 0, 0, 0, 0, 0);
+        assert_pixels_eq!(drawn, expected);
+    }
+
+    #[test]
+    fn test_draw_polygon_antialiased_uses_even_odd_rule() {
+        let image = GrayImage::new(40, 40);
+        let star: Vec<Point<f32>> = (0..5)
+            .map(|k| {
+                let angle =
+                    -std::f32::consts::FRAC_PI_2 + 4.0 * std::f32::consts::PI * k as f32 / 5.0;
+                Point::new(20.0 + 18.0 * angle.cos(), 20.0 + 18.0 * angle.sin())
+            })
+            .collect();
+        let drawn = draw_polygon_antialiased(&image, &star, Luma([255]), interpolate);
+        // The central pentagon is crossed twice and so is not filled, but the points are.
+        assert_eq!(drawn.get_pixel(20, 20)[0], 0);
+        assert_eq!(drawn.get_pixel(20, 6)[0], 255);
+    }
+
+    #[test]
+    fn test_draw_polygon_antialiased_clips_to_image() {
+        let image = GrayImage::new(10, 10);
+        let poly = [
+            Point::new(-20.0, -20.0),
+            Point::new(30.0, -20.0),
+            Point::new(30.0, 4.5),
+            Point::new(-20.0, 4.5),
+        ];
+        let drawn = draw_polygon_antialiased(&image, &poly, Luma([255]), interpolate);
+        for (_, y, p) in drawn.enumerate_pixels() {
+            assert_eq!(p[0], if y < 5 { 255 } else { 0 });
+        }
+    }
+
+    #[test]
+    fn test_draw_hollow_polygon_antialiased() {
+        let image = GrayImage::new(40, 40);
+        let triangle = rotated_triangle();
+        let drawn = draw_hollow_polygon_antialiased(&image, &triangle, Luma([255]), interpolate);
+
+        // The interior is left unchanged and the outline is smooth.
+        assert_eq!(drawn.get_pixel(20, 20)[0], 0);
+        assert!(count_intermediate(&drawn) > 50);
+
+        // Coverage is close to the perimeter times the line width.
+        let perimeter = 3.0 * 3f32.sqrt() * 15.0;
+        let coverage = total_coverage(&drawn);
+        assert!(
+            (coverage - perimeter).abs() < 3.0,
+            "{} vs {}",
+            coverage,
+            perimeter
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_draw_polygon_antialiased_rejects_closed_path() {
+        let poly = [
+            Point::new(1.0, 1.0),
+            Point::new(4.0, 1.0),
+            Point::new(1.0, 1.0),
+        ];
+        let _ = draw_polygon_antialiased(&GrayImage::new(5, 5), &poly, Luma([255]), interpolate);
+    }
+}
@@ -1,6 +1,8 @@
 use crate::definitions::Image;
 use crate::drawing::line::draw_line_segment_mut;
+use crate::drawing::polygon::{fill_shapes, Shape};
 use crate::drawing::Canvas;
+use crate::geometry::RotatedRect;
 use crate::rect::Rect;
 use image::GenericImage;
 use std::f32;
@@ -64,10 +66,87 @@ where
     }
 }
 
+/// Draws a rotated rectangle and its contents on an image.
+///
+/// A pixel is drawn if its centre lies inside `rect`, so a rectangle with an angle of zero
+/// and centre `(x + (w - 1) / 2, y + (h - 1) / 2)` covers the same pixels as
+/// [`draw_filled_rect`] with `Rect::at(x, y).of_size(w, h)`. This can be used to draw the
+/// result of [`min_area_rotated_rect`](crate::geometry::min_area_rotated_rect).
+///
+/// Draws as much of the rectangle and its contents as lies inside the image bounds.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_filled_rotated_rect;
+/// use imageproc::geometry::RotatedRect;
+///
+/// let rect = RotatedRect {
+///     center: (10.0, 10.0),
+///     size: (12.0, 6.0),
+///     angle: std::f32::consts::FRAC_PI_4,
+/// };
+/// let image = draw_filled_rotated_rect(&GrayImage::new(20, 20), rect, Luma([255]));
+/// assert_eq!(image.get_pixel(10, 10)[0], 255);
+/// assert_eq!(image.get_pixel(13, 13)[0], 255);
+/// assert_eq!(image.get_pixel(13, 7)[0], 0);
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn draw_filled_rotated_rect<I>(image: &I, rect: RotatedRect, color: I::Pixel) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_filled_rotated_rect_mut(&mut out, rect, color);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_filled_rotated_rect")]
+pub fn draw_filled_rotated_rect_mut<C>(canvas: &mut C, rect: RotatedRect, color: C::Pixel)
+where
+    C: Canvas,
+{
+    let corners = rect.corners().iter().map(|p| (p.x, p.y)).collect();
+    fill_shapes(canvas, &[Shape::Polygon(corners)], color);
+}
+
+/// Draws the outline of a rotated rectangle on an image.
+///
+/// The outline passes through the centres of the outermost pixels drawn by
+/// [`draw_filled_rotated_rect`], so a rectangle with an angle of zero is drawn exactly as
+/// by [`draw_hollow_rect`] for the corresponding [`Rect`].
+///
+/// Draws as much of the outline as lies inside the image bounds.
+#[must_use = "the function does not modify the original image"]
+pub fn draw_hollow_rotated_rect<I>(image: &I, rect: RotatedRect, color: I::Pixel) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_hollow_rotated_rect_mut(&mut out, rect, color);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_hollow_rotated_rect")]
+pub fn draw_hollow_rotated_rect_mut<C>(canvas: &mut C, rect: RotatedRect, color: C::Pixel)
+where
+    C: Canvas,
+{
+    let inset = RotatedRect {
+        size: ((rect.size.0 - 1.0).max(0.0), (rect.size.1 - 1.0).max(0.0)),
+        ..rect
+    };
+    let corners = inset.corners().map(|p| (p.x.round(), p.y.round()));
+    for i in 0..4 {
+        draw_line_segment_mut(canvas, corners[i], corners[(i + 1) % 4], color);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::drawing::Blend;
+    use crate::geometry::RotatedRect;
     use crate::rect::Rect;
     use image::{GrayImage, Luma, Pixel, Rgba, RgbaImage};
 
@@ -137,6 +216,80 @@ mod tests {
         draw_filled_rect_mut(&mut image, Rect::at(2, 2).of_size(1, 1), blue);
         assert_eq!(*image.0.get_pixel(2, 2), blue);
     }
+
+    #[test]
+    fn test_zero_angle_rotated_rect_matches_rect() {
+        let image = GrayImage::new(20, 15);
+        let white = Luma([255u8]);
+        for &(x, y, w, h) in &[(2, 3, 5, 4), (0, 0, 20, 15), (7, 1, 1, 9), (4, 6, 10, 2)] {
+            let rect = Rect::at(x, y).of_size(w, h);
+            let rotated = RotatedRect {
+                center: (
+                    x as f32 + (w as f32 - 1.0) / 2.0,
+                    y as f32 + (h as f32 - 1.0) / 2.0,
+                ),
+                size: (w as f32, h as f32),
+                angle: 0.0,
+            };
+            assert_pixels_eq!(
+                draw_filled_rotated_rect(&image, rotated, white),
+                draw_filled_rect(&image, rect, white)
+            );
+            assert_pixels_eq!(
+                draw_hollow_rotated_rect(&image, rotated, white),
+                draw_hollow_rect(&image, rect, white)
+            );
+        }
+    }
+
+    #[test]
+    fn test_quarter_turn_swaps_width_and_height() {
+        let image = GrayImage::new(30, 30);
+        let white = Luma([255u8]);
+        let rect = RotatedRect {
+            center: (15.0, 15.0),
+            size: (11.0, 5.0),
+            angle: 0.0,
+        };
+        let turned = RotatedRect {
+            angle: std::f32::consts::FRAC_PI_2,
+            ..rect
+        };
+        let swapped = RotatedRect {
+            size: (5.0, 11.0),
+            ..rect
+        };
+        let expected = draw_filled_rect(&image, Rect::at(13, 10).of_size(5, 11), white);
+        assert_pixels_eq!(draw_filled_rotated_rect(&image, turned, white), expected);
+        assert_pixels_eq!(draw_filled_rotated_rect(&image, swapped, white), expected);
+        assert_pixels_eq!(
+            draw_hollow_rotated_rect(&image, turned, white),
+            draw_hollow_rect(&image, Rect::at(13, 10).of_size(5, 11), white)
+        );
+    }
+
+    #[test]
+    fn test_rotated_rect_covers_its_area() {
+        let image = GrayImage::new(60, 60);
+        let rect = RotatedRect {
+            center: (30.0, 30.0),
+            size: (30.0, 12.0),
+            angle: 0.6,
+        };
+        let drawn = draw_filled_rotated_rect(&image, rect, Luma([255]));
+        let area = drawn.iter().filter(|&&p| p > 0).count() as f32;
+        assert!((area - 360.0).abs() < 15.0, "area {}", area);
+
+        // The outline lies along the boundary of the filled rectangle, up to rounding.
+        let outline = draw_hollow_rotated_rect(&image, rect, Luma([255]));
+        for (x, y, p) in outline.enumerate_pixels() {
+            if p[0] > 0 {
+                let near_fill = (x - 1..=x + 1)
+                    .any(|nx| (y - 1..=y + 1).any(|ny| drawn.get_pixel(nx, ny)[0] > 0));
+                assert!(near_fill, "({}, {})", x, y);
+            }
+        }
+    }
 }
 
 #[cfg(not(miri))]
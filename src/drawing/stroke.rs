@@ -0,0 +1,435 @@
+use crate::definitions::Image;
+use crate::drawing::polygon::{fill_shapes, Shape};
+use crate::drawing::Canvas;
+use image::GenericImage;
+
+/// The shape drawn at the ends of a thick line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    /// The line ends exactly at its endpoints.
+    Butt,
+    /// The line is extended past each endpoint by half its thickness.
+    Square,
+    /// Each endpoint is covered by a disk whose diameter is the thickness of the line.
+    Round,
+}
+
+/// The shape drawn where consecutive segments of a thick polyline meet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    /// The outer edges of the segments are extended until they meet. Joins whose
+    /// point would extend more than twice the thickness of the line past the vertex
+    /// are drawn as [`LineJoin::Bevel`] instead.
+    Miter,
+    /// Each vertex is covered by a disk whose diameter is the thickness of the line.
+    Round,
+    /// The gap between the outer corners of the segments is filled by a triangle.
+    Bevel,
+}
+
+// Miter joins whose point is further than this multiple of the thickness from the
+// vertex are drawn as bevel joins. This matches the default miter limit of 4 in SVG,
+// which is expressed as a multiple of half the thickness.
+const MITER_LIMIT: f32 = 2.0;
+
+/// Draws a line segment of the given `thickness` on an image.
+///
+/// A pixel is drawn if its centre lies inside the rectangle of width `thickness` centred
+/// on the segment, extended at each end as specified by `cap`. Centres lying exactly on
+/// the boundary are drawn if they are on its left or top side, so with [`LineCap::Butt`] a
+/// horizontal line of integer thickness `n` from `(x0, y)` to `(x1, y)` covers exactly `n`
+/// rows and the `x1 - x0` columns from `x0` to `x1 - 1`.
+///
+/// Draws as much of the line segment as lies inside the image bounds.
+///
+/// # Panics
+///
+/// If `thickness` is not positive.
+#[must_use = "the function does not modify the original image"]
+pub fn draw_thick_line_segment<I>(
+    image: &I,
+    start: (f32, f32),
+    end: (f32, f32),
+    color: I::Pixel,
+    thickness: f32,
+    cap: LineCap,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_thick_line_segment_mut(&mut out, start, end, color, thickness, cap);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_thick_line_segment")]
+pub fn draw_thick_line_segment_mut<C>(
+    canvas: &mut C,
+    start: (f32, f32),
+    end: (f32, f32),
+    color: C::Pixel,
+    thickness: f32,
+    cap: LineCap,
+) where
+    C: Canvas,
+{
+    draw_thick_polyline_mut(
+        canvas,
+        &[start, end],
+        color,
+        thickness,
+        cap,
+        LineJoin::Miter,
+    );
+}
+
+/// Draws a connected sequence of line segments of the given `thickness` on an image.
+///
+/// Segments are drawn as by [`draw_thick_line_segment`], with `cap` applied at the first
+/// and last points and `join` applied at every other point. Each pixel is drawn at most
+/// once, so this can be used with blending canvases.
+///
+/// Draws as much of the polyline as lies inside the image bounds.
+///
+/// # Panics
+///
+/// If `thickness` is not positive.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::{draw_thick_polyline, LineCap, LineJoin};
+///
+/// let image = GrayImage::new(20, 20);
+/// let points = [(3.0, 16.0), (10.0, 4.0), (17.0, 16.0)];
+/// let drawn = draw_thick_polyline(&image, &points, Luma([255]), 3.0, LineCap::Round, LineJoin::Round);
+///
+/// for (x, y) in points {
+///     assert_eq!(drawn.get_pixel(x as u32, y as u32), &Luma([255]));
+/// }
+/// assert_eq!(drawn.get_pixel(10, 12), &Luma([0]));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn draw_thick_polyline<I>(
+    image: &I,
+    points: &[(f32, f32)],
+    color: I::Pixel,
+    thickness: f32,
+    cap: LineCap,
+    join: LineJoin,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_thick_polyline_mut(&mut out, points, color, thickness, cap, join);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_thick_polyline")]
+pub fn draw_thick_polyline_mut<C>(
+    canvas: &mut C,
+    points: &[(f32, f32)],
+    color: C::Pixel,
+    thickness: f32,
+    cap: LineCap,
+    join: LineJoin,
+) where
+    C: Canvas,
+{
+    assert!(thickness > 0.0, "thickness must be positive");
+
+    // Consecutive duplicate points have no direction, so are dropped.
+    let mut points = points.to_vec();
+    points.dedup();
+    if points.is_empty() {
+        return;
+    }
+
+    let half = thickness / 2.0;
+    let mut shapes = Vec::new();
+
+    if points.len() == 1 {
+        let (x, y) = points[0];
+        match cap {
+            LineCap::Butt => {}
+            LineCap::Square => shapes.push(Shape::Polygon(vec![
+                (x - half, y - half),
+                (x + half, y - half),
+                (x + half, y + half),
+                (x - half, y + half),
+            ])),
+            LineCap::Round => shapes.push(Shape::Disk((x, y), half)),
+        }
+        fill_shapes(canvas, &shapes, color);
+        return;
+    }
+
+    let directions: Vec<(f32, f32)> = points
+        .windows(2)
+        .map(|w| {
+            let (dx, dy) = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+            let length = (dx * dx + dy * dy).sqrt();
+            (dx / length, dy / length)
+        })
+        .collect();
+
+    for (i, segment) in points.windows(2).enumerate() {
+        let (mut start, mut end) = (segment[0], segment[1]);
+        let (dx, dy) = directions[i];
+        if cap == LineCap::Square {
+            if i == 0 {
+                start = (start.0 - dx * half, start.1 - dy * half);
+            }
+            if i == directions.len() - 1 {
+                end = (end.0 + dx * half, end.1 + dy * half);
+            }
+        }
+        let (nx, ny) = (-dy * half, dx * half);
+        shapes.push(Shape::Polygon(vec![
+            (start.0 + nx, start.1 + ny),
+            (end.0 + nx, end.1 + ny),
+            (end.0 - nx, end.1 - ny),
+            (start.0 - nx, start.1 - ny),
+        ]));
+    }
+
+    if cap == LineCap::Round {
+        shapes.push(Shape::Disk(points[0], half));
+        shapes.push(Shape::Disk(points[points.len() - 1], half));
+    }
+
+    for (i, &vertex) in points.iter().enumerate().take(points.len() - 1).skip(1) {
+        let (d0, d1) = (directions[i - 1], directions[i]);
+        let normals = [(-d0.1, d0.0), (-d1.1, d1.0)];
+        match join {
+            LineJoin::Round => shapes.push(Shape::Disk(vertex, half)),
+            LineJoin::Bevel | LineJoin::Miter => {
+                for side in [1.0, -1.0] {
+                    let [(n0x, n0y), (n1x, n1y)] = normals;
+                    let a = (vertex.0 + side * half * n0x, vertex.1 + side * half * n0y);
+                    let b = (vertex.0 + side * half * n1x, vertex.1 + side * half * n1y);
+
+                    // The miter point lies along the bisector of the normals, at the
+                    // distance where it meets both offset edges.
+                    let (bx, by) = (n0x + n1x, n0y + n1y);
+                    let bisector_length = (bx * bx + by * by).sqrt();
+                    let cos_half_angle = bisector_length / 2.0;
+                    let miter_length = half / cos_half_angle;
+                    if join == LineJoin::Miter
+                        && bisector_length > 0.0
+                        && miter_length <= MITER_LIMIT * thickness
+                    {
+                        let scale = side * miter_length / bisector_length;
+                        let tip = (vertex.0 + scale * bx, vertex.1 + scale * by);
+                        shapes.push(Shape::Polygon(vec![vertex, a, tip, b]));
+                    } else {
+                        shapes.push(Shape::Polygon(vec![vertex, a, b]));
+                    }
+                }
+            }
+        }
+    }
+
+    fill_shapes(canvas, &shapes, color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::{draw_line_segment, Blend};
+    use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+    fn drawn_rows(image: &GrayImage, x: u32) -> Vec<u32> {
+        (0..image.height())
+            .filter(|&y| image.get_pixel(x, y)[0] > 0)
+            .collect()
+    }
+
+    fn drawn_columns(image: &GrayImage, y: u32) -> Vec<u32> {
+        (0..image.width())
+            .filter(|&x| image.get_pixel(x, y)[0] > 0)
+            .collect()
+    }
+
+    #[test]
+    fn test_horizontal_thick_line_fills_band() {
+        let image = GrayImage::new(40, 30);
+        for thickness in 1..8 {
+            let line = draw_thick_line_segment(
+                &image,
+                (5.0, 15.0),
+                (30.0, 15.0),
+                Luma([255]),
+                thickness as f32,
+                LineCap::Butt,
+            );
+            // Pixels are drawn if their centres lie within the half-open interval
+            // [5, 30) along the line.
+            for x in 5..30 {
+                assert_eq!(drawn_rows(&line, x).len(), thickness, "x = {}", x);
+            }
+            assert!(drawn_rows(&line, 4).is_empty());
+            assert!(drawn_rows(&line, 30).is_empty());
+            assert_eq!(
+                line.iter().filter(|&&p| p > 0).count(),
+                25 * thickness,
+                "thickness {}",
+                thickness
+            );
+        }
+    }
+
+    #[test]
+    fn test_vertical_thick_line_fills_band() {
+        let image = GrayImage::new(20, 20);
+        let line = draw_thick_line_segment(
+            &image,
+            (10.0, 2.0),
+            (10.0, 17.0),
+            Luma([255]),
+            5.0,
+            LineCap::Butt,
+        );
+        for y in 2..17 {
+            assert_eq!(drawn_columns(&line, y), vec![8, 9, 10, 11, 12]);
+        }
+    }
+
+    #[test]
+    fn test_caps_extend_beyond_endpoints() {
+        let image = GrayImage::new(40, 20);
+        let draw = |cap| {
+            draw_thick_line_segment(&image, (10.0, 10.0), (30.0, 10.0), Luma([255]), 7.0, cap)
+        };
+
+        assert_eq!(
+            drawn_columns(&draw(LineCap::Butt), 10),
+            (10..30).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            drawn_columns(&draw(LineCap::Square), 10),
+            (7..=33).collect::<Vec<_>>()
+        );
+
+        let round = draw(LineCap::Round);
+        assert_eq!(drawn_columns(&round, 10), (7..=33).collect::<Vec<_>>());
+        // Round caps do not fill the corners covered by square caps.
+        assert_eq!(drawn_rows(&round, 7), vec![9, 10, 11]);
+        assert_eq!(drawn_rows(&draw(LineCap::Square), 7).len(), 7);
+        assert_eq!(drawn_rows(&round, 9), vec![7, 8, 9, 10, 11, 12, 13]);
+    }
+
+    #[test]
+    fn test_diagonal_thick_line_has_expected_area() {
+        let image = GrayImage::new(60, 60);
+        let line = draw_thick_line_segment(
+            &image,
+            (10.0, 10.0),
+            (50.0, 40.0),
+            Luma([255]),
+            4.0,
+            LineCap::Butt,
+        );
+        let area = line.iter().filter(|&&p| p > 0).count() as f32;
+        // The segment has length 50.
+        assert!((area - 200.0).abs() < 10.0, "area {}", area);
+
+        let thin = draw_line_segment(&image, (10.0, 10.0), (50.0, 40.0), Luma([255]));
+        let covered = thin.enumerate_pixels().filter(|(_, _, p)| p[0] > 0);
+        assert!(covered
+            .filter(|(x, y, _)| line.get_pixel(*x, *y)[0] == 0)
+            .all(|(x, y, _)| x == 10 && y == 10 || x == 50 && y == 40));
+    }
+
+    #[test]
+    fn test_point_with_caps() {
+        let image = GrayImage::new(10, 10);
+        let draw =
+            |cap| draw_thick_line_segment(&image, (5.0, 5.0), (5.0, 5.0), Luma([1]), 5.0, cap);
+        assert_pixels_eq!(draw(LineCap::Butt), image);
+        let count = |image: GrayImage| image.iter().filter(|&&p| p > 0).count();
+        assert_eq!(count(draw(LineCap::Square)), 25);
+        assert_eq!(count(draw(LineCap::Round)), 21);
+    }
+
+    #[test]
+    fn test_polyline_joins() {
+        let image = GrayImage::new(40, 40);
+        let points = [(5.0, 30.0), (20.0, 30.0), (20.0, 5.0)];
+        let draw =
+            |join| draw_thick_polyline(&image, &points, Luma([255]), 5.0, LineCap::Butt, join);
+        let (miter, round, bevel) = (
+            draw(LineJoin::Miter),
+            draw(LineJoin::Round),
+            draw(LineJoin::Bevel),
+        );
+
+        // A right-angled miter join fills the outer corner.
+        assert_eq!(miter.get_pixel(22, 32)[0], 255);
+        assert_eq!(bevel.get_pixel(22, 32)[0], 0);
+        assert_eq!(round.get_pixel(22, 32)[0], 0);
+        assert_eq!(round.get_pixel(21, 31)[0], 255);
+        assert_eq!(bevel.get_pixel(21, 31)[0], 255);
+
+        let count = |image: &GrayImage| image.iter().filter(|&&p| p > 0).count();
+        assert!(count(&bevel) < count(&round));
+        assert!(count(&round) < count(&miter));
+    }
+
+    #[test]
+    fn test_sharp_miter_falls_back_to_bevel() {
+        let image = GrayImage::new(60, 30);
+        let points = [(5.0, 5.0), (50.0, 10.0), (5.0, 15.0)];
+        let miter = draw_thick_polyline(
+            &image,
+            &points,
+            Luma([255]),
+            3.0,
+            LineCap::Butt,
+            LineJoin::Miter,
+        );
+        let bevel = draw_thick_polyline(
+            &image,
+            &points,
+            Luma([255]),
+            3.0,
+            LineCap::Butt,
+            LineJoin::Bevel,
+        );
+        assert_pixels_eq!(miter, bevel);
+    }
+
+    #[test]
+    fn test_polyline_draws_each_pixel_once() {
+        let background = Rgba([0u8, 0, 0, 255]);
+        let color = Rgba([255u8, 255, 255, 128]);
+        let mut canvas = Blend(RgbaImage::from_pixel(40, 40, background));
+        let points = [(5.0, 5.0), (30.0, 8.0), (12.0, 30.0), (35.0, 35.0)];
+        draw_thick_polyline_mut(
+            &mut canvas,
+            &points,
+            color,
+            4.0,
+            LineCap::Round,
+            LineJoin::Round,
+        );
+
+        let mut once = background;
+        image::Pixel::blend(&mut once, &color);
+        assert!(canvas.0.pixels().all(|p| *p == background || *p == once));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_thick_line_rejects_zero_thickness() {
+        let _ = draw_thick_line_segment(
+            &GrayImage::new(5, 5),
+            (0.0, 0.0),
+            (4.0, 4.0),
+            Luma([1]),
+            0.0,
+            LineCap::Butt,
+        );
+    }
+}
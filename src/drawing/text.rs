@@ -47,7 +47,8 @@ pub fn text_size(scale: impl Into<PxScale> + Copy, font: &impl Font, text: &str)
 ///
 /// `scale` is augmented font scaling on both the x and y axis (in pixels).
 ///
-/// Note that this function *does not* support newlines, you must do this manually.
+/// Note that this function *does not* support newlines. Use [`draw_text_aligned`] to draw
+/// multiple lines of text.
 #[must_use = "the function does not modify the original image"]
 pub fn draw_text<I>(
     image: &I,
@@ -99,3 +100,253 @@ pub fn draw_text_mut<C>(
         })
     });
 }
+
+/// Horizontal alignment of text relative to the anchor point passed to [`draw_text_aligned`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Alignment {
+    /// Each line starts at the anchor.
+    #[default]
+    Left,
+    /// Each line is centred on the anchor.
+    Center,
+    /// Each line ends at the anchor.
+    Right,
+}
+
+/// Draws colored text on an image, with each line aligned horizontally relative to `x`.
+///
+/// `text` may contain newlines. The first line is drawn with its top at `y`, as for
+/// [`draw_text`], and each subsequent line is drawn one line height (the font's ascent minus
+/// its descent, plus its line gap) below the previous one. Each line is measured using
+/// [`text_size`] and positioned so that it starts at, is centred on, or ends at `x`,
+/// depending on `align`.
+///
+/// # Examples
+/// ```
+/// use ab_glyph::FontRef;
+/// use image::{Rgb, RgbImage};
+/// use imageproc::drawing::{draw_text_aligned, Alignment};
+///
+/// let font = FontRef::try_from_slice(include_bytes!("../../tests/data/fonts/DejaVuSans.ttf")).unwrap();
+/// let image = RgbImage::new(200, 100);
+///
+/// // Two lines of text, each horizontally centred in the image
+/// let labelled = draw_text_aligned(
+///     &image, Rgb([255, 255, 255]), 100, 10, 20.0, &font, "Hello,\nworld!", Alignment::Center
+/// );
+/// ```
+#[must_use = "the function does not modify the original image"]
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text_aligned<I>(
+    image: &I,
+    color: I::Pixel,
+    x: i32,
+    y: i32,
+    scale: impl Into<PxScale> + Copy,
+    font: &impl Font,
+    text: &str,
+    align: Alignment,
+) -> Image<I::Pixel>
+where
+    I: GenericImage,
+    <I::Pixel as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+{
+    let mut out = Image::new(image.width(), image.height());
+    out.copy_from(image, 0, 0).unwrap();
+    draw_text_aligned_mut(&mut out, color, x, y, scale, font, text, align);
+    out
+}
+#[doc=generate_mut_doc_comment!("draw_text_aligned")]
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text_aligned_mut<C>(
+    canvas: &mut C,
+    color: C::Pixel,
+    x: i32,
+    y: i32,
+    scale: impl Into<PxScale> + Copy,
+    font: &impl Font,
+    text: &str,
+    align: Alignment,
+) where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+{
+    let scaled = font.as_scaled(scale);
+    let line_height = scaled.height() + scaled.line_gap();
+
+    for (i, line) in text.lines().enumerate() {
+        let width = text_size(scale, font, line).0 as i32;
+        let line_x = match align {
+            Alignment::Left => x,
+            Alignment::Center => x - width / 2,
+            Alignment::Right => x - width,
+        };
+        let line_y = y + (i as f32 * line_height).round() as i32;
+        draw_text_mut(canvas, color, line_x, line_y, scale, font, line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ab_glyph::FontRef;
+    use image::{GrayImage, Luma};
+
+    fn font() -> FontRef<'static> {
+        FontRef::try_from_slice(include_bytes!("../../tests/data/fonts/DejaVuSans.ttf")).unwrap()
+    }
+
+    // The inclusive ranges of columns and rows containing non-zero pixels.
+    fn ink_bounds(image: &GrayImage) -> ((u32, u32), (u32, u32)) {
+        let inked: Vec<(u32, u32)> = image
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p[0] > 0)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        let xs = inked.iter().map(|p| p.0);
+        let ys = inked.iter().map(|p| p.1);
+        (
+            (xs.clone().min().unwrap(), xs.max().unwrap()),
+            (ys.clone().min().unwrap(), ys.max().unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_text_size_increases_with_text_length() {
+        let font = font();
+        let widths: Vec<u32> = ["a", "ab", "abc", "abcd"]
+            .iter()
+            .map(|text| text_size(20.0, &font, text).0)
+            .collect();
+        assert!(widths.windows(2).all(|w| w[0] < w[1]), "{:?}", widths);
+        assert!(text_size(40.0, &font, "abcd").0 > widths[3]);
+    }
+
+    #[test]
+    fn test_draw_text_aligned_center_is_symmetric_about_x() {
+        let font = font();
+        let image = GrayImage::new(200, 60);
+        for text in ["HOH", "HOOH", "IOI"] {
+            let drawn = draw_text_aligned(
+                &image,
+                Luma([255]),
+                100,
+                10,
+                30.0,
+                &font,
+                text,
+                Alignment::Center,
+            );
+            let ((left, right), _) = ink_bounds(&drawn);
+            let (left_extent, right_extent) = (100 - left as i32, right as i32 - 100);
+            assert!(
+                (left_extent - right_extent).abs() <= 2,
+                "{}: ink spans {}..={}",
+                text,
+                left,
+                right
+            );
+        }
+    }
+
+    #[test]
+    fn test_draw_text_aligned_left_and_right() {
+        let font = font();
+        let image = GrayImage::new(200, 60);
+        let text = "Label";
+        let width = text_size(24.0, &font, text).0 as i32;
+
+        let left = draw_text_aligned(
+            &image,
+            Luma([255]),
+            50,
+            10,
+            24.0,
+            &font,
+            text,
+            Alignment::Left,
+        );
+        assert_pixels_eq!(
+            left,
+            draw_text(&image, Luma([255]), 50, 10, 24.0, &font, text)
+        );
+
+        let right = draw_text_aligned(
+            &image,
+            Luma([255]),
+            150,
+            10,
+            24.0,
+            &font,
+            text,
+            Alignment::Right,
+        );
+        assert_pixels_eq!(
+            right,
+            draw_text(&image, Luma([255]), 150 - width, 10, 24.0, &font, text)
+        );
+    }
+
+    #[test]
+    fn test_draw_text_aligned_draws_lines_below_each_other() {
+        let font = font();
+        let image = GrayImage::new(200, 120);
+        let one_line = draw_text_aligned(
+            &image,
+            Luma([255]),
+            100,
+            5,
+            24.0,
+            &font,
+            "H",
+            Alignment::Center,
+        );
+        let two_lines = draw_text_aligned(
+            &image,
+            Luma([255]),
+            100,
+            5,
+            24.0,
+            &font,
+            "H\nH",
+            Alignment::Center,
+        );
+
+        let (columns, (top, bottom)) = ink_bounds(&one_line);
+        let (two_columns, (two_top, two_bottom)) = ink_bounds(&two_lines);
+        assert_eq!(columns, two_columns);
+        assert_eq!(top, two_top);
+
+        let scaled = font.as_scaled(24.0);
+        let line_height = (scaled.height() + scaled.line_gap()).round() as u32;
+        assert!((two_bottom as i32 - (bottom + line_height) as i32).abs() <= 1);
+    }
+
+    #[test]
+    fn test_draw_text_aligned_ignores_trailing_newline() {
+        let font = font();
+        let image = GrayImage::new(100, 80);
+        assert_pixels_eq!(
+            draw_text_aligned(
+                &image,
+                Luma([255]),
+                10,
+                5,
+                20.0,
+                &font,
+                "ab\n",
+                Alignment::Left
+            ),
+            draw_text_aligned(
+                &image,
+                Luma([255]),
+                10,
+                5,
+                20.0,
+                &font,
+                "ab",
+                Alignment::Left
+            )
+        );
+    }
+}
@@ -29,6 +29,69 @@ use std::f32;
 /// of the horizontal and vertical Sobel gradients at `p`.
 pub fn canny(image: &GrayImage, low_threshold: f32, high_threshold: f32) -> GrayImage {
     assert!(high_threshold >= low_threshold);
+    let (_, thinned) = edge_strengths(image);
+
+    // 4. Hysteresis to filter out edges based on thresholds.
+    hysteresis(&thinned, low_threshold, high_threshold)
+}
+
+/// The greatest possible edge strength computed by [`canny`], `sqrt(5) * 2 * 255`.
+const MAX_CANNY_EDGE_STRENGTH: f32 = 1140.3946;
+
+/// Runs the canny edge detection algorithm, choosing the hysteresis thresholds automatically.
+///
+/// The thresholds are derived from the median `m` of the edge strengths of all pixels in the
+/// image, as defined for [`canny`]. The low threshold is `(1 - sigma) * m` and the high
+/// threshold is `(1 + sigma) * m`, capped to the greatest possible edge strength. Smaller
+/// values of `sigma` give narrower threshold bands, and `sigma = 0.33` is a common choice.
+///
+/// The low threshold is always positive, so that pixels of zero edge strength are never
+/// edges. In particular, for images in which most pixels lie in flat regions every local
+/// maximum of the edge strength is an edge.
+///
+/// Returns the edge map, as for [`canny`], and the `(low, high)` thresholds used.
+///
+/// # Panics
+///
+/// If `sigma < 0.0`.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_filled_rect_mut;
+/// use imageproc::edges::canny_auto;
+/// use imageproc::rect::Rect;
+///
+/// let mut image = GrayImage::new(40, 40);
+/// draw_filled_rect_mut(&mut image, Rect::at(10, 10).of_size(20, 20), Luma([200]));
+///
+/// let (edges, (low, high)) = canny_auto(&image, 0.33);
+/// assert!(0.0 < low && low <= high);
+/// assert_eq!(edges.get_pixel(20, 10), &Luma([255]));
+/// assert_eq!(edges.get_pixel(20, 20), &Luma([0]));
+/// ```
+pub fn canny_auto(image: &GrayImage, sigma: f32) -> (GrayImage, (f32, f32)) {
+    assert!(sigma >= 0.0, "sigma must be >= 0.0");
+    let (strengths, thinned) = edge_strengths(image);
+
+    let mut values = strengths.into_raw();
+    let median = if values.is_empty() {
+        0.0
+    } else {
+        let middle = values.len() / 2;
+        *values.select_nth_unstable_by(middle, f32::total_cmp).1
+    };
+
+    let low = ((1.0 - sigma) * median).max(f32::MIN_POSITIVE);
+    let high = ((1.0 + sigma) * median)
+        .min(MAX_CANNY_EDGE_STRENGTH)
+        .max(low);
+    (hysteresis(&thinned, low, high), (low, high))
+}
+
+/// Returns the edge strengths of the image, i.e. the Sobel gradient magnitudes of a blurred
+/// copy of the image, before and after non-maximum suppression.
+fn edge_strengths(image: &GrayImage) -> (Image<Luma<f32>>, Image<Luma<f32>>) {
     // Heavily based on the implementation proposed by wikipedia.
     // 1. Gaussian blur.
     const SIGMA: f32 = 1.4;
@@ -47,9 +110,7 @@ pub fn canny(image: &GrayImage, low_threshold: f32, high_threshold: f32) -> Gray
 
     // 3. Non-maximum-suppression (Make edges thinner)
     let thinned = non_maximum_suppression(&g, &gx, &gy);
-
-    // 4. Hysteresis to filter out edges based on thresholds.
-    hysteresis(&thinned, low_threshold, high_threshold)
+    (g, thinned)
 }
 
 /// Finds local maxima to make the edges thinner.
@@ -152,6 +213,73 @@ fn hysteresis(input: &Image<Luma<f32>>, low_thresh: f32, high_thresh: f32) -> Im
     out
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::{draw_filled_circle_mut, draw_filled_rect_mut};
+    use crate::noise::gaussian_noise;
+    use crate::rect::Rect;
+    use crate::region_labelling::{connected_components, Connectivity};
+
+    fn edge_components(edges: &GrayImage) -> u32 {
+        let labels = connected_components(edges, Connectivity::Eight, Luma([0]));
+        labels.iter().copied().max().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_canny_auto_finds_connected_outline_of_clean_shape() {
+        let mut image = GrayImage::from_pixel(60, 50, Luma([30]));
+        draw_filled_rect_mut(&mut image, Rect::at(12, 10).of_size(35, 28), Luma([220]));
+
+        let (edges, (low, high)) = canny_auto(&image, 0.33);
+        assert!(0.0 < low && low <= high, "({}, {})", low, high);
+        assert_eq!(edge_components(&edges), 1);
+        // Every edge pixel lies near the boundary of the rectangle
+        for (x, y, p) in edges.enumerate_pixels() {
+            if p[0] > 0 {
+                let near_vertical =
+                    (10..=39).contains(&y) && (x.abs_diff(12) <= 2 || x.abs_diff(46) <= 2);
+                let near_horizontal =
+                    (10..=48).contains(&x) && (y.abs_diff(10) <= 2 || y.abs_diff(37) <= 2);
+                assert!(near_vertical || near_horizontal, "edge at ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_canny_auto_thresholds_follow_median_edge_strength() {
+        // Away from the left and right borders the edge strength of a ramp is 16 = 4 * (2 + 2)
+        let image = GrayImage::from_fn(60, 10, |x, _| Luma([(2 * x) as u8]));
+        let (_, (low, high)) = canny_auto(&image, 0.25);
+        assert_eq!((low, high), (12.0, 20.0));
+    }
+
+    #[test]
+    fn test_canny_auto_matches_canny_with_chosen_thresholds() {
+        let mut image = GrayImage::from_pixel(50, 50, Luma([60]));
+        draw_filled_circle_mut(&mut image, (25, 25), 14, Luma([180]));
+        let image = gaussian_noise(&image, 0.0, 20.0, 1);
+
+        let (edges, (low, high)) = canny_auto(&image, 0.33);
+        assert!(low > 1.0 && low < high, "({}, {})", low, high);
+        assert_eq!(edges, canny(&image, low, high));
+        // The outline of the circle is found
+        for (x, y) in [(25, 11), (25, 39), (11, 25), (39, 25)] {
+            let found =
+                (x - 2..=x + 2).any(|x| (y - 2..=y + 2).any(|y| edges.get_pixel(x, y)[0] > 0));
+            assert!(found, "no edge near ({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_canny_auto_on_flat_image() {
+        let image = GrayImage::from_pixel(20, 20, Luma([77]));
+        let (edges, (low, high)) = canny_auto(&image, 0.33);
+        assert!(edges.iter().all(|&p| p == 0));
+        assert!(low > 0.0 && low == high);
+    }
+}
+
 #[cfg(not(miri))]
 #[cfg(test)]
 mod benches {
@@ -54,13 +54,13 @@ where
 ///
 /// * `image` - Image to be filtered.
 /// * `radius` - The radius of the kernel used for the filtering. 0 -> 1x1, 1 -> 3x3, 2 -> 5x5, 3
-///     -> 7x7, etc..
+///   -> 7x7, etc..
 /// * `spatial_sigma` - Standard deviation for euclidean spatial distance. A larger value results in
-///     averaging of pixels with larger spatial distances.
+///   averaging of pixels with larger spatial distances.
 /// * `color_distance` - A type which implements [`ColorDistance`]. This defines the metric used to
-///     define how different two pixels are based on their colors. Common examples may include simple
-///     absolute difference for greyscale pixels or cartesian distance in the CIE-Lab color space
-///     \[1\].
+///   define how different two pixels are based on their colors. Common examples may include simple
+///   absolute difference for greyscale pixels or cartesian distance in the CIE-Lab color space
+///   \[1\].
 ///
 /// This filter averages pixels based on their spatial distance as well as their color
 /// distance. Spatial distance is measured by the Gaussian function of the Euclidean distance
@@ -203,6 +203,7 @@ fn gaussian_weight(x_squared: f32, sigma_squared: f32) -> f32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use image::{GrayImage, Luma};
 
     #[test]
     fn test_bilateral_filter_greyscale() {
@@ -219,6 +220,31 @@ mod tests {
 
         assert_pixels_eq!(actual, expect);
     }
+
+    #[test]
+    fn test_bilateral_filter_preserves_step_edge() {
+        // Noisy flat regions either side of a vertical step edge
+        let image = GrayImage::from_fn(20, 10, |x, y| {
+            let base = if x < 10 { 50 } else { 200 };
+            let noise = if (x + y) % 2 == 0 { 4 } else { -4 };
+            Luma([(base + noise) as u8])
+        });
+        let filtered = bilateral_filter(&image, 3, 2.0, GaussianEuclideanColorDistance::new(10.0));
+
+        for y in 0..10 {
+            // The edge remains sharp...
+            let left = filtered.get_pixel(9, y)[0] as i32;
+            let right = filtered.get_pixel(10, y)[0] as i32;
+            assert!(right - left > 140, "left: {}, right: {}", left, right);
+
+            // ...while the flat regions are smoothed
+            for x in 0..20 {
+                let base = if x < 10 { 50 } else { 200 };
+                let value = filtered.get_pixel(x, y)[0] as i32;
+                assert!((value - base).abs() <= 2, "({}, {}): {}", x, y, value);
+            }
+        }
+    }
 }
 
 #[cfg(not(miri))]
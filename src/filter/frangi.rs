@@ -0,0 +1,234 @@
+//! The Frangi vesselness filter for detecting ridges and tubular structures.
+
+use super::separable_filter;
+use crate::definitions::Image;
+use crate::map::map_subpixels;
+use image::{GrayImage, Luma};
+
+/// Computes the Frangi vesselness of each pixel, as described in "Multiscale Vessel
+/// Enhancement Filtering" by Frangi, Niessen, Vincken and Viergever.
+///
+/// At each scale `sigma` in `scales` the Hessian of the image smoothed by a Gaussian of
+/// standard deviation `sigma` is computed and multiplied by `sigma²`, so that responses are
+/// comparable between scales. If the eigenvalues of the Hessian at a pixel are `l1` and `l2`,
+/// with `|l1| <= |l2|`, then the vesselness at this scale is zero if `l2 > 0`, and otherwise
+///
+/// `exp(-rb² / (2 beta²)) * (1 - exp(-s² / (2 c²)))`
+///
+/// where `rb = l1 / l2` measures how far the local structure is from being line-like and
+/// `s = sqrt(l1² + l2²)` measures the strength of the local structure. The result at each
+/// pixel is the maximum vesselness over all scales, and lies in `[0, 1)`.
+///
+/// Bright ridges on dark backgrounds have strong responses, at scales close to half their
+/// width. To detect dark ridges on bright backgrounds, invert the image first.
+///
+/// `beta` controls the sensitivity to blob-like structures and is typically 0.5. `c`
+/// controls the sensitivity to background noise, and is typically around half of the largest
+/// value of `s` in the image. As intensities are not normalised, suitable values of `c`
+/// scale with the contrast of the image.
+///
+/// The Gaussian derivative kernels are truncated at `4 * sigma`, padding by continuity.
+///
+/// # Panics
+///
+/// If `scales` is empty, any scale is not positive, or `beta` or `c` is not positive.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::filter::frangi_vesselness;
+///
+/// // A bright vertical line of width 3
+/// let image = GrayImage::from_fn(30, 30, |x, _| {
+///     Luma([if (14..17).contains(&x) { 200 } else { 20 }])
+/// });
+/// let vesselness = frangi_vesselness(&image, &[1.0, 1.5, 2.0], 0.5, 15.0);
+///
+/// assert!(vesselness.get_pixel(15, 15)[0] > 0.9);
+/// assert!(vesselness.get_pixel(3, 15)[0] < 0.01);
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn frangi_vesselness(image: &GrayImage, scales: &[f32], beta: f32, c: f32) -> Image<Luma<f32>> {
+    assert!(!scales.is_empty(), "scales must not be empty");
+    assert!(
+        scales.iter().all(|&sigma| sigma > 0.0),
+        "scales must be > 0.0"
+    );
+    assert!(beta > 0.0, "beta must be > 0.0");
+    assert!(c > 0.0, "c must be > 0.0");
+
+    let input = map_subpixels(image, f32::from);
+    let mut out: Image<Luma<f32>> = Image::new(image.width(), image.height());
+
+    for &sigma in scales {
+        let [smooth, first, second] = gaussian_derivative_kernels(sigma);
+        let normalisation = sigma * sigma;
+        let hxx = separable_filter(&input, &second, &smooth);
+        let hyy = separable_filter(&input, &smooth, &second);
+        let hxy = separable_filter(&input, &first, &first);
+
+        for (((v, xx), yy), xy) in out
+            .iter_mut()
+            .zip(hxx.iter())
+            .zip(hyy.iter())
+            .zip(hxy.iter())
+        {
+            let (xx, yy, xy) = (normalisation * xx, normalisation * yy, normalisation * xy);
+            // Eigenvalues of the symmetric matrix [xx, xy; xy, yy]
+            let half_trace = 0.5 * (xx + yy);
+            let root = (0.25 * (xx - yy) * (xx - yy) + xy * xy).sqrt();
+            let (a, b) = (half_trace + root, half_trace - root);
+            let (l1, l2) = if a.abs() <= b.abs() { (a, b) } else { (b, a) };
+
+            if l2 >= 0.0 {
+                continue;
+            }
+            let rb = l1 / l2;
+            let s_squared = l1 * l1 + l2 * l2;
+            let response =
+                (-rb * rb / (2.0 * beta * beta)).exp() * (1.0 - (-s_squared / (2.0 * c * c)).exp());
+            *v = v.max(response);
+        }
+    }
+    out
+}
+
+// Returns kernels for correlating with a Gaussian and its first and second derivatives,
+// truncated at 4 * sigma. The derivative kernels are normalised to give exact results for
+// linear and quadratic functions respectively.
+fn gaussian_derivative_kernels(sigma: f32) -> [Vec<f32>; 3] {
+    let radius = (4.0 * sigma).ceil() as i32;
+    let offsets: Vec<f32> = (-radius..=radius).map(|i| i as f32).collect();
+
+    let mut smooth: Vec<f32> = offsets
+        .iter()
+        .map(|i| (-i * i / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = smooth.iter().sum();
+    smooth.iter_mut().for_each(|g| *g /= sum);
+
+    let mut first: Vec<f32> = offsets.iter().zip(&smooth).map(|(i, g)| i * g).collect();
+    let moment: f32 = offsets.iter().zip(&first).map(|(i, k)| i * k).sum();
+    first.iter_mut().for_each(|k| *k /= moment);
+
+    let mut second: Vec<f32> = offsets
+        .iter()
+        .zip(&smooth)
+        .map(|(i, g)| (i * i - sigma * sigma) * g)
+        .collect();
+    let mean = second.iter().sum::<f32>() / second.len() as f32;
+    second.iter_mut().for_each(|k| *k -= mean);
+    let moment: f32 = offsets
+        .iter()
+        .zip(&second)
+        .map(|(i, k)| 0.5 * i * i * k)
+        .sum();
+    second.iter_mut().for_each(|k| *k /= moment);
+
+    [smooth, first, second]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drawing::{draw_filled_circle_mut, draw_line_segment_mut};
+
+    #[test]
+    fn test_gaussian_derivative_kernels_differentiate_polynomials() {
+        let [smooth, first, second] = gaussian_derivative_kernels(1.5);
+        let radius = (smooth.len() / 2) as f32;
+        let apply = |kernel: &[f32], f: &dyn Fn(f32) -> f32| -> f32 {
+            kernel
+                .iter()
+                .enumerate()
+                .map(|(i, k)| k * f(i as f32 - radius))
+                .sum()
+        };
+        assert!((apply(&smooth, &|_| 3.0) - 3.0).abs() < 1e-5);
+        assert!((apply(&first, &|x| 2.0 * x + 7.0) - 2.0).abs() < 1e-4);
+        assert!(apply(&second, &|_| 5.0).abs() < 1e-4);
+        assert!((apply(&second, &|x| 3.0 * x * x + x) - 6.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_frangi_responds_to_bright_line_and_not_background() {
+        let mut image = GrayImage::from_pixel(60, 60, Luma([30]));
+        for offset in [-1.0, 0.0, 1.0] {
+            draw_line_segment_mut(
+                &mut image,
+                (5.0, 10.0 + offset),
+                (55.0, 50.0 + offset),
+                Luma([220]),
+            );
+        }
+        let vesselness = frangi_vesselness(&image, &[1.0, 2.0, 3.0], 0.5, 15.0);
+
+        // Along the line, away from its ends
+        for x in 15..45 {
+            let y = (10.0 + 0.8 * (x as f32 - 5.0)).round() as u32;
+            let response = vesselness.get_pixel(x, y)[0];
+            assert!(response > 0.8, "response {} at ({}, {})", response, x, y);
+        }
+        // Flat background far from the line
+        for (x, y) in [(50, 10), (45, 15), (10, 50), (5, 40)] {
+            let response = vesselness.get_pixel(x, y)[0];
+            assert!(response < 1e-3, "response {} at ({}, {})", response, x, y);
+        }
+        assert!(vesselness.iter().all(|&v| (0.0..1.0).contains(&v)));
+    }
+
+    #[test]
+    fn test_frangi_prefers_bright_lines_to_dark_lines_and_blobs() {
+        let dark_line = GrayImage::from_fn(40, 40, |x, _| {
+            Luma([if (19..22).contains(&x) { 20 } else { 200 }])
+        });
+        let vesselness = frangi_vesselness(&dark_line, &[1.0, 2.0], 0.5, 15.0);
+        assert!(vesselness.get_pixel(20, 20)[0] < 1e-3);
+
+        // A bright disk is blob-like, so responds less than a line of the same width
+        let mut disk = GrayImage::from_pixel(40, 40, Luma([20]));
+        draw_filled_circle_mut(&mut disk, (20, 20), 2, Luma([200]));
+        let line = GrayImage::from_fn(40, 40, |x, _| {
+            Luma([if (18..23).contains(&x) { 200 } else { 20 }])
+        });
+        let scales = [1.5, 2.0, 2.5];
+        let disk_response = frangi_vesselness(&disk, &scales, 0.5, 15.0).get_pixel(20, 20)[0];
+        let line_response = frangi_vesselness(&line, &scales, 0.5, 15.0).get_pixel(20, 20)[0];
+        assert!(
+            disk_response < 0.5 * line_response,
+            "{} vs {}",
+            disk_response,
+            line_response
+        );
+    }
+
+    #[test]
+    fn test_frangi_constant_image_has_no_response() {
+        let image = GrayImage::from_pixel(20, 20, Luma([120]));
+        let vesselness = frangi_vesselness(&image, &[1.0, 2.0], 0.5, 15.0);
+        assert!(vesselness.iter().all(|&v| v == 0.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_frangi_rejects_empty_scales() {
+        let _ = frangi_vesselness(&GrayImage::new(5, 5), &[], 0.5, 15.0);
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::gray_bench_image;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_frangi_vesselness(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let vesselness = frangi_vesselness(&image, &[1.0, 2.0, 3.0], 0.5, 15.0);
+            black_box(vesselness);
+        });
+    }
+}
@@ -0,0 +1,217 @@
+//! Gabor filters for texture analysis.
+
+use super::filter;
+use crate::definitions::Image;
+use crate::kernel::Kernel;
+use image::{GrayImage, Luma};
+use std::f32::consts::PI;
+
+/// Constructs the coefficients of a `size x size` Gabor kernel, in row-major order.
+///
+/// The kernel is a sinusoidal plane wave modulated by a Gaussian envelope:
+///
+/// `exp(-(x'² + gamma² y'²) / (2 sigma²)) * cos(2π x' / lambda + psi)`
+///
+/// where `x' = x cos(theta) + y sin(theta)` and `y' = -x sin(theta) + y cos(theta)`.
+///
+/// * `sigma` is the standard deviation of the Gaussian envelope.
+/// * `theta` is the orientation of the wave's direction of travel, in radians clockwise
+///   from the positive x-axis (as the y-axis points down). The kernel responds most
+///   strongly to stripes perpendicular to this direction.
+/// * `lambda` is the wavelength of the sinusoid, in pixels.
+/// * `gamma` is the aspect ratio of the envelope.
+/// * `psi` is the phase offset of the sinusoid.
+///
+/// The mean of the coefficients is subtracted from each coefficient, so that the kernel
+/// does not respond to constant regions.
+///
+/// The coefficients are returned rather than a [`Kernel`] because kernels borrow their data.
+/// Use `Kernel::new(&coefficients, size, size)` to filter with them.
+///
+/// # Panics
+///
+/// If `size` is not odd, or if `sigma`, `lambda` or `gamma` are not strictly positive.
+pub fn gabor_kernel(
+    size: u32,
+    sigma: f32,
+    theta: f32,
+    lambda: f32,
+    gamma: f32,
+    psi: f32,
+) -> Vec<f32> {
+    assert!(size % 2 == 1, "size must be odd");
+    assert!(sigma > 0.0, "sigma must be > 0.0");
+    assert!(lambda > 0.0, "lambda must be > 0.0");
+    assert!(gamma > 0.0, "gamma must be > 0.0");
+
+    let radius = (size / 2) as i32;
+    let (sin, cos) = theta.sin_cos();
+    let mut coefficients = Vec::with_capacity((size * size) as usize);
+    for y in -radius..=radius {
+        for x in -radius..=radius {
+            let (x, y) = (x as f32, y as f32);
+            let xr = x * cos + y * sin;
+            let yr = -x * sin + y * cos;
+            let envelope = (-(xr * xr + gamma * gamma * yr * yr) / (2.0 * sigma * sigma)).exp();
+            coefficients.push(envelope * (2.0 * PI * xr / lambda + psi).cos());
+        }
+    }
+
+    let mean = coefficients.iter().sum::<f32>() / coefficients.len() as f32;
+    coefficients.iter_mut().for_each(|c| *c -= mean);
+    coefficients
+}
+
+/// Convolves an image with the Gabor kernel constructed by [`gabor_kernel`]
+/// from the given parameters.
+///
+/// Pads by continuity.
+#[must_use = "the function does not modify the original image"]
+pub fn gabor_filter(
+    image: &GrayImage,
+    size: u32,
+    sigma: f32,
+    theta: f32,
+    lambda: f32,
+    gamma: f32,
+    psi: f32,
+) -> Image<Luma<f32>> {
+    let coefficients = gabor_kernel(size, sigma, theta, lambda, gamma, psi);
+    filter(image, Kernel::new(&coefficients, size, size), |x| x)
+}
+
+/// Constructs a bank of Gabor kernels, one for each combination of an orientation in
+/// `orientations` and a frequency in `frequencies`.
+///
+/// Frequencies are in cycles per pixel, i.e. the reciprocal of the `lambda` parameter of
+/// [`gabor_kernel`]. Kernels are ordered by frequency and then by orientation, so the
+/// kernel for `frequencies[i]` and `orientations[j]` is at index `i * orientations.len() + j`.
+///
+/// # Examples
+/// ```
+/// use image::Luma;
+/// use imageproc::definitions::Image;
+/// use imageproc::filter::{gabor_bank, filter};
+/// use imageproc::kernel::Kernel;
+/// use imageproc::utils::gray_bench_image;
+/// use std::f32::consts::PI;
+///
+/// let orientations = [0.0, PI / 4.0, PI / 2.0, 3.0 * PI / 4.0];
+/// let frequencies = [0.1, 0.2];
+/// let bank = gabor_bank(&orientations, &frequencies, 15, 3.0, 0.5, 0.0);
+/// assert_eq!(bank.len(), 8);
+///
+/// let image = gray_bench_image(50, 50);
+/// let responses: Vec<Image<Luma<f32>>> = bank
+///     .iter()
+///     .map(|k| filter(&image, Kernel::new(k, 15, 15), |x: f32| x))
+///     .collect();
+/// ```
+pub fn gabor_bank(
+    orientations: &[f32],
+    frequencies: &[f32],
+    size: u32,
+    sigma: f32,
+    gamma: f32,
+    psi: f32,
+) -> Vec<Vec<f32>> {
+    frequencies
+        .iter()
+        .flat_map(|&frequency| {
+            orientations
+                .iter()
+                .map(move |&theta| gabor_kernel(size, sigma, theta, frequency.recip(), gamma, psi))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A sinusoidal grating whose intensity varies along direction theta
+    fn grating(size: u32, theta: f32, lambda: f32) -> GrayImage {
+        let (sin, cos) = theta.sin_cos();
+        GrayImage::from_fn(size, size, |x, y| {
+            let t = x as f32 * cos + y as f32 * sin;
+            Luma([(127.5 + 100.0 * (2.0 * PI * t / lambda).cos()).round() as u8])
+        })
+    }
+
+    // Sum of squared responses, ignoring a border affected by padding
+    fn energy(response: &Image<Luma<f32>>, border: u32) -> f32 {
+        let (width, height) = response.dimensions();
+        let mut sum = 0.0;
+        for y in border..height - border {
+            for x in border..width - border {
+                sum += response.get_pixel(x, y)[0].powi(2);
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn test_gabor_kernel_is_dc_free() {
+        for &theta in &[0.0, 0.3, PI / 2.0] {
+            for &psi in &[0.0, PI / 2.0] {
+                let kernel = gabor_kernel(11, 2.5, theta, 6.0, 0.7, psi);
+                assert_eq!(kernel.len(), 121);
+                assert!(kernel.iter().sum::<f32>().abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gabor_filter_of_constant_image_is_zero() {
+        let image = GrayImage::from_pixel(20, 20, Luma([200]));
+        let response = gabor_filter(&image, 9, 2.0, 0.5, 5.0, 1.0, 0.0);
+        assert!(response.iter().all(|v| v.abs() < 1e-2));
+    }
+
+    #[test]
+    fn test_gabor_filter_responds_most_to_tuned_orientation() {
+        let orientations = [0.0, PI / 4.0, PI / 2.0, 3.0 * PI / 4.0];
+        for (i, &grating_theta) in orientations.iter().enumerate() {
+            let image = grating(40, grating_theta, 8.0);
+            let energies: Vec<f32> = orientations
+                .iter()
+                .map(|&theta| energy(&gabor_filter(&image, 15, 3.0, theta, 8.0, 1.0, 0.0), 7))
+                .collect();
+            let best = (0..energies.len())
+                .max_by(|&a, &b| energies[a].partial_cmp(&energies[b]).unwrap())
+                .unwrap();
+            assert_eq!(best, i, "energies: {:?}", energies);
+        }
+    }
+
+    #[test]
+    fn test_gabor_filter_responds_most_to_tuned_frequency() {
+        let frequencies: [f32; 4] = [0.05, 0.1, 0.2, 0.3];
+        for (i, &frequency) in frequencies.iter().enumerate() {
+            let image = grating(40, 0.0, frequency.recip());
+            let energies: Vec<f32> = gabor_bank(&[0.0], &frequencies, 15, 3.0, 1.0, 0.0)
+                .iter()
+                .map(|k| energy(&filter(&image, Kernel::new(k, 15, 15), |x| x), 7))
+                .collect();
+            let best = (0..energies.len())
+                .max_by(|&a, &b| energies[a].partial_cmp(&energies[b]).unwrap())
+                .unwrap();
+            assert_eq!(best, i, "energies: {:?}", energies);
+        }
+    }
+
+    #[test]
+    fn test_gabor_bank_order() {
+        let orientations = [0.0, 1.0, 2.0];
+        let frequencies = [0.1, 0.25];
+        let bank = gabor_bank(&orientations, &frequencies, 7, 2.0, 0.5, 0.3);
+        assert_eq!(bank.len(), 6);
+        assert_eq!(bank[4], gabor_kernel(7, 2.0, 1.0, 4.0, 0.5, 0.3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gabor_kernel_rejects_even_size() {
+        let _ = gabor_kernel(4, 1.0, 0.0, 4.0, 1.0, 0.0);
+    }
+}
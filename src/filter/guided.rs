@@ -0,0 +1,219 @@
+//! Guided image filtering.
+
+use image::{GrayImage, Luma};
+
+/// Applies a guided filter to `input`, using the structure of `guide` to preserve edges.
+///
+/// This is the filter described by He, Sun and Tang in "Guided Image Filtering". Within each
+/// `(2 * radius + 1) x (2 * radius + 1)` window the output is modelled as a linear function
+/// of `guide`, fitted to `input` by least squares and regularised by `epsilon`. The output
+/// at each pixel averages the models of all windows containing it.
+///
+/// Intensities are scaled to `[0, 1]` before filtering, so `epsilon` is interpreted relative
+/// to variances on that scale: image regions whose variance in `guide` is much less than
+/// `epsilon` are smoothed, and regions whose variance is much greater are preserved.
+/// Values in the range `0.001` to `0.04` are typical.
+///
+/// `guide` may be the same image as `input`, in which case this acts as an edge-preserving
+/// smoothing filter. Windows extending past the image boundary are clipped to the image.
+/// Every operation is performed using box filters, so the running time is independent
+/// of `radius`.
+///
+/// If `epsilon` is zero then windows in which `guide` is constant have no well-defined model,
+/// and the output in these windows is taken to be the mean of `input`.
+///
+/// # Panics
+///
+/// If `guide` and `input` have different dimensions, or `epsilon` is negative.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::filter::guided_filter;
+///
+/// let image = gray_image!(
+///     10, 12, 200, 202;
+///     11, 10, 201, 200;
+///     12, 11, 200, 201);
+///
+/// // Self-guided filtering smooths small variations but keeps the edge
+/// let filtered = guided_filter(&image, &image, 1, 0.01);
+/// assert!(filtered.get_pixel(1, 1)[0] < 20);
+/// assert!(filtered.get_pixel(2, 1)[0] > 190);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn guided_filter(guide: &GrayImage, input: &GrayImage, radius: u32, epsilon: f32) -> GrayImage {
+    assert_eq!(
+        guide.dimensions(),
+        input.dimensions(),
+        "guide and input must have the same dimensions"
+    );
+    assert!(epsilon >= 0.0, "epsilon must be non-negative");
+
+    let (width, height) = guide.dimensions();
+    let (w, h, r) = (width as usize, height as usize, radius as usize);
+    let epsilon = epsilon as f64;
+
+    let guide: Vec<f64> = guide.iter().map(|&p| p as f64 / 255.0).collect();
+    let input: Vec<f64> = input.iter().map(|&p| p as f64 / 255.0).collect();
+
+    let mean_guide = box_mean(&guide, w, h, r);
+    let mean_input = box_mean(&input, w, h, r);
+    let products: Vec<f64> = guide.iter().zip(&input).map(|(i, p)| i * p).collect();
+    let mean_product = box_mean(&products, w, h, r);
+    let squares: Vec<f64> = guide.iter().map(|i| i * i).collect();
+    let mean_square = box_mean(&squares, w, h, r);
+
+    let mut a = vec![0f64; w * h];
+    let mut b = vec![0f64; w * h];
+    for k in 0..w * h {
+        let variance = mean_square[k] - mean_guide[k] * mean_guide[k];
+        let covariance = mean_product[k] - mean_guide[k] * mean_input[k];
+        // Variances of non-constant windows are far larger than this, as intensities are
+        // multiples of 1 / 255. Smaller values are rounding errors in constant windows.
+        a[k] = if variance + epsilon > 1e-12 {
+            covariance / (variance + epsilon)
+        } else {
+            0.0
+        };
+        b[k] = mean_input[k] - a[k] * mean_guide[k];
+    }
+
+    let mean_a = box_mean(&a, w, h, r);
+    let mean_b = box_mean(&b, w, h, r);
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let k = y as usize * w + x as usize;
+        let q = mean_a[k] * guide[k] + mean_b[k];
+        Luma([(q * 255.0).round().clamp(0.0, 255.0) as u8])
+    })
+}
+
+/// Returns the mean of `values` over the `(2 * radius + 1) x (2 * radius + 1)` window
+/// centred on each pixel, with windows clipped to the image.
+pub(crate) fn box_mean(values: &[f64], width: usize, height: usize, radius: usize) -> Vec<f64> {
+    let stride = width + 1;
+    let mut integral = vec![0f64; stride * (height + 1)];
+    for y in 0..height {
+        let mut row_sum = 0f64;
+        for x in 0..width {
+            row_sum += values[y * width + x];
+            integral[(y + 1) * stride + x + 1] = integral[y * stride + x + 1] + row_sum;
+        }
+    }
+
+    let mut means = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let y0 = y.saturating_sub(radius);
+        let y1 = (y + radius + 1).min(height);
+        for x in 0..width {
+            let x0 = x.saturating_sub(radius);
+            let x1 = (x + radius + 1).min(width);
+            let sum = integral[y1 * stride + x1]
+                - integral[y0 * stride + x1]
+                - integral[y1 * stride + x0]
+                + integral[y0 * stride + x0];
+            means.push(sum / ((x1 - x0) * (y1 - y0)) as f64);
+        }
+    }
+    means
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::gaussian_noise;
+
+    fn mean_squared_error(a: &GrayImage, b: &GrayImage) -> f64 {
+        let sum: f64 = a
+            .iter()
+            .zip(b.iter())
+            .map(|(&p, &q)| (p as f64 - q as f64).powi(2))
+            .sum();
+        sum / a.len() as f64
+    }
+
+    fn step_image() -> GrayImage {
+        GrayImage::from_fn(40, 30, |x, _| if x < 20 { Luma([50]) } else { Luma([200]) })
+    }
+
+    #[test]
+    fn test_box_mean_clips_windows_at_boundary() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let means = box_mean(&values, 3, 2, 1);
+        assert_eq!(means, vec![3.0, 3.5, 4.0, 3.0, 3.5, 4.0]);
+    }
+
+    #[test]
+    fn test_guided_filter_preserves_constant_image() {
+        let image = GrayImage::from_pixel(10, 10, Luma([123]));
+        assert_pixels_eq!(guided_filter(&image, &image, 3, 0.01), image);
+    }
+
+    #[test]
+    fn test_guided_filter_with_zero_epsilon_is_identity_when_self_guided() {
+        let image = gray_image!(
+            10, 50, 30;
+            200, 0, 90;
+            15, 16, 17);
+        assert_pixels_eq!(guided_filter(&image, &image, 1, 0.0), image);
+    }
+
+    #[test]
+    fn test_guided_filter_with_zero_epsilon_handles_constant_windows() {
+        let flat = GrayImage::from_pixel(8, 8, Luma([77]));
+        assert_pixels_eq!(guided_filter(&flat, &flat, 1, 0.0), flat);
+        assert_pixels_eq!(guided_filter(&flat, &flat, 3, 0.0), flat);
+
+        let step = step_image();
+        assert_pixels_eq!(guided_filter(&step, &step, 1, 0.0), step);
+    }
+
+    #[test]
+    fn test_guided_filter_reduces_noise_keeping_edges_of_guide() {
+        let clean = step_image();
+        let noisy = gaussian_noise(&clean, 0.0, 12.0, 3);
+        let filtered = guided_filter(&clean, &noisy, 4, 0.01);
+
+        let noisy_error = mean_squared_error(&noisy, &clean);
+        let filtered_error = mean_squared_error(&filtered, &clean);
+        assert!(
+            filtered_error < noisy_error / 4.0,
+            "noisy: {}, filtered: {}",
+            noisy_error,
+            filtered_error
+        );
+
+        for y in 0..30 {
+            assert!((filtered.get_pixel(19, y)[0] as i32 - 50).abs() < 15);
+            assert!((filtered.get_pixel(20, y)[0] as i32 - 200).abs() < 15);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_guided_filter_rejects_mismatched_dimensions() {
+        let _ = guided_filter(&GrayImage::new(3, 3), &GrayImage::new(3, 4), 1, 0.01);
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::gray_bench_image;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_guided_filter(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let filtered = guided_filter(&image, &image, 4, 0.01);
+            black_box(filtered);
+        });
+    }
+}
@@ -0,0 +1,229 @@
+//! Kuwahara edge-preserving smoothing.
+
+use crate::definitions::Image;
+use crate::integral_image::{integral_image, integral_squared_image, sum_image_pixels};
+use image::{GrayImage, Luma, Rgb, RgbImage};
+
+/// Applies a Kuwahara filter to a grayscale image.
+///
+/// The `(2 * radius + 1) x (2 * radius + 1)` neighbourhood of each pixel is divided into
+/// four overlapping `(radius + 1) x (radius + 1)` quadrants, each having the pixel as
+/// one of its corners. The output pixel is the mean of the quadrant with the lowest variance.
+///
+/// This smooths flat regions while preserving edges, as the quadrant chosen for a pixel
+/// near an edge is the one lying entirely on the pixel's side of the edge.
+///
+/// Quadrants extending past the image boundary are clamped to the image, so
+/// nothing outside the image contributes to the output.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::filter::kuwahara_filter;
+///
+/// let image = gray_image!(
+///     10, 10, 90, 90;
+///     10, 10, 90, 90;
+///     10, 10, 90, 90);
+///
+/// // Unlike a box filter, the Kuwahara filter preserves the edge
+/// assert_pixels_eq!(kuwahara_filter(&image, 1), image);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn kuwahara_filter(image: &GrayImage, radius: u32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let integral = integral_image::<_, u64>(image);
+    let integral_squared = integral_squared_image::<_, u64>(image);
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let (left, top, right, bottom) =
+            lowest_variance_quadrant(&integral, &integral_squared, x, y, radius);
+        let count = quadrant_area(left, top, right, bottom);
+        let sum = sum_image_pixels(&integral, left, top, right, bottom)[0];
+        Luma([rounded_mean(sum, count)])
+    })
+}
+
+/// Applies a Kuwahara filter to a colour image.
+///
+/// Behaves as [`kuwahara_filter`], except that the quadrant for each pixel is chosen
+/// by the variance of the image's luminance, and the output is the mean colour of the
+/// chosen quadrant.
+#[must_use = "the function does not modify the original image"]
+pub fn kuwahara_filter_rgb(image: &RgbImage, radius: u32) -> RgbImage {
+    let (width, height) = image.dimensions();
+    let luma = image::imageops::grayscale(image);
+    let integral_luma = integral_image::<_, u64>(&luma);
+    let integral_luma_squared = integral_squared_image::<_, u64>(&luma);
+    let integral = integral_image::<_, u64>(image);
+
+    RgbImage::from_fn(width, height, |x, y| {
+        let (left, top, right, bottom) =
+            lowest_variance_quadrant(&integral_luma, &integral_luma_squared, x, y, radius);
+        let count = quadrant_area(left, top, right, bottom);
+        let sum = sum_image_pixels(&integral, left, top, right, bottom);
+        Rgb([
+            rounded_mean(sum[0], count),
+            rounded_mean(sum[1], count),
+            rounded_mean(sum[2], count),
+        ])
+    })
+}
+
+/// Returns the bounds `(left, top, right, bottom)` of the quadrant around `(x, y)` with the
+/// lowest variance, clamped to the image. Ties are broken in favour of the first quadrant
+/// in the order top-left, top-right, bottom-left, bottom-right.
+fn lowest_variance_quadrant(
+    integral: &Image<Luma<u64>>,
+    integral_squared: &Image<Luma<u64>>,
+    x: u32,
+    y: u32,
+    radius: u32,
+) -> (u32, u32, u32, u32) {
+    // Integral images are one pixel larger than their source in each dimension
+    let (width, height) = (integral.width() - 1, integral.height() - 1);
+    let x0 = x.saturating_sub(radius);
+    let x1 = (x + radius).min(width - 1);
+    let y0 = y.saturating_sub(radius);
+    let y1 = (y + radius).min(height - 1);
+
+    let quadrants = [
+        (x0, y0, x, y),
+        (x, y0, x1, y),
+        (x0, y, x, y1),
+        (x, y, x1, y1),
+    ];
+
+    let mut best = quadrants[0];
+    let mut best_variance = f64::INFINITY;
+    for &(left, top, right, bottom) in &quadrants {
+        let count = quadrant_area(left, top, right, bottom) as f64;
+        let sum = sum_image_pixels(integral, left, top, right, bottom)[0] as f64;
+        let sum_squared = sum_image_pixels(integral_squared, left, top, right, bottom)[0] as f64;
+        let mean = sum / count;
+        let variance = sum_squared / count - mean * mean;
+        if variance < best_variance {
+            best = (left, top, right, bottom);
+            best_variance = variance;
+        }
+    }
+    best
+}
+
+fn quadrant_area(left: u32, top: u32, right: u32, bottom: u32) -> u64 {
+    (right - left + 1) as u64 * (bottom - top + 1) as u64
+}
+
+fn rounded_mean(sum: u64, count: u64) -> u8 {
+    ((sum + count / 2) / count) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::box_filter;
+
+    fn diagonal_edge_image(size: u32) -> GrayImage {
+        GrayImage::from_fn(
+            size,
+            size,
+            |x, y| if x > y { Luma([200]) } else { Luma([40]) },
+        )
+    }
+
+    fn sum_absolute_difference(a: &GrayImage, b: &GrayImage) -> u64 {
+        a.iter()
+            .zip(b.iter())
+            .map(|(&p, &q)| (p as i64 - q as i64).unsigned_abs())
+            .sum()
+    }
+
+    #[test]
+    fn test_kuwahara_filter_radius_zero_is_identity() {
+        let image = gray_image!(
+            1, 2, 3;
+            4, 5, 6);
+        assert_pixels_eq!(kuwahara_filter(&image, 0), image);
+    }
+
+    #[test]
+    fn test_kuwahara_filter_picks_lowest_variance_quadrant() {
+        let image = gray_image!(
+            0, 0, 50;
+            0, 7, 90;
+            80, 60, 70);
+        let filtered = kuwahara_filter(&image, 1);
+        // The centre pixel's top-left quadrant {0, 0, 0, 7} has the lowest variance
+        assert_eq!(filtered.get_pixel(1, 1)[0], 2);
+    }
+
+    #[test]
+    fn test_kuwahara_filter_preserves_diagonal_edge_better_than_box_filter() {
+        let image = diagonal_edge_image(20);
+        let kuwahara = kuwahara_filter(&image, 2);
+        let boxed = box_filter(&image, 2, 2);
+
+        let kuwahara_error = sum_absolute_difference(&kuwahara, &image);
+        let box_error = sum_absolute_difference(&boxed, &image);
+        assert!(
+            kuwahara_error * 4 < box_error,
+            "kuwahara: {}, box: {}",
+            kuwahara_error,
+            box_error
+        );
+    }
+
+    #[test]
+    fn test_kuwahara_filter_rgb_averages_colour_of_chosen_quadrant() {
+        let image = rgb_image!(
+            [10, 20, 30], [10, 20, 30], [200, 0, 0];
+            [10, 20, 30], [12, 22, 32], [0, 200, 0];
+            [0, 0, 200], [200, 200, 0], [0, 200, 200]);
+        let filtered = kuwahara_filter_rgb(&image, 1);
+        assert_eq!(filtered.get_pixel(1, 1), &Rgb([11, 21, 31]));
+    }
+
+    #[test]
+    fn test_kuwahara_filter_rgb_matches_grayscale_on_gray_input() {
+        let gray = diagonal_edge_image(10);
+        let rgb = RgbImage::from_fn(10, 10, |x, y| {
+            let v = gray.get_pixel(x, y)[0];
+            Rgb([v, v, v])
+        });
+        let filtered = kuwahara_filter_rgb(&rgb, 2);
+        let expected = kuwahara_filter(&gray, 2);
+        for (x, y, p) in filtered.enumerate_pixels() {
+            assert_eq!(p[0], expected.get_pixel(x, y)[0]);
+        }
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::{gray_bench_image, rgb_bench_image};
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_kuwahara_filter(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let filtered = kuwahara_filter(&image, 4);
+            black_box(filtered);
+        });
+    }
+
+    #[bench]
+    fn bench_kuwahara_filter_rgb(b: &mut Bencher) {
+        let image = rgb_bench_image(200, 200);
+        b.iter(|| {
+            let filtered = kuwahara_filter_rgb(&image, 4);
+            black_box(filtered);
+        });
+    }
+}
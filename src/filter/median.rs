@@ -7,6 +7,10 @@ use std::cmp::{max, min};
 ///
 /// Pads by continuity. Performs O(max(x_radius, y_radius)) operations per pixel.
 ///
+/// This uses Huang's sliding-window histogram algorithm: a histogram of the kernel
+/// contents is updated incrementally as the kernel moves across the image, so the
+/// filter remains practical for large kernel radii.
+///
 /// # Examples
 /// ```
 /// # extern crate image;
@@ -406,6 +410,8 @@ mod benches {
     bench_median_filter!(bench_median_filter_s100_r1, side: 100, x_radius: 1,y_radius: 1);
     bench_median_filter!(bench_median_filter_s100_r4, side: 100, x_radius: 4,y_radius: 4);
     bench_median_filter!(bench_median_filter_s100_r8, side: 100, x_radius: 8,y_radius: 8);
+    bench_median_filter!(bench_median_filter_s100_r16, side: 100, x_radius: 16,y_radius: 16);
+    bench_median_filter!(bench_median_filter_s100_r32, side: 100, x_radius: 32,y_radius: 32);
 
     // benchmark on non-square kernels
     bench_median_filter!(bench_median_filter_s100_rx1_ry4, side: 100, x_radius: 1,y_radius: 4);
@@ -486,4 +492,21 @@ mod tests {
         }
         quickcheck(prop as fn(GrayTestImage, u32, u32) -> TestResult);
     }
+
+    #[cfg_attr(miri, ignore = "slow")]
+    #[test]
+    fn test_median_filter_matches_reference_implementation_for_large_radii() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for &(x_radius, y_radius) in &[(6, 6), (10, 3), (2, 12), (15, 15), (40, 1)] {
+            // Include images smaller than the kernel to exercise border handling
+            for &(width, height) in &[(37, 29), (8, 50), (5, 5)] {
+                let image = GrayImage::from_fn(width, height, |_, _| Luma([rng.gen()]));
+                let expected = reference_median_filter(&image, x_radius, y_radius);
+                let actual = median_filter(&image, x_radius, y_radius);
+                assert_pixels_eq!(actual, expected);
+            }
+        }
+    }
 }
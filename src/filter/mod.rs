@@ -1,20 +1,39 @@
 //! Functions for filtering images.
 
 pub mod bilateral;
+mod frangi;
+pub use self::frangi::frangi_vesselness;
+
+mod gabor;
+pub use self::gabor::{gabor_bank, gabor_filter, gabor_kernel};
+
+mod guided;
+pub(crate) use self::guided::box_mean;
+pub use self::guided::guided_filter;
+
+mod kuwahara;
+pub use self::kuwahara::{kuwahara_filter, kuwahara_filter_rgb};
+
 mod median;
 pub use self::bilateral::bilateral_filter;
 pub use self::median::median_filter;
 
+mod nl_means;
+pub use self::nl_means::nl_means_denoise;
+
 mod sharpen;
 pub use self::sharpen::*;
 
 use image::{GenericImage, GenericImageView, GrayImage, Luma, Pixel, Primitive};
 use itertools::Itertools;
 
+use crate::border::{pad, BorderMode};
 use crate::definitions::{Clamp, Image};
-use crate::integral_image::{column_running_sum, row_running_sum};
+use crate::integral_image::{
+    column_running_sum, integral_image, row_running_sum, sum_image_pixels,
+};
 use crate::kernel::{self, Kernel};
-use crate::map::{ChannelMap, WithChannel};
+use crate::map::{map_subpixels, ChannelMap, WithChannel};
 use num::Num;
 
 use std::cmp::{max, min};
@@ -84,6 +103,56 @@ pub fn box_filter(image: &GrayImage, x_radius: u32, y_radius: u32) -> Image<Luma
     out
 }
 
+/// Computes the mean of each (2 * `x_radius` + 1) by (2 * `y_radius` + 1) window of an 8bpp
+/// grayscale image, using an integral image so that the cost per pixel is independent of the
+/// radii.
+///
+/// Unlike [`box_filter`], windows extending past the image boundary are clipped to the image,
+/// and each output pixel is the mean of the input pixels within its clipped window, rounded
+/// to the nearest integer. The result is computed from an exact integer sum, so there is no
+/// accumulated rounding error.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::filter::box_filter_integral;
+///
+/// let image = gray_image!(
+///     1, 2, 3;
+///     4, 5, 6;
+///     7, 8, 9);
+///
+/// // The top left window contains the pixels 1, 2, 4 and 5.
+/// let expected = gray_image!(
+///     3, 4, 4;
+///     5, 5, 6;
+///     6, 7, 7);
+///
+/// assert_pixels_eq!(box_filter_integral(&image, 1, 1), expected);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn box_filter_integral(image: &GrayImage, x_radius: u32, y_radius: u32) -> GrayImage {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return GrayImage::new(width, height);
+    }
+
+    let integral = integral_image::<_, u64>(image);
+    GrayImage::from_fn(width, height, |x, y| {
+        let left = x.saturating_sub(x_radius);
+        let right = x.saturating_add(x_radius).min(width - 1);
+        let top = y.saturating_sub(y_radius);
+        let bottom = y.saturating_add(y_radius).min(height - 1);
+        let sum = sum_image_pixels(&integral, left, top, right, bottom)[0];
+        let area = (right - left + 1) as u64 * (bottom - top + 1) as u64;
+        Luma([((2 * sum + area) / (2 * area)) as u8])
+    })
+}
+
 /// Calculates the new pixel value for a particular pixel and kernel.
 fn filter_pixel<P, K, F, Q>(x: u32, y: u32, kernel: Kernel<K>, f: F, image: &Image<P>) -> Q
 where
@@ -138,6 +207,8 @@ where
 
 /// Returns 2d correlation of an image. Intermediate calculations are performed
 /// at type K, and the results converted to pixel Q via f. Pads by continuity.
+///
+/// Use [`filter_with_border`] to handle the image borders differently.
 pub fn filter<P, K, F, Q>(image: &Image<P>, kernel: Kernel<K>, f: F) -> Image<Q>
 where
     P: Pixel,
@@ -159,6 +230,49 @@ where
 
     out
 }
+
+/// Returns 2d correlation of an image, with pixels outside the image chosen according to
+/// `mode`. Intermediate calculations are performed at type K, and the results converted to
+/// pixel Q via f.
+///
+/// [`filter`] is equivalent to this function with [`BorderMode::Replicate`]. Returns an
+/// empty image if `image` is empty, whatever the value of `mode`.
+pub fn filter_with_border<P, K, F, Q>(
+    image: &Image<P>,
+    kernel: Kernel<K>,
+    f: F,
+    mode: BorderMode<P>,
+) -> Image<Q>
+where
+    P: Pixel,
+    Q: Pixel,
+    F: Fn(K) -> Q::Subpixel,
+    K: num::Num + Copy + From<P::Subpixel>,
+{
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Image::new(width, height);
+    }
+
+    let (left, top) = (kernel.width / 2, kernel.height / 2);
+    let padded = pad(
+        image,
+        top,
+        kernel.height - 1 - top,
+        left,
+        kernel.width - 1 - left,
+        mode,
+    );
+
+    let mut out = Image::<Q>::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            // The kernel never extends past the border of the padded image
+            out.put_pixel(x, y, filter_pixel(x + left, y + top, kernel, &f, &padded));
+        }
+    }
+    out
+}
 #[cfg(feature = "rayon")]
 #[doc = generate_parallel_doc_comment!("filter")]
 pub fn filter_parallel<P, K, F, Q>(image: &Image<P>, kernel: Kernel<K>, f: F) -> Image<Q>
@@ -215,6 +329,9 @@ fn gaussian_kernel_f32(sigma: f32) -> Vec<f32> {
 /// The kernel used has type f32 and all intermediate calculations are performed
 /// at this type.
 ///
+/// A parallelized version of this function exists with [`gaussian_blur_f32_parallel`] when
+/// the crate `rayon` feature is enabled.
+///
 /// # Panics
 ///
 /// Panics if `sigma <= 0.0`.
@@ -229,6 +346,18 @@ where
     let kernel = gaussian_kernel_f32(sigma);
     separable_filter_equal(image, &kernel)
 }
+#[cfg(feature = "rayon")]
+#[doc = generate_parallel_doc_comment!("gaussian_blur_f32")]
+#[must_use = "the function does not modify the original image"]
+pub fn gaussian_blur_f32_parallel<P>(image: &Image<P>, sigma: f32) -> Image<P>
+where
+    P: Pixel + Sync,
+    <P as Pixel>::Subpixel: Into<f32> + Clamp<f32> + Send + Sync,
+{
+    assert!(sigma > 0.0, "sigma must be > 0.0");
+    let kernel = gaussian_kernel_f32(sigma);
+    separable_filter_equal_parallel(image, &kernel)
+}
 
 /// Returns 2d correlation of view with the outer product of the 1d
 /// kernels `h_kernel` and `v_kernel`.
@@ -248,6 +377,24 @@ where
     let h = horizontal_filter(image, h_kernel);
     vertical_filter(&h, v_kernel)
 }
+#[cfg(feature = "rayon")]
+#[doc = generate_parallel_doc_comment!("separable_filter")]
+#[must_use = "the function does not modify the original image"]
+pub fn separable_filter_parallel<P, K>(image: &Image<P>, h_kernel: &[K], v_kernel: &[K]) -> Image<P>
+where
+    P: Pixel + Sync,
+    <P as Pixel>::Subpixel: Into<K> + Clamp<K> + Send + Sync,
+    K: Num + Copy + Sync,
+{
+    assert_eq!(
+        h_kernel.len(),
+        v_kernel.len(),
+        "the two 1D kernels must be the same length"
+    );
+
+    let h = horizontal_filter_parallel(image, h_kernel);
+    vertical_filter_parallel(&h, v_kernel)
+}
 
 /// Returns 2d correlation of an image with the outer product of the 1d
 /// kernel filter with itself.
@@ -260,12 +407,25 @@ where
 {
     separable_filter(image, kernel, kernel)
 }
+#[cfg(feature = "rayon")]
+#[doc = generate_parallel_doc_comment!("separable_filter_equal")]
+#[must_use = "the function does not modify the original image"]
+pub fn separable_filter_equal_parallel<P, K>(image: &Image<P>, kernel: &[K]) -> Image<P>
+where
+    P: Pixel + Sync,
+    <P as Pixel>::Subpixel: Into<K> + Clamp<K> + Send + Sync,
+    K: Num + Copy + Sync,
+{
+    separable_filter_parallel(image, kernel, kernel)
+}
 
 /// Returns 2d correlation of an image with a row-major kernel. Intermediate calculations are
 /// performed at type K, and the results clamped to subpixel type S. Pads by continuity.
 ///
 /// A parallelized version of this function exists with [`filter_clamped_parallel`] when
-/// the crate `rayon` feature is enabled.
+/// the crate `rayon` feature is enabled. For `f32` kernels, [`filter_clamped_f32`] is
+/// much faster for large separable kernels. Use [`filter_clamped_with_border`] to handle
+/// the image borders differently.
 pub fn filter_clamped<P, K, S>(image: &Image<P>, kernel: Kernel<K>) -> Image<ChannelMap<P, S>>
 where
     P::Subpixel: Into<K>,
@@ -275,6 +435,109 @@ where
 {
     filter(image, kernel, S::clamp)
 }
+
+/// Returns 2d correlation of an image with a row-major kernel, with pixels outside the image
+/// chosen according to `mode`. Intermediate calculations are performed at type K, and the
+/// results clamped to subpixel type S.
+///
+/// [`filter_clamped`] is equivalent to this function with [`BorderMode::Replicate`]. Use
+/// [`BorderMode::Wrap`] for periodic signals, or [`BorderMode::Reflect`] to avoid darkening
+/// the edges of an image when blurring.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use image::{GrayImage, Luma};
+/// use imageproc::border::BorderMode;
+/// use imageproc::filter::filter_clamped_with_border;
+/// use imageproc::kernel::Kernel;
+///
+/// let image = gray_image!(0, 0, 0, 30);
+/// let kernel = Kernel::new(&[1i32, 1, 1], 3, 1);
+///
+/// let wrapped: GrayImage = filter_clamped_with_border(&image, kernel, BorderMode::Wrap);
+/// assert_pixels_eq!(wrapped, gray_image!(30, 0, 30, 30));
+///
+/// let zero_padded: GrayImage =
+///     filter_clamped_with_border(&image, kernel, BorderMode::Constant(Luma([0])));
+/// assert_pixels_eq!(zero_padded, gray_image!(0, 0, 30, 30));
+/// # }
+/// ```
+pub fn filter_clamped_with_border<P, K, S>(
+    image: &Image<P>,
+    kernel: Kernel<K>,
+    mode: BorderMode<P>,
+) -> Image<ChannelMap<P, S>>
+where
+    P::Subpixel: Into<K>,
+    S: Clamp<K> + Primitive,
+    P: WithChannel<S>,
+    K: Num + Copy + From<<P as image::Pixel>::Subpixel>,
+{
+    filter_with_border(image, kernel, S::clamp, mode)
+}
+
+/// Returns 2d correlation of an image with a row-major `f32` kernel, with the results
+/// clamped to subpixel type S. Pads by continuity.
+///
+/// Equivalent to [`filter_clamped`], except that kernels larger than 3x3 are checked
+/// for separability using [`try_separate`](crate::kernel::try_separate). Separable
+/// kernels are applied as a horizontal pass followed by a vertical pass, which takes
+/// O(width + height) operations per pixel rather than O(width * height). Results may
+/// differ from [`filter_clamped`] due to floating-point rounding.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use image::GrayImage;
+/// use imageproc::filter::filter_clamped_f32;
+/// use imageproc::kernel::Kernel;
+///
+/// let image = gray_image!(
+///     0, 0, 0, 0, 0;
+///     0, 0, 0, 0, 0;
+///     0, 0, 250, 0, 0;
+///     0, 0, 0, 0, 0;
+///     0, 0, 0, 0, 0);
+///
+/// // The outer product of [0.2; 5] with itself
+/// let data = [0.04; 25];
+/// let filtered: GrayImage = filter_clamped_f32(&image, Kernel::new(&data, 5, 5));
+///
+/// assert_pixels_eq!(filtered, GrayImage::from_pixel(5, 5, image::Luma([10])));
+/// # }
+/// ```
+pub fn filter_clamped_f32<P, S>(image: &Image<P>, kernel: Kernel<f32>) -> Image<ChannelMap<P, S>>
+where
+    S: Clamp<f32> + Primitive,
+    P: WithChannel<S> + WithChannel<f32>,
+    f32: From<P::Subpixel>,
+{
+    if kernel.width <= 3 && kernel.height <= 3 {
+        return filter(image, kernel, S::clamp);
+    }
+    match kernel::try_separate(&kernel) {
+        Some((row, col)) => {
+            let image = map_subpixels(image, f32::from);
+            let filtered = vertical_filter(&horizontal_filter(&image, &row), &col);
+            let (width, height) = filtered.dimensions();
+            Image::from_vec(
+                width,
+                height,
+                filtered.into_raw().into_iter().map(S::clamp).collect(),
+            )
+            .unwrap()
+        }
+        None => filter(image, kernel, S::clamp),
+    }
+}
+
 #[cfg(feature = "rayon")]
 #[doc = generate_parallel_doc_comment!("filter_clamped")]
 pub fn filter_clamped_parallel<P, K, S>(
@@ -497,6 +760,82 @@ where
     out
 }
 
+#[cfg(feature = "rayon")]
+#[doc = generate_parallel_doc_comment!("horizontal_filter")]
+#[must_use = "the function does not modify the original image"]
+pub fn horizontal_filter_parallel<P, K>(image: &Image<P>, kernel: &[K]) -> Image<P>
+where
+    P: Pixel + Sync,
+    <P as Pixel>::Subpixel: Into<K> + Clamp<K> + Send + Sync,
+    K: Num + Copy + Sync,
+{
+    let max_x = image.width() as i32 - 1;
+    filter_rows_parallel(image, kernel, |x, y, offset| {
+        ((x as i32 + offset).clamp(0, max_x) as u32, y)
+    })
+}
+
+#[cfg(feature = "rayon")]
+#[doc = generate_parallel_doc_comment!("vertical_filter")]
+#[must_use = "the function does not modify the original image"]
+pub fn vertical_filter_parallel<P, K>(image: &Image<P>, kernel: &[K]) -> Image<P>
+where
+    P: Pixel + Sync,
+    <P as Pixel>::Subpixel: Into<K> + Clamp<K> + Send + Sync,
+    K: Num + Copy + Sync,
+{
+    let max_y = image.height() as i32 - 1;
+    filter_rows_parallel(image, kernel, |x, y, offset| {
+        (x, (y as i32 + offset).clamp(0, max_y) as u32)
+    })
+}
+
+// Correlates an image with a 1d kernel, computing each output row in parallel.
+// source(x, y, offset) gives the coordinates of the input pixel weighted by the kernel entry
+// at the given offset from the kernel centre when computing output pixel (x, y). Entries are
+// accumulated in the same order as in horizontal_filter and vertical_filter, so the results
+// are identical to theirs.
+#[cfg(feature = "rayon")]
+fn filter_rows_parallel<P, K, F>(image: &Image<P>, kernel: &[K], source: F) -> Image<P>
+where
+    P: Pixel + Sync,
+    <P as Pixel>::Subpixel: Into<K> + Clamp<K> + Send + Sync,
+    K: Num + Copy + Sync,
+    F: Fn(u32, u32, i32) -> (u32, u32) + Sync,
+{
+    use rayon::prelude::*;
+
+    let (width, height) = image.dimensions();
+    let mut out = Image::<P>::new(width, height);
+    if width == 0 || height == 0 {
+        return out;
+    }
+
+    let channels = P::CHANNEL_COUNT as usize;
+    let half_k = kernel.len() as i32 / 2;
+
+    out.par_chunks_mut(width as usize * channels)
+        .enumerate()
+        .for_each(|(y, row)| {
+            let zero = K::zero();
+            let mut acc = vec![zero; channels];
+            for (x, out_channels) in row.chunks_exact_mut(channels).enumerate() {
+                for (i, k) in kernel.iter().enumerate() {
+                    let (x_p, y_p) = source(x as u32, y as u32, i as i32 - half_k);
+                    debug_assert!(image.in_bounds(x_p, y_p));
+                    let p = unsafe { image.unsafe_get_pixel(x_p, y_p) };
+                    accumulate(&mut acc, &p, *k);
+                }
+                for (a, c) in acc.iter_mut().zip(out_channels.iter_mut()) {
+                    *c = <P as Pixel>::Subpixel::clamp(*a);
+                    *a = zero;
+                }
+            }
+        });
+
+    out
+}
+
 fn accumulate<P, K>(acc: &mut [K], pixel: &P, weight: K)
 where
     P: Pixel,
@@ -561,6 +900,77 @@ mod tests {
 
         assert_pixels_eq!(box_filter(&image, 1, 1), expected);
     }
+
+    // Computes each window mean by direct summation over the in-bounds pixels.
+    fn box_filter_naive(image: &GrayImage, x_radius: u32, y_radius: u32) -> GrayImage {
+        let (width, height) = image.dimensions();
+        GrayImage::from_fn(width, height, |x, y| {
+            let (mut sum, mut count) = (0u32, 0u32);
+            for wy in y.saturating_sub(y_radius)..(y + y_radius + 1).min(height) {
+                for wx in x.saturating_sub(x_radius)..(x + x_radius + 1).min(width) {
+                    sum += image.get_pixel(wx, wy)[0] as u32;
+                    count += 1;
+                }
+            }
+            Luma([((sum as f64 / count as f64).round()) as u8])
+        })
+    }
+
+    #[test]
+    fn test_box_filter_integral_matches_naive_box_filter() {
+        let image = gray_bench_image(37, 23);
+        for (x_radius, y_radius) in [(0, 0), (1, 1), (2, 5), (4, 0), (7, 3), (30, 40)] {
+            assert_pixels_eq!(
+                box_filter_integral(&image, x_radius, y_radius),
+                box_filter_naive(&image, x_radius, y_radius)
+            );
+        }
+    }
+
+    #[test]
+    fn test_box_filter_integral_with_huge_radii_averages_whole_image() {
+        let image = gray_image!(
+            10, 20, 30;
+            40, 50, 60);
+        assert_pixels_eq!(
+            box_filter_integral(&image, u32::MAX, u32::MAX),
+            GrayImage::from_pixel(3, 2, Luma([35]))
+        );
+        assert_pixels_eq!(
+            box_filter_integral(&image, u32::MAX, 0),
+            gray_image!(
+                20, 20, 20;
+                50, 50, 50)
+        );
+    }
+
+    #[test]
+    fn test_box_filter_integral_averages_borders_over_valid_pixels() {
+        let image = gray_image!(
+            90, 0, 0, 0;
+            0, 0, 0, 0;
+            0, 0, 0, 60);
+
+        // Corner windows contain 4 pixels, edge windows 6 and interior windows 9.
+        let expected = gray_image!(
+            23, 15, 0, 0;
+            15, 10, 7, 10;
+            0, 0, 10, 15);
+
+        assert_pixels_eq!(box_filter_integral(&image, 1, 1), expected);
+        assert_pixels_eq!(
+            box_filter_integral(&GrayImage::from_pixel(5, 4, Luma([77])), 3, 2),
+            GrayImage::from_pixel(5, 4, Luma([77]))
+        );
+    }
+
+    #[test]
+    fn test_box_filter_integral_handles_empty_images() {
+        let _ = box_filter_integral(&GrayImage::new(0, 0), 3, 3);
+        let _ = box_filter_integral(&GrayImage::new(1, 0), 3, 3);
+        let _ = box_filter_integral(&GrayImage::new(0, 1), 3, 3);
+    }
+
     #[test]
     fn test_separable_filter() {
         let image = gray_image!(
@@ -580,6 +990,138 @@ mod tests {
         assert_pixels_eq!(filtered, expected);
     }
 
+    pub(super) fn gaussian_kernel_2d(sigma: f32) -> (Vec<f32>, u32) {
+        let kernel_1d = gaussian_kernel_f32(sigma);
+        let data = kernel_1d
+            .iter()
+            .flat_map(|c| kernel_1d.iter().map(move |r| r * c))
+            .collect();
+        (data, kernel_1d.len() as u32)
+    }
+
+    #[test]
+    fn test_gaussian_blur_with_reflected_border_preserves_constant_image() {
+        let image = GrayImage::from_pixel(20, 15, Luma([200]));
+        let (data, side) = gaussian_kernel_2d(2.0);
+        let kernel = Kernel::new(&data, side, side);
+
+        let reflected: Image<Luma<f32>> =
+            filter_clamped_with_border(&image, kernel, BorderMode::Reflect);
+        for p in reflected.iter() {
+            assert!((p - 200.0).abs() < 1e-3, "{}", p);
+        }
+
+        // Zero padding darkens pixels near the border
+        let zero_padded: Image<Luma<f32>> =
+            filter_clamped_with_border(&image, kernel, BorderMode::Constant(Luma([0])));
+        assert!(zero_padded.get_pixel(0, 0)[0] < 100.0);
+        assert!(zero_padded.get_pixel(10, 0)[0] < 150.0);
+        assert!((zero_padded.get_pixel(10, 7)[0] - 200.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_filter_with_replicated_border_matches_filter() {
+        let image = gray_bench_image(23, 17);
+        let data: Vec<i32> = (0..15).map(|i| i % 4 - 1).collect();
+        let kernel = Kernel::new(&data, 5, 3);
+        let expected: Image<Luma<i16>> = filter_clamped(&image, kernel);
+        let actual: Image<Luma<i16>> =
+            filter_clamped_with_border(&image, kernel, BorderMode::Replicate);
+        assert_pixels_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_filter_with_border_of_empty_image_is_empty() {
+        let kernel = Kernel::new(&[1i32, 2, 1, 2, 4, 2, 1, 2, 1], 3, 3);
+        for mode in [
+            BorderMode::Constant(Luma([7])),
+            BorderMode::Replicate,
+            BorderMode::Reflect,
+            BorderMode::Reflect101,
+            BorderMode::Wrap,
+        ] {
+            for (width, height) in [(0, 0), (0, 4), (4, 0)] {
+                let image = GrayImage::new(width, height);
+                let filtered: GrayImage = filter_clamped_with_border(&image, kernel, mode);
+                assert_eq!(filtered.dimensions(), (width, height), "{:?}", mode);
+                let filtered: Image<Luma<i32>> = filter_with_border(&image, kernel, |x| x, mode);
+                assert_eq!(filtered.dimensions(), (width, height), "{:?}", mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_filter_with_border_uses_kernel_anchor_of_filter() {
+        // Kernels of even size are anchored at (width / 2, height / 2), as in filter
+        let image = gray_image!(
+            1, 2, 3;
+            4, 5, 6);
+        let kernel = Kernel::new(&[1i32, 0, 0, 0], 2, 2);
+        let shifted: GrayImage =
+            filter_clamped_with_border(&image, kernel, BorderMode::Constant(Luma([9])));
+        let expected = gray_image!(
+            9, 9, 9;
+            9, 1, 2);
+        assert_pixels_eq!(shifted, expected);
+        assert_pixels_eq!(
+            filter_clamped_with_border::<_, _, u8>(&image, kernel, BorderMode::Replicate),
+            filter_clamped::<_, _, u8>(&image, kernel)
+        );
+    }
+
+    #[test]
+    fn test_separated_gaussian_matches_2d_filter() {
+        let image = GrayImage::from_fn(30, 25, |x, y| Luma([((x * 37 + y * 91) % 256) as u8]));
+        let (data, side) = gaussian_kernel_2d(3.0);
+        let kernel = Kernel::new(&data, side, side);
+
+        let (row, col) = kernel::try_separate(&kernel).unwrap();
+        let image_f32 = map_subpixels(&image, f32::from);
+        let separated = vertical_filter(&horizontal_filter(&image_f32, &row), &col);
+        let full: Image<Luma<f32>> = filter(&image, kernel, |x| x);
+        for (a, b) in separated.iter().zip(full.iter()) {
+            assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+        }
+
+        let clamped: GrayImage = filter_clamped_f32(&image, kernel);
+        let expected: GrayImage = filter_clamped(&image, kernel);
+        for (a, b) in clamped.iter().zip(expected.iter()) {
+            assert!((*a as i32 - *b as i32).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_filter_clamped_f32_non_separable_kernel() {
+        let image = GrayImage::from_fn(12, 9, |x, y| Luma([(x * 20 + y * 3) as u8]));
+        let mut data = [0.0f32; 25];
+        data[0] = 0.5;
+        data[24] = 0.5;
+        data[12] = -0.25;
+        let kernel = Kernel::new(&data, 5, 5);
+        assert!(kernel::try_separate(&kernel).is_none());
+
+        let actual: GrayImage = filter_clamped_f32(&image, kernel);
+        let expected: GrayImage = filter_clamped(&image, kernel);
+        assert_pixels_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_filter_clamped_f32_keeps_negative_intermediate_values() {
+        // A horizontal gradient smoothed vertically - the horizontal pass is negative
+        // for a decreasing image, so must not be clamped before the vertical pass
+        let image = GrayImage::from_fn(10, 10, |x, _| Luma([200 - 10 * x as u8]));
+        let row = [-1.0, -1.0, 0.0, 1.0, 1.0];
+        let col = [-0.2; 5];
+        let data: Vec<f32> = col
+            .iter()
+            .flat_map(|c| row.iter().map(move |r| r * c))
+            .collect();
+        let kernel = Kernel::new(&data, 5, 5);
+
+        let actual: GrayImage = filter_clamped_f32(&image, kernel);
+        assert_eq!(actual.get_pixel(5, 5)[0], 60);
+    }
+
     #[test]
     fn test_separable_filter_integer_kernel() {
         let image = gray_image!(
@@ -776,6 +1318,47 @@ mod tests {
         assert_pixels_eq!(filtered, expected);
     }
 
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_gaussian_blur_f32_parallel_matches_serial() {
+        use crate::utils::rgb_bench_image;
+
+        let rgb = rgb_bench_image(67, 41);
+        for sigma in [0.5, 1.7, 4.0, 30.0] {
+            assert_pixels_eq!(
+                gaussian_blur_f32_parallel(&rgb, sigma),
+                gaussian_blur_f32(&rgb, sigma)
+            );
+        }
+
+        let gray = gray_bench_image(52, 73);
+        let float = map_subpixels(&gray, f32::from);
+        assert_pixels_eq!(
+            gaussian_blur_f32_parallel(&float, 2.3),
+            gaussian_blur_f32(&float, 2.3)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_separable_filter_parallel_matches_serial() {
+        let image = gray_bench_image(31, 17);
+        let h_kernel = [1i32, 4, -2, 3];
+        let v_kernel = [2i32, -1, 0, 5];
+        assert_pixels_eq!(
+            separable_filter_parallel(&image, &h_kernel, &v_kernel),
+            separable_filter(&image, &h_kernel, &v_kernel)
+        );
+        assert_pixels_eq!(
+            horizontal_filter_parallel(&image, &[1i32; 40]),
+            horizontal_filter(&image, &[1i32; 40])
+        );
+        assert_pixels_eq!(
+            vertical_filter_parallel(&image, &[1i32; 40]),
+            vertical_filter(&image, &[1i32; 40])
+        );
+    }
+
     #[test]
     #[cfg(feature = "rayon")]
     fn test_filter_clamped_parallel_with_results_outside_input_channel_range() {
@@ -965,6 +1548,7 @@ mod proptests {
 #[cfg(not(miri))]
 #[cfg(test)]
 mod benches {
+    use super::tests::gaussian_kernel_2d;
     use super::*;
     use crate::definitions::Image;
     use crate::utils::{gray_bench_image, rgb_bench_image};
@@ -981,6 +1565,15 @@ mod benches {
         });
     }
 
+    #[bench]
+    fn bench_box_filter_integral(b: &mut Bencher) {
+        let image = gray_bench_image(500, 500);
+        b.iter(|| {
+            let filtered = box_filter_integral(&image, 7, 7);
+            black_box(filtered);
+        });
+    }
+
     #[bench]
     fn bench_separable_filter(b: &mut Bencher) {
         let image = gray_bench_image(300, 300);
@@ -992,6 +1585,46 @@ mod benches {
         });
     }
 
+    #[bench]
+    fn bench_filter_clamped_gaussian_7x7(b: &mut Bencher) {
+        let image = gray_bench_image(300, 300);
+        let (data, side) = gaussian_kernel_2d(1.5);
+        b.iter(|| {
+            let filtered: GrayImage = filter_clamped(&image, Kernel::new(&data, side, side));
+            black_box(filtered);
+        });
+    }
+
+    #[bench]
+    fn bench_filter_clamped_f32_gaussian_7x7(b: &mut Bencher) {
+        let image = gray_bench_image(300, 300);
+        let (data, side) = gaussian_kernel_2d(1.5);
+        b.iter(|| {
+            let filtered: GrayImage = filter_clamped_f32(&image, Kernel::new(&data, side, side));
+            black_box(filtered);
+        });
+    }
+
+    #[bench]
+    fn bench_filter_clamped_gaussian_21x21(b: &mut Bencher) {
+        let image = gray_bench_image(300, 300);
+        let (data, side) = gaussian_kernel_2d(5.0);
+        b.iter(|| {
+            let filtered: GrayImage = filter_clamped(&image, Kernel::new(&data, side, side));
+            black_box(filtered);
+        });
+    }
+
+    #[bench]
+    fn bench_filter_clamped_f32_gaussian_21x21(b: &mut Bencher) {
+        let image = gray_bench_image(300, 300);
+        let (data, side) = gaussian_kernel_2d(5.0);
+        b.iter(|| {
+            let filtered: GrayImage = filter_clamped_f32(&image, Kernel::new(&data, side, side));
+            black_box(filtered);
+        });
+    }
+
     #[bench]
     fn bench_horizontal_filter(b: &mut Bencher) {
         let image = gray_bench_image(500, 500);
@@ -1117,4 +1750,27 @@ mod benches {
             black_box(blurred);
         });
     }
+
+    const LARGE_GAUSSIAN_SIDE: u32 = 4000;
+
+    #[bench]
+    #[ignore] // Slow on a large image. Compare with bench_gaussian_f32_parallel_large_image
+    fn bench_gaussian_f32_large_image(b: &mut Bencher) {
+        let image = gray_bench_image(LARGE_GAUSSIAN_SIDE, LARGE_GAUSSIAN_SIDE);
+        b.iter(|| {
+            let blurred = gaussian_blur_f32(&image, 3f32);
+            black_box(blurred);
+        });
+    }
+
+    #[bench]
+    #[ignore] // Slow on a large image. Compare with bench_gaussian_f32_large_image
+    #[cfg(feature = "rayon")]
+    fn bench_gaussian_f32_parallel_large_image(b: &mut Bencher) {
+        let image = gray_bench_image(LARGE_GAUSSIAN_SIDE, LARGE_GAUSSIAN_SIDE);
+        b.iter(|| {
+            let blurred = gaussian_blur_f32_parallel(&image, 3f32);
+            black_box(blurred);
+        });
+    }
 }
@@ -0,0 +1,213 @@
+//! Non-local means denoising.
+
+use crate::definitions::Image;
+use image::{GrayImage, Luma};
+
+/// Denoises a grayscale image using non-local means.
+///
+/// Each output pixel is a weighted average of the pixels in the
+/// `search_window x search_window` square centred on it. Each pixel is weighted by how
+/// similar its surrounding patch is to the patch around the output pixel, with weight
+/// `exp(-d / h^2)` where `d` is the mean squared difference between the two
+/// `patch_size x patch_size` patches.
+///
+/// Larger values of `h` remove more noise at the cost of also removing more detail -
+/// a good starting point is the standard deviation of the noise.
+///
+/// Patch distances for each offset in the search window are computed using an
+/// integral image of squared differences, so the cost per pixel is
+/// O(`search_window`²) and independent of `patch_size`. Patches and search windows
+/// which extend past the image boundary are handled by reflecting the image at its
+/// boundary.
+///
+/// # Panics
+///
+/// If `patch_size` or `search_window` is not odd, or `h` is not strictly positive.
+///
+/// # Examples
+/// ```
+/// use imageproc::filter::nl_means_denoise;
+/// use imageproc::noise::gaussian_noise;
+/// use imageproc::utils::gray_bench_image;
+///
+/// let image = gaussian_noise(&gray_bench_image(50, 50), 0.0, 10.0, 1);
+/// let denoised = nl_means_denoise(&image, 3, 11, 10.0);
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn nl_means_denoise(
+    image: &GrayImage,
+    patch_size: u32,
+    search_window: u32,
+    h: f32,
+) -> GrayImage {
+    assert!(patch_size % 2 == 1, "patch_size must be odd");
+    assert!(search_window % 2 == 1, "search_window must be odd");
+    assert!(h > 0.0, "h must be strictly positive, got {}", h);
+
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return image.clone();
+    }
+
+    let (width, height) = (width as usize, height as usize);
+    let patch_radius = (patch_size / 2) as usize;
+    let search_radius = (search_window / 2) as usize;
+
+    // Pad so that every patch around every pixel within the search window is in bounds
+    let pad = patch_radius + search_radius;
+    let padded_width = width + 2 * pad;
+    let padded_height = height + 2 * pad;
+    let mut padded = Vec::with_capacity(padded_width * padded_height);
+    for y in 0..padded_height {
+        let src_y = reflect(y as i64 - pad as i64, height);
+        for x in 0..padded_width {
+            let src_x = reflect(x as i64 - pad as i64, width);
+            padded.push(image.get_pixel(src_x as u32, src_y as u32)[0] as f64);
+        }
+    }
+
+    // The region covered by the patches around each image pixel
+    let region_width = width + 2 * patch_radius;
+    let region_height = height + 2 * patch_radius;
+    let patch_side = patch_size as usize;
+    let patch_area = (patch_side * patch_side) as f64;
+    let h_squared = (h as f64) * (h as f64);
+
+    let mut weight_sums = vec![0f64; width * height];
+    let mut value_sums = vec![0f64; width * height];
+    let mut integral = vec![0f64; (region_width + 1) * (region_height + 1)];
+    let integral_width = region_width + 1;
+
+    for dy in 0..2 * search_radius + 1 {
+        for dx in 0..2 * search_radius + 1 {
+            // Integral image of squared differences between the region
+            // and the region shifted by (dx, dy) - (search_radius, search_radius)
+            for y in 0..region_height {
+                let mut row_sum = 0f64;
+                let row = (y + search_radius) * padded_width + search_radius;
+                let shifted_row = (y + dy) * padded_width + dx;
+                for x in 0..region_width {
+                    let diff = padded[row + x] - padded[shifted_row + x];
+                    row_sum += diff * diff;
+                    integral[(y + 1) * integral_width + x + 1] =
+                        integral[y * integral_width + x + 1] + row_sum;
+                }
+            }
+
+            for y in 0..height {
+                for x in 0..width {
+                    // The patch around image pixel (x, y) spans [x, x + patch_side)
+                    // in region coordinates
+                    let (x0, y0) = (x, y);
+                    let (x1, y1) = (x + patch_side, y + patch_side);
+                    let ssd = integral[y1 * integral_width + x1]
+                        - integral[y0 * integral_width + x1]
+                        - integral[y1 * integral_width + x0]
+                        + integral[y0 * integral_width + x0];
+                    let distance = (ssd / patch_area).max(0.0);
+                    let weight = (-distance / h_squared).exp();
+
+                    let value =
+                        padded[(y + patch_radius + dy) * padded_width + x + patch_radius + dx];
+                    weight_sums[y * width + x] += weight;
+                    value_sums[y * width + x] += weight * value;
+                }
+            }
+        }
+    }
+
+    Image::from_fn(width as u32, height as u32, |x, y| {
+        let i = y as usize * width + x as usize;
+        let value = value_sums[i] / weight_sums[i];
+        Luma([value.round().clamp(0.0, 255.0) as u8])
+    })
+}
+
+// Maps a possibly out of bounds index to an index in [0, len) by reflecting
+// at the boundaries, i.e. ... 2 1 0 | 0 1 2 ... len - 1 | len - 1 len - 2 ...
+fn reflect(index: i64, len: usize) -> usize {
+    let len = len as i64;
+    let period = 2 * len;
+    let index = index.rem_euclid(period);
+    if index < len {
+        index as usize
+    } else {
+        (period - 1 - index) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::gaussian_noise;
+
+    fn mean_squared_error(a: &GrayImage, b: &GrayImage) -> f64 {
+        let sum: f64 = a
+            .iter()
+            .zip(b.iter())
+            .map(|(&p, &q)| (p as f64 - q as f64).powi(2))
+            .sum();
+        sum / a.len() as f64
+    }
+
+    #[test]
+    fn test_reflect() {
+        let reflected: Vec<usize> = (-4..8).map(|i| reflect(i, 4)).collect();
+        assert_eq!(reflected, vec![3, 2, 1, 0, 0, 1, 2, 3, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_nl_means_denoise_constant_image() {
+        let image = GrayImage::from_pixel(10, 8, Luma([77]));
+        assert_pixels_eq!(nl_means_denoise(&image, 3, 7, 5.0), image);
+    }
+
+    #[test]
+    fn test_nl_means_denoise_reduces_noise_and_preserves_edge() {
+        let clean =
+            GrayImage::from_fn(40, 40, |x, _| if x < 20 { Luma([60]) } else { Luma([190]) });
+        let noisy = gaussian_noise(&clean, 0.0, 15.0, 7);
+        let denoised = nl_means_denoise(&noisy, 5, 11, 15.0);
+
+        let noisy_error = mean_squared_error(&noisy, &clean);
+        let denoised_error = mean_squared_error(&denoised, &clean);
+        assert!(
+            denoised_error < noisy_error / 2.0,
+            "noisy: {}, denoised: {}",
+            noisy_error,
+            denoised_error
+        );
+
+        // The mean values of the columns either side of the edge are close to the clean values
+        let column_mean = |x| {
+            (0..40)
+                .map(|y| denoised.get_pixel(x, y)[0] as f64)
+                .sum::<f64>()
+                / 40.0
+        };
+        assert!((column_mean(19) - 60.0).abs() < 10.0);
+        assert!((column_mean(20) - 190.0).abs() < 10.0);
+    }
+    #[test]
+    #[should_panic(expected = "patch_size must be odd")]
+    fn test_nl_means_denoise_rejects_even_patch_size() {
+        let _ = nl_means_denoise(&GrayImage::new(5, 5), 2, 5, 10.0);
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::gray_bench_image;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_nl_means_denoise(b: &mut Bencher) {
+        let image = gray_bench_image(100, 100);
+        b.iter(|| {
+            let filtered = nl_means_denoise(&image, 5, 11, 10.0);
+            black_box(filtered);
+        });
+    }
+}
@@ -4,9 +4,9 @@ use super::{filter_clamped, gaussian_blur_f32};
 use crate::{
     definitions::{Clamp, Image},
     kernel::Kernel,
-    map::{map_pixels2, map_subpixels},
+    map::{map_pixels2, map_subpixels, ChannelMap, WithChannel},
 };
-use image::{GrayImage, Luma};
+use image::{GrayImage, Luma, Pixel, RgbImage};
 
 /// Sharpens a grayscale image by applying a 3x3 approximation to the Laplacian.
 #[must_use = "the function does not modify the original image"]
@@ -36,3 +36,149 @@ pub fn sharpen_gaussian(image: &GrayImage, sigma: f32, amount: f32) -> GrayImage
         Luma([<u8 as Clamp<f32>>::clamp(v)])
     })
 }
+
+/// Sharpens a grayscale image using unsharp masking.
+///
+/// Computes `image + amount * (image - blurred)`, where `blurred` is `image` convolved
+/// with a Gaussian of standard deviation `sigma`. Pixels which differ from `blurred` by
+/// less than `threshold` are left unchanged, which avoids amplifying noise in flat regions.
+/// Outputs are clamped to `[0, 255]`.
+///
+/// # Panics
+///
+/// If `sigma <= 0.0`.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::filter::unsharp_mask;
+///
+/// let image = gray_image!(
+///     50, 50, 100, 150, 150;
+///     50, 50, 100, 150, 150);
+///
+/// let sharpened = unsharp_mask(&image, 1.0, 1.0, 0);
+///
+/// // The edge is steeper after sharpening
+/// assert!(sharpened.get_pixel(1, 0)[0] < 50);
+/// assert!(sharpened.get_pixel(3, 0)[0] > 150);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn unsharp_mask(image: &GrayImage, sigma: f32, amount: f32, threshold: u8) -> GrayImage {
+    unsharp_mask_impl(image, sigma, amount, threshold)
+}
+
+/// Sharpens a colour image using unsharp masking, applied to each channel independently.
+///
+/// See [`unsharp_mask`] for details.
+#[must_use = "the function does not modify the original image"]
+pub fn unsharp_mask_rgb(image: &RgbImage, sigma: f32, amount: f32, threshold: u8) -> RgbImage {
+    unsharp_mask_impl(image, sigma, amount, threshold)
+}
+
+fn unsharp_mask_impl<P>(image: &Image<P>, sigma: f32, amount: f32, threshold: u8) -> Image<P>
+where
+    P: Pixel<Subpixel = u8> + WithChannel<f32>,
+    ChannelMap<P, f32>: Pixel<Subpixel = f32>,
+{
+    let smooth = gaussian_blur_f32(&map_subpixels(image, |x| x as f32), sigma);
+    let threshold = threshold as f32;
+
+    let mut out = image.clone();
+    for (p, q) in out.iter_mut().zip(smooth.iter()) {
+        let detail = *p as f32 - q;
+        if detail.abs() >= threshold {
+            *p = <u8 as Clamp<f32>>::clamp(*p as f32 + amount * detail);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    fn blurred_step() -> GrayImage {
+        GrayImage::from_fn(20, 5, |x, _| {
+            Luma([match x {
+                0..=7 => 60,
+                8 => 90,
+                9 => 120,
+                10 => 150,
+                _ => 180,
+            }])
+        })
+    }
+
+    fn max_step(image: &GrayImage, y: u32) -> i32 {
+        (1..image.width())
+            .map(|x| image.get_pixel(x, y)[0] as i32 - image.get_pixel(x - 1, y)[0] as i32)
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unsharp_mask_steepens_blurred_edge() {
+        let image = blurred_step();
+        let sharpened = unsharp_mask(&image, 1.5, 1.0, 0);
+        assert!(max_step(&sharpened, 2) > max_step(&image, 2));
+        // Overshoot either side of the edge
+        assert!(sharpened.get_pixel(7, 2)[0] < 60);
+        assert!(sharpened.get_pixel(11, 2)[0] > 180);
+    }
+
+    #[test]
+    fn test_unsharp_mask_leaves_low_contrast_regions_untouched() {
+        let mut image = GrayImage::from_pixel(20, 20, Luma([100]));
+        // Low contrast texture in the left half and a strong edge on the right
+        for y in 0..20 {
+            for x in 0..10 {
+                if (x + y) % 2 == 0 {
+                    image.put_pixel(x, y, Luma([103]));
+                }
+            }
+            for x in 15..20 {
+                image.put_pixel(x, y, Luma([200]));
+            }
+        }
+        let sharpened = unsharp_mask(&image, 1.0, 2.0, 10);
+        for y in 0..20 {
+            for x in 0..10 {
+                assert_eq!(sharpened.get_pixel(x, y), image.get_pixel(x, y));
+            }
+        }
+        assert!(sharpened.get_pixel(15, 10)[0] > 200);
+        assert!(sharpened.get_pixel(14, 10)[0] < 100);
+    }
+
+    #[test]
+    fn test_unsharp_mask_clamps_output() {
+        let image = gray_image!(
+            0, 0, 255, 255;
+            0, 0, 255, 255);
+        let sharpened = unsharp_mask(&image, 1.0, 10.0, 0);
+        assert_pixels_eq!(sharpened, image);
+    }
+
+    #[test]
+    fn test_unsharp_mask_rgb_matches_per_channel_grayscale() {
+        let image = blurred_step();
+        let rgb = RgbImage::from_fn(20, 5, |x, y| {
+            let v = image.get_pixel(x, y)[0];
+            Rgb([v, 255 - v, v / 2])
+        });
+        let sharpened = unsharp_mask_rgb(&rgb, 1.5, 0.8, 5);
+        for c in 0..3 {
+            let channel = GrayImage::from_fn(20, 5, |x, y| Luma([rgb.get_pixel(x, y)[c]]));
+            let expected = unsharp_mask(&channel, 1.5, 0.8, 5);
+            for (x, y, p) in sharpened.enumerate_pixels() {
+                assert_eq!(p[c], expected.get_pixel(x, y)[0]);
+            }
+        }
+    }
+}
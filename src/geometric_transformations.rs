@@ -1,8 +1,8 @@
-//! Geometric transformations of images. This includes rotations, translation, and general
-//! projective transformations.
+//! Geometric transformations of images. This includes rotations, translation, general
+//! projective transformations and resizing.
 
 use crate::definitions::{Clamp, Image};
-use image::{GenericImageView, Pixel};
+use image::{GenericImageView, GrayImage, Luma, Pixel};
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
 use std::{cmp, ops::Mul};
@@ -127,10 +127,37 @@ impl Projection {
     }
 
     /// Calculates a projection from a set of four control point pairs.
+    ///
+    /// The returned projection maps each point in `from` to the corresponding point in `to`.
+    /// Returns `None` if any three points of either `from` or `to` are collinear, as
+    /// then no unique projective transformation between them exists.
+    ///
+    /// Use [`warp`] to apply the projection to an image.
+    ///
+    /// # Examples
+    /// ```
+    /// use imageproc::geometric_transformations::Projection;
+    ///
+    /// // Map a square to a trapezoid
+    /// let square = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+    /// let trapezoid = [(3.0, 0.0), (7.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+    /// let projection = Projection::from_control_points(square, trapezoid).unwrap();
+    ///
+    /// let (x, y) = projection * (10.0, 0.0);
+    /// assert!((x - 7.0).abs() < 1e-3 && y.abs() < 1e-3);
+    ///
+    /// // Three collinear points
+    /// let degenerate = [(0.0, 0.0), (5.0, 5.0), (10.0, 10.0), (0.0, 10.0)];
+    /// assert!(Projection::from_control_points(degenerate, trapezoid).is_none());
+    /// ```
     pub fn from_control_points(from: [(f32, f32); 4], to: [(f32, f32); 4]) -> Option<Projection> {
         use approx::AbsDiffEq;
         use nalgebra::{linalg::SVD, OMatrix, OVector, U8};
 
+        if has_collinear_triple(&from) || has_collinear_triple(&to) {
+            return None;
+        }
+
         let (xf1, yf1, xf2, yf2, xf3, yf3, xf4, yf4) = (
             from[0].0 as f64,
             from[0].1 as f64,
@@ -219,6 +246,20 @@ impl Projection {
     }
 }
 
+// Returns true if any three of the given points are collinear, to within a small
+// tolerance relative to their distances from each other.
+fn has_collinear_triple(points: &[(f32, f32); 4]) -> bool {
+    let triples = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]];
+    triples.iter().any(|&[a, b, c]| {
+        let (ax, ay) = (points[a].0 as f64, points[a].1 as f64);
+        let (ux, uy) = (points[b].0 as f64 - ax, points[b].1 as f64 - ay);
+        let (vx, vy) = (points[c].0 as f64 - ax, points[c].1 as f64 - ay);
+        let cross = ux * vy - uy * vx;
+        let scale = (ux * ux + uy * uy).max(vx * vx + vy * vy);
+        cross.abs() <= 1e-6 * scale
+    })
+}
+
 impl Mul<Projection> for Projection {
     type Output = Projection;
 
@@ -243,7 +284,7 @@ impl Mul<Projection> for Projection {
     }
 }
 
-impl<'a, 'b> Mul<&'b Projection> for &'a Projection {
+impl Mul<&Projection> for &Projection {
     type Output = Projection;
 
     fn mul(self, rhs: &Projection) -> Projection {
@@ -264,7 +305,7 @@ impl Mul<(f32, f32)> for Projection {
     }
 }
 
-impl<'a, 'b> Mul<&'b (f32, f32)> for &'a Projection {
+impl Mul<&(f32, f32)> for &Projection {
     type Output = (f32, f32);
 
     fn mul(self, rhs: &(f32, f32)) -> (f32, f32) {
@@ -272,6 +313,367 @@ impl<'a, 'b> Mul<&'b (f32, f32)> for &'a Projection {
     }
 }
 
+/// Estimates the affine transformation that best maps the points `from` onto the points `to`,
+/// in the least squares sense.
+///
+/// The six parameters of the transformation are chosen to minimise the sum of the squared
+/// distances between `projection * from[i]` and `to[i]`. Affine transformations are
+/// represented by [`Projection`]s whose matrices have final row `[0, 0, 1]`.
+///
+/// Returns `None` if there are fewer than three correspondences, if the points `from` are
+/// all collinear, or if the estimated transformation is not invertible. Use
+/// [`estimate_affine_ransac`] if some correspondences may be incorrect.
+///
+/// # Panics
+///
+/// If `from` and `to` have different lengths.
+///
+/// # Examples
+/// ```
+/// use imageproc::geometric_transformations::estimate_affine;
+///
+/// let from = [(0.0, 0.0), (10.0, 0.0), (0.0, 10.0), (10.0, 10.0)];
+/// // Scale by 2 horizontally and shift down by 5
+/// let to = [(0.0, 5.0), (20.0, 5.0), (0.0, 15.0), (20.0, 15.0)];
+///
+/// let affine = estimate_affine(&from, &to).unwrap();
+/// let (x, y) = affine * (3.0, 4.0);
+/// assert!((x - 6.0).abs() < 1e-4 && (y - 9.0).abs() < 1e-4);
+///
+/// // Collinear points do not determine an affine transformation
+/// let line = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0)];
+/// assert!(estimate_affine(&line, &line).is_none());
+/// ```
+pub fn estimate_affine(from: &[(f32, f32)], to: &[(f32, f32)]) -> Option<Projection> {
+    assert_eq!(
+        from.len(),
+        to.len(),
+        "from and to must have the same length"
+    );
+    if from.len() < 3 {
+        return None;
+    }
+
+    let mean = |points: &[(f32, f32)]| {
+        let (sx, sy) = points.iter().fold((0f64, 0f64), |(sx, sy), p| {
+            (sx + p.0 as f64, sy + p.1 as f64)
+        });
+        (sx / points.len() as f64, sy / points.len() as f64)
+    };
+    let (fx, fy) = mean(from);
+    let (tx, ty) = mean(to);
+
+    // Second moments of the centred points. The linear part of the least squares solution
+    // is cross * covariance^-1, and the translation maps the mean of from onto the mean of to
+    let (mut sxx, mut sxy, mut syy) = (0f64, 0f64, 0f64);
+    let (mut cxx, mut cxy, mut cyx, mut cyy) = (0f64, 0f64, 0f64, 0f64);
+    for (f, t) in from.iter().zip(to) {
+        let (ux, uy) = (f.0 as f64 - fx, f.1 as f64 - fy);
+        let (vx, vy) = (t.0 as f64 - tx, t.1 as f64 - ty);
+        sxx += ux * ux;
+        sxy += ux * uy;
+        syy += uy * uy;
+        cxx += vx * ux;
+        cxy += vx * uy;
+        cyx += vy * ux;
+        cyy += vy * uy;
+    }
+
+    let det = sxx * syy - sxy * sxy;
+    let scale = sxx + syy;
+    if scale == 0.0 || det <= 1e-10 * scale * scale {
+        return None;
+    }
+    let (ixx, ixy, iyy) = (syy / det, -sxy / det, sxx / det);
+
+    let a = cxx * ixx + cxy * ixy;
+    let b = cxx * ixy + cxy * iyy;
+    let c = cyx * ixx + cyy * ixy;
+    let d = cyx * ixy + cyy * iyy;
+
+    Projection::from_matrix([
+        a as f32,
+        b as f32,
+        (tx - a * fx - b * fy) as f32,
+        c as f32,
+        d as f32,
+        (ty - c * fx - d * fy) as f32,
+        0.0,
+        0.0,
+        1.0,
+    ])
+}
+
+/// Robustly estimates the affine transformation mapping the points `from` onto the points
+/// `to` when some of the correspondences may be incorrect, using RANSAC.
+///
+/// On each of `max_iters` iterations three correspondences are chosen at random and the
+/// affine transformation they define is computed. Correspondences are inliers of this
+/// transformation if the distance between `projection * from[i]` and `to[i]` is at most
+/// `threshold`. The transformation with the most inliers is then refined by fitting it to
+/// all of its inliers using [`estimate_affine`].
+///
+/// Returns the estimated transformation and a mask of which correspondences are inliers to
+/// it, or `None` if no transformation could be estimated. Random choices are made using a
+/// generator initialised with `seed`, so results are reproducible.
+///
+/// # Panics
+///
+/// If `from` and `to` have different lengths.
+///
+/// # Examples
+/// ```
+/// use imageproc::geometric_transformations::{estimate_affine_ransac, Projection};
+///
+/// let truth = Projection::rotate(0.3) * Projection::scale(1.5, 0.8);
+/// let from: Vec<(f32, f32)> = (0..20).map(|i| ((i % 5) as f32 * 10.0, (i / 5) as f32 * 7.0)).collect();
+/// let mut to: Vec<(f32, f32)> = from.iter().map(|&p| truth * p).collect();
+/// // An incorrect correspondence
+/// to[7] = (100.0, -40.0);
+///
+/// let (affine, inliers) = estimate_affine_ransac(&from, &to, 0.5, 100, 1).unwrap();
+/// assert!(!inliers[7]);
+/// assert_eq!(inliers.iter().filter(|&&inlier| inlier).count(), 19);
+/// let (x, y) = affine * from[7];
+/// let (ex, ey) = truth * from[7];
+/// assert!((x - ex).abs() < 1e-3 && (y - ey).abs() < 1e-3);
+/// ```
+pub fn estimate_affine_ransac(
+    from: &[(f32, f32)],
+    to: &[(f32, f32)],
+    threshold: f32,
+    max_iters: u32,
+    seed: u64,
+) -> Option<(Projection, Vec<bool>)> {
+    ransac(from, to, 3, threshold, max_iters, seed, estimate_affine)
+}
+
+/// Estimates the projective transformation that best maps the points `from` onto the points
+/// `to`, using the normalised direct linear transformation algorithm.
+///
+/// For exactly four correspondences this is equivalent to [`Projection::from_control_points`].
+/// For more than four, the transformation minimises an algebraic error that approximates the
+/// sum of squared distances between `projection * from[i]` and `to[i]`.
+///
+/// Returns `None` if there are fewer than four correspondences, if the correspondences do not
+/// determine a unique transformation (for example if too many points are collinear), or if
+/// the estimated transformation is not invertible. Use [`estimate_homography_ransac`] if
+/// some correspondences may be incorrect.
+///
+/// # Panics
+///
+/// If `from` and `to` have different lengths.
+pub fn estimate_homography(from: &[(f32, f32)], to: &[(f32, f32)]) -> Option<Projection> {
+    use nalgebra::{linalg::SymmetricEigen, Matrix3, SMatrix};
+
+    assert_eq!(
+        from.len(),
+        to.len(),
+        "from and to must have the same length"
+    );
+    if from.len() < 4 {
+        return None;
+    }
+    if let (Ok(f), Ok(t)) = (
+        <[(f32, f32); 4]>::try_from(from),
+        <[(f32, f32); 4]>::try_from(to),
+    ) {
+        if has_collinear_triple(&f) || has_collinear_triple(&t) {
+            return None;
+        }
+    }
+
+    // Translates points to have centroid at the origin and scales them to have mean distance
+    // sqrt(2) from it, to improve the conditioning of the linear system
+    let normalization = |points: &[(f32, f32)]| -> Option<Matrix3<f64>> {
+        let n = points.len() as f64;
+        let cx = points.iter().map(|p| p.0 as f64).sum::<f64>() / n;
+        let cy = points.iter().map(|p| p.1 as f64).sum::<f64>() / n;
+        let mean_distance = points
+            .iter()
+            .map(|p| (p.0 as f64 - cx).hypot(p.1 as f64 - cy))
+            .sum::<f64>()
+            / n;
+        if mean_distance == 0.0 || !mean_distance.is_finite() {
+            return None;
+        }
+        let s = std::f64::consts::SQRT_2 / mean_distance;
+        #[rustfmt::skip]
+        let t = Matrix3::new(
+            s, 0.0, -s * cx,
+            0.0, s, -s * cy,
+            0.0, 0.0, 1.0,
+        );
+        Some(t)
+    };
+    let t_from = normalization(from)?;
+    let t_to = normalization(to)?;
+    let apply = |t: &Matrix3<f64>, p: (f32, f32)| {
+        (
+            t[(0, 0)] * p.0 as f64 + t[(0, 2)],
+            t[(1, 1)] * p.1 as f64 + t[(1, 2)],
+        )
+    };
+
+    // Accumulates A^T A, where each correspondence contributes two rows to A and the
+    // homography is the null vector of A
+    let mut ata = SMatrix::<f64, 9, 9>::zeros();
+    for (&f, &t) in from.iter().zip(to) {
+        let (x, y) = apply(&t_from, f);
+        let (u, v) = apply(&t_to, t);
+        let rows = [
+            [-x, -y, -1.0, 0.0, 0.0, 0.0, u * x, u * y, u],
+            [0.0, 0.0, 0.0, -x, -y, -1.0, v * x, v * y, v],
+        ];
+        for row in &rows {
+            let row = SMatrix::<f64, 9, 1>::from_row_slice(row);
+            ata += row * row.transpose();
+        }
+    }
+
+    let eigen = SymmetricEigen::new(ata);
+    let mut order: Vec<usize> = (0..9).collect();
+    order.sort_by(|&i, &j| eigen.eigenvalues[i].total_cmp(&eigen.eigenvalues[j]));
+    let largest = eigen.eigenvalues[order[8]];
+    // A unique solution requires a one dimensional null space
+    if eigen.eigenvalues[order[1]] <= 1e-12 * largest {
+        return None;
+    }
+
+    let h = eigen.eigenvectors.column(order[0]);
+    let normalized = Matrix3::from_row_slice(h.as_slice());
+    let t_to_inverse = t_to.try_inverse()?;
+    let m = t_to_inverse * normalized * t_from;
+    if m[(2, 2)] == 0.0 {
+        return None;
+    }
+
+    let mut transform = [0f32; 9];
+    for (i, value) in transform.iter_mut().enumerate() {
+        *value = (m[(i / 3, i % 3)] / m[(2, 2)]) as f32;
+    }
+    Projection::from_matrix(transform)
+}
+
+/// Robustly estimates the projective transformation mapping the points `from` onto the points
+/// `to` when some of the correspondences may be incorrect, using RANSAC.
+///
+/// On each of `max_iters` iterations four correspondences are chosen at random and the
+/// projective transformation they define is computed. Correspondences are inliers of this
+/// transformation if the distance between `projection * from[i]` and `to[i]` is at most
+/// `threshold`. The transformation with the most inliers is then refined by fitting it to
+/// all of its inliers using [`estimate_homography`].
+///
+/// Returns the estimated transformation and a mask of which correspondences are inliers to
+/// it, or `None` if no transformation could be estimated. Random choices are made using a
+/// generator initialised with `seed`, so results are reproducible.
+///
+/// # Panics
+///
+/// If `from` and `to` have different lengths.
+///
+/// # Examples
+/// ```
+/// use imageproc::geometric_transformations::{estimate_homography_ransac, Projection};
+///
+/// let truth = Projection::from_matrix([1.1, 0.2, 5.0, -0.1, 0.9, 3.0, 1e-3, 2e-3, 1.0]).unwrap();
+/// let from: Vec<(f32, f32)> = (0..25).map(|i| ((i % 5) as f32 * 20.0, (i / 5) as f32 * 20.0)).collect();
+/// let mut to: Vec<(f32, f32)> = from.iter().map(|&p| truth * p).collect();
+/// // An incorrect correspondence
+/// to[3] = (0.0, 90.0);
+///
+/// let (homography, inliers) = estimate_homography_ransac(&from, &to, 0.5, 100, 1).unwrap();
+/// assert!(!inliers[3]);
+/// assert_eq!(inliers.iter().filter(|&&inlier| inlier).count(), 24);
+/// let (x, y) = homography * from[3];
+/// let (ex, ey) = truth * from[3];
+/// assert!((x - ex).abs() < 1e-2 && (y - ey).abs() < 1e-2);
+/// ```
+pub fn estimate_homography_ransac(
+    from: &[(f32, f32)],
+    to: &[(f32, f32)],
+    threshold: f32,
+    max_iters: u32,
+    seed: u64,
+) -> Option<(Projection, Vec<bool>)> {
+    ransac(from, to, 4, threshold, max_iters, seed, estimate_homography)
+}
+
+// Fits a transformation to correspondences using RANSAC. fit estimates a transformation from
+// sample_size or more correspondences.
+fn ransac<F>(
+    from: &[(f32, f32)],
+    to: &[(f32, f32)],
+    sample_size: usize,
+    threshold: f32,
+    max_iters: u32,
+    seed: u64,
+    fit: F,
+) -> Option<(Projection, Vec<bool>)>
+where
+    F: Fn(&[(f32, f32)], &[(f32, f32)]) -> Option<Projection>,
+{
+    use rand::{rngs::StdRng, SeedableRng};
+
+    assert_eq!(
+        from.len(),
+        to.len(),
+        "from and to must have the same length"
+    );
+    if from.len() < sample_size {
+        return None;
+    }
+
+    let inliers = |projection: &Projection| -> Vec<bool> {
+        from.iter()
+            .zip(to)
+            .map(|(&f, &t)| {
+                let (x, y) = *projection * f;
+                let (dx, dy) = (x - t.0, y - t.1);
+                // NaN distances are never inliers
+                (dx * dx + dy * dy).sqrt() <= threshold
+            })
+            .collect()
+    };
+    let count = |mask: &[bool]| mask.iter().filter(|&&inlier| inlier).count();
+    let select = |mask: &[bool], points: &[(f32, f32)]| -> Vec<(f32, f32)> {
+        points
+            .iter()
+            .zip(mask)
+            .filter(|(_, &inlier)| inlier)
+            .map(|(&p, _)| p)
+            .collect()
+    };
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut best: Option<(Projection, Vec<bool>)> = None;
+    for _ in 0..max_iters {
+        let sample = rand::seq::index::sample(&mut rng, from.len(), sample_size);
+        let sample_from: Vec<_> = sample.iter().map(|i| from[i]).collect();
+        let sample_to: Vec<_> = sample.iter().map(|i| to[i]).collect();
+        if let Some(projection) = fit(&sample_from, &sample_to) {
+            let mask = inliers(&projection);
+            if best.as_ref().map_or(true, |(_, b)| count(&mask) > count(b)) {
+                best = Some((projection, mask));
+            }
+        }
+    }
+
+    let (projection, mask) = best?;
+    // Refit to all inliers, keeping the refined transformation unless it loses inliers
+    match fit(&select(&mask, from), &select(&mask, to)) {
+        Some(refined) => {
+            let refined_mask = inliers(&refined);
+            if count(&refined_mask) >= count(&mask) {
+                Some((refined, refined_mask))
+            } else {
+                Some((projection, mask))
+            }
+        }
+        None => Some((projection, mask)),
+    }
+}
+
 /// Rotates an image clockwise about its center.
 /// The output image has the same dimensions as the input. Output pixels
 /// whose pre-image lies outside the input image are set to `default`.
@@ -490,6 +892,539 @@ pub fn warp_into_with<P, F>(
     }
 }
 
+/// Samples `image` at the locations given by a pair of coordinate maps.
+///
+/// The output pixel at `(x, y)` is `image` sampled at `(map_x[(x, y)], map_y[(x, y)])`
+/// using `interpolation`, or `default` if that location lies outside `image`. The
+/// returned image has the dimensions of the maps, which need not match those of `image`.
+///
+/// This allows arbitrary distortions to be applied by precomputing their maps, which is
+/// faster than [`warp_with`] when the same distortion is applied to many images.
+///
+/// # Panics
+///
+/// If `map_x` and `map_y` have different dimensions.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use image::Luma;
+/// use imageproc::definitions::Image;
+/// use imageproc::geometric_transformations::{remap, Interpolation};
+///
+/// let image = gray_image!(
+///     1, 2, 3;
+///     4, 5, 6);
+///
+/// // Mirror the image horizontally
+/// let map_x = Image::from_fn(3, 2, |x, _| Luma([2.0 - x as f32]));
+/// let map_y = Image::from_fn(3, 2, |_, y| Luma([y as f32]));
+///
+/// let mirrored = gray_image!(
+///     3, 2, 1;
+///     6, 5, 4);
+///
+/// assert_pixels_eq!(
+///     remap(&image, &map_x, &map_y, Interpolation::Nearest, Luma([0])),
+///     mirrored
+/// );
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn remap<P>(
+    image: &Image<P>,
+    map_x: &Image<Luma<f32>>,
+    map_y: &Image<Luma<f32>>,
+    interpolation: Interpolation,
+    default: P,
+) -> Image<P>
+where
+    P: Pixel + Send + Sync,
+    <P as Pixel>::Subpixel: Send + Sync,
+    <P as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+{
+    assert_eq!(
+        map_x.dimensions(),
+        map_y.dimensions(),
+        "map_x and map_y must have the same dimensions"
+    );
+    let (width, height) = map_x.dimensions();
+    let mut out = Image::new(width, height);
+    warp_into_with(
+        image,
+        |x, y| {
+            let (x, y) = (x as u32, y as u32);
+            (map_x.get_pixel(x, y)[0], map_y.get_pixel(x, y)[0])
+        },
+        interpolation,
+        default,
+        &mut out,
+    );
+    out
+}
+
+/// Swirls the pixels of an image around `center`.
+///
+/// Each pixel within `radius` of `center` is rotated clockwise about `center` by an angle
+/// that decreases smoothly from `strength` radians at the center to zero at distance
+/// `radius`. Pixels at least `radius` from `center` are unchanged. Swirling by `-strength`
+/// approximately undoes a swirl by `strength`.
+///
+/// The output is computed by inverse mapping, sampling `image` using `interpolation`.
+/// Output pixels whose pre-image lies outside the input image keep their original value.
+///
+/// # Panics
+///
+/// If `radius` is not strictly positive.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::geometric_transformations::{swirl, Interpolation};
+///
+/// let image = GrayImage::from_fn(21, 21, |x, y| Luma([(10 * x + y) as u8]));
+/// let swirled = swirl(&image, (10.0, 10.0), 2.0, 6.0, Interpolation::Bilinear);
+///
+/// // The center and all pixels at least 6 pixels from it are fixed
+/// assert_eq!(swirled.get_pixel(10, 10), image.get_pixel(10, 10));
+/// assert_eq!(swirled.get_pixel(10, 4), image.get_pixel(10, 4));
+/// assert_eq!(swirled.get_pixel(3, 15), image.get_pixel(3, 15));
+/// // Pixels in between are moved
+/// assert_ne!(swirled.get_pixel(10, 7), image.get_pixel(10, 7));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn swirl<P>(
+    image: &Image<P>,
+    center: (f32, f32),
+    strength: f32,
+    radius: f32,
+    interpolation: Interpolation,
+) -> Image<P>
+where
+    P: Pixel,
+    <P as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+{
+    assert!(radius > 0.0, "radius must be > 0.0");
+    let (cx, cy) = center;
+
+    let mut out = image.clone();
+    for (x, y, p) in out.enumerate_pixels_mut() {
+        let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+        let rho = (dx * dx + dy * dy).sqrt();
+        if rho >= radius {
+            continue;
+        }
+        let falloff = 1.0 - rho / radius;
+        let (sin, cos) = (-strength * falloff * falloff).sin_cos();
+        let (sx, sy) = (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos);
+        *p = match interpolation {
+            Interpolation::Nearest => interpolate_nearest(image, sx, sy, *p),
+            Interpolation::Bilinear => interpolate_bilinear(image, sx, sy, *p),
+            Interpolation::Bicubic => interpolate_bicubic(image, sx, sy, *p),
+        };
+    }
+    out
+}
+
+/// Projects an image onto a cylinder, as is commonly done before stitching a panorama
+/// from images taken by a camera rotating about its vertical axis.
+///
+/// `focal_length` is the focal length of the camera in pixels, and the optical axis is
+/// assumed to pass through the center of the image. A pixel at offset `(x, y)` from the
+/// center is mapped to `(f θ, f y / √(x² + f²))`, where `θ = atan(x / f)`, so vertical
+/// lines remain vertical while horizontal distances are compressed towards the left and
+/// right edges.
+///
+/// The output is just large enough to contain the projection of the input. It is computed
+/// by inverse mapping with bilinear sampling, and output pixels whose pre-image lies outside
+/// the input image are set to `default`.
+///
+/// # Panics
+///
+/// If `focal_length` is not strictly positive.
+#[must_use = "the function does not modify the original image"]
+pub fn warp_cylindrical<P>(image: &Image<P>, focal_length: f32, default: P) -> Image<P>
+where
+    P: Pixel + Send + Sync,
+    <P as Pixel>::Subpixel: Send + Sync,
+    <P as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+{
+    assert!(focal_length > 0.0, "focal_length must be > 0.0");
+    let f = focal_length;
+    warp_to_projection(
+        image,
+        |x, y| ((x / f).atan() * f, f * y / (x * x + f * f).sqrt()),
+        |u, v| {
+            let theta = u / f;
+            (f * theta.tan(), v / theta.cos())
+        },
+        default,
+    )
+}
+
+/// Projects an image onto a sphere, as is commonly done before stitching a panorama
+/// from images taken by a camera rotating freely about its center.
+///
+/// `focal_length` is the focal length of the camera in pixels, and the optical axis is
+/// assumed to pass through the center of the image. A pixel at offset `(x, y)` from the
+/// center is mapped to its longitude and latitude `(f θ, f φ)`, where
+/// `θ = atan(x / f)` and `φ = atan(y / √(x² + f²))`.
+///
+/// The output is just large enough to contain the projection of the input. It is computed
+/// by inverse mapping with bilinear sampling, and output pixels whose pre-image lies outside
+/// the input image are set to `default`.
+///
+/// # Panics
+///
+/// If `focal_length` is not strictly positive.
+#[must_use = "the function does not modify the original image"]
+pub fn warp_spherical<P>(image: &Image<P>, focal_length: f32, default: P) -> Image<P>
+where
+    P: Pixel + Send + Sync,
+    <P as Pixel>::Subpixel: Send + Sync,
+    <P as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+{
+    assert!(focal_length > 0.0, "focal_length must be > 0.0");
+    let f = focal_length;
+    warp_to_projection(
+        image,
+        |x, y| ((x / f).atan() * f, (y / (x * x + f * f).sqrt()).atan() * f),
+        |u, v| {
+            let (theta, phi) = (u / f, v / f);
+            (f * theta.tan(), f * phi.tan() / theta.cos())
+        },
+        default,
+    )
+}
+
+// Warps an image by a projection given in coordinates relative to the image center.
+// The output canvas is sized to fit the projection of the image boundary, on which
+// the extremes of the projections used here lie.
+fn warp_to_projection<P, F, G>(image: &Image<P>, forward: F, inverse: G, default: P) -> Image<P>
+where
+    P: Pixel + Send + Sync,
+    <P as Pixel>::Subpixel: Send + Sync,
+    <P as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+    F: Fn(f32, f32) -> (f32, f32),
+    G: Fn(f32, f32) -> (f32, f32) + Send + Sync,
+{
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Image::new(0, 0);
+    }
+    let (cx, cy) = ((width as f32 - 1.0) / 2.0, (height as f32 - 1.0) / 2.0);
+
+    let (right, bottom) = (width - 1, height - 1);
+    let boundary = (0..width)
+        .flat_map(|x| [(x, 0), (x, bottom)])
+        .chain((0..height).flat_map(|y| [(0, y), (right, y)]));
+    let (mut min_u, mut min_v) = (f32::INFINITY, f32::INFINITY);
+    let (mut max_u, mut max_v) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for (x, y) in boundary {
+        let (u, v) = forward(x as f32 - cx, y as f32 - cy);
+        min_u = min_u.min(u);
+        max_u = max_u.max(u);
+        min_v = min_v.min(v);
+        max_v = max_v.max(v);
+    }
+
+    // Keep the canvas centered on the optical axis, so that its center maps back to the
+    // image center.
+    let half_width = max_u.max(-min_u);
+    let half_height = max_v.max(-min_v);
+    let out_width = (2.0 * half_width).round() as u32 + 1;
+    let out_height = (2.0 * half_height).round() as u32 + 1;
+    let (out_cx, out_cy) = (
+        (out_width as f32 - 1.0) / 2.0,
+        (out_height as f32 - 1.0) / 2.0,
+    );
+
+    let mut out = Image::new(out_width, out_height);
+    warp_into_with(
+        image,
+        |u, v| {
+            let (x, y) = inverse(u - out_cx, v - out_cy);
+            (x + cx, y + cy)
+        },
+        Interpolation::Bilinear,
+        default,
+        &mut out,
+    );
+    out
+}
+
+/// Removes lens distortion from an image, using the Brown-Conrady model of radial and
+/// tangential distortion.
+///
+/// `camera_matrix` is the intrinsic matrix of the camera, mapping normalized image
+/// coordinates to pixel coordinates:
+///
+/// ```text
+/// [[fx, skew, cx],
+///  [ 0,   fy, cy],
+///  [ 0,    0,  1]]
+/// ```
+///
+/// `dist_coeffs` holds the distortion coefficients `[k1, k2, p1, p2, k3]`, in the order used
+/// by OpenCV. A point `(x, y)` in normalized coordinates, with `r² = x² + y²`, is distorted to
+///
+/// ```text
+/// x' = x (1 + k1 r² + k2 r⁴ + k3 r⁶) + 2 p1 x y + p2 (r² + 2 x²)
+/// y' = y (1 + k1 r² + k2 r⁴ + k3 r⁶) + p1 (r² + 2 y²) + 2 p2 x y
+/// ```
+///
+/// Each output pixel is set by distorting its position and sampling `image` there using
+/// `interpolation`. The undistorted image uses the same camera matrix as `image`, and
+/// pixels whose pre-image lies outside `image` are set to black.
+///
+/// # Panics
+///
+/// If `fx` or `fy` is zero.
+#[must_use = "the function does not modify the original image"]
+pub fn undistort(
+    image: &GrayImage,
+    camera_matrix: &[[f32; 3]; 3],
+    dist_coeffs: &[f32; 5],
+    interpolation: Interpolation,
+) -> GrayImage {
+    let camera = CameraModel::new(camera_matrix, dist_coeffs);
+    warp_with(
+        image,
+        |x, y| {
+            let (nx, ny) = camera.normalize(x as f64, y as f64);
+            let (dx, dy) = camera.distort(nx, ny);
+            let (px, py) = camera.to_pixel(dx, dy);
+            (px as f32, py as f32)
+        },
+        interpolation,
+        Luma([0]),
+    )
+}
+
+/// Removes lens distortion from a set of points in pixel coordinates, for example
+/// features detected in a distorted image.
+///
+/// This is the inverse of the mapping applied by [`undistort`]: each returned point is
+/// the location in the output of [`undistort`] of the corresponding input point. See
+/// [`undistort`] for the meanings of `camera_matrix` and `dist_coeffs`.
+///
+/// The distortion model has no closed-form inverse, so it is inverted iteratively. This
+/// converges for the moderate distortion of typical lenses.
+///
+/// # Panics
+///
+/// If `fx` or `fy` is zero.
+pub fn undistort_points(
+    points: &[(f32, f32)],
+    camera_matrix: &[[f32; 3]; 3],
+    dist_coeffs: &[f32; 5],
+) -> Vec<(f32, f32)> {
+    let camera = CameraModel::new(camera_matrix, dist_coeffs);
+    points
+        .iter()
+        .map(|&(x, y)| {
+            let (nx, ny) = camera.normalize(x as f64, y as f64);
+            let (ux, uy) = camera.undistort(nx, ny);
+            let (px, py) = camera.to_pixel(ux, uy);
+            (px as f32, py as f32)
+        })
+        .collect()
+}
+
+// Camera intrinsics and Brown-Conrady distortion coefficients.
+struct CameraModel {
+    fx: f64,
+    fy: f64,
+    cx: f64,
+    cy: f64,
+    skew: f64,
+    k: [f64; 3],
+    p: [f64; 2],
+}
+
+impl CameraModel {
+    fn new(camera_matrix: &[[f32; 3]; 3], dist_coeffs: &[f32; 5]) -> CameraModel {
+        let [[fx, skew, cx], [_, fy, cy], _] = *camera_matrix;
+        assert!(fx != 0.0 && fy != 0.0, "focal lengths must be non-zero");
+        let [k1, k2, p1, p2, k3] = *dist_coeffs;
+        CameraModel {
+            fx: fx as f64,
+            fy: fy as f64,
+            cx: cx as f64,
+            cy: cy as f64,
+            skew: skew as f64,
+            k: [k1 as f64, k2 as f64, k3 as f64],
+            p: [p1 as f64, p2 as f64],
+        }
+    }
+
+    // Maps pixel coordinates to normalized coordinates.
+    fn normalize(&self, x: f64, y: f64) -> (f64, f64) {
+        let ny = (y - self.cy) / self.fy;
+        let nx = (x - self.cx - self.skew * ny) / self.fx;
+        (nx, ny)
+    }
+
+    // Maps normalized coordinates to pixel coordinates.
+    fn to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.fx * x + self.skew * y + self.cx, self.fy * y + self.cy)
+    }
+
+    // Returns the radial scale factor and the tangential offset at a normalized point.
+    fn distortion_terms(&self, x: f64, y: f64) -> (f64, (f64, f64)) {
+        let [k1, k2, k3] = self.k;
+        let [p1, p2] = self.p;
+        let r2 = x * x + y * y;
+        let radial = 1.0 + r2 * (k1 + r2 * (k2 + r2 * k3));
+        let tangential = (
+            2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+            p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y,
+        );
+        (radial, tangential)
+    }
+
+    fn distort(&self, x: f64, y: f64) -> (f64, f64) {
+        let (radial, (tx, ty)) = self.distortion_terms(x, y);
+        (x * radial + tx, y * radial + ty)
+    }
+
+    // Inverts distort by fixed-point iteration.
+    fn undistort(&self, xd: f64, yd: f64) -> (f64, f64) {
+        let (mut x, mut y) = (xd, yd);
+        for _ in 0..50 {
+            let (radial, (tx, ty)) = self.distortion_terms(x, y);
+            let (nx, ny) = ((xd - tx) / radial, (yd - ty) / radial);
+            let converged = (nx - x).abs() < 1e-12 && (ny - y).abs() < 1e-12;
+            x = nx;
+            y = ny;
+            if converged {
+                break;
+            }
+        }
+        (x, y)
+    }
+}
+
+/// Resizes an image to the given dimensions using a Lanczos filter with `a` lobes.
+///
+/// The filter is applied separably, first along rows and then along columns. Input and output
+/// pixel centres are aligned so that the image content is not shifted, i.e. output pixel `x`
+/// samples the input at `(x + 0.5) * width / new_width - 0.5`. When shrinking, the filter is
+/// stretched by the scale factor so that it also acts as an anti-aliasing low-pass filter.
+///
+/// Filter weights falling outside the image are discarded, and the remaining weights
+/// renormalised to sum to one, so that the borders of the image are not darkened.
+/// Values of `a` of 2 or 3 are typical; larger values preserve more detail but cause more
+/// ringing around sharp edges.
+///
+/// # Panics
+///
+/// If `a` is zero, or if the input image is empty and the output image is not.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::geometric_transformations::resize_lanczos;
+///
+/// let image = GrayImage::from_pixel(20, 10, Luma([40]));
+/// let resized = resize_lanczos(&image, 57, 4, 3);
+///
+/// assert_eq!(resized.dimensions(), (57, 4));
+/// assert!(resized.iter().all(|&p| p == 40));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn resize_lanczos(image: &GrayImage, new_width: u32, new_height: u32, a: u32) -> GrayImage {
+    assert!(a > 0, "a must be > 0");
+    let (width, height) = image.dimensions();
+    if new_width == 0 || new_height == 0 {
+        return GrayImage::new(new_width, new_height);
+    }
+    assert!(
+        width > 0 && height > 0,
+        "cannot resize an empty image to a non-empty one"
+    );
+
+    let columns = lanczos_weights(width, new_width, a);
+    let rows = lanczos_weights(height, new_height, a);
+
+    // Horizontal pass, from width x height to new_width x height.
+    let mut horizontal = vec![0f32; (new_width * height) as usize];
+    for y in 0..height {
+        for (x, (start, weights)) in columns.iter().enumerate() {
+            let mut sum = 0f32;
+            for (i, w) in weights.iter().enumerate() {
+                sum += w * image.get_pixel(start + i as u32, y)[0] as f32;
+            }
+            horizontal[(y * new_width) as usize + x] = sum;
+        }
+    }
+
+    // Vertical pass, from new_width x height to new_width x new_height.
+    let mut out = GrayImage::new(new_width, new_height);
+    for (y, (start, weights)) in rows.iter().enumerate() {
+        for x in 0..new_width {
+            let mut sum = 0f32;
+            for (i, w) in weights.iter().enumerate() {
+                sum += w * horizontal[((start + i as u32) * new_width + x) as usize];
+            }
+            out.put_pixel(x, y as u32, Luma([sum.round().clamp(0.0, 255.0) as u8]));
+        }
+    }
+    out
+}
+
+// For each output index, the first contributing input index and the normalised Lanczos
+// weights of the contributing input pixels.
+fn lanczos_weights(len: u32, new_len: u32, a: u32) -> Vec<(u32, Vec<f32>)> {
+    use std::f32::consts::PI;
+
+    let sinc = |x: f32| {
+        if x == 0.0 {
+            1.0
+        } else {
+            (PI * x).sin() / (PI * x)
+        }
+    };
+    let a = a as f32;
+    let lanczos = |x: f32| {
+        if x.abs() < a {
+            sinc(x) * sinc(x / a)
+        } else {
+            0.0
+        }
+    };
+
+    let scale = len as f32 / new_len as f32;
+    let filter_scale = scale.max(1.0);
+    let support = a * filter_scale;
+
+    (0..new_len)
+        .map(|i| {
+            let centre = (i as f32 + 0.5) * scale - 0.5;
+            let start = ((centre - support).floor() + 1.0).max(0.0) as u32;
+            let end = ((centre + support).ceil() as i64).clamp(0, len as i64 - 1) as u32;
+            let start = start.min(end);
+            let mut weights: Vec<f32> = (start..=end)
+                .map(|j| lanczos((j as f32 - centre) / filter_scale))
+                .collect();
+            let sum: f32 = weights.iter().sum();
+            if sum.abs() > f32::EPSILON {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            } else {
+                // Only possible if every tap lies on a zero of the kernel, so fall back
+                // to the nearest input pixel.
+                let nearest = (centre.round().max(0.0) as u32).clamp(start, end);
+                weights.iter_mut().for_each(|w| *w = 0.0);
+                weights[(nearest - start) as usize] = 1.0;
+            }
+            (start, weights)
+        })
+        .collect()
+}
+
 // Work horse of all warp functions
 // TODO: make faster by avoiding boundary checks in inner section of src image
 fn warp_inner<P, Fc, Fi>(out: &mut Image<P>, mapping: Fc, get_pixel: Fi)
@@ -615,41 +1550,48 @@ where
     outp
 }
 
+// Interpolates using Catmull-Rom cubic convolution over the 4x4 pixels nearest to (x, y).
+// Returns default if (x, y) lies outside the rectangle spanned by the pixel centres, and
+// otherwise uses default for any of the 16 taps lying outside the image.
 fn interpolate_bicubic<P>(image: &Image<P>, x: f32, y: f32, default: P) -> P
 where
     P: Pixel,
     <P as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
 {
-    let left = x.floor() - 1f32;
-    let right = left + 4f32;
-    let top = y.floor() - 1f32;
-    let bottom = top + 4f32;
-
-    let x_weight = x - (left + 1f32);
-    let y_weight = y - (top + 1f32);
-
-    let mut col: [P; 4] = [default, default, default, default];
-
     let (width, height) = image.dimensions();
-    if left < 0f32 || right >= width as f32 || top < 0f32 || bottom >= height as f32 {
-        default
-    } else {
-        for row in top as u32..bottom as u32 {
-            let (p0, p1, p2, p3): (P, P, P, P) = unsafe {
-                (
-                    image.unsafe_get_pixel(left as u32, row),
-                    image.unsafe_get_pixel(left as u32 + 1, row),
-                    image.unsafe_get_pixel(left as u32 + 2, row),
-                    image.unsafe_get_pixel(left as u32 + 3, row),
-                )
-            };
+    if width == 0
+        || height == 0
+        || !(x >= 0f32 && y >= 0f32 && x <= (width - 1) as f32 && y <= (height - 1) as f32)
+    {
+        return default;
+    }
+
+    let left = x.floor() as i64 - 1;
+    let top = y.floor() as i64 - 1;
+    let x_weight = x - x.floor();
+    let y_weight = y - y.floor();
 
-            let c = blend_cubic(&p0, &p1, &p2, &p3, x_weight);
-            col[row as usize - top as usize] = c;
+    let tap = |px: i64, py: i64| {
+        if px >= 0 && py >= 0 && px < width as i64 && py < height as i64 {
+            unsafe { image.unsafe_get_pixel(px as u32, py as u32) }
+        } else {
+            default
         }
+    };
 
-        blend_cubic(&col[0], &col[1], &col[2], &col[3], y_weight)
+    let mut col: [P; 4] = [default, default, default, default];
+    for (i, c) in col.iter_mut().enumerate() {
+        let row = top + i as i64;
+        *c = blend_cubic(
+            &tap(left, row),
+            &tap(left + 1, row),
+            &tap(left + 2, row),
+            &tap(left + 3, row),
+            x_weight,
+        );
     }
+
+    blend_cubic(&col[0], &col[1], &col[2], &col[3], y_weight)
 }
 
 fn blend_bilinear<P>(
@@ -677,38 +1619,99 @@ where
     })
 }
 
-fn interpolate_bilinear<P>(image: &Image<P>, x: f32, y: f32, default: P) -> P
+/// Returns the value of `image` at the sub-pixel position `(x, y)`, bilinearly interpolated
+/// from the four nearest pixels.
+///
+/// Pixel centres lie at integer coordinates, so integer positions return the exact pixel
+/// value. Returns `default` unless all four neighbouring pixels lie within the image, i.e.
+/// unless `(x, y)` lies in `[0, width - 1) x [0, height - 1)`. In particular, positions on
+/// the last row or column of the image return `default`.
+///
+/// This is the interpolation used by [`warp`] and [`rotate`] with [`Interpolation::Bilinear`].
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use image::Luma;
+/// use imageproc::geometric_transformations::interpolate_bilinear;
+///
+/// let image = gray_image!(
+///     0, 10;
+///     20, 30);
+///
+/// assert_eq!(interpolate_bilinear(&image, 0.5, 0.5, Luma([255])), Luma([15]));
+/// assert_eq!(interpolate_bilinear(&image, 0.0, 0.25, Luma([255])), Luma([5]));
+/// // The last column has no right neighbour
+/// assert_eq!(interpolate_bilinear(&image, 1.0, 0.25, Luma([255])), Luma([255]));
+/// # }
+/// ```
+pub fn interpolate_bilinear<I>(image: &I, x: f32, y: f32, default: I::Pixel) -> I::Pixel
 where
-    P: Pixel,
-    <P as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+    I: GenericImageView,
+    <I::Pixel as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
 {
+    // default if any neighbour is out of bounds. Written so that NaN coordinates are out
+    // of bounds
+    let (width, height) = image.dimensions();
+    if width == 0
+        || height == 0
+        || !(x >= 0f32 && y >= 0f32 && x < (width - 1) as f32 && y < (height - 1) as f32)
+    {
+        return default;
+    }
+
     let left = x.floor();
-    let right = left + 1f32;
     let top = y.floor();
-    let bottom = top + 1f32;
-
     let right_weight = x - left;
     let bottom_weight = y - top;
 
-    // default if out of bound
-    let (width, height) = image.dimensions();
-    if left < 0f32 || right >= width as f32 || top < 0f32 || bottom >= height as f32 {
-        default
-    } else {
-        let (tl, tr, bl, br) = unsafe {
-            (
-                image.unsafe_get_pixel(left as u32, top as u32),
-                image.unsafe_get_pixel(right as u32, top as u32),
-                image.unsafe_get_pixel(left as u32, bottom as u32),
-                image.unsafe_get_pixel(right as u32, bottom as u32),
-            )
-        };
-        blend_bilinear(tl, tr, bl, br, right_weight, bottom_weight)
-    }
+    let (left, top) = (left as u32, top as u32);
+    let (right, bottom) = (left + 1, top + 1);
+
+    // Safety: all coordinates were checked to be in bounds above
+    let (tl, tr, bl, br) = unsafe {
+        (
+            image.unsafe_get_pixel(left, top),
+            image.unsafe_get_pixel(right, top),
+            image.unsafe_get_pixel(left, bottom),
+            image.unsafe_get_pixel(right, bottom),
+        )
+    };
+    blend_bilinear(tl, tr, bl, br, right_weight, bottom_weight)
 }
 
+/// Returns the value of the pixel of `image` nearest to the sub-pixel position `(x, y)`.
+///
+/// Pixel centres lie at integer coordinates, so the pixel at `(x.round(), y.round())` is
+/// returned. Returns `default` if this pixel lies outside the image.
+///
+/// This is the interpolation used by [`warp`] and [`rotate`] with [`Interpolation::Nearest`].
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use image::Luma;
+/// use imageproc::geometric_transformations::interpolate_nearest;
+///
+/// let image = gray_image!(
+///     0, 10;
+///     20, 30);
+///
+/// assert_eq!(interpolate_nearest(&image, 0.7, 0.2, Luma([255])), Luma([10]));
+/// assert_eq!(interpolate_nearest(&image, -0.6, 0.0, Luma([255])), Luma([255]));
+/// # }
+/// ```
 #[inline(always)]
-fn interpolate_nearest<P: Pixel>(image: &Image<P>, x: f32, y: f32, default: P) -> P {
+pub fn interpolate_nearest<I>(image: &I, x: f32, y: f32, default: I::Pixel) -> I::Pixel
+where
+    I: GenericImageView,
+{
     if x < -0.5 || y < -0.5 {
         return default;
     }
@@ -721,6 +1724,7 @@ fn interpolate_nearest<P: Pixel>(image: &Image<P>, x: f32, y: f32, default: P) -
     if rx >= width || ry >= height {
         default
     } else {
+        // Safety: (rx, ry) was checked to be in bounds above
         unsafe { image.unsafe_get_pixel(rx, ry) }
     }
 }
@@ -734,15 +1738,266 @@ pub enum Interpolation {
     /// Bilinearly interpolate between the four pixels
     /// closest to the pre-image of the output pixel.
     Bilinear,
-    /// Bicubicly interpolate between the four pixels
-    /// closest to the pre-image of the output pixel.
+    /// Bicubicly interpolate between the sixteen pixels
+    /// closest to the pre-image of the output pixel, using
+    /// Catmull-Rom cubic convolution. Pixels outside the image
+    /// are treated as having the default value.
     Bicubic,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use image::Luma;
+    use crate::utils::gray_bench_image;
+    use image::{GrayImage, Luma, Rgb, RgbImage};
+
+    fn assert_matrix_near(actual: &Projection, expected: &Projection, tolerance: f32) {
+        for (a, e) in actual.transform.iter().zip(expected.transform.iter()) {
+            assert!(
+                (a - e).abs() <= tolerance,
+                "{:?} != {:?}",
+                actual.transform,
+                expected.transform
+            );
+        }
+    }
+
+    fn grid_points() -> Vec<(f32, f32)> {
+        (0..50)
+            .map(|i| ((i % 10) as f32 * 11.0 + 3.0, (i / 10) as f32 * 17.0 - 20.0))
+            .collect()
+    }
+
+    // Returns the images of points under projection, with uniform noise of the given
+    // magnitude added.
+    fn noisy_images(
+        projection: &Projection,
+        points: &[(f32, f32)],
+        noise: f32,
+        seed: u64,
+    ) -> Vec<(f32, f32)> {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        points
+            .iter()
+            .map(|&p| {
+                let (x, y) = *projection * p;
+                (
+                    x + rng.gen_range(-noise..=noise),
+                    y + rng.gen_range(-noise..=noise),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimate_affine_from_three_points_is_exact() {
+        let truth =
+            Projection::from_matrix([1.2, -0.3, 5.0, 0.4, 0.9, -7.0, 0.0, 0.0, 1.0]).unwrap();
+        let from = [(0.0, 0.0), (10.0, 2.0), (3.0, 8.0)];
+        let to: Vec<_> = from.iter().map(|&p| truth * p).collect();
+        let estimate = estimate_affine(&from, &to).unwrap();
+        assert_matrix_near(&estimate, &truth, 1e-4);
+    }
+
+    #[test]
+    fn test_estimate_affine_from_noisy_points() {
+        let truth = Projection::translate(12.0, -4.0)
+            * Projection::rotate(0.4)
+            * Projection::scale(1.3, 0.7);
+        let from = grid_points();
+        let to = noisy_images(&truth, &from, 0.2, 3);
+        let estimate = estimate_affine(&from, &to).unwrap();
+        assert_matrix_near(&estimate, &truth, 0.05);
+        for &p in &from {
+            let (x, y) = estimate * p;
+            let (ex, ey) = truth * p;
+            assert!((x - ex).abs() < 0.2 && (y - ey).abs() < 0.2);
+        }
+    }
+
+    #[test]
+    fn test_estimate_affine_rejects_degenerate_inputs() {
+        let square = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        assert!(estimate_affine(&square[..2], &square[..2]).is_none());
+        assert!(estimate_affine(&[], &[]).is_none());
+        let line = [(0.0, 1.0), (2.0, 2.0), (4.0, 3.0), (-2.0, 0.0)];
+        assert!(estimate_affine(&line, &square).is_none());
+        assert!(estimate_affine(&[(3.0, 3.0); 4], &square).is_none());
+        // The points are mapped onto a line by a non-invertible transformation
+        assert!(estimate_affine(&square, &line).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_estimate_affine_rejects_mismatched_lengths() {
+        let _ = estimate_affine(&[(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)], &[(0.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_estimate_affine_ransac_rejects_outliers() {
+        let truth = Projection::translate(-3.0, 8.0)
+            * Projection::rotate(-0.7)
+            * Projection::scale(0.9, 1.1);
+        let from = grid_points();
+        let mut to = noisy_images(&truth, &from, 0.1, 5);
+        // Replace 30% of the correspondences by gross errors
+        let outliers: Vec<usize> = (0..from.len()).filter(|i| i % 10 < 3).collect();
+        for &i in &outliers {
+            to[i] = (to[i].1 + 40.0, -to[i].0);
+        }
+
+        assert!(estimate_affine(&from, &to).is_some_and(|naive| {
+            let (x, y) = naive * from[5];
+            let (ex, ey) = truth * from[5];
+            (x - ex).abs() > 1.0 || (y - ey).abs() > 1.0
+        }));
+
+        let (estimate, inliers) = estimate_affine_ransac(&from, &to, 1.0, 200, 7).unwrap();
+        assert_matrix_near(&estimate, &truth, 0.05);
+        for (i, &inlier) in inliers.iter().enumerate() {
+            assert_eq!(inlier, !outliers.contains(&i), "correspondence {}", i);
+        }
+    }
+
+    #[test]
+    fn test_estimate_affine_ransac_is_reproducible() {
+        let truth = Projection::rotate(0.2);
+        let from = grid_points();
+        let mut to = noisy_images(&truth, &from, 0.5, 9);
+        to[0] = (500.0, 500.0);
+        let first = estimate_affine_ransac(&from, &to, 1.0, 20, 42).unwrap();
+        let second = estimate_affine_ransac(&from, &to, 1.0, 20, 42).unwrap();
+        assert_eq!(first.0.transform, second.0.transform);
+        assert_eq!(first.1, second.1);
+        assert!(estimate_affine_ransac(&from[..2], &to[..2], 1.0, 20, 42).is_none());
+    }
+
+    fn assert_maps_like(actual: &Projection, expected: &Projection, tolerance: f32) {
+        for &p in &grid_points() {
+            let (x, y) = *actual * p;
+            let (ex, ey) = *expected * p;
+            assert!(
+                (x - ex).abs() <= tolerance && (y - ey).abs() <= tolerance,
+                "{:?} maps to ({}, {}), expected ({}, {})",
+                p,
+                x,
+                y,
+                ex,
+                ey
+            );
+        }
+    }
+
+    fn example_homography() -> Projection {
+        Projection::from_matrix([0.9, 0.15, 12.0, -0.2, 1.1, -6.0, 8e-4, -5e-4, 1.0]).unwrap()
+    }
+
+    #[test]
+    fn test_estimate_homography_from_four_points_matches_control_points() {
+        let from = [(0.0, 0.0), (50.0, 3.0), (55.0, 40.0), (-4.0, 45.0)];
+        let to = from.map(|p| example_homography() * p);
+        let expected = Projection::from_control_points(from, to).unwrap();
+        let estimate = estimate_homography(&from, &to).unwrap();
+        assert_maps_like(&estimate, &expected, 1e-2);
+        assert_maps_like(&estimate, &example_homography(), 1e-2);
+    }
+
+    #[test]
+    fn test_estimate_homography_from_noisy_points() {
+        let from = grid_points();
+        let to = noisy_images(&example_homography(), &from, 0.2, 11);
+        let estimate = estimate_homography(&from, &to).unwrap();
+        assert_maps_like(&estimate, &example_homography(), 0.3);
+    }
+
+    #[test]
+    fn test_estimate_homography_rejects_degenerate_inputs() {
+        let square = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        assert!(estimate_homography(&square[..3], &square[..3]).is_none());
+        let collinear_triple = [(0.0, 0.0), (1.0, 1.0), (2.0, 2.0), (0.0, 1.0)];
+        assert!(estimate_homography(&collinear_triple, &square).is_none());
+        let line: Vec<_> = (0..8).map(|i| (i as f32, 2.0 * i as f32)).collect();
+        assert!(estimate_homography(&line, &line).is_none());
+        assert!(estimate_homography(&[(1.0, 1.0); 6], &[(2.0, 2.0); 6]).is_none());
+    }
+
+    #[test]
+    fn test_estimate_homography_ransac_flags_outliers() {
+        let truth = example_homography();
+        let from = grid_points();
+        let mut to = noisy_images(&truth, &from, 0.1, 13);
+        // Replace 30% of the correspondences by gross errors
+        let outliers: Vec<usize> = (0..from.len()).filter(|i| i % 10 >= 7).collect();
+        for &i in &outliers {
+            to[i] = (150.0 - to[i].0, to[i].1 + 25.0);
+        }
+
+        let (estimate, inliers) = estimate_homography_ransac(&from, &to, 1.0, 300, 3).unwrap();
+        assert_maps_like(&estimate, &truth, 0.2);
+        for (i, &inlier) in inliers.iter().enumerate() {
+            assert_eq!(inlier, !outliers.contains(&i), "correspondence {}", i);
+        }
+
+        let again = estimate_homography_ransac(&from, &to, 1.0, 300, 3).unwrap();
+        assert_eq!(again.0.transform, estimate.transform);
+        assert!(estimate_homography_ransac(&from[..3], &to[..3], 1.0, 300, 3).is_none());
+    }
+
+    #[test]
+    fn test_interpolate_bilinear_at_pixel_centres_is_exact() {
+        let image = gray_bench_image(7, 5);
+        let default = Luma([200]);
+        for y in 0..5 {
+            for x in 0..7 {
+                // Pixels on the last row or column have missing neighbours
+                let expected = if x < 6 && y < 4 {
+                    *image.get_pixel(x, y)
+                } else {
+                    default
+                };
+                let actual = interpolate_bilinear(&image, x as f32, y as f32, default);
+                assert_eq!(actual, expected, "at ({}, {})", x, y);
+            }
+        }
+        // Positions just before the last row or column are interpolated
+        assert_ne!(interpolate_bilinear(&image, 5.9, 3.9, default), default);
+    }
+
+    #[test]
+    fn test_interpolate_bilinear_between_four_pixels_is_their_average() {
+        let image = RgbImage::from_fn(3, 3, |x, y| Rgb([(40 * x) as u8, (60 * y) as u8, 7]));
+        assert_eq!(
+            interpolate_bilinear(&image, 1.5, 0.5, Rgb([0, 0, 0])),
+            Rgb([60, 30, 7])
+        );
+        assert_eq!(
+            interpolate_bilinear(&image, 0.5, 1.5, Rgb([0, 0, 0])),
+            Rgb([20, 90, 7])
+        );
+    }
+
+    #[test]
+    fn test_interpolate_out_of_bounds_returns_default() {
+        let image = GrayImage::from_pixel(4, 3, Luma([50]));
+        let default = Luma([1]);
+        for (x, y) in [
+            (-0.1, 1.0),
+            (3.1, 1.0),
+            (1.0, -0.1),
+            (1.0, 2.1),
+            (f32::NAN, 1.0),
+        ] {
+            assert_eq!(interpolate_bilinear(&image, x, y, default), default);
+        }
+        for (x, y) in [(-0.6, 1.0), (3.6, 1.0), (1.0, -0.6), (1.0, 2.6)] {
+            assert_eq!(interpolate_nearest(&image, x, y, default), default);
+        }
+        assert_eq!(interpolate_nearest(&image, 3.4, 2.4, default), Luma([50]));
+        let empty = GrayImage::new(0, 0);
+        assert_eq!(interpolate_bilinear(&empty, 0.0, 0.0, default), default);
+    }
 
     #[test]
     fn test_rotate_nearest_zero_radians() {
@@ -956,13 +2211,14 @@ mod tests {
             30, 31, 32, 33, 34;
             40, 41, 42, 43, 44);
 
-        // Expect 2 pixels each side lost due to kernel size
+        // Sampling at pixel centres reproduces the input exactly, even where
+        // some of the taps lie outside the image
         let expected = gray_image!(
             00, 00, 00, 00, 00;
-            00, 00, 00, 00, 00;
-            00, 00, 11, 00, 00;
-            00, 00, 00, 00, 00;
-            00, 00, 00, 00, 00);
+            00, 99, 01, 02, 03;
+            00, 10, 11, 12, 13;
+            00, 20, 21, 22, 23;
+            00, 30, 31, 32, 33);
 
         #[rustfmt::skip]
         let aff = Projection::from_matrix([
@@ -975,6 +2231,151 @@ mod tests {
         assert_pixels_eq!(translated_bicubic, expected);
     }
 
+    #[test]
+    fn test_resize_lanczos_downscaling_aliases_less_than_nearest() {
+        use std::f32::consts::PI;
+
+        // Frequencies well above the Nyquist limit of the downscaled image
+        let image = GrayImage::from_fn(120, 120, |x, y| {
+            let v = (2.0 * PI * 0.23 * x as f32).sin() * (2.0 * PI * 0.19 * y as f32).sin();
+            Luma([(128.0 + 100.0 * v).round() as u8])
+        });
+        let factor = 4;
+        let (width, height) = (120 / factor, 120 / factor);
+
+        // Averaging each block of the input removes most of the high frequency content
+        let reference = GrayImage::from_fn(width, height, |x, y| {
+            let mut sum = 0u32;
+            for dy in 0..factor {
+                for dx in 0..factor {
+                    sum += image.get_pixel(factor * x + dx, factor * y + dy)[0] as u32;
+                }
+            }
+            Luma([(sum / (factor * factor)) as u8])
+        });
+        let nearest = GrayImage::from_fn(width, height, |x, y| {
+            *image.get_pixel(factor * x + factor / 2, factor * y + factor / 2)
+        });
+        let lanczos = resize_lanczos(&image, width, height, 3);
+
+        let error_variance = |resized: &GrayImage| {
+            let errors: Vec<f32> = resized
+                .iter()
+                .zip(reference.iter())
+                .map(|(&p, &r)| p as f32 - r as f32)
+                .collect();
+            let mean = errors.iter().sum::<f32>() / errors.len() as f32;
+            errors.iter().map(|e| (e - mean).powi(2)).sum::<f32>() / errors.len() as f32
+        };
+
+        let (nearest_variance, lanczos_variance) =
+            (error_variance(&nearest), error_variance(&lanczos));
+        assert!(
+            lanczos_variance * 10.0 < nearest_variance,
+            "nearest: {}, lanczos: {}",
+            nearest_variance,
+            lanczos_variance
+        );
+    }
+
+    #[test]
+    fn test_resize_lanczos_preserves_constant_image() {
+        let image = GrayImage::from_pixel(7, 5, Luma([201]));
+        for (width, height, a) in [(31, 22, 3), (8, 5, 2), (3, 2, 3), (7, 19, 1), (1, 1, 4)] {
+            let resized = resize_lanczos(&image, width, height, a);
+            assert_pixels_eq!(resized, GrayImage::from_pixel(width, height, Luma([201])));
+        }
+    }
+
+    #[test]
+    fn test_resize_lanczos_to_same_size_is_identity() {
+        let image = gray_bench_image(23, 17);
+        assert_pixels_eq!(resize_lanczos(&image, 23, 17, 3), image);
+    }
+
+    #[test]
+    fn test_resize_lanczos_handles_empty_output() {
+        let image = gray_bench_image(5, 5);
+        assert_eq!(resize_lanczos(&image, 0, 3, 2).dimensions(), (0, 3));
+    }
+
+    #[test]
+    fn test_bicubic_identity_reproduces_input() {
+        let image = gray_bench_image(13, 9);
+        let identity = Projection::translate(0.0, 0.0);
+        assert_pixels_eq!(
+            warp(&image, &identity, Interpolation::Bicubic, Luma([7u8])),
+            image
+        );
+        assert_pixels_eq!(
+            rotate_about_center(&image, 0.0, Interpolation::Bicubic, Luma([7u8])),
+            image
+        );
+    }
+
+    #[test]
+    fn test_bicubic_uses_default_for_taps_outside_image() {
+        let image = GrayImage::from_pixel(4, 4, Luma([100u8]));
+        // Halfway between the first two columns the taps at -1 and 2 have equal, negative,
+        // weights, so a default brighter than the image darkens the result.
+        let shift = Projection::translate(-0.5, 0.0);
+        let shifted = warp(&image, &shift, Interpolation::Bicubic, Luma([200u8]));
+        assert!(shifted.get_pixel(0, 1)[0] < 100);
+        assert_eq!(shifted.get_pixel(1, 1)[0], 100);
+        assert_eq!(shifted.get_pixel(3, 1)[0], 200);
+    }
+
+    #[test]
+    fn test_bicubic_on_empty_image_returns_default() {
+        let empty = GrayImage::new(0, 0);
+        assert_eq!(
+            interpolate_bicubic(&empty, 0.0, 0.0, Luma([9u8])),
+            Luma([9u8])
+        );
+
+        let mut out = GrayImage::new(3, 2);
+        let identity = Projection::translate(0.0, 0.0);
+        warp_into(
+            &empty,
+            &identity,
+            Interpolation::Bicubic,
+            Luma([9u8]),
+            &mut out,
+        );
+        assert!(out.iter().all(|&p| p == 9));
+    }
+
+    #[test]
+    fn test_bicubic_upsampling_of_smooth_image_beats_bilinear() {
+        let f = |x: f32, y: f32| 120.0 + 60.0 * (x / 3.0).sin() + 50.0 * (y / 4.0).cos();
+        let image: Image<Luma<f32>> = Image::from_fn(24, 24, |x, y| Luma([f(x as f32, y as f32)]));
+        let upscale = Projection::scale(4.0, 4.0);
+
+        let error = |interpolation| {
+            let mut upsampled = Image::new(96, 96);
+            warp_into(&image, &upscale, interpolation, Luma([0.0]), &mut upsampled);
+            let mut sum = 0.0;
+            for y in 8..84 {
+                for x in 8..84 {
+                    let expected = f(x as f32 / 4.0, y as f32 / 4.0);
+                    sum += (upsampled.get_pixel(x, y)[0] - expected).powi(2);
+                }
+            }
+            sum
+        };
+
+        let (bilinear, bicubic) = (
+            error(Interpolation::Bilinear),
+            error(Interpolation::Bicubic),
+        );
+        assert!(
+            bicubic * 4.0 < bilinear,
+            "bilinear: {}, bicubic: {}",
+            bilinear,
+            bicubic
+        );
+    }
+
     #[cfg_attr(miri, ignore = "Miri detected UB in nalgebra")]
     #[test]
     fn test_from_control_points_translate() {
@@ -1068,6 +2469,64 @@ mod tests {
         assert!(p.is_none());
     }
 
+    #[cfg_attr(miri, ignore = "Miri detected UB in nalgebra")]
+    #[test]
+    fn test_from_control_points_colinear_from() {
+        let from = [(0f32, 0.0), (10.0, 10.0), (20.0, 20.0), (0.0, 50.0)];
+        let to = [(0f32, 0.0), (50.0, 50.0), (50.0, 0.0), (0.0, 50.0)];
+        assert!(Projection::from_control_points(from, to).is_none());
+
+        // Repeated points are degenerate too
+        let from = [(0f32, 0.0), (10.0, 0.0), (10.0, 10.0), (10.0, 10.0)];
+        assert!(Projection::from_control_points(from, to).is_none());
+    }
+
+    #[cfg_attr(miri, ignore = "Miri detected UB in nalgebra")]
+    #[test]
+    fn test_from_control_points_square_to_trapezoid_and_back() {
+        let square = [(10f32, 10.0), (50.0, 10.0), (50.0, 50.0), (10.0, 50.0)];
+        let trapezoid = [(22f32, 14.0), (38.0, 14.0), (56.0, 48.0), (4.0, 48.0)];
+
+        let forward = Projection::from_control_points(square, trapezoid).unwrap();
+        let backward = Projection::from_control_points(trapezoid, square).unwrap();
+        for (s, t) in square.iter().zip(trapezoid.iter()) {
+            let mapped = forward * *s;
+            assert_approx_eq!(mapped.0, t.0, 1e-3);
+            assert_approx_eq!(mapped.1, t.1, 1e-3);
+        }
+
+        // Warping an image forwards and then backwards approximately recovers it
+        let image = GrayImage::from_fn(60, 60, |x, y| {
+            if (10..=50).contains(&x) && (10..=50).contains(&y) {
+                Luma([(3 * x + y) as u8])
+            } else {
+                Luma([0])
+            }
+        });
+        let warped = warp(&image, &forward, Interpolation::Bilinear, Luma([0]));
+        let recovered = warp(&warped, &backward, Interpolation::Bilinear, Luma([0]));
+        for y in 14..47 {
+            for x in 14..47 {
+                let (original, result) = (image.get_pixel(x, y)[0], recovered.get_pixel(x, y)[0]);
+                assert!(
+                    (original as i32 - result as i32).abs() <= 3,
+                    "({}, {}): {} vs {}",
+                    x,
+                    y,
+                    original,
+                    result
+                );
+            }
+        }
+
+        // Points round trip with sub-pixel error
+        let round_trip = backward * forward;
+        for &(x, y) in &[(10f32, 10.0), (30.5, 20.25), (47.0, 12.0), (15.0, 45.0)] {
+            let (rx, ry) = round_trip * (x, y);
+            assert!((rx - x).abs() < 0.01 && (ry - y).abs() < 0.01);
+        }
+    }
+
     #[cfg_attr(miri, ignore = "Miri detected UB in nalgebra")]
     #[test]
     fn test_from_control_points_translation() {
@@ -1101,6 +2560,302 @@ mod tests {
         let p = Projection::from_control_points(from, to);
         p.unwrap();
     }
+
+    fn identity_maps(width: u32, height: u32) -> (Image<Luma<f32>>, Image<Luma<f32>>) {
+        (
+            Image::from_fn(width, height, |x, _| Luma([x as f32])),
+            Image::from_fn(width, height, |_, y| Luma([y as f32])),
+        )
+    }
+
+    #[test]
+    fn test_remap_identity_reproduces_input() {
+        let image = GrayImage::from_fn(9, 8, |x, y| Luma([(x * y + 3 * x) as u8]));
+        let (map_x, map_y) = identity_maps(9, 8);
+        let remapped = remap(&image, &map_x, &map_y, Interpolation::Nearest, Luma([0]));
+        assert_pixels_eq!(remapped, image);
+
+        // Bilinear and bicubic interpolation need neighbouring pixels
+        // on one or both sides, so are exact only away from the border
+        let inner = |image: &GrayImage| image.view(1, 1, 5, 4).to_image();
+        for interpolation in [Interpolation::Bilinear, Interpolation::Bicubic] {
+            let remapped = remap(&image, &map_x, &map_y, interpolation, Luma([0]));
+            assert_pixels_eq!(inner(&remapped), inner(&image));
+        }
+    }
+
+    #[test]
+    fn test_remap_shift_translates_image() {
+        let image = GrayImage::from_fn(7, 6, |x, y| Luma([(10 * x + y) as u8]));
+        let (mut map_x, mut map_y) = identity_maps(7, 6);
+        map_x.iter_mut().for_each(|x| *x -= 2.0);
+        map_y.iter_mut().for_each(|y| *y += 1.0);
+
+        let expected = GrayImage::from_fn(7, 6, |x, y| {
+            if x < 2 || y == 5 {
+                Luma([99])
+            } else {
+                *image.get_pixel(x - 2, y + 1)
+            }
+        });
+        let remapped = remap(&image, &map_x, &map_y, Interpolation::Nearest, Luma([99]));
+        assert_pixels_eq!(remapped, expected);
+
+        let remapped = remap(&image, &map_x, &map_y, Interpolation::Bilinear, Luma([99]));
+        let inner = |image: &GrayImage| image.view(2, 0, 4, 4).to_image();
+        assert_pixels_eq!(inner(&remapped), inner(&expected));
+    }
+
+    #[test]
+    fn test_remap_output_has_dimensions_of_maps() {
+        let image = gray_image!(
+            1, 2;
+            3, 4);
+        let map_x = Image::from_pixel(3, 1, Luma([1.0]));
+        let map_y = Image::from_fn(3, 1, |x, _| Luma([x as f32]));
+        let remapped = remap(&image, &map_x, &map_y, Interpolation::Nearest, Luma([0]));
+        assert_pixels_eq!(remapped, gray_image!(2, 4, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_remap_rejects_mismatched_maps() {
+        let image = GrayImage::new(3, 3);
+        let _ = remap(
+            &image,
+            &Image::new(3, 3),
+            &Image::new(3, 2),
+            Interpolation::Nearest,
+            Luma([0]),
+        );
+    }
+
+    // A smoothly varying image, so that interpolation errors are small
+    fn smooth_image(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, y| {
+            let (x, y) = (x as f32, y as f32);
+            Luma([(128.0 + 50.0 * (x / 6.0).sin() + 50.0 * (y / 7.0).cos()) as u8])
+        })
+    }
+
+    #[test]
+    fn test_swirl_leaves_outside_radius_and_center_unchanged() {
+        let image = GrayImage::from_fn(30, 25, |x, y| Luma([(7 * x + 3 * y) as u8]));
+        let (center, radius) = ((14.0, 12.0), 8.0);
+        let swirled = swirl(&image, center, 3.0, radius, Interpolation::Bilinear);
+
+        let mut changed = 0;
+        for (x, y, p) in swirled.enumerate_pixels() {
+            let distance = ((x as f32 - center.0).powi(2) + (y as f32 - center.1).powi(2)).sqrt();
+            if distance >= radius {
+                assert_eq!(p, image.get_pixel(x, y), "({}, {})", x, y);
+            } else if p != image.get_pixel(x, y) {
+                changed += 1;
+            }
+        }
+        assert_eq!(swirled.get_pixel(14, 12), image.get_pixel(14, 12));
+        assert!(changed > 100);
+    }
+
+    #[test]
+    fn test_swirl_then_negated_swirl_approximately_restores_image() {
+        let image = smooth_image(40, 40);
+        let (center, radius) = ((20.0, 19.0), 15.0);
+        let swirled = swirl(&image, center, 2.0, radius, Interpolation::Bilinear);
+        let restored = swirl(&swirled, center, -2.0, radius, Interpolation::Bilinear);
+
+        let mut max_swirled_error = 0;
+        for (x, y, p) in restored.enumerate_pixels() {
+            let original = image.get_pixel(x, y)[0] as i32;
+            assert!((p[0] as i32 - original).abs() <= 4, "({}, {})", x, y);
+            max_swirled_error =
+                max_swirled_error.max((swirled.get_pixel(x, y)[0] as i32 - original).abs());
+        }
+        assert!(max_swirled_error > 20);
+    }
+
+    // Intensity weighted mean column of the pixels in row y between x0 and x1.
+    fn mean_column(image: &GrayImage, y: u32, x0: u32, x1: u32) -> f32 {
+        let (mut sum, mut weighted) = (0f32, 0f32);
+        for x in x0..x1 {
+            let v = image.get_pixel(x, y)[0] as f32;
+            sum += v;
+            weighted += v * x as f32;
+        }
+        weighted / sum
+    }
+
+    // Vertical lines one pixel wide, every 10 pixels from x = 5.
+    fn vertical_lines(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, _| {
+            Luma([if x % 10 == 5 { 255 } else { 0 }])
+        })
+    }
+
+    // The positions of the vertical lines in row y of an image, from left to right.
+    fn line_positions(image: &GrayImage, y: u32) -> Vec<f32> {
+        let mut positions = Vec::new();
+        let mut x = 0;
+        while x < image.width() {
+            if image.get_pixel(x, y)[0] > 0 {
+                let start = x;
+                while x < image.width() && image.get_pixel(x, y)[0] > 0 {
+                    x += 1;
+                }
+                positions.push(mean_column(image, y, start, x));
+            }
+            x += 1;
+        }
+        positions
+    }
+
+    #[test]
+    fn test_warp_cylindrical_keeps_vertical_lines_straight_and_compresses_edges() {
+        let image = vertical_lines(101, 61);
+        let warped = warp_cylindrical(&image, 60.0, Luma([0]));
+
+        // Horizontal extents shrink and vertical extents are unchanged
+        assert!(warped.width() < image.width());
+        assert_eq!(warped.height(), image.height());
+
+        // The line just right of the center stays straight
+        let (cx, height) = (warped.width() / 2, warped.height());
+        let top = mean_column(&warped, 5, cx, cx + 8);
+        for y in 5..height - 5 {
+            assert!(
+                (mean_column(&warped, y, cx, cx + 8) - top).abs() < 0.05,
+                "row {}",
+                y
+            );
+        }
+
+        // Lines are closer together towards the edges
+        let positions = line_positions(&warped, height / 2);
+        assert_eq!(positions.len(), 10);
+        let spacings: Vec<f32> = positions.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(spacings[0] < spacings[4] - 1.0, "{:?}", spacings);
+        assert!(spacings[8] < spacings[4] - 1.0, "{:?}", spacings);
+    }
+
+    #[test]
+    fn test_warp_spherical_compresses_towards_edges() {
+        let image = vertical_lines(101, 61);
+        let warped = warp_spherical(&image, 60.0, Luma([0]));
+
+        assert!(warped.width() < image.width());
+        assert!(warped.height() < image.height());
+
+        // The center row is unchanged apart from horizontal compression
+        let positions = line_positions(&warped, warped.height() / 2);
+        assert_eq!(positions.len(), 10);
+        let spacings: Vec<f32> = positions.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(spacings[0] < spacings[4] - 1.0, "{:?}", spacings);
+        assert!(spacings[8] < spacings[4] - 1.0, "{:?}", spacings);
+    }
+
+    #[test]
+    fn test_warp_cylindrical_with_long_focal_length_is_near_identity() {
+        let image = smooth_image(30, 20);
+        let warped = warp_cylindrical(&image, 1e5, Luma([0]));
+        assert_eq!(warped.dimensions(), image.dimensions());
+        let inner = |image: &GrayImage| image.view(1, 1, 27, 17).to_image();
+        assert_pixels_eq_within!(inner(&warped), inner(&image), 1);
+    }
+
+    const CAMERA_MATRIX: [[f32; 3]; 3] = [[50.0, 0.0, 40.0], [0.0, 50.0, 30.0], [0.0, 0.0, 1.0]];
+    const DIST_COEFFS: [f32; 5] = [-0.3, 0.1, 0.002, -0.003, 0.0];
+
+    // Bright one pixel wide lines every 10 pixels on a dark background
+    fn grid_image() -> GrayImage {
+        GrayImage::from_fn(80, 60, |x, y| {
+            if x % 10 == 5 || y % 10 == 5 {
+                Luma([255])
+            } else {
+                Luma([0])
+            }
+        })
+    }
+
+    // Simulates capturing an image through a lens with the given distortion.
+    fn distort(image: &GrayImage) -> GrayImage {
+        warp_with(
+            image,
+            |x, y| undistort_points(&[(x, y)], &CAMERA_MATRIX, &DIST_COEFFS)[0],
+            Interpolation::Bilinear,
+            Luma([0]),
+        )
+    }
+
+    // The largest distance from the row containing each horizontal line to the intensity
+    // weighted mean row of the nearby pixels, over all columns away from the image edges.
+    fn max_row_deviation(image: &GrayImage) -> f32 {
+        let mut max = 0f32;
+        for line in [15, 25, 35, 45] {
+            for x in 15..66 {
+                if (3..=7).contains(&(x % 10)) {
+                    continue;
+                }
+                let (mut sum, mut weighted) = (0f32, 0f32);
+                for y in line - 4..=line + 4 {
+                    let v = image.get_pixel(x, y)[0] as f32;
+                    sum += v;
+                    weighted += v * y as f32;
+                }
+                let deviation = if sum > 0.0 {
+                    (weighted / sum - line as f32).abs()
+                } else {
+                    f32::INFINITY
+                };
+                max = max.max(deviation);
+            }
+        }
+        max
+    }
+
+    #[test]
+    fn test_undistort_straightens_grid_lines() {
+        let ideal = grid_image();
+        let distorted = distort(&ideal);
+        assert!(max_row_deviation(&distorted) > 1.0);
+
+        let corrected = undistort(
+            &distorted,
+            &CAMERA_MATRIX,
+            &DIST_COEFFS,
+            Interpolation::Bilinear,
+        );
+        let deviation = max_row_deviation(&corrected);
+        assert!(deviation < 0.3, "deviation: {}", deviation);
+    }
+
+    #[test]
+    fn test_undistort_with_zero_coefficients_is_identity() {
+        let image = grid_image();
+        let corrected = undistort(&image, &CAMERA_MATRIX, &[0.0; 5], Interpolation::Nearest);
+        assert_pixels_eq!(corrected, image);
+    }
+
+    #[test]
+    fn test_undistort_points_inverts_distortion() {
+        let camera = CameraModel::new(&CAMERA_MATRIX, &DIST_COEFFS);
+        let points = [(40f32, 30.0), (3.0, 4.0), (75.5, 12.25), (20.0, 55.0)];
+        let distorted: Vec<(f32, f32)> = points
+            .iter()
+            .map(|&(x, y)| {
+                let (nx, ny) = camera.normalize(x as f64, y as f64);
+                let (dx, dy) = camera.distort(nx, ny);
+                let (px, py) = camera.to_pixel(dx, dy);
+                (px as f32, py as f32)
+            })
+            .collect();
+        assert!(distorted[1] != points[1]);
+
+        let undistorted = undistort_points(&distorted, &CAMERA_MATRIX, &DIST_COEFFS);
+        for (p, u) in points.iter().zip(&undistorted) {
+            assert_approx_eq!(p.0, u.0, 1e-3);
+            assert_approx_eq!(p.1, u.1, 1e-3);
+        }
+    }
 }
 
 #[cfg(not(miri))]
@@ -1144,6 +2899,15 @@ mod benches {
         });
     }
 
+    #[bench]
+    fn bench_resize_lanczos(b: &mut Bencher) {
+        let image = gray_bench_image(400, 300);
+        b.iter(|| {
+            let resized = resize_lanczos(&image, 157, 113, 3);
+            black_box(resized);
+        });
+    }
+
     #[bench]
     fn bench_translate(b: &mut Bencher) {
         let image = gray_bench_image(500, 500);
@@ -1,5 +1,6 @@
 //! Computational geometry functions, for example finding convex hulls.
 
+use crate::contours::Contour;
 use crate::point::{distance, Line, Point, Rotation};
 use num::{cast, NumCast};
 use std::cmp::{Ord, Ordering};
@@ -22,22 +23,105 @@ where
 
 /// Approximates a polygon using the [Douglas–Peucker algorithm].
 ///
+/// Points are removed from `curve` while this moves the curve by at most `epsilon`.
+/// The first and last points of an open curve are always kept. If `closed` is `true` then
+/// `curve` is treated as a loop, with an implicit edge from its last point back to its first,
+/// and both the first point and the point furthest from it are kept. The returned polygon
+/// then does not repeat its first point.
+///
+/// If `epsilon` is zero then `curve` is returned unchanged.
+///
+/// # Panics
+///
+/// If `epsilon` is negative.
+///
+/// # Examples
+/// ```
+/// use imageproc::geometry::approximate_polygon_dp;
+/// use imageproc::point::Point;
+///
+/// // A densely sampled square
+/// let mut square = Vec::new();
+/// for i in 0..10 {
+///     square.push(Point::new(i, 0));
+/// }
+/// for i in 0..10 {
+///     square.push(Point::new(10, i));
+/// }
+/// for i in 0..10 {
+///     square.push(Point::new(10 - i, 10));
+/// }
+/// for i in 0..10 {
+///     square.push(Point::new(0, 10 - i));
+/// }
+///
+/// assert_eq!(
+///     approximate_polygon_dp(&square, 0.5, true),
+///     vec![
+///         Point::new(0, 0),
+///         Point::new(10, 0),
+///         Point::new(10, 10),
+///         Point::new(0, 10),
+///     ]
+/// );
+/// ```
+///
 /// [Douglas–Peucker algorithm]: https://en.wikipedia.org/wiki/Ramer-Douglas-Peucker_algorithm
 pub fn approximate_polygon_dp<T>(curve: &[Point<T>], epsilon: f64, closed: bool) -> Vec<Point<T>>
 where
     T: NumCast + Copy,
 {
-    if epsilon <= 0.0 {
-        panic!("epsilon must be greater than 0.0");
+    if epsilon < 0.0 {
+        panic!("epsilon must be non-negative");
+    }
+    if epsilon == 0.0 || curve.len() < 3 {
+        return curve.to_vec();
+    }
+
+    if !closed {
+        return approximate_polyline_dp(curve, epsilon);
+    }
+
+    // Split the loop at the point furthest from its first point, and simplify each half
+    let first = curve[0].to_f64();
+    let (furthest, _) = curve
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i, distance(first, p.to_f64())))
+        .fold(
+            (0, 0.0),
+            |best, next| if next.1 > best.1 { next } else { best },
+        );
+    if furthest == 0 {
+        // Every point is equal to the first
+        return vec![curve[0]];
+    }
+
+    let mut res = approximate_polyline_dp(&curve[0..=furthest], epsilon);
+    let mut second_half = curve[furthest..].to_vec();
+    second_half.push(curve[0]);
+    res.pop();
+    res.extend(approximate_polyline_dp(&second_half, epsilon));
+    res.pop();
+    res
+}
+
+// Douglas-Peucker simplification of an open polyline with at least one point.
+fn approximate_polyline_dp<T>(curve: &[Point<T>], epsilon: f64) -> Vec<Point<T>>
+where
+    T: NumCast + Copy,
+{
+    let end = curve.len() - 1;
+    if end < 2 {
+        return curve.to_vec();
     }
 
     // Find the point with the maximum distance
+    let (start, finish) = (curve[0].to_f64(), curve[end].to_f64());
     let mut dmax = 0.0;
     let mut index = 0;
-    let end = curve.len() - 1;
-    let line = Line::from_points(curve[0].to_f64(), curve[end].to_f64());
-    for (i, point) in curve.iter().enumerate().skip(1) {
-        let d = line.distance_from_point(point.to_f64());
+    for (i, point) in curve.iter().enumerate().take(end).skip(1) {
+        let d = segment_deviation(start, finish, point.to_f64());
         if d > dmax {
             index = i;
             dmax = d;
@@ -45,10 +129,9 @@ where
     }
 
     // If max distance is greater than epsilon, recursively simplify
-    let mut res = if dmax > epsilon {
-        // Recursive call
-        let mut partial1 = approximate_polygon_dp(&curve[0..=index], epsilon, false);
-        let partial2 = approximate_polygon_dp(&curve[index..=end], epsilon, false);
+    if dmax > epsilon {
+        let mut partial1 = approximate_polyline_dp(&curve[0..=index], epsilon);
+        let partial2 = approximate_polyline_dp(&curve[index..=end], epsilon);
 
         // Build the result list
         partial1.pop();
@@ -56,13 +139,17 @@ where
         partial1
     } else {
         vec![curve[0], curve[end]]
-    };
-
-    if closed {
-        res.pop();
     }
+}
 
-    res
+// The distance from point to the line through start and finish,
+// or to start if start and finish coincide.
+fn segment_deviation(start: Point<f64>, finish: Point<f64>, point: Point<f64>) -> f64 {
+    if start == finish {
+        distance(start, point)
+    } else {
+        Line::from_points(start, finish).distance_from_point(point)
+    }
 }
 
 /// Calculates the area of the contour using the [shoelace formula].
@@ -95,6 +182,218 @@ where
     })
 }
 
+/// Spatial, central and normalised central moments of a polygon up to order three,
+/// as computed by [`contour_moments`].
+///
+/// The spatial moment `m_pq` is the integral of `x^p y^q` over the polygon, the central
+/// moment `mu_pq` is the integral of `(x - cx)^p (y - cy)^q` where `(cx, cy)` is the centroid,
+/// and the normalised central moment `nu_pq` is `mu_pq / m00^((p + q) / 2 + 1)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Moments {
+    /// Spatial moment of order (0, 0).
+    pub m00: f64,
+    /// Spatial moment of order (1, 0).
+    pub m10: f64,
+    /// Spatial moment of order (0, 1).
+    pub m01: f64,
+    /// Spatial moment of order (2, 0).
+    pub m20: f64,
+    /// Spatial moment of order (1, 1).
+    pub m11: f64,
+    /// Spatial moment of order (0, 2).
+    pub m02: f64,
+    /// Spatial moment of order (3, 0).
+    pub m30: f64,
+    /// Spatial moment of order (2, 1).
+    pub m21: f64,
+    /// Spatial moment of order (1, 2).
+    pub m12: f64,
+    /// Spatial moment of order (0, 3).
+    pub m03: f64,
+    /// Central moment of order (2, 0).
+    pub mu20: f64,
+    /// Central moment of order (1, 1).
+    pub mu11: f64,
+    /// Central moment of order (0, 2).
+    pub mu02: f64,
+    /// Central moment of order (3, 0).
+    pub mu30: f64,
+    /// Central moment of order (2, 1).
+    pub mu21: f64,
+    /// Central moment of order (1, 2).
+    pub mu12: f64,
+    /// Central moment of order (0, 3).
+    pub mu03: f64,
+    /// Normalised central moment of order (2, 0).
+    pub nu20: f64,
+    /// Normalised central moment of order (1, 1).
+    pub nu11: f64,
+    /// Normalised central moment of order (0, 2).
+    pub nu02: f64,
+    /// Normalised central moment of order (3, 0).
+    pub nu30: f64,
+    /// Normalised central moment of order (2, 1).
+    pub nu21: f64,
+    /// Normalised central moment of order (1, 2).
+    pub nu12: f64,
+    /// Normalised central moment of order (0, 3).
+    pub nu03: f64,
+}
+
+impl Moments {
+    /// The area of the polygon.
+    pub fn area(&self) -> f64 {
+        self.m00
+    }
+
+    /// The `(x, y)` coordinates of the centroid of the polygon, or `None` if its area is zero.
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        if self.m00 == 0.0 {
+            None
+        } else {
+            Some((self.m10 / self.m00, self.m01 / self.m00))
+        }
+    }
+
+    /// The seven [Hu moment invariants], computed from the normalised central moments.
+    ///
+    /// These are invariant under translation, scaling and rotation. The seventh
+    /// changes sign under reflection.
+    ///
+    /// [Hu moment invariants]: https://en.wikipedia.org/wiki/Image_moment#Rotation_invariants
+    pub fn hu_moments(&self) -> [f64; 7] {
+        let (n20, n11, n02) = (self.nu20, self.nu11, self.nu02);
+        let (n30, n21, n12, n03) = (self.nu30, self.nu21, self.nu12, self.nu03);
+        let (a, b) = (n30 + n12, n21 + n03);
+        [
+            n20 + n02,
+            (n20 - n02).powi(2) + 4.0 * n11 * n11,
+            (n30 - 3.0 * n12).powi(2) + (3.0 * n21 - n03).powi(2),
+            a * a + b * b,
+            (n30 - 3.0 * n12) * a * (a * a - 3.0 * b * b)
+                + (3.0 * n21 - n03) * b * (3.0 * a * a - b * b),
+            (n20 - n02) * (a * a - b * b) + 4.0 * n11 * a * b,
+            (3.0 * n21 - n03) * a * (a * a - 3.0 * b * b)
+                - (n30 - 3.0 * n12) * b * (3.0 * a * a - b * b),
+        ]
+    }
+
+    /// The [`hu_moments`](Moments::hu_moments) scaled to comparable magnitudes by
+    /// `h -> -sign(h) * log10(|h|)`. Invariants equal to zero are left as zero.
+    pub fn log_hu_moments(&self) -> [f64; 7] {
+        self.hu_moments().map(|h| {
+            if h == 0.0 {
+                0.0
+            } else {
+                -h.signum() * h.abs().log10()
+            }
+        })
+    }
+}
+
+/// Computes the moments of the polygon with the given vertices, using Green's theorem.
+///
+/// The polygon is closed by an edge from the last point to the first. Moments do not depend
+/// on the orientation of the polygon, and the moments of self-intersecting polygons are
+/// not meaningful. Normalised central moments are zero if the area of the polygon is zero.
+///
+/// # Examples
+/// ```
+/// use imageproc::geometry::contour_moments;
+/// use imageproc::point::Point;
+///
+/// let rect = [
+///     Point::new(2, 1),
+///     Point::new(8, 1),
+///     Point::new(8, 5),
+///     Point::new(2, 5),
+/// ];
+///
+/// let moments = contour_moments(&rect);
+/// assert_eq!(moments.area(), 24.0);
+/// assert_eq!(moments.centroid(), Some((5.0, 3.0)));
+/// ```
+pub fn contour_moments<T>(points: &[Point<T>]) -> Moments
+where
+    T: NumCast + Copy,
+{
+    let mut m = [0f64; 10];
+    let n = points.len();
+    for i in 0..n {
+        let (p, q) = (points[i].to_f64(), points[(i + 1) % n].to_f64());
+        let (x0, y0, x1, y1) = (p.x, p.y, q.x, q.y);
+        let a = x0 * y1 - x1 * y0;
+        m[0] += a;
+        m[1] += a * (x0 + x1);
+        m[2] += a * (y0 + y1);
+        m[3] += a * (x0 * x0 + x0 * x1 + x1 * x1);
+        m[4] += a * (x0 * (2.0 * y0 + y1) + x1 * (y0 + 2.0 * y1));
+        m[5] += a * (y0 * y0 + y0 * y1 + y1 * y1);
+        m[6] += a * (x0 + x1) * (x0 * x0 + x1 * x1);
+        m[7] +=
+            a * (x0 * x0 * (3.0 * y0 + y1) + 2.0 * x0 * x1 * (y0 + y1) + x1 * x1 * (y0 + 3.0 * y1));
+        m[8] +=
+            a * (y0 * y0 * (3.0 * x0 + x1) + 2.0 * y0 * y1 * (x0 + x1) + y1 * y1 * (x0 + 3.0 * x1));
+        m[9] += a * (y0 + y1) * (y0 * y0 + y1 * y1);
+    }
+
+    let divisors = [2.0, 6.0, 6.0, 12.0, 24.0, 12.0, 20.0, 60.0, 60.0, 20.0];
+    // Clockwise polygons have negative signed area
+    let sign = if m[0] < 0.0 { -1.0 } else { 1.0 };
+    for (v, d) in m.iter_mut().zip(divisors) {
+        *v *= sign / d;
+    }
+    let [m00, m10, m01, m20, m11, m02, m30, m21, m12, m03] = m;
+
+    let (cx, cy) = if m00 == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (m10 / m00, m01 / m00)
+    };
+    let mu20 = m20 - cx * m10;
+    let mu11 = m11 - cx * m01;
+    let mu02 = m02 - cy * m01;
+    let mu30 = m30 - 3.0 * cx * m20 + 2.0 * cx * cx * m10;
+    let mu21 = m21 - 2.0 * cx * m11 - cy * m20 + 2.0 * cx * cx * m01;
+    let mu12 = m12 - 2.0 * cy * m11 - cx * m02 + 2.0 * cy * cy * m10;
+    let mu03 = m03 - 3.0 * cy * m02 + 2.0 * cy * cy * m01;
+
+    let normalise = |mu: f64, order: i32| {
+        if m00 == 0.0 {
+            0.0
+        } else {
+            mu / m00.powf(order as f64 / 2.0 + 1.0)
+        }
+    };
+
+    Moments {
+        m00,
+        m10,
+        m01,
+        m20,
+        m11,
+        m02,
+        m30,
+        m21,
+        m12,
+        m03,
+        mu20,
+        mu11,
+        mu02,
+        mu30,
+        mu21,
+        mu12,
+        mu03,
+        nu20: normalise(mu20, 2),
+        nu11: normalise(mu11, 2),
+        nu02: normalise(mu02, 2),
+        nu30: normalise(mu30, 3),
+        nu21: normalise(mu21, 3),
+        nu12: normalise(mu12, 3),
+        nu03: normalise(mu03, 3),
+    }
+}
+
 /// Finds the rectangle of least area that includes all input points. This rectangle need not be axis-aligned.
 ///
 /// The returned points are the [top left, top right, bottom right, bottom left] points of this rectangle.
@@ -111,18 +410,105 @@ where
     }
 }
 
-/// An implementation of [rotating calipers] used for determining the
-/// bounding rectangle with the smallest area.
+/// A rectangle which need not be axis-aligned.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RotatedRect {
+    /// The `(x, y)` coordinates of the centre of the rectangle.
+    pub center: (f32, f32),
+    /// The lengths of the sides of the rectangle. The first side is in the direction
+    /// given by `angle`, and the second is perpendicular to it.
+    pub size: (f32, f32),
+    /// The angle in radians from the positive x-axis to the direction of the first side,
+    /// measured clockwise in image coordinates (i.e. with the y-axis pointing down).
+    pub angle: f32,
+}
+
+impl RotatedRect {
+    /// Returns the corners of the rectangle, in order around its boundary. For an angle of
+    /// zero these are the top left, top right, bottom right and bottom left corners.
+    pub fn corners(&self) -> [Point<f32>; 4] {
+        let (sin, cos) = self.angle.sin_cos();
+        let (hw, hh) = (self.size.0 / 2.0, self.size.1 / 2.0);
+        let (cx, cy) = self.center;
+        let corner = |s: f32, t: f32| {
+            Point::new(
+                cx + s * hw * cos - t * hh * sin,
+                cy + s * hw * sin + t * hh * cos,
+            )
+        };
+        [
+            corner(-1.0, -1.0),
+            corner(1.0, -1.0),
+            corner(1.0, 1.0),
+            corner(-1.0, 1.0),
+        ]
+    }
+}
+
+/// Finds the rectangle of least area that includes all input points. This rectangle need
+/// not be axis-aligned.
 ///
-/// [rotating calipers]: https://en.wikipedia.org/wiki/Rotating_calipers
-fn rotating_calipers<T>(points: &[Point<T>]) -> [Point<T>; 4]
+/// One side of the minimal rectangle is collinear with an edge of the convex hull of the
+/// points, so each hull edge is tried in turn in the same way as [`min_area_rect`]. This
+/// takes `O(h^2)` time for a hull with `h` vertices, after `O(n log n)` time to find the
+/// hull of `n` points. The returned rectangle has an angle in `[0, π/2)`. Unlike
+/// [`min_area_rect`], corners are not rounded to integer coordinates.
+///
+/// If all points are collinear then the second side of the returned rectangle has a length
+/// of zero, up to rounding error.
+///
+/// # Panics
+///
+/// If `points` is empty.
+///
+/// # Examples
+/// ```
+/// use imageproc::geometry::min_area_rotated_rect;
+/// use imageproc::point::Point;
+///
+/// // A diamond, i.e. a square rotated by 45 degrees
+/// let diamond = [
+///     Point::new(10, 0),
+///     Point::new(20, 10),
+///     Point::new(10, 20),
+///     Point::new(0, 10),
+/// ];
+///
+/// let rect = min_area_rotated_rect(&diamond);
+/// assert_eq!(rect.center, (10.0, 10.0));
+/// assert!((rect.size.0 - 200f32.sqrt()).abs() < 1e-4);
+/// assert!((rect.size.1 - 200f32.sqrt()).abs() < 1e-4);
+/// assert!((rect.angle - std::f32::consts::FRAC_PI_4).abs() < 1e-4);
+/// ```
+pub fn min_area_rotated_rect<T>(points: &[Point<T>]) -> RotatedRect
 where
-    T: NumCast + Copy,
+    T: NumCast + Copy + Ord,
 {
-    let mut edge_angles: Vec<f64> = points
-        .windows(2)
-        .map(|e| {
-            let edge = e[1].to_f64() - e[0].to_f64();
+    let hull: Vec<Point<f64>> = convex_hull(points).iter().map(|p| p.to_f64()).collect();
+    if hull.is_empty() {
+        panic!("no points are defined");
+    }
+
+    let (angle, rotation, (min_x, max_x, min_y, max_y)) = min_area_edge_bounds(&hull);
+    let center = Point::new((min_x + max_x) / 2.0, (min_y + max_y) / 2.0).invert_rotation(rotation);
+    RotatedRect {
+        center: (center.x as f32, center.y as f32),
+        size: ((max_x - min_x) as f32, (max_y - min_y) as f32),
+        angle: angle as f32,
+    }
+}
+
+/// Tries each edge of the convex hull `points` as the direction of one side of a
+/// bounding rectangle, and returns the angle in `[0, π/2)` and rotation of the edge
+/// giving the least area, along with the `(min_x, max_x, min_y, max_y)` bounds of
+/// the hull after applying that rotation.
+///
+/// This takes `O(h^2)` time for a hull with `h` vertices.
+fn min_area_edge_bounds(points: &[Point<f64>]) -> (f64, Rotation, (f64, f64, f64, f64)) {
+    let n = points.len();
+    let mut edge_angles: Vec<f64> = (0..n)
+        .map(|i| {
+            let edge = points[(i + 1) % n] - points[i];
             ((edge.y.atan2(edge.x) + PI) % (PI / 2.)).abs()
         })
         .collect();
@@ -130,30 +516,44 @@ where
     edge_angles.dedup();
 
     let mut min_area = f64::MAX;
-    let mut res = [Point::new(0.0, 0.0); 4];
+    let mut best = (0.0, Rotation::new(0.0), (0.0, 0.0, 0.0, 0.0));
     for angle in edge_angles {
         let rotation = Rotation::new(angle);
-        let rotated_points = points.iter().map(|p| p.to_f64().rotate(rotation));
-
-        let (min_x, max_x, min_y, max_y) =
-            rotated_points.fold((f64::MAX, f64::MIN, f64::MAX, f64::MIN), |acc, p| {
-                (
-                    acc.0.min(p.x),
-                    acc.1.max(p.x),
-                    acc.2.min(p.y),
-                    acc.3.max(p.y),
-                )
-            });
-
-        let area = (max_x - min_x) * (max_y - min_y);
+        let rotated_points = points.iter().map(|p| p.rotate(rotation));
+
+        let bounds = rotated_points.fold((f64::MAX, f64::MIN, f64::MAX, f64::MIN), |acc, p| {
+            (
+                acc.0.min(p.x),
+                acc.1.max(p.x),
+                acc.2.min(p.y),
+                acc.3.max(p.y),
+            )
+        });
+
+        let area = (bounds.1 - bounds.0) * (bounds.3 - bounds.2);
         if area < min_area {
             min_area = area;
-            res[0] = Point::new(max_x, min_y).invert_rotation(rotation);
-            res[1] = Point::new(min_x, min_y).invert_rotation(rotation);
-            res[2] = Point::new(min_x, max_y).invert_rotation(rotation);
-            res[3] = Point::new(max_x, max_y).invert_rotation(rotation);
+            best = (angle, rotation, bounds);
         }
     }
+    best
+}
+
+/// Determines the bounding rectangle with the smallest area of a convex hull with
+/// at least three vertices, with corners rounded outwards to integer coordinates.
+fn rotating_calipers<T>(points: &[Point<T>]) -> [Point<T>; 4]
+where
+    T: NumCast + Copy,
+{
+    let hull: Vec<Point<f64>> = points.iter().map(|p| p.to_f64()).collect();
+    let (_, rotation, (min_x, max_x, min_y, max_y)) = min_area_edge_bounds(&hull);
+
+    let mut res = [
+        Point::new(max_x, min_y).invert_rotation(rotation),
+        Point::new(min_x, min_y).invert_rotation(rotation),
+        Point::new(min_x, max_y).invert_rotation(rotation),
+        Point::new(max_x, max_y).invert_rotation(rotation),
+    ];
 
     res.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
 
@@ -182,74 +582,96 @@ where
     ]
 }
 
-/// Finds the convex hull of a set of points, using the [Graham scan algorithm].
+/// Finds the convex hull of a set of points, using [Andrew's monotone chain algorithm].
+///
+/// The returned points are the vertices of the hull, starting from the point with the
+/// smallest `y` coordinate (and the smallest `x` coordinate among such points).
+///
+/// In image coordinates, where the y-axis points down, the vertices are in clockwise order
+/// as displayed: from the top of the hull they proceed right, then down, then left. This
+/// is counter-clockwise order in the usual mathematical convention with the y-axis pointing
+/// up, and the signed area `sum(x[i] * y[i + 1] - x[i + 1] * y[i]) / 2` of the returned
+/// polygon is positive. Points lying on an edge of the hull and duplicate points are not
+/// included.
 ///
-/// [Graham scan algorithm]: https://en.wikipedia.org/wiki/Graham_scan
+/// If all points are equal then the result contains a single point, and if all points
+/// are collinear then the result contains the two endpoints of the line they lie on.
+///
+/// # Examples
+/// ```
+/// use imageproc::geometry::convex_hull;
+/// use imageproc::point::Point;
+///
+/// let points = [
+///     Point::new(0, 0),
+///     Point::new(0, 4),
+///     Point::new(2, 1),
+///     Point::new(4, 4),
+///     Point::new(4, 0),
+/// ];
+///
+/// // Top left, top right, bottom right, bottom left
+/// assert_eq!(
+///     convex_hull(points.as_slice()),
+///     [Point::new(0, 0), Point::new(4, 0), Point::new(4, 4), Point::new(0, 4)]
+/// );
+/// ```
+///
+/// [Andrew's monotone chain algorithm]: https://en.wikibooks.org/wiki/Algorithm_Implementation/Geometry/Convex_hull/Monotone_chain
 pub fn convex_hull<T>(points: impl Into<Vec<Point<T>>>) -> Vec<Point<T>>
 where
     T: NumCast + Copy + Ord,
 {
     let mut points = points.into();
-    if points.is_empty() {
-        return vec![];
-    }
-    let mut start_point_pos = 0;
-    let mut start_point = points[0];
-    for (i, &point) in points.iter().enumerate().skip(1) {
-        if point.y < start_point.y || point.y == start_point.y && point.x < start_point.x {
-            start_point_pos = i;
-            start_point = point;
-        }
-    }
-    points.swap(0, start_point_pos);
-    points.remove(0);
-    points.sort_by(
-        |a, b| match orientation(start_point.to_i32(), a.to_i32(), b.to_i32()) {
-            Orientation::Collinear => {
-                if distance(start_point, *a) < distance(start_point, *b) {
-                    Ordering::Less
-                } else {
-                    Ordering::Greater
-                }
+    points.sort_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)));
+    points.dedup_by(|a, b| a.x == b.x && a.y == b.y);
+
+    // Builds one half of the hull, discarding points which don't make a strict left turn
+    let half_hull = |points: &mut dyn Iterator<Item = &Point<T>>| {
+        let mut chain: Vec<Point<T>> = Vec::new();
+        for &p in points {
+            while chain.len() > 1
+                && orientation(
+                    chain[chain.len() - 2].to_i32(),
+                    chain[chain.len() - 1].to_i32(),
+                    p.to_i32(),
+                ) != Orientation::CounterClockwise
+            {
+                chain.pop();
             }
-            Orientation::Clockwise => Ordering::Greater,
-            Orientation::CounterClockwise => Ordering::Less,
-        },
-    );
-
-    let mut iter = points.iter().peekable();
-    let mut remaining_points = Vec::with_capacity(points.len());
-    while let Some(mut p) = iter.next() {
-        while iter.peek().is_some()
-            && orientation(
-                start_point.to_i32(),
-                p.to_i32(),
-                iter.peek().unwrap().to_i32(),
-            ) == Orientation::Collinear
-        {
-            p = iter.next().unwrap();
-        }
-        remaining_points.push(p);
-    }
-
-    let mut stack: Vec<Point<T>> = vec![Point::new(
-        cast(start_point.x).unwrap(),
-        cast(start_point.y).unwrap(),
-    )];
-
-    for p in points {
-        while stack.len() > 1
-            && orientation(
-                stack[stack.len() - 2].to_i32(),
-                stack[stack.len() - 1].to_i32(),
-                p.to_i32(),
-            ) != Orientation::CounterClockwise
-        {
-            stack.pop();
+            chain.push(p);
         }
-        stack.push(p);
+        // The last point of each half is the first point of the other
+        chain.pop();
+        chain
+    };
+
+    let mut hull = if points.len() < 3 {
+        points
+    } else {
+        let mut hull = half_hull(&mut points.iter());
+        hull.extend(half_hull(&mut points.iter().rev()));
+        hull
+    };
+    if hull.is_empty() {
+        return hull;
     }
-    stack
+
+    let start = (0..hull.len())
+        .min_by(|&i, &j| hull[i].y.cmp(&hull[j].y).then(hull[i].x.cmp(&hull[j].x)))
+        .unwrap();
+    hull.rotate_left(start);
+    hull
+}
+
+/// Finds the convex hull of the points of a contour.
+///
+/// See [`convex_hull`] for details of the returned points.
+pub fn contour_convex_hull<T>(contour: &Contour<T>) -> Vec<Point<T>>
+where
+    T: NumCast + Copy + Ord,
+{
+    convex_hull(contour.points.as_slice())
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -306,6 +728,74 @@ mod tests {
         );
     }
 
+    // The square with corners (x0, y0) and (x1, y1), sampled at every integer point
+    // on its boundary, clockwise from (x0, y0) with the y-axis pointing down
+    fn dense_square(x0: i32, y0: i32, x1: i32, y1: i32) -> Vec<Point<i32>> {
+        let mut points = Vec::new();
+        points.extend((x0..x1).map(|x| Point::new(x, y0)));
+        points.extend((y0..y1).map(|y| Point::new(x1, y)));
+        points.extend((x0 + 1..=x1).rev().map(|x| Point::new(x, y1)));
+        points.extend((y0 + 1..=y1).rev().map(|y| Point::new(x0, y)));
+        points
+    }
+
+    #[test]
+    fn test_approximate_polygon_dp_dense_square_reduces_to_corners() {
+        let square = dense_square(3, 5, 40, 42);
+        assert_eq!(square.len(), 4 * 37);
+
+        let corners = vec![
+            Point::new(3, 5),
+            Point::new(40, 5),
+            Point::new(40, 42),
+            Point::new(3, 42),
+        ];
+        assert_eq!(approximate_polygon_dp(&square, 1.0, true), corners);
+
+        // Starting part way along an edge keeps the starting point unless
+        // it lies within epsilon of an edge
+        let mut rotated = square.clone();
+        rotated.rotate_left(10);
+        let approx = approximate_polygon_dp(&rotated, 1.0, true);
+        assert_eq!(approx.len(), 5);
+        assert!(corners.iter().all(|c| approx.contains(c)));
+    }
+
+    #[test]
+    fn test_approximate_polygon_dp_open_curve_keeps_endpoints() {
+        let curve: Vec<Point<i32>> = (0..=20i32).map(|x| Point::new(x, (x - 10).abs())).collect();
+        assert_eq!(
+            approximate_polygon_dp(&curve, 0.5, false),
+            vec![Point::new(0, 10), Point::new(10, 0), Point::new(20, 10)]
+        );
+    }
+
+    #[test]
+    fn test_approximate_polygon_dp_zero_epsilon_returns_input() {
+        let square = dense_square(0, 0, 5, 5);
+        assert_eq!(approximate_polygon_dp(&square, 0.0, true), square);
+        assert_eq!(approximate_polygon_dp(&square, 0.0, false), square);
+    }
+
+    #[test]
+    fn test_approximate_polygon_dp_short_curves() {
+        let empty: Vec<Point<i32>> = vec![];
+        assert_eq!(approximate_polygon_dp(&empty, 1.0, true), empty);
+        let pair = vec![Point::new(1, 2), Point::new(3, 4)];
+        assert_eq!(approximate_polygon_dp(&pair, 1.0, false), pair);
+        let repeated = vec![Point::new(1, 1); 4];
+        assert_eq!(
+            approximate_polygon_dp(&repeated, 1.0, true),
+            vec![Point::new(1, 1)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_approximate_polygon_dp_rejects_negative_epsilon() {
+        let _ = approximate_polygon_dp(&dense_square(0, 0, 5, 5), -1.0, true);
+    }
+
     #[test]
     fn convex_hull_points() {
         let star = vec![
@@ -366,6 +856,136 @@ mod tests {
         );
     }
 
+    #[test]
+    fn convex_hull_degenerate_inputs() {
+        assert_eq!(convex_hull(vec![Point::new(3, 4)]), [Point::new(3, 4)]);
+        assert_eq!(
+            convex_hull(vec![Point::new(3, 4), Point::new(3, 4), Point::new(3, 4)]),
+            [Point::new(3, 4)]
+        );
+        assert_eq!(
+            convex_hull(vec![Point::new(5, 1), Point::new(3, 4)]),
+            [Point::new(5, 1), Point::new(3, 4)]
+        );
+        let collinear: Vec<Point<i32>> = (0..10).map(|i| Point::new(2 * i, 10 - i)).collect();
+        assert_eq!(
+            convex_hull(collinear),
+            [Point::new(18, 1), Point::new(0, 10)]
+        );
+    }
+
+    #[test]
+    fn convex_hull_orientation() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        // Twice the signed area, which is positive for polygons whose vertices are in
+        // clockwise order when displayed with the y-axis pointing down
+        let signed_area = |polygon: &[Point<i32>]| -> i64 {
+            (0..polygon.len())
+                .map(|i| {
+                    let (p, q) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+                    p.x as i64 * q.y as i64 - q.x as i64 * p.y as i64
+                })
+                .sum()
+        };
+
+        // A triangle pointing down in image coordinates: top left, top right, bottom
+        let hull = convex_hull(vec![Point::new(5, 8), Point::new(0, 0), Point::new(10, 0)]);
+        assert_eq!(
+            hull,
+            [Point::new(0, 0), Point::new(10, 0), Point::new(5, 8)]
+        );
+        assert!(signed_area(&hull) > 0);
+
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let points: Vec<Point<i32>> = (0..50)
+                .map(|_| Point::new(rng.gen_range(-100..100), rng.gen_range(-100..100)))
+                .collect();
+            let hull = convex_hull(points);
+            assert!(hull.len() >= 3);
+            assert!(signed_area(&hull) > 0);
+            // Every turn has the same direction as the whole polygon
+            for i in 0..hull.len() {
+                let (p, q, r) = (
+                    hull[i],
+                    hull[(i + 1) % hull.len()],
+                    hull[(i + 2) % hull.len()],
+                );
+                assert_eq!(orientation(p, q, r), Orientation::CounterClockwise);
+            }
+        }
+    }
+
+    #[test]
+    fn convex_hull_ignores_duplicates_and_points_on_edges() {
+        let points = vec![
+            Point::new(0, 0),
+            Point::new(4, 0),
+            Point::new(2, 0),
+            Point::new(4, 4),
+            Point::new(4, 4),
+            Point::new(0, 4),
+            Point::new(0, 2),
+            Point::new(2, 2),
+            Point::new(0, 0),
+        ];
+        assert_eq!(
+            convex_hull(points),
+            [
+                Point::new(0, 0),
+                Point::new(4, 0),
+                Point::new(4, 4),
+                Point::new(0, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn convex_hull_of_random_points_is_minimal_and_contains_all_points() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let cross = |o: Point<i32>, a: Point<i32>, b: Point<i32>| {
+            (a.x - o.x) as i64 * (b.y - o.y) as i64 - (a.y - o.y) as i64 * (b.x - o.x) as i64
+        };
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            let n = rng.gen_range(3..200);
+            let points: Vec<Point<i32>> = (0..n)
+                .map(|_| Point::new(rng.gen_range(-50..50), rng.gen_range(-50..50)))
+                .collect();
+            let hull = convex_hull(points.as_slice());
+            assert!(hull.len() >= 3);
+            assert!(hull.iter().all(|h| points.contains(h)));
+
+            for i in 0..hull.len() {
+                let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+                // Every vertex is a strict turn, so no vertex can be removed
+                let c = hull[(i + 2) % hull.len()];
+                assert!(cross(a, b, c) > 0, "{:?}", hull);
+                // Every point is on or to the left of every edge
+                for &p in &points {
+                    assert!(cross(a, b, p) >= 0, "{:?} outside {:?}", p, hull);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn contour_convex_hull_matches_convex_hull() {
+        use crate::contours::BorderType;
+        let points = vec![
+            Point::new(1, 1),
+            Point::new(2, 2),
+            Point::new(3, 1),
+            Point::new(3, 3),
+            Point::new(1, 3),
+        ];
+        let contour = Contour::new(points.clone(), BorderType::Outer, None);
+        assert_eq!(contour_convex_hull(&contour), convex_hull(points));
+    }
+
     #[test]
     fn test_min_area() {
         assert_eq!(
@@ -385,6 +1005,169 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_min_area_rotated_rect_recovers_rotated_rectangle() {
+        let (cx, cy, width, height): (f32, f32, f32, f32) = (50.0, 40.0, 36.0, 14.0);
+        for &angle in &[0.0f32, 0.3, 0.9, 1.4] {
+            let rect = RotatedRect {
+                center: (cx, cy),
+                size: (width, height),
+                angle,
+            };
+            // Points on and inside the rectangle, rounded to integer coordinates
+            let (sin, cos) = angle.sin_cos();
+            let mut points = Vec::new();
+            for i in 0..=36 {
+                for j in 0..=14 {
+                    let (s, t) = (i as f32 - width / 2.0, j as f32 - height / 2.0);
+                    let x = cx + s * cos - t * sin;
+                    let y = cy + s * sin + t * cos;
+                    points.push(Point::new(x.round() as i32, y.round() as i32));
+                }
+            }
+
+            let found = min_area_rotated_rect(&points);
+            assert!((found.center.0 - cx).abs() < 1.0, "{:?}", found);
+            assert!((found.center.1 - cy).abs() < 1.0, "{:?}", found);
+            // The found rectangle may have its sides swapped relative to the true one
+            let (size, found_angle) = if (found.angle - angle).abs() < 0.5 {
+                (found.size, found.angle)
+            } else {
+                ((found.size.1, found.size.0), found.angle + PI as f32 / 2.0)
+            };
+            assert!((size.0 - width).abs() < 1.5, "{:?}", found);
+            assert!((size.1 - height).abs() < 1.5, "{:?}", found);
+            assert!((found_angle - angle).abs() < 0.06, "{:?} {:?}", found, rect);
+        }
+    }
+
+    #[test]
+    fn test_min_area_rotated_rect_of_thin_line() {
+        let points: Vec<Point<i32>> = (0..=10).map(|k| Point::new(3 + 2 * k, 1 + k)).collect();
+        let rect = min_area_rotated_rect(&points);
+        assert!(rect.size.1.abs() < 1e-4);
+        assert!((rect.size.0 - 500f32.sqrt()).abs() < 1e-4);
+        assert!((rect.angle - 0.5f32.atan()).abs() < 1e-5);
+        assert!((rect.center.0 - 13.0).abs() < 1e-4 && (rect.center.1 - 6.0).abs() < 1e-4);
+
+        let corners = rect.corners();
+        assert!((corners[0].x - 3.0).abs() < 1e-4 && (corners[0].y - 1.0).abs() < 1e-4);
+        assert!((corners[1].x - 23.0).abs() < 1e-4 && (corners[1].y - 11.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_min_area_rotated_rect_single_point() {
+        let rect = min_area_rotated_rect(&[Point::new(4, 7), Point::new(4, 7)]);
+        assert_eq!(rect.center, (4.0, 7.0));
+        assert_eq!(rect.size, (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_rotated_rect_corners() {
+        let rect = RotatedRect {
+            center: (5.0, 3.0),
+            size: (4.0, 2.0),
+            angle: 0.0,
+        };
+        assert_eq!(
+            rect.corners(),
+            [
+                Point::new(3.0, 2.0),
+                Point::new(7.0, 2.0),
+                Point::new(7.0, 4.0),
+                Point::new(3.0, 4.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_contour_moments_area_matches_contour_area() {
+        let points = [
+            Point::new(3, 4),
+            Point::new(5, 11),
+            Point::new(12, 8),
+            Point::new(9, 5),
+            Point::new(5, 6),
+        ];
+        let moments = contour_moments(&points);
+        assert_eq!(moments.area(), contour_area(&points));
+
+        // Reversing the orientation doesn't change the moments
+        let mut reversed = points;
+        reversed.reverse();
+        assert_eq!(contour_moments(&reversed), moments);
+    }
+
+    #[test]
+    fn test_contour_moments_of_rectangle() {
+        // The rectangle [0, 4] x [0, 2]
+        let rect = [
+            Point::new(0, 0),
+            Point::new(4, 0),
+            Point::new(4, 2),
+            Point::new(0, 2),
+        ];
+        let m = contour_moments(&rect);
+        assert_eq!(m.m00, 8.0);
+        assert_eq!((m.m10, m.m01), (16.0, 8.0));
+        assert_eq!((m.m20, m.m11, m.m02), (128.0 / 3.0, 16.0, 32.0 / 3.0));
+        assert_eq!(m.m30, 128.0);
+        assert!((m.mu20 - 32.0 / 3.0).abs() < 1e-9);
+        assert!((m.mu02 - 8.0 / 3.0).abs() < 1e-9);
+        assert!(m.mu11.abs() < 1e-9);
+        // Third order central moments of a symmetric shape vanish
+        for mu in [m.mu30, m.mu21, m.mu12, m.mu03] {
+            assert!(mu.abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_hu_moments_invariant_under_translation_and_rotation() {
+        let shape = [
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 1.0),
+            Point::new(13.0, 7.0),
+            Point::new(6.0, 5.0),
+            Point::new(2.0, 12.0),
+        ];
+        let expected = contour_moments(&shape).hu_moments();
+
+        let (sin, cos) = 0.7f64.sin_cos();
+        let transformed: Vec<Point<f64>> = shape
+            .iter()
+            .map(|p| Point::new(p.x * cos - p.y * sin + 37.0, p.x * sin + p.y * cos - 11.0))
+            .collect();
+        let actual = contour_moments(&transformed).hu_moments();
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!(
+                (a - e).abs() <= 1e-9 * e.abs().max(1e-12),
+                "{:?} {:?}",
+                actual,
+                expected
+            );
+        }
+
+        // Scaling leaves the invariants unchanged too
+        let scaled: Vec<Point<f64>> = shape
+            .iter()
+            .map(|p| Point::new(3.0 * p.x, 3.0 * p.y))
+            .collect();
+        let log_expected = contour_moments(&shape).log_hu_moments();
+        let log_actual = contour_moments(&scaled).log_hu_moments();
+        for (a, e) in log_actual.iter().zip(log_expected.iter()) {
+            assert!((a - e).abs() < 1e-8, "{:?} {:?}", log_actual, log_expected);
+        }
+    }
+
+    #[test]
+    fn test_contour_moments_of_degenerate_polygon() {
+        let line = [Point::new(0, 0), Point::new(5, 5)];
+        let moments = contour_moments(&line);
+        assert_eq!(moments.area(), 0.0);
+        assert_eq!(moments.centroid(), None);
+        assert_eq!(moments.hu_moments(), [0.0; 7]);
+    }
+
     #[test]
     fn test_contour_area() {
         let points = [
@@ -1,10 +1,11 @@
 //! Functions for computing gradients of image intensities.
 
+use crate::colorspace::vector_field_to_rgb;
 use crate::definitions::{Clamp, HasBlack, Image};
 use crate::filter::{filter, filter_clamped};
 use crate::kernel::{self, Kernel};
 use crate::map::{ChannelMap, WithChannel};
-use image::{GenericImage, GenericImageView, GrayImage, Luma, Pixel};
+use image::{GenericImage, GenericImageView, GrayImage, Luma, Pixel, RgbImage};
 use itertools::multizip;
 
 /// A special version of `gradient()` function for greyscale images which doesn't require giving a
@@ -145,6 +146,144 @@ where
     out
 }
 
+/// Returns the horizontal and vertical Sobel kernels of size `aperture x aperture`,
+/// in row-major order.
+///
+/// The kernels are built by convolving the binomial smoothing kernel of length
+/// `aperture` in one direction with the derivative kernel `[-1, 0, 1]`, smoothed by the
+/// binomial kernel of length `aperture - 2`, in the other direction. For `aperture == 3`
+/// these are [`SOBEL_HORIZONTAL_3X3`](crate::kernel::SOBEL_HORIZONTAL_3X3) and
+/// [`SOBEL_VERTICAL_3X3`](crate::kernel::SOBEL_VERTICAL_3X3).
+///
+/// # Panics
+///
+/// If `aperture` is not 3, 5 or 7.
+///
+/// # Examples
+/// ```
+/// use imageproc::gradients::sobel_kernels;
+///
+/// let (horizontal, vertical) = sobel_kernels(5);
+/// assert_eq!(&horizontal[..5], &[-1, -2, 0, 2, 1]);
+/// assert_eq!(&horizontal[10..15], &[-6, -12, 0, 12, 6]);
+/// assert_eq!(&vertical[..5], &[-1, -4, -6, -4, -1]);
+/// ```
+pub fn sobel_kernels(aperture: u32) -> (Vec<i32>, Vec<i32>) {
+    assert!(
+        matches!(aperture, 3 | 5 | 7),
+        "aperture must be 3, 5 or 7, got {}",
+        aperture
+    );
+    let n = aperture as usize;
+
+    let smoothing = binomial_coefficients(n);
+    let mut derivative = vec![0i32; n];
+    for (i, c) in binomial_coefficients(n - 2).into_iter().enumerate() {
+        derivative[i] -= c;
+        derivative[i + 2] += c;
+    }
+
+    let mut horizontal = Vec::with_capacity(n * n);
+    let mut vertical = Vec::with_capacity(n * n);
+    for y in 0..n {
+        for x in 0..n {
+            horizontal.push(smoothing[y] * derivative[x]);
+            vertical.push(derivative[y] * smoothing[x]);
+        }
+    }
+    (horizontal, vertical)
+}
+
+/// The `n`th row of Pascal's triangle, starting from `[1]` for `n == 1`.
+fn binomial_coefficients(n: usize) -> Vec<i32> {
+    let mut row = vec![1i32];
+    for _ in 1..n {
+        let mut next = vec![1i32; row.len() + 1];
+        for i in 1..row.len() {
+            next[i] = row[i - 1] + row[i];
+        }
+        row = next;
+    }
+    row
+}
+
+/// Returns the magnitudes of gradients in an image using Sobel filters of size
+/// `aperture x aperture`, where `aperture` is 3, 5 or 7.
+///
+/// Larger apertures are less sensitive to noise but localise edges less precisely.
+/// Magnitudes are not normalised, so grow with the aperture, and are returned as `f32`
+/// as they can exceed `u16::MAX` for `aperture == 7`. See [`sobel_kernels`] for the
+/// kernels used. Pads by continuity, as for the 3x3 kernels.
+///
+/// # Panics
+///
+/// If `aperture` is not 3, 5 or 7.
+pub fn sobel_gradients_sized(image: &GrayImage, aperture: u32) -> Image<Luma<f32>> {
+    let (horizontal, vertical) = sobel_kernels(aperture);
+    let horizontal: Image<Luma<f32>> = filter(
+        image,
+        Kernel::new(&horizontal, aperture, aperture),
+        |x: i32| x as f32,
+    );
+    let vertical: Image<Luma<f32>> = filter(
+        image,
+        Kernel::new(&vertical, aperture, aperture),
+        |x: i32| x as f32,
+    );
+
+    let (width, height) = image.dimensions();
+    Image::from_fn(width, height, |x, y| {
+        let h = horizontal.get_pixel(x, y)[0];
+        let v = vertical.get_pixel(x, y)[0];
+        Luma([(h * h + v * v).sqrt()])
+    })
+}
+
+/// Returns the orientation of the gradient at each pixel of an image, in radians.
+///
+/// Gradients are computed using the 3x3 Sobel kernels [`kernel::SOBEL_HORIZONTAL_3X3`] and
+/// [`kernel::SOBEL_VERTICAL_3X3`], padding by continuity. The orientation of a gradient
+/// `(dx, dy)` is `dy.atan2(dx)`, which lies in `[-pi, pi]`. Since the y-axis points down, an
+/// orientation of `pi / 2` means that intensity increases towards the bottom of the image.
+/// Pixels with zero gradient have orientation 0.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::gradients::gradient_orientation;
+///
+/// // Intensity increases from left to right
+/// let image = GrayImage::from_fn(5, 5, |x, _| Luma([if x < 2 { 0 } else { 255 }]));
+/// let orientation = gradient_orientation(&image);
+///
+/// assert_eq!(orientation.get_pixel(2, 2)[0], 0.0);
+/// ```
+pub fn gradient_orientation(image: &GrayImage) -> Image<Luma<f32>> {
+    let (gx, gy) = sobel_components(image);
+    let (width, height) = image.dimensions();
+    Image::from_fn(width, height, |x, y| {
+        Luma([gy.get_pixel(x, y)[0].atan2(gx.get_pixel(x, y)[0])])
+    })
+}
+
+/// Renders the gradients of an image as a color image, with the hue of each pixel giving the
+/// orientation of its gradient and the value giving its magnitude.
+///
+/// Gradients are computed as for [`gradient_orientation`] and colored as by
+/// [`vector_field_to_rgb`], so that a gradient pointing right
+/// is red and the strongest gradient in the image has full brightness. Pixels with zero
+/// gradient are black.
+pub fn visualize_gradients(image: &GrayImage) -> RgbImage {
+    let (gx, gy) = sobel_components(image);
+    vector_field_to_rgb(&gx, &gy)
+}
+
+fn sobel_components(image: &GrayImage) -> (Image<Luma<f32>>, Image<Luma<f32>>) {
+    let gx = filter(image, kernel::SOBEL_HORIZONTAL_3X3, |x: i32| x as f32);
+    let gy = filter(image, kernel::SOBEL_VERTICAL_3X3, |x: i32| x as f32);
+    (gx, gy)
+}
+
 #[inline]
 fn gradient_magnitude(dx: f32, dy: f32) -> u16 {
     (dx.powi(2) + dy.powi(2)).sqrt() as u16
@@ -224,6 +363,18 @@ pub fn sobel_gradients(image: &GrayImage) -> Image<Luma<u16>> {
     )
 }
 
+/// Returns the magnitudes of gradients in an image using Scharr filters.
+///
+/// A drop-in replacement for [`sobel_gradients`] with better rotational symmetry.
+pub fn scharr_gradients(image: &GrayImage) -> Image<Luma<u16>> {
+    gradients(
+        image,
+        kernel::SCHARR_HORIZONTAL_3X3,
+        kernel::SCHARR_VERTICAL_3X3,
+        |p| p,
+    )
+}
+
 /// Returns the magnitudes of gradients in an image using Prewitt filters.
 #[deprecated(
     since = "0.25.0",
@@ -332,6 +483,71 @@ mod tests {
     use super::*;
     use image::Luma;
 
+    #[test]
+    fn test_gradient_orientation_of_vertical_edge_is_horizontal() {
+        let rising = GrayImage::from_fn(8, 6, |x, _| Luma([if x < 4 { 10 } else { 200 }]));
+        let falling = GrayImage::from_fn(8, 6, |x, _| Luma([if x < 4 { 200 } else { 10 }]));
+        for y in 0..6 {
+            for x in 3..5 {
+                assert_eq!(gradient_orientation(&rising).get_pixel(x, y)[0], 0.0);
+                let angle = gradient_orientation(&falling).get_pixel(x, y)[0];
+                assert!(
+                    (angle.abs() - std::f32::consts::PI).abs() < 1e-6,
+                    "{}",
+                    angle
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_gradient_orientation_of_horizontal_edge_is_vertical() {
+        use std::f32::consts::FRAC_PI_2;
+        let down = GrayImage::from_fn(6, 8, |_, y| Luma([if y < 4 { 10 } else { 200 }]));
+        let up = GrayImage::from_fn(6, 8, |_, y| Luma([if y < 4 { 200 } else { 10 }]));
+        for y in 3..5 {
+            for x in 0..6 {
+                assert!((gradient_orientation(&down).get_pixel(x, y)[0] - FRAC_PI_2).abs() < 1e-6);
+                assert!((gradient_orientation(&up).get_pixel(x, y)[0] + FRAC_PI_2).abs() < 1e-6);
+            }
+        }
+        // Flat regions have orientation 0
+        assert_eq!(gradient_orientation(&down).get_pixel(2, 0)[0], 0.0);
+    }
+
+    #[test]
+    fn test_gradient_orientation_of_diagonal_edge() {
+        let image = GrayImage::from_fn(9, 9, |x, y| Luma([if x + y < 9 { 0 } else { 100 }]));
+        let angle = gradient_orientation(&image).get_pixel(4, 4)[0];
+        assert!(
+            (angle - std::f32::consts::FRAC_PI_4).abs() < 1e-6,
+            "{}",
+            angle
+        );
+    }
+
+    #[test]
+    fn test_visualize_gradients_colors_edges_by_orientation() {
+        let image = GrayImage::from_fn(8, 8, |x, y| {
+            Luma([if x >= 4 {
+                200
+            } else if y >= 4 {
+                100
+            } else {
+                0
+            }])
+        });
+        let colors = visualize_gradients(&image);
+        // Flat regions are black
+        assert_eq!(colors.get_pixel(0, 0), &image::Rgb([0, 0, 0]));
+        assert_eq!(colors.get_pixel(6, 6), &image::Rgb([0, 0, 0]));
+        // The gradient across the upper part of the vertical edge points right and is strongest
+        assert_eq!(colors.get_pixel(4, 1), &image::Rgb([255, 0, 0]));
+        // The gradient across the horizontal edge points down
+        let down = colors.get_pixel(1, 4);
+        assert!(down[1] > down[0] && down[1] > down[2], "{:?}", down);
+    }
+
     #[test]
     fn test_gradients_constant_image_sobel() {
         let image = Image::from_pixel(5, 5, Luma([15u8]));
@@ -525,6 +741,117 @@ mod tests {
         assert_pixels_eq!(filtered, expected);
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn test_scharr_matches_analytic_gradients_of_ramp() {
+        // f(x, y) = 2x + 3y, so the Scharr responses in the interior are
+        // (3 + 10 + 3) * 2 * 2 = 64 and (3 + 10 + 3) * 2 * 3 = 96
+        let image = GrayImage::from_fn(6, 6, |x, y| Luma([(2 * x + 3 * y) as u8]));
+        let horizontal = horizontal_scharr(&image);
+        let vertical = vertical_scharr(&image);
+        let magnitudes = scharr_gradients(&image);
+
+        for y in 1..5 {
+            for x in 1..5 {
+                assert_eq!(horizontal.get_pixel(x, y)[0], 64);
+                assert_eq!(vertical.get_pixel(x, y)[0], 96);
+                assert_eq!(magnitudes.get_pixel(x, y)[0], 115);
+            }
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_scharr_signatures_match_sobel() {
+        type Operator = fn(&GrayImage) -> Image<Luma<i16>>;
+        type Magnitude = fn(&GrayImage) -> Image<Luma<u16>>;
+
+        let operators: [Operator; 4] = [
+            horizontal_sobel,
+            vertical_sobel,
+            horizontal_scharr,
+            vertical_scharr,
+        ];
+        let magnitudes: [Magnitude; 2] = [sobel_gradients, scharr_gradients];
+
+        let image = GrayImage::from_pixel(3, 3, Luma([7]));
+        for operator in operators {
+            assert_pixels_eq!(operator(&image), Image::from_pixel(3, 3, Luma([0i16])));
+        }
+        for magnitude in magnitudes {
+            assert_pixels_eq!(magnitude(&image), Image::from_pixel(3, 3, Luma([0u16])));
+        }
+    }
+
+    #[test]
+    fn test_sobel_kernels_3x3_match_constants() {
+        let (horizontal, vertical) = sobel_kernels(3);
+        assert_eq!(horizontal, kernel::SOBEL_HORIZONTAL_3X3.data);
+        assert_eq!(vertical, kernel::SOBEL_VERTICAL_3X3.data);
+    }
+
+    #[test]
+    fn test_sobel_kernels_5x5_match_opencv() {
+        let (horizontal, vertical) = sobel_kernels(5);
+        #[rustfmt::skip]
+        let expected_horizontal = vec![
+            -1,  -2, 0,  2, 1,
+            -4,  -8, 0,  8, 4,
+            -6, -12, 0, 12, 6,
+            -4,  -8, 0,  8, 4,
+            -1,  -2, 0,  2, 1,
+        ];
+        #[rustfmt::skip]
+        let expected_vertical = vec![
+            -1, -4,  -6, -4, -1,
+            -2, -8, -12, -8, -2,
+             0,  0,   0,  0,  0,
+             2,  8,  12,  8,  2,
+             1,  4,   6,  4,  1,
+        ];
+        assert_eq!(horizontal, expected_horizontal);
+        assert_eq!(vertical, expected_vertical);
+    }
+
+    #[test]
+    fn test_sobel_kernels_7x7() {
+        let (horizontal, _) = sobel_kernels(7);
+        assert_eq!(&horizontal[..7], &[-1, -4, -5, 0, 5, 4, 1]);
+        assert_eq!(&horizontal[21..28], &[-20, -80, -100, 0, 100, 80, 20]);
+    }
+
+    #[test]
+    fn test_sobel_gradients_sized_3_matches_3x3_gradients() {
+        let image = GrayImage::from_fn(9, 7, |x, y| Luma([((x * 31 + y * y * 7) % 256) as u8]));
+        let sized = sobel_gradients_sized(&image, 3);
+        let expected = gradients(
+            &image,
+            kernel::SOBEL_HORIZONTAL_3X3,
+            kernel::SOBEL_VERTICAL_3X3,
+            |p| p,
+        );
+        for (x, y, p) in expected.enumerate_pixels() {
+            assert_eq!(sized.get_pixel(x, y)[0] as u16, p[0]);
+        }
+    }
+
+    #[test]
+    fn test_sobel_gradients_sized_ramp() {
+        // f(x, y) = 3x. The interior response of the n x n kernel is
+        // 3 * 2^(n - 1) * (sum of i * derivative[i]) = 3 * 2^(n - 1) * 2^(n - 2) * 2
+        let image = GrayImage::from_fn(15, 15, |x, _| Luma([(3 * x) as u8]));
+        for &(aperture, expected) in &[(3u32, 24.0f32), (5, 384.0), (7, 6144.0)] {
+            let gradients = sobel_gradients_sized(&image, aperture);
+            assert_eq!(gradients.get_pixel(7, 7)[0], expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sobel_kernels_rejects_even_aperture() {
+        let _ = sobel_kernels(4);
+    }
+
     #[test]
     fn test_horizontal_prewitt_gradient_image() {
         let image = gray_image!(
@@ -21,7 +21,7 @@ pub struct HogOptions {
     /// Width and height of block in cells.
     pub block_side: usize,
     /// Offset of the start of one block from the next in cells.
-    pub block_stride: usize, // TODO: choice of normalisation - for now we just scale to unit L2 norm
+    pub block_stride: usize,
 }
 
 impl HogOptions {
@@ -188,6 +188,12 @@ fn num_blocks(num_cells: usize, block_side: usize, block_stride: usize) -> usize
 
 /// Computes the HoG descriptor of an image, or None if the provided
 /// options are incompatible with the image size.
+///
+/// Gradient orientation histograms are computed for each cell, grouped into blocks
+/// and normalised using L2-Hys: each block descriptor is scaled to unit L2 norm,
+/// its values clipped at 0.2, and then scaled to unit L2 norm again. The descriptor
+/// is the concatenation of all block descriptors, and has length
+/// [`HogSpec::descriptor_length`].
 // TODO: support color images by taking the channel with maximum gradient at each point
 pub fn hog(image: &GrayImage, options: HogOptions) -> Result<Vec<f32>, String> {
     match HogSpec::from_options(image.width(), image.height(), options) {
@@ -227,13 +233,7 @@ fn hog_descriptor_from_hist_grid(grid: View3d<'_, f32>, spec: HogSpec) -> Vec<f3
 
         for by in 0..spec.blocks_high {
             for bx in 0..spec.blocks_wide {
-                let norm = block_norm(&block_view, bx, by);
-                if norm > 0f32 {
-                    let block_mut = block_view.inner_slice_mut(bx, by);
-                    for i in 0..block_mut.len() {
-                        block_mut[i] /= norm;
-                    }
-                }
+                normalize_l2_hys(block_view.inner_slice_mut(bx, by));
             }
         }
     }
@@ -241,10 +241,23 @@ fn hog_descriptor_from_hist_grid(grid: View3d<'_, f32>, spec: HogSpec) -> Vec<f3
     descriptor.data
 }
 
-/// L2 norm of the block descriptor at given location within an image descriptor.
-fn block_norm(view: &View3d<'_, f32>, bx: usize, by: usize) -> f32 {
-    let block_data = view.inner_slice(bx, by);
-    l2_norm(block_data)
+/// Largest value in a block descriptor after the first normalisation step of L2-Hys.
+const L2_HYS_CLIP: f32 = 0.2;
+
+/// Scales a block descriptor to unit L2 norm, clips its values at `L2_HYS_CLIP`
+/// and renormalises. Descriptors that are entirely zero are left unchanged.
+fn normalize_l2_hys(block: &mut [f32]) {
+    let norm = l2_norm(block);
+    if norm == 0f32 {
+        return;
+    }
+    for x in block.iter_mut() {
+        *x = (*x / norm).min(L2_HYS_CLIP);
+    }
+    let norm = l2_norm(block);
+    for x in block.iter_mut() {
+        *x /= norm;
+    }
 }
 
 fn copy<T: Copy>(from: &[T], to: &mut [T]) {
@@ -643,17 +656,73 @@ mod tests {
         let counts = [1, 3, 2, 3, 5, 0, 3, 7, 2, 3, 0, 1, 3, 7, 6, 1];
         let mut expected = [0f32; 16];
 
+        // L2-Hys normalisation: scale to unit norm, clip at 0.2, and renormalise.
         let left_norm = 106f32.sqrt();
         let right_norm = 109f32.sqrt();
 
         for i in 0..8 {
-            expected[i] = counts[i] as f32 / left_norm;
+            expected[i] = (counts[i] as f32 / left_norm).min(0.2);
         }
         for i in 8..16 {
-            expected[i] = counts[i] as f32 / right_norm;
+            expected[i] = (counts[i] as f32 / right_norm).min(0.2);
+        }
+        for block in expected.chunks_mut(8) {
+            let norm = l2_norm(block);
+            block.iter_mut().for_each(|x| *x /= norm);
+        }
+
+        for (actual, expected) in descriptor.iter().zip(expected.iter()) {
+            assert!((actual - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_normalize_l2_hys() {
+        // A single dominant value is clipped, so no value exceeds the others by much
+        let mut block = [10f32, 2.0, 2.0, 2.0];
+        normalize_l2_hys(&mut block);
+        assert!((l2_norm(&block) - 1.0).abs() < 1e-6);
+        assert!(block[0] < 1.1 * block[1]);
+
+        let mut zeros = [0f32; 4];
+        normalize_l2_hys(&mut zeros);
+        assert_eq!(zeros, [0f32; 4]);
+    }
+
+    #[test]
+    fn test_hog_descriptor_length_matches_formula() {
+        let (width, height) = (64, 48);
+        for &(orientations, cell_side, block_side, block_stride) in
+            &[(9, 8, 2, 1), (8, 4, 3, 1), (6, 8, 2, 2), (12, 16, 1, 1)]
+        {
+            let options = HogOptions::new(orientations, false, cell_side, block_side, block_stride);
+            let (cells_wide, cells_high) = (width / cell_side, height / cell_side);
+            let blocks_wide = (cells_wide - block_side) / block_stride + 1;
+            let blocks_high = (cells_high - block_side) / block_stride + 1;
+            let expected = blocks_wide * blocks_high * block_side * block_side * orientations;
+
+            let image = GrayImage::from_fn(width as u32, height as u32, |x, y| {
+                Luma([((x * 7 + y * 13) % 256) as u8])
+            });
+            let descriptor = hog(&image, options).unwrap();
+            assert_eq!(descriptor.len(), expected, "{:?}", options);
         }
+    }
+
+    #[test]
+    fn test_hog_of_uniform_image_is_zero() {
+        let image = GrayImage::from_pixel(32, 32, Luma([120]));
+        let descriptor = hog(&image, HogOptions::new(9, false, 8, 2, 1)).unwrap();
+        assert!(descriptor.iter().all(|x| x.abs() < 1e-6));
+    }
 
-        assert_eq!(descriptor, expected);
+    #[test]
+    fn test_hog_blocks_have_unit_norm() {
+        let image = GrayImage::from_fn(32, 32, |x, y| Luma([((x * x + 3 * y) % 256) as u8]));
+        let descriptor = hog(&image, HogOptions::new(9, true, 8, 2, 1)).unwrap();
+        for block in descriptor.chunks(9 * 4) {
+            assert!((l2_norm(block) - 1.0).abs() < 1e-4);
+        }
     }
 
     #[test]
@@ -1,12 +1,18 @@
-//! Line detection via the [Hough transform].
+//! Line and circle detection via the [Hough transform].
 //!
 //! [Hough transform]: https://en.wikipedia.org/wiki/Hough_transform
 
 use crate::definitions::Image;
 use crate::drawing::draw_line_segment_mut;
+use crate::edges::canny;
+use crate::filter::{filter_clamped, gaussian_blur_f32};
+use crate::kernel;
+use crate::point::Point;
 use crate::suppress::suppress_non_maximum;
 use image::{GenericImage, GenericImageView, GrayImage, Luma, Pixel};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use std::f32;
+use std::ops::Range;
 
 /// A detected line, in polar coordinates.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -196,6 +202,349 @@ pub fn intersection_points(
     None
 }
 
+/// A detected line segment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LineSegment {
+    /// One endpoint of the segment.
+    pub start: Point<i32>,
+    /// The other endpoint of the segment.
+    pub end: Point<i32>,
+}
+
+impl LineSegment {
+    /// Length of the segment, in pixels.
+    pub fn length(&self) -> f32 {
+        let dx = (self.end.x - self.start.x) as f32;
+        let dy = (self.end.y - self.start.y) as f32;
+        (dx * dx + dy * dy).sqrt()
+    }
+}
+
+/// Detects line segments in a binary input image using the progressive probabilistic
+/// Hough transform of Matas, Galambos and Kittler.
+///
+/// Points are considered to be in the foreground if their intensity is non-zero.
+/// Foreground points are visited in a random order, each voting in an accumulator with
+/// bins of size `rho` pixels by `theta` radians. When a point's vote brings a bin to at
+/// least `threshold` votes, the corresponding line is followed in both directions from the
+/// point, allowing gaps of up to `max_line_gap` pixels. Points on the followed section are
+/// then removed from further consideration, and if the section is at least
+/// `min_line_length` long its votes are removed from the accumulator and it is returned
+/// as a segment.
+///
+/// The random order is generated from a fixed seed, so results are deterministic.
+///
+/// # Panics
+///
+/// If `rho` or `theta` are not strictly positive.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_line_segment_mut;
+/// use imageproc::hough::detect_line_segments;
+///
+/// let mut image = GrayImage::new(100, 100);
+/// draw_line_segment_mut(&mut image, (10.0, 20.0), (80.0, 20.0), Luma([255]));
+///
+/// let segments = detect_line_segments(&image, 1.0, std::f32::consts::PI / 180.0, 20, 30.0, 3.0);
+/// assert_eq!(segments.len(), 1);
+/// assert!((segments[0].length() - 70.0).abs() <= 2.0);
+/// ```
+pub fn detect_line_segments(
+    image: &GrayImage,
+    rho: f32,
+    theta: f32,
+    threshold: u32,
+    min_line_length: f32,
+    max_line_gap: f32,
+) -> Vec<LineSegment> {
+    assert!(rho > 0.0, "rho must be > 0.0");
+    assert!(theta > 0.0, "theta must be > 0.0");
+
+    let (width, height) = image.dimensions();
+    let num_angles = ((f32::consts::PI / theta).round() as usize).max(1);
+    let max_distance = ((width + height) as f32 / rho).ceil() as i32;
+    let num_distances = (2 * max_distance + 1) as usize;
+
+    let trig: Vec<(f32, f32)> = (0..num_angles)
+        .map(|n| (n as f32 * theta).sin_cos())
+        .collect();
+    let bin = |x: i32, y: i32, n: usize| -> usize {
+        let (sin, cos) = trig[n];
+        (((x as f32 * cos + y as f32 * sin) / rho).round() as i32 + max_distance) as usize
+    };
+
+    let mut mask: Vec<bool> = image.iter().map(|&p| p > 0).collect();
+    let mut points: Vec<(i32, i32)> = image
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p[0] > 0)
+        .map(|(x, y, _)| (x as i32, y as i32))
+        .collect();
+    points.shuffle(&mut StdRng::seed_from_u64(0));
+
+    let in_bounds = |x: i32, y: i32| x >= 0 && y >= 0 && x < width as i32 && y < height as i32;
+    let index = |x: i32, y: i32| (y as u32 * width + x as u32) as usize;
+
+    let mut acc = vec![0u32; num_angles * num_distances];
+    let mut segments = Vec::new();
+
+    for (x, y) in points {
+        if !mask[index(x, y)] {
+            continue;
+        }
+
+        // Vote, keeping track of the strongest line through this point
+        let mut best = (0, 0);
+        for n in 0..num_angles {
+            let cell = &mut acc[n * num_distances + bin(x, y, n)];
+            *cell += 1;
+            if *cell > best.0 {
+                best = (*cell, n);
+            }
+        }
+        if best.0 < threshold {
+            continue;
+        }
+
+        // Follow the line in both directions, stepping one pixel along its major axis
+        let (sin, cos) = trig[best.1];
+        let (dx, dy) = (-sin, cos);
+        let scale = dx.abs().max(dy.abs());
+        let (dx, dy) = (dx / scale, dy / scale);
+
+        let position = |k: i32| {
+            (
+                (x as f32 + k as f32 * dx).round() as i32,
+                (y as f32 + k as f32 * dy).round() as i32,
+            )
+        };
+
+        // Returns the last step in direction `sign` which lands on a foreground point
+        let walk = |sign: i32, mask: &[bool]| -> i32 {
+            let mut last = 0;
+            let mut gap = 0;
+            for k in 1.. {
+                let (px, py) = position(sign * k);
+                if !in_bounds(px, py) {
+                    break;
+                }
+                if mask[index(px, py)] {
+                    gap = 0;
+                    last = sign * k;
+                } else {
+                    gap += 1;
+                    if gap as f32 > max_line_gap {
+                        break;
+                    }
+                }
+            }
+            last
+        };
+        let (first, last) = (walk(-1, &mask), walk(1, &mask));
+
+        let (start, end) = (position(first), position(last));
+        let segment = LineSegment {
+            start: Point::new(start.0, start.1),
+            end: Point::new(end.0, end.1),
+        };
+        let is_long_enough = segment.length() >= min_line_length;
+
+        // Remove the followed points, and their votes if a segment was found
+        for k in first..=last {
+            let (px, py) = position(k);
+            if !mask[index(px, py)] {
+                continue;
+            }
+            mask[index(px, py)] = false;
+            if is_long_enough {
+                for n in 0..num_angles {
+                    let cell = &mut acc[n * num_distances + bin(px, py, n)];
+                    *cell = cell.saturating_sub(1);
+                }
+            }
+        }
+
+        if is_long_enough {
+            segments.push(segment);
+        }
+    }
+
+    segments
+}
+
+// The minimum absolute cosine of the angle between an edge pixel's gradient and the direction
+// from a circle's centre for the pixel to count as lying on the circle.
+const MIN_RADIAL_ALIGNMENT: f32 = 0.9;
+
+/// A detected circle.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HoughCircle {
+    /// Centre of the circle.
+    pub center: Point<i32>,
+    /// Radius of the circle, in pixels.
+    pub radius: u32,
+    /// Number of edge pixels lying on the circle.
+    pub votes: u32,
+}
+
+/// Detects circles in a grayscale image using the gradient-based Hough transform.
+///
+/// Edges are found using [`canny`] with thresholds of 50 and 100. Rather than voting in
+/// the full three-dimensional space of centres and radii, each edge pixel votes for the
+/// centres lying along its gradient direction, in both directions, at distances in
+/// `radius_range`. Centres receiving at least `vote_threshold` votes are then assigned
+/// the radius in `radius_range` supported by the most edge pixels. A circle is returned
+/// if at least `vote_threshold` edge pixels lie within a pixel of it and have gradients
+/// pointing roughly towards or away from its centre.
+///
+/// Circles are considered in order of decreasing votes, and circles whose centres are
+/// less than `min_dist` from the centre of an already detected circle are discarded.
+/// The returned circles are sorted by decreasing votes.
+///
+/// # Panics
+///
+/// If `radius_range` is empty or starts at zero.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::drawing::draw_filled_circle_mut;
+/// use imageproc::hough::detect_circles;
+///
+/// let mut image = GrayImage::new(80, 80);
+/// draw_filled_circle_mut(&mut image, (40, 35), 15, Luma([255]));
+///
+/// let circles = detect_circles(&image, 5..30, 40, 10);
+/// assert_eq!(circles.len(), 1);
+/// assert!((circles[0].center.x - 40).abs() <= 1);
+/// assert!((circles[0].center.y - 35).abs() <= 1);
+/// assert!((circles[0].radius as i32 - 15).abs() <= 1);
+/// ```
+pub fn detect_circles(
+    image: &GrayImage,
+    radius_range: Range<u32>,
+    vote_threshold: u32,
+    min_dist: u32,
+) -> Vec<HoughCircle> {
+    assert!(
+        radius_range.start > 0 && radius_range.start < radius_range.end,
+        "radius_range must be non-empty and must not contain 0"
+    );
+
+    let (width, height) = image.dimensions();
+    let edges = canny(image, 50.0, 100.0);
+    let blurred = gaussian_blur_f32(image, 1.4);
+    let gx: Image<Luma<i16>> = filter_clamped(&blurred, kernel::SOBEL_HORIZONTAL_3X3);
+    let gy: Image<Luma<i16>> = filter_clamped(&blurred, kernel::SOBEL_VERTICAL_3X3);
+
+    // Edge pixels and their unit gradient directions
+    let edge_points: Vec<(i32, i32, f32, f32)> = edges
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p[0] > 0)
+        .filter_map(|(x, y, _)| {
+            let dx = gx.get_pixel(x, y)[0] as f32;
+            let dy = gy.get_pixel(x, y)[0] as f32;
+            let norm = (dx * dx + dy * dy).sqrt();
+            (norm > 0.0).then(|| (x as i32, y as i32, dx / norm, dy / norm))
+        })
+        .collect();
+
+    // Vote for centres along each edge pixel's gradient direction
+    let mut acc = vec![0u32; (width * height) as usize];
+    for &(x, y, dx, dy) in &edge_points {
+        for sign in [-1.0f32, 1.0] {
+            for r in radius_range.clone() {
+                let cx = (x as f32 + sign * r as f32 * dx).round() as i32;
+                let cy = (y as f32 + sign * r as f32 * dy).round() as i32;
+                if cx < 0 || cy < 0 || cx >= width as i32 || cy >= height as i32 {
+                    break;
+                }
+                acc[(cy as u32 * width + cx as u32) as usize] += 1;
+            }
+        }
+    }
+
+    // Rays from a circle's edge pixels pass within a pixel of its centre,
+    // so score candidate centres by the votes in the 3x3 block around them
+    let block_votes = |cx: u32, cy: u32| -> u32 {
+        let mut sum = 0;
+        for y in cy.saturating_sub(1)..(cy + 2).min(height) {
+            for x in cx.saturating_sub(1)..(cx + 2).min(width) {
+                sum += acc[(y * width + x) as usize];
+            }
+        }
+        sum
+    };
+    let mut centre_votes = Image::<Luma<u32>>::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            centre_votes.put_pixel(x, y, Luma([block_votes(x, y)]));
+        }
+    }
+    let centre_votes = suppress_non_maximum(&centre_votes, 1);
+
+    let mut circles = Vec::new();
+    let radius_bins = radius_range.end as usize + 1;
+    let mut histogram = vec![0u32; radius_bins];
+    for (cx, cy, votes) in centre_votes.enumerate_pixels() {
+        if votes[0] < vote_threshold {
+            continue;
+        }
+
+        histogram.iter_mut().for_each(|h| *h = 0);
+        for &(x, y, gx, gy) in &edge_points {
+            let (dx, dy) = ((x - cx as i32) as f32, (y - cy as i32) as f32);
+            let distance = (dx * dx + dy * dy).sqrt();
+            // Only count edge pixels whose gradients point roughly towards or away from the centre
+            if distance == 0.0 || (dx * gx + dy * gy).abs() < MIN_RADIAL_ALIGNMENT * distance {
+                continue;
+            }
+            let distance = distance.round() as usize;
+            if distance < radius_bins {
+                histogram[distance] += 1;
+            }
+        }
+
+        // Count the edge pixels within a pixel of each radius
+        let support = |r: usize| histogram[r - 1] + histogram[r] + histogram[r + 1];
+        let (radius, votes) = radius_range.clone().map(|r| (r, support(r as usize))).fold(
+            (0, 0),
+            |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            },
+        );
+
+        if votes >= vote_threshold {
+            circles.push(HoughCircle {
+                center: Point::new(cx as i32, cy as i32),
+                radius,
+                votes,
+            });
+        }
+    }
+
+    circles.sort_by_key(|c| std::cmp::Reverse(c.votes));
+
+    let min_dist_squared = (min_dist as i64) * (min_dist as i64);
+    let mut detected: Vec<HoughCircle> = Vec::new();
+    for circle in circles {
+        let too_close = detected.iter().any(|d| {
+            let dx = (d.center.x - circle.center.x) as i64;
+            let dy = (d.center.y - circle.center.y) as i64;
+            dx * dx + dy * dy < min_dist_squared
+        });
+        if !too_close {
+            detected.push(circle);
+        }
+    }
+    detected
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -532,6 +881,119 @@ mod tests {
     test_detect_line!(detect_line_eps_135, 0.001, 135);
     // https://github.com/image-rs/imageproc/issues/280
     test_detect_line!(detect_line_neg10_120, -10.0, 120);
+
+    fn assert_segment_near(segment: &LineSegment, p: (i32, i32), q: (i32, i32), tolerance: i32) {
+        let near = |a: Point<i32>, b: (i32, i32)| {
+            (a.x - b.0).abs() <= tolerance && (a.y - b.1).abs() <= tolerance
+        };
+        assert!(
+            (near(segment.start, p) && near(segment.end, q))
+                || (near(segment.start, q) && near(segment.end, p)),
+            "{:?} is not near {:?} -> {:?}",
+            segment,
+            p,
+            q
+        );
+    }
+
+    #[test]
+    fn detect_line_segments_recovers_endpoints() {
+        use crate::drawing::draw_line_segment_mut;
+
+        let mut image = GrayImage::new(100, 100);
+        draw_line_segment_mut(&mut image, (10.0, 20.0), (80.0, 60.0), Luma([255]));
+        draw_line_segment_mut(&mut image, (30.0, 90.0), (30.0, 40.0), Luma([255]));
+
+        let segments =
+            detect_line_segments(&image, 1.0, std::f32::consts::PI / 180.0, 20, 30.0, 3.0);
+        assert_eq!(segments.len(), 2, "{:?}", segments);
+
+        let diagonal = segments
+            .iter()
+            .find(|s| s.start.y != s.end.y && s.start.x != s.end.x);
+        assert_segment_near(diagonal.unwrap(), (10, 20), (80, 60), 2);
+        let vertical = segments.iter().find(|s| s.start.x == s.end.x).unwrap();
+        assert_segment_near(vertical, (30, 90), (30, 40), 2);
+    }
+
+    #[test]
+    fn detect_line_segments_bridges_small_gaps_only() {
+        let mut image = GrayImage::new(100, 20);
+        for x in (10..40).chain(43..70).chain(80..95) {
+            image.put_pixel(x, 10, Luma([255]));
+        }
+
+        let segments =
+            detect_line_segments(&image, 1.0, std::f32::consts::PI / 180.0, 10, 10.0, 3.0);
+        assert_eq!(segments.len(), 2, "{:?}", segments);
+        let lengths: Vec<i32> = segments
+            .iter()
+            .map(|s| (s.end.x - s.start.x).abs())
+            .collect();
+        assert!(lengths.contains(&59), "{:?}", segments);
+        assert!(lengths.contains(&14), "{:?}", segments);
+    }
+
+    #[test]
+    fn detect_line_segments_ignores_short_segments() {
+        let mut image = GrayImage::new(50, 50);
+        for x in 10..20 {
+            image.put_pixel(x, 25, Luma([255]));
+        }
+        assert!(
+            detect_line_segments(&image, 1.0, std::f32::consts::PI / 180.0, 5, 20.0, 2.0)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn detect_two_circles_of_different_radii() {
+        use crate::drawing::draw_filled_circle_mut;
+
+        let mut image = GrayImage::new(120, 100);
+        draw_filled_circle_mut(&mut image, (35, 40), 12, Luma([255]));
+        draw_filled_circle_mut(&mut image, (85, 55), 22, Luma([180]));
+
+        let circles = detect_circles(&image, 8..30, 30, 10);
+        assert_eq!(circles.len(), 2, "{:?}", circles);
+
+        for &((x, y), r) in &[((35, 40), 12), ((85, 55), 22)] {
+            let circle = circles
+                .iter()
+                .find(|c| (c.center.x - x).abs() <= 2 && (c.center.y - y).abs() <= 2)
+                .unwrap_or_else(|| panic!("circle at ({}, {}) not found: {:?}", x, y, circles));
+            assert!((circle.radius as i32 - r).abs() <= 2, "{:?}", circle);
+        }
+    }
+
+    #[test]
+    fn detect_circles_ignores_radii_outside_range() {
+        use crate::drawing::draw_filled_circle_mut;
+
+        let mut image = GrayImage::new(80, 80);
+        draw_filled_circle_mut(&mut image, (40, 40), 25, Luma([255]));
+        assert!(detect_circles(&image, 5..15, 40, 10).is_empty());
+    }
+
+    #[test]
+    fn detect_circles_min_dist_suppresses_nearby_centres() {
+        use crate::drawing::draw_hollow_circle_mut;
+
+        let mut image = GrayImage::new(80, 80);
+        draw_hollow_circle_mut(&mut image, (34, 40), 18, Luma([255]));
+        draw_hollow_circle_mut(&mut image, (46, 40), 18, Luma([255]));
+
+        let circles = detect_circles(&image, 10..25, 40, 5);
+        assert_eq!(circles.len(), 2, "{:?}", circles);
+
+        let circles = detect_circles(&image, 10..25, 40, 20);
+        assert_eq!(circles.len(), 1, "{:?}", circles);
+    }
+
+    #[test]
+    fn detect_circles_empty_image() {
+        assert!(detect_circles(&GrayImage::new(30, 30), 3..10, 10, 5).is_empty());
+    }
 }
 
 #[cfg(not(miri))]
@@ -581,6 +1043,36 @@ mod benches {
         })
     }
 
+    #[bench]
+    fn bench_detect_line_segments(b: &mut Bencher) {
+        use crate::drawing::draw_line_segment_mut;
+
+        let mut image = GrayImage::new(200, 200);
+        draw_line_segment_mut(&mut image, (10.0, 20.0), (180.0, 160.0), Luma([255]));
+        draw_line_segment_mut(&mut image, (30.0, 190.0), (150.0, 15.0), Luma([255]));
+        draw_line_segment_mut(&mut image, (5.0, 100.0), (195.0, 100.0), Luma([255]));
+
+        b.iter(|| {
+            let segments =
+                detect_line_segments(&image, 1.0, std::f32::consts::PI / 180.0, 20, 30.0, 3.0);
+            black_box(segments);
+        });
+    }
+
+    #[bench]
+    fn bench_detect_circles(b: &mut Bencher) {
+        use crate::drawing::draw_filled_circle_mut;
+
+        let mut image = GrayImage::new(200, 200);
+        draw_filled_circle_mut(&mut image, (60, 70), 25, Luma([255]));
+        draw_filled_circle_mut(&mut image, (140, 120), 40, Luma([200]));
+
+        b.iter(|| {
+            let circles = detect_circles(&image, 10..50, 40, 20);
+            black_box(circles);
+        });
+    }
+
     #[bench]
     fn bench_detect_lines(b: &mut Bencher) {
         let image = chessboard(100, 100);
@@ -3,6 +3,7 @@
 
 use crate::definitions::Image;
 use crate::map::{ChannelMap, WithChannel};
+use crate::rect::Rect;
 use image::{GenericImageView, GrayImage, Luma, Pixel, Primitive, Rgb, Rgba};
 use std::ops::AddAssign;
 
@@ -312,7 +313,202 @@ pub fn variance(
     let n = (right - left + 1) as f64 * (bottom - top + 1) as f64;
     let sum_sq = sum_image_pixels(integral_squared_image, left, top, right, bottom)[0];
     let sum = sum_image_pixels(integral_image, left, top, right, bottom)[0];
-    (sum_sq as f64 - (sum as f64).powi(2) / n) / n
+    variance_from_sums(sum as f64, sum_sq as f64, n)
+}
+
+// The variance of n values with the given sum and sum of squares.
+fn variance_from_sums(sum: f64, sum_sq: f64, n: f64) -> f64 {
+    (sum_sq - sum.powi(2) / n) / n
+}
+
+/// Computes the variance of the pixels of F in `rect`, where `sum` is the integral image of F
+/// and `sum_sq` is the integral image of the squares of the pixels in F.
+///
+/// This is equivalent to [`variance`], but uses 64 bit integral images so that it does not
+/// overflow for large images.
+///
+/// # Panics
+///
+/// If `rect` does not lie within the source image F, or if `sum` and `sum_sq` have
+/// different dimensions.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::integral_image::{integral_image, integral_squared_image, rectangular_variance};
+/// use imageproc::rect::Rect;
+///
+/// let image = gray_image!(
+///     1, 2, 3;
+///     4, 5, 6);
+///
+/// let sum = integral_image::<_, u64>(&image);
+/// let sum_sq = integral_squared_image::<_, u64>(&image);
+///
+/// // The variance of 2, 3, 5 and 6
+/// let rect = Rect::at(1, 0).of_size(2, 2);
+/// assert_eq!(rectangular_variance(&sum, &sum_sq, rect), 2.5);
+/// # }
+/// ```
+pub fn rectangular_variance(sum: &Image<Luma<u64>>, sum_sq: &Image<Luma<u64>>, rect: Rect) -> f32 {
+    assert_eq!(
+        sum.dimensions(),
+        sum_sq.dimensions(),
+        "integral images must have the same dimensions"
+    );
+    assert!(
+        rect.left() >= 0
+            && rect.top() >= 0
+            && rect.right() < sum.width() as i32 - 1
+            && rect.bottom() < sum.height() as i32 - 1,
+        "rect must lie within the source image"
+    );
+
+    let (left, top) = (rect.left() as u32, rect.top() as u32);
+    let (right, bottom) = (rect.right() as u32, rect.bottom() as u32);
+    let n = rect.width() as f64 * rect.height() as f64;
+    let total = sum_image_pixels(sum, left, top, right, bottom)[0] as f64;
+    let total_sq = sum_image_pixels(sum_sq, left, top, right, bottom)[0] as f64;
+    // Rounding errors can make the variance of a constant region slightly negative
+    variance_from_sums(total, total_sq, n).max(0.0) as f32
+}
+
+/// Computes the 45 degree rotated integral image of an image, for computing sums of
+/// rotated rectangles in constant time using [`rotated_sum`].
+///
+/// The rotated integral image contains at each point `(X, Y)` the sum of the pixels
+/// `(x', y')` of F with `y' <= Y` and `|X - x'| <= Y - y'`, i.e. of the pixels in the
+/// upward facing 90 degree cone with apex `(X, Y)`. This is computed for
+/// `-1 <= X <= width` and `-1 <= Y < height`, and stored at `(X + 1, Y + 1)`, so the
+/// returned image has width `image.width() + 2` and height `image.height() + 1`.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::integral_image::rotated_integral_image;
+///
+/// let image = gray_image!(
+///     1, 2, 3;
+///     4, 5, 6);
+///
+/// let integral = gray_image!(type: u64,
+///     0, 0,  0,  0, 0;
+///     0, 1,  2,  3, 0;
+///     1, 7, 11, 11, 3);
+///
+/// assert_pixels_eq!(rotated_integral_image(&image), integral);
+/// # }
+/// ```
+pub fn rotated_integral_image(image: &GrayImage) -> Image<Luma<u64>> {
+    let (width, height) = image.dimensions();
+    let (w, h) = (width as i64, height as i64);
+    let mut out = Image::<Luma<u64>>::new(width + 2, height + 1);
+    if width == 0 || height == 0 {
+        return out;
+    }
+
+    let pixel = |x: i64, y: i64| -> u64 {
+        if x >= 0 && x < w && y >= 0 && y < h {
+            image.get_pixel(x as u32, y as u32)[0] as u64
+        } else {
+            0
+        }
+    };
+
+    // For X <= 0 the cone at (X, Y) contains exactly the pixels with x' + y' <= X + Y,
+    // and for X >= width - 1 those with y' - x' <= Y - X. Cumulative sums along these
+    // diagonals give the cones just outside the stored range needed by the recurrence.
+    let mut anti_diagonal = vec![0u64; (w + h - 1) as usize];
+    let mut diagonal = vec![0u64; (w + h - 1) as usize];
+    for (x, y, p) in image.enumerate_pixels() {
+        anti_diagonal[(x + y) as usize] += p[0] as u64;
+        diagonal[(y as i64 - x as i64 + w - 1) as usize] += p[0] as u64;
+    }
+    for k in 1..anti_diagonal.len() {
+        anti_diagonal[k] += anti_diagonal[k - 1];
+        diagonal[k] += diagonal[k - 1];
+    }
+    let cumulative = |sums: &[u64], k: i64| -> u64 {
+        if k < 0 {
+            0
+        } else {
+            sums[(k as usize).min(sums.len() - 1)]
+        }
+    };
+    let left_of_image = |x: i64, y: i64| cumulative(&anti_diagonal, x + y);
+    let right_of_image = |x: i64, y: i64| cumulative(&diagonal, y - x + w - 1);
+
+    for y in 0..h {
+        for x in -1..=w {
+            let cone = |cx: i64, cy: i64| -> u64 {
+                if cy < 0 {
+                    0
+                } else if cx < -1 {
+                    left_of_image(cx, cy)
+                } else if cx > w {
+                    right_of_image(cx, cy)
+                } else {
+                    out.get_pixel((cx + 1) as u32, (cy + 1) as u32)[0]
+                }
+            };
+            let value = cone(x - 1, y - 1) + cone(x + 1, y - 1) + pixel(x, y) + pixel(x, y - 1)
+                - cone(x, y - 2);
+            out.put_pixel((x + 1) as u32, (y + 1) as u32, Luma([value]));
+        }
+    }
+    out
+}
+
+/// Sums the pixels of F in a rectangle rotated by 45 degrees, where `integral` is the
+/// rotated integral image of F computed by [`rotated_integral_image`].
+///
+/// The rectangle has its top corner at pixel `(x, y)`, extends `w` steps down and to the
+/// right and `h` steps down and to the left. It contains the `2 * w * h` pixels
+/// `(x + i - j, y + i + j + k)` for `0 <= i < w`, `0 <= j < h` and `k` in `{0, 1}`.
+///
+/// # Panics
+///
+/// If `w` or `h` is zero, or if the rectangle does not lie within F.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::integral_image::{rotated_integral_image, rotated_sum};
+///
+/// let image = gray_image!(
+///     0, 0, 1, 0, 0;
+///     0, 2, 3, 4, 0;
+///     5, 6, 7, 8, 9;
+///     0, 2, 1, 2, 0;
+///     0, 0, 3, 0, 0);
+///
+/// let integral = rotated_integral_image(&image);
+///
+/// // The diamond of the eight pixels 1, 2, 3, 4, 6, 7, 8 and 1
+/// assert_eq!(rotated_sum(&integral, 2, 0, 2, 2), 32);
+/// # }
+/// ```
+pub fn rotated_sum(integral: &Image<Luma<u64>>, x: u32, y: u32, w: u32, h: u32) -> u64 {
+    assert!(w > 0 && h > 0, "w and h must be non-zero");
+    let (width, height) = (integral.width() as i64 - 2, integral.height() as i64 - 1);
+    let (x, y, w, h) = (x as i64, y as i64, w as i64, h as i64);
+    assert!(
+        x - h + 1 >= 0 && x + w <= width && y + w + h <= height,
+        "rotated rectangle must lie within the source image"
+    );
+
+    let cone = |cx: i64, cy: i64| integral.get_pixel((cx + 1) as u32, (cy + 1) as u32)[0];
+    (cone(x + w - h, y + w + h - 1) + cone(x, y - 1))
+        - (cone(x - h, y + h - 1) + cone(x + w, y + w - 1))
 }
 
 /// Computes the running sum of one row of image, padded
@@ -593,6 +789,149 @@ mod tests {
         }
         quickcheck(prop as fn(GrayTestImage) -> TestResult);
     }
+
+    #[test]
+    fn test_rectangular_variance_matches_brute_force() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..10 {
+            let (width, height) = (rng.gen_range(1..30), rng.gen_range(1..30));
+            let image = GrayImage::from_fn(width, height, |_, _| Luma([rng.gen()]));
+            let sum = integral_image::<_, u64>(&image);
+            let sum_sq = integral_squared_image::<_, u64>(&image);
+
+            for _ in 0..20 {
+                let (left, top) = (rng.gen_range(0..width), rng.gen_range(0..height));
+                let rect_width = rng.gen_range(1..=width - left);
+                let rect_height = rng.gen_range(1..=height - top);
+                let rect = Rect::at(left as i32, top as i32).of_size(rect_width, rect_height);
+
+                let values: Vec<f64> = (top..top + rect_height)
+                    .flat_map(|y| (left..left + rect_width).map(move |x| (x, y)))
+                    .map(|(x, y)| image.get_pixel(x, y)[0] as f64)
+                    .collect();
+                let n = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / n;
+                let expected = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+
+                let actual = rectangular_variance(&sum, &sum_sq, rect);
+                assert!(
+                    (actual as f64 - expected).abs() <= 1e-3 * expected.max(1.0),
+                    "{:?}: expected {}, got {}",
+                    rect,
+                    expected,
+                    actual
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rectangular_variance_does_not_overflow_for_large_images() {
+        let image = GrayImage::from_fn(300, 300, |x, _| Luma([if x % 2 == 0 { 0 } else { 255 }]));
+        let sum = integral_image::<_, u64>(&image);
+        let sum_sq = integral_squared_image::<_, u64>(&image);
+        let rect = Rect::at(0, 0).of_size(300, 300);
+        assert_eq!(rectangular_variance(&sum, &sum_sq, rect), 127.5 * 127.5);
+    }
+
+    // The cone sums of pixels, computed directly from their definition.
+    fn rotated_integral_image_ref(image: &GrayImage) -> Image<Luma<u64>> {
+        let (width, height) = image.dimensions();
+        Image::from_fn(width + 2, height + 1, |sx, sy| {
+            let (cx, cy) = (sx as i64 - 1, sy as i64 - 1);
+            let sum = image
+                .enumerate_pixels()
+                .filter(|(x, y, _)| {
+                    let (x, y) = (*x as i64, *y as i64);
+                    y <= cy && (cx - x).abs() <= cy - y
+                })
+                .map(|(_, _, p)| p[0] as u64)
+                .sum();
+            Luma([sum])
+        })
+    }
+
+    #[cfg_attr(miri, ignore = "slow")]
+    #[test]
+    fn test_rotated_integral_image_matches_reference_implementation() {
+        fn prop(image: GrayTestImage) -> TestResult {
+            let expected = rotated_integral_image_ref(&image.0);
+            let actual = rotated_integral_image(&image.0);
+            match pixel_diff_summary(&actual, &expected) {
+                None => TestResult::passed(),
+                Some(err) => TestResult::error(err),
+            }
+        }
+        quickcheck(prop as fn(GrayTestImage) -> TestResult);
+    }
+
+    // Calls f(x, y, w, h) for every rotated rectangle lying within an image.
+    fn for_each_rotated_rect(width: u32, height: u32, mut f: impl FnMut(u32, u32, u32, u32)) {
+        for y in 0..height {
+            for x in 0..width {
+                for w in 1..=width - x {
+                    for h in 1..=x + 1 {
+                        if y + w + h <= height {
+                            f(x, y, w, h);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotated_sum_of_constant_image_is_area_times_value() {
+        let image = GrayImage::from_pixel(9, 11, Luma([7]));
+        let integral = rotated_integral_image(&image);
+        let mut count = 0;
+        for_each_rotated_rect(9, 11, |x, y, w, h| {
+            assert_eq!(
+                rotated_sum(&integral, x, y, w, h),
+                2 * w as u64 * h as u64 * 7
+            );
+            count += 1;
+        });
+        assert!(count > 100);
+    }
+
+    #[test]
+    fn test_rotated_sum_matches_brute_force() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(2);
+        let image = GrayImage::from_fn(10, 9, |_, _| Luma([rng.gen()]));
+        let integral = rotated_integral_image(&image);
+        for_each_rotated_rect(10, 9, |x, y, w, h| {
+            let mut expected = 0u64;
+            for i in 0..w {
+                for j in 0..h {
+                    for k in 0..2 {
+                        expected += image.get_pixel(x + i - j, y + i + j + k)[0] as u64;
+                    }
+                }
+            }
+            assert_eq!(rotated_sum(&integral, x, y, w, h), expected);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rotated_sum_rejects_rect_outside_image() {
+        let integral = rotated_integral_image(&GrayImage::new(5, 5));
+        let _ = rotated_sum(&integral, 1, 0, 1, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rectangular_variance_rejects_rect_outside_image() {
+        let image = GrayImage::new(4, 4);
+        let sum = integral_image::<_, u64>(&image);
+        let sum_sq = integral_squared_image::<_, u64>(&image);
+        let _ = rectangular_variance(&sum, &sum_sq, Rect::at(2, 2).of_size(3, 1));
+    }
 }
 
 #[cfg(not(miri))]
@@ -68,3 +68,101 @@ pub const FOUR_LAPLACIAN_3X3: Kernel<'static, i16> =
 /// The 8-connectivity laplacian 3x3 kernel.
 pub const EIGHT_LAPLACIAN_3X3: Kernel<'static, i16> =
     Kernel::new(&[1, 1, 1, 1, -8, 1, 1, 1, 1], 3, 3);
+
+/// Attempts to factor a kernel into the outer product of a column vector and a row vector,
+/// returning `(row_kernel, col_kernel)` if successful.
+///
+/// A kernel can be factored in this way if and only if it has rank one. Such kernels
+/// can be applied as a horizontal filter by `row_kernel` followed by a vertical filter
+/// by `col_kernel`, which is much faster than applying the full 2D kernel.
+///
+/// Kernel entries are allowed to deviate from the product of the factors by up to `1e-5`
+/// times the largest absolute entry in the kernel. Returns `None` if the kernel has rank
+/// greater than one, or if all its entries are zero.
+///
+/// # Examples
+/// ```
+/// use imageproc::kernel::{try_separate, Kernel};
+///
+/// let data = [1.0, 2.0, 1.0, 2.0, 4.0, 2.0];
+/// let kernel = Kernel::new(&data, 3, 2);
+/// let (row, col) = try_separate(&kernel).unwrap();
+/// for y in 0..2 {
+///     for x in 0..3 {
+///         assert!((row[x] * col[y] - kernel.at(x as u32, y as u32)).abs() < 1e-6);
+///     }
+/// }
+///
+/// let data = [1.0, 0.0, 0.0, 1.0];
+/// assert!(try_separate(&Kernel::new(&data, 2, 2)).is_none());
+/// ```
+pub fn try_separate(kernel: &Kernel<f32>) -> Option<(Vec<f32>, Vec<f32>)> {
+    let (width, height) = (kernel.width as usize, kernel.height as usize);
+
+    // Use the row and column through the largest entry as the factors,
+    // as this is the best conditioned choice
+    let (pivot_index, pivot) = kernel
+        .data
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))?;
+    let pivot = *pivot;
+    if pivot == 0.0 || !pivot.is_finite() {
+        return None;
+    }
+    let (pivot_x, pivot_y) = (pivot_index % width, pivot_index / width);
+
+    let row: Vec<f32> = kernel.data[pivot_y * width..(pivot_y + 1) * width].to_vec();
+    let col: Vec<f32> = (0..height)
+        .map(|y| kernel.data[y * width + pivot_x] / pivot)
+        .collect();
+
+    let tolerance = 1e-5 * pivot.abs();
+    for y in 0..height {
+        for x in 0..width {
+            if (kernel.data[y * width + x] - row[x] * col[y]).abs() > tolerance {
+                return None;
+            }
+        }
+    }
+
+    Some((row, col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outer_product(row: &[f32], col: &[f32]) -> Vec<f32> {
+        col.iter()
+            .flat_map(|c| row.iter().map(move |r| r * c))
+            .collect()
+    }
+
+    #[test]
+    fn test_try_separate_recovers_factors() {
+        let row = [-1.0, 0.0, 1.0, 3.0];
+        let col = [0.5, 2.0, -1.0];
+        let data = outer_product(&row, &col);
+        let kernel = Kernel::new(&data, 4, 3);
+
+        let (r, c) = try_separate(&kernel).unwrap();
+        assert_eq!(r.len(), 4);
+        assert_eq!(c.len(), 3);
+        for (a, b) in outer_product(&r, &c).iter().zip(&data) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_try_separate_rejects_rank_two_kernel() {
+        let data = [1.0, 2.0, 3.0, 4.0, 5.0, 7.0];
+        assert!(try_separate(&Kernel::new(&data, 3, 2)).is_none());
+    }
+
+    #[test]
+    fn test_try_separate_rejects_zero_kernel() {
+        let data = [0.0; 9];
+        assert!(try_separate(&Kernel::new(&data, 3, 3)).is_none());
+    }
+}
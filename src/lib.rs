@@ -18,6 +18,9 @@ pub mod utils;
 #[macro_use]
 pub mod doc_macros;
 pub mod binary_descriptors;
+pub mod blobs;
+pub mod border;
+pub mod colorspace;
 pub mod contours;
 pub mod contrast;
 pub mod corners;
@@ -36,15 +39,21 @@ pub mod integral_image;
 pub mod kernel;
 pub mod local_binary_patterns;
 pub mod map;
+pub mod matching;
 pub mod math;
 pub mod morphology;
 pub mod noise;
+pub mod optical_flow;
 pub mod pixelops;
 pub mod point;
 #[cfg(any(feature = "property-testing", test))]
 pub mod property_testing;
+pub mod pyramid;
+pub mod quantization;
 pub mod rect;
 pub mod region_labelling;
+#[cfg(feature = "fft")]
+pub mod registration;
 pub mod seam_carving;
 pub mod stats;
 pub mod suppress;
@@ -1,6 +1,6 @@
 //! Functions for computing [local binary patterns](https://en.wikipedia.org/wiki/Local_binary_patterns).
 
-use image::{GenericImage, Luma};
+use image::{GenericImage, GrayImage, Luma};
 use std::cmp;
 
 /// Computes the basic local binary pattern of a pixel, or None
@@ -125,6 +125,177 @@ pub fn count_transitions(byte: u8) -> u32 {
     (byte ^ byte.rotate_right(1)).count_ones()
 }
 
+/// How [`local_binary_pattern_image`] maps the bits of a local binary pattern to a code.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LbpMapping {
+    /// The code is the pattern itself, with bit `k` set if the center is strictly brighter
+    /// than neighbor `k`. Requires at most 8 points.
+    Default,
+    /// The code is the least value of all circular rotations of the pattern, as for
+    /// [`min_shift`], so that it is unchanged if the image is rotated about the pixel.
+    /// Requires at most 8 points.
+    RotationInvariant,
+    /// The code is the number of set bits if the pattern is uniform, i.e. has at most two
+    /// circular bit transitions, and `points + 1` otherwise. This is the rotation invariant
+    /// uniform mapping of Ojala et al., and collapses all non-uniform patterns into a
+    /// single code.
+    Uniform,
+}
+
+impl LbpMapping {
+    /// Returns the number of distinct codes produced by this mapping for patterns with
+    /// the given number of points. All codes are smaller than this value.
+    pub fn num_codes(&self, points: u32) -> usize {
+        match self {
+            LbpMapping::Default | LbpMapping::RotationInvariant => 1 << points,
+            LbpMapping::Uniform => points as usize + 2,
+        }
+    }
+}
+
+/// Computes a local binary pattern code for every pixel of an image, comparing each pixel
+/// with `points` neighbors evenly spaced on the circle of radius `radius` centered on it.
+///
+/// Neighbor `k` lies at angle `2πk / points` clockwise from vertically above the pixel, and
+/// its intensity is bilinearly interpolated, padding the image by continuity. Bit `k` of
+/// the pattern is 1 if the center pixel is strictly brighter than neighbor `k`, as for
+/// [`local_binary_pattern`] (although the diagonal neighbors used by that function are at
+/// distance `sqrt(2)` rather than 1). Patterns are mapped to codes according to `mapping`.
+///
+/// Flat regions have code 0 for all mappings.
+///
+/// # Panics
+///
+/// If `radius` or `points` is 0, if `points > 8` and `mapping` is not
+/// [`LbpMapping::Uniform`], or if `points > 32`.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::local_binary_patterns::{local_binary_pattern_image, LbpMapping};
+///
+/// let image = gray_image!(
+///     06, 11, 14;
+///     09, 10, 10;
+///     19, 00, 22);
+///
+/// let codes = local_binary_pattern_image(&image, 1, 4, LbpMapping::Default);
+/// // The center is brighter than the neighbors to its left and below it
+/// assert_eq!(codes.get_pixel(1, 1)[0], 0b1100);
+///
+/// let uniform = local_binary_pattern_image(&image, 1, 4, LbpMapping::Uniform);
+/// assert_eq!(uniform.get_pixel(1, 1)[0], 2);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn local_binary_pattern_image(
+    image: &GrayImage,
+    radius: u32,
+    points: u32,
+    mapping: LbpMapping,
+) -> GrayImage {
+    assert!(radius > 0, "radius must be > 0");
+    assert!(points > 0, "points must be > 0");
+    assert!(points <= 32, "points must be <= 32");
+    assert!(
+        points <= 8 || mapping == LbpMapping::Uniform,
+        "points must be <= 8 unless using LbpMapping::Uniform"
+    );
+
+    let offsets: Vec<(f32, f32)> = (0..points)
+        .map(|k| {
+            let angle = 2.0 * std::f32::consts::PI * k as f32 / points as f32;
+            (radius as f32 * angle.sin(), -(radius as f32) * angle.cos())
+        })
+        .map(|(dx, dy)| (snap_to_integer(dx), snap_to_integer(dy)))
+        .collect();
+
+    let (width, height) = image.dimensions();
+    let at = |x: i64, y: i64| {
+        let x = x.clamp(0, width as i64 - 1) as u32;
+        let y = y.clamp(0, height as i64 - 1) as u32;
+        image.get_pixel(x, y)[0] as f32
+    };
+    let mask = if points == 32 {
+        u32::MAX
+    } else {
+        (1 << points) - 1
+    };
+
+    GrayImage::from_fn(width, height, |x, y| {
+        let center = image.get_pixel(x, y)[0] as f32;
+        let mut pattern = 0u32;
+        for (k, &(dx, dy)) in offsets.iter().enumerate() {
+            let (sx, sy) = (x as f32 + dx, y as f32 + dy);
+            let (x0, y0) = (sx.floor(), sy.floor());
+            let (fx, fy) = (sx - x0, sy - y0);
+            let (x0, y0) = (x0 as i64, y0 as i64);
+            // Interpolate as differences, so that sampling a constant region is exact
+            let top = at(x0, y0) + fx * (at(x0 + 1, y0) - at(x0, y0));
+            let bottom = at(x0, y0 + 1) + fx * (at(x0 + 1, y0 + 1) - at(x0, y0 + 1));
+            let neighbor = top + fy * (bottom - top);
+            pattern |= u32::from(neighbor < center) << k;
+        }
+
+        let code = match mapping {
+            LbpMapping::Default => pattern,
+            LbpMapping::RotationInvariant => (1..points)
+                .map(|i| ((pattern >> i) | (pattern << (points - i))) & mask)
+                .fold(pattern, u32::min),
+            LbpMapping::Uniform => {
+                let rotated = ((pattern >> 1) | (pattern << (points - 1))) & mask;
+                if (pattern ^ rotated).count_ones() <= 2 {
+                    pattern.count_ones()
+                } else {
+                    points + 1
+                }
+            }
+        };
+        Luma([code as u8])
+    })
+}
+
+// Removes floating point error from offsets which should be integers, so that
+// neighbors at integer offsets are read directly rather than interpolated.
+fn snap_to_integer(value: f32) -> f32 {
+    if (value - value.round()).abs() < 1e-4 {
+        value.round()
+    } else {
+        value
+    }
+}
+
+/// Returns the normalised histogram of the codes in an image computed by
+/// [`local_binary_pattern_image`], for use as a texture descriptor.
+///
+/// Entry `i` of the result is the fraction of pixels with code `i`. `num_codes` should
+/// be [`LbpMapping::num_codes`] of the mapping used. The result is empty if `codes` is.
+///
+/// # Panics
+///
+/// If `codes` contains a code that is not smaller than `num_codes`.
+pub fn lbp_histogram(codes: &GrayImage, num_codes: usize) -> Vec<f32> {
+    let mut histogram = vec![0f32; num_codes];
+    for &code in codes.iter() {
+        assert!(
+            (code as usize) < num_codes,
+            "found code {}, but num_codes is {}",
+            code,
+            num_codes
+        );
+        histogram[code as usize] += 1.0;
+    }
+    let total = codes.len() as f32;
+    if total == 0.0 {
+        return Vec::new();
+    }
+    histogram.iter_mut().for_each(|h| *h /= total);
+    histogram
+}
+
 /// Maps uniform bytes (i.e. those with at most two bit transitions) to their
 /// least circular shifts, and non-uniform bytes to 10101010 (an arbitrarily chosen
 /// non-uniform representative).
@@ -660,6 +831,92 @@ mod tests {
         let c = 0b10011001;
         assert_eq!(UNIFORM_REPRESENTATIVE_2[c], 0b10101010);
     }
+
+    #[test]
+    fn test_local_binary_pattern_image_flat_region_has_constant_code() {
+        let image = GrayImage::from_pixel(12, 10, Luma([93]));
+        for (radius, points) in [(1, 8), (2, 8), (3, 5)] {
+            for mapping in [
+                LbpMapping::Default,
+                LbpMapping::RotationInvariant,
+                LbpMapping::Uniform,
+            ] {
+                let codes = local_binary_pattern_image(&image, radius, points, mapping);
+                assert!(codes.iter().all(|&c| c == 0), "{:?}", mapping);
+            }
+        }
+        let codes = local_binary_pattern_image(&image, 3, 24, LbpMapping::Uniform);
+        assert!(codes.iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn test_local_binary_pattern_image_matches_pixel_pattern_at_edges() {
+        // Along horizontal and vertical edges the interpolated diagonal neighbors lie on
+        // the same side of the edge as the integer ones
+        let image = GrayImage::from_fn(7, 7, |x, _| Luma([if x < 3 { 10 } else { 200 }]));
+        let codes = local_binary_pattern_image(&image, 1, 8, LbpMapping::Default);
+        for y in 1..6 {
+            for x in 1..6 {
+                assert_eq!(
+                    Some(codes.get_pixel(x, y)[0]),
+                    local_binary_pattern(&image, x, y)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_local_binary_pattern_image_uniform_collapses_non_uniform_codes() {
+        let image = GrayImage::from_fn(30, 30, |x, y| {
+            Luma([((x * 37 + y * 91 + x * y * 17) % 256) as u8])
+        });
+        let default = local_binary_pattern_image(&image, 1, 8, LbpMapping::Default);
+        let uniform = local_binary_pattern_image(&image, 1, 8, LbpMapping::Uniform);
+
+        let mut num_non_uniform = 0;
+        for (&d, &u) in default.iter().zip(uniform.iter()) {
+            if count_transitions(d) > 2 {
+                num_non_uniform += 1;
+                assert_eq!(u, 9);
+            } else {
+                assert_eq!(u as u32, d.count_ones());
+            }
+        }
+        assert!(num_non_uniform > 0);
+
+        let histogram = lbp_histogram(&uniform, LbpMapping::Uniform.num_codes(8));
+        assert_eq!(histogram.len(), 10);
+        assert_eq!(histogram[9], num_non_uniform as f32 / 900.0);
+        assert!((histogram.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_local_binary_pattern_image_rotation_invariant() {
+        let image = GrayImage::from_fn(20, 16, |x, y| {
+            Luma([((x * 29 + y * y * 13 + x * y) % 256) as u8])
+        });
+        let rotated = image::imageops::rotate90(&image);
+
+        let codes = local_binary_pattern_image(&image, 1, 8, LbpMapping::RotationInvariant);
+        let rotated_codes =
+            local_binary_pattern_image(&rotated, 1, 8, LbpMapping::RotationInvariant);
+        for y in 0..16 {
+            for x in 0..20 {
+                // rotate90 sends pixel (x, y) to (height - 1 - y, x)
+                assert_eq!(codes.get_pixel(x, y), rotated_codes.get_pixel(15 - y, x));
+                assert_eq!(
+                    codes.get_pixel(x, y)[0],
+                    min_shift(codes.get_pixel(x, y)[0])
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_local_binary_pattern_image_rejects_too_many_points() {
+        let _ = local_binary_pattern_image(&GrayImage::new(5, 5), 2, 16, LbpMapping::Default);
+    }
 }
 
 #[cfg(not(miri))]
@@ -681,4 +938,13 @@ mod benches {
             }
         });
     }
+
+    #[bench]
+    fn bench_local_binary_pattern_image(b: &mut Bencher) {
+        let image = GrayImage::from_fn(100, 100, |x, y| Luma([(x * 7 + y * 13) as u8]));
+        b.iter(|| {
+            let codes = local_binary_pattern_image(&image, 2, 8, LbpMapping::Uniform);
+            black_box(codes);
+        });
+    }
 }
@@ -272,6 +272,29 @@ where
 }
 #[cfg(feature = "rayon")]
 #[doc = generate_parallel_doc_comment!("map_enumerated_pixels")]
+///
+/// The rows of the output image are split across threads. As `f` is given the coordinates
+/// of each pixel this is convenient for position-dependent transforms such as vignettes and
+/// gradient masks.
+///
+/// # Examples
+/// ```
+/// use image::{Luma, Rgb, RgbImage};
+/// use imageproc::map::{map_enumerated_pixels, map_enumerated_pixels_parallel};
+///
+/// let image = RgbImage::from_pixel(300, 200, Rgb([200, 150, 100]));
+/// // Darken pixels in proportion to their distance from the image centre
+/// let vignette = |x: u32, y: u32, p: Rgb<u8>| {
+///     let (dx, dy) = (x as f32 - 150.0, y as f32 - 100.0);
+///     let scale = 1.0 - (dx * dx + dy * dy).sqrt() / 250.0;
+///     Rgb(p.0.map(|c| (c as f32 * scale) as u8))
+/// };
+///
+/// assert_eq!(
+///     map_enumerated_pixels_parallel(&image, vignette),
+///     map_enumerated_pixels(&image, vignette)
+/// );
+/// ```
 pub fn map_enumerated_pixels_parallel<P, Q, F>(image: &Image<P>, f: F) -> Image<Q>
 where
     P: Pixel + Sync,
@@ -280,18 +303,26 @@ where
     Q::Subpixel: Send,
     F: Fn(u32, u32, P) -> Q + Sync,
 {
-    use rayon::iter::ParallelIterator;
+    use rayon::prelude::*;
 
-    Image::from_vec(
-        image.width(),
-        image.height(),
-        image
-            .par_enumerate_pixels()
-            //optimisation: remove allocation if Pixel ever gets compile-time size information
-            .flat_map(|(x, y, pixel)| f(x, y, *pixel).channels().to_vec())
-            .collect(),
-    )
-    .expect("of course the length is good, it's just a map")
+    let (width, height) = image.dimensions();
+    let mut out = Image::<Q>::new(width, height);
+    if width == 0 || height == 0 {
+        return out;
+    }
+
+    let channels = Q::CHANNEL_COUNT as usize;
+    out.par_chunks_mut(width as usize * channels)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for (x, out_channels) in row.chunks_exact_mut(channels).enumerate() {
+                let (x, y) = (x as u32, y as u32);
+                let pixel = f(x, y, *image.get_pixel(x, y));
+                out_channels.copy_from_slice(pixel.channels());
+            }
+        });
+
+    out
 }
 #[cfg(feature = "rayon")]
 #[doc = generate_parallel_doc_comment!("map_enumerated_pixels_mut")]
@@ -549,3 +580,96 @@ where
 {
     map_pixels(image, |p| Rgb([C::zero(), C::zero(), p.0[0]]))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn vignette(width: u32, height: u32) -> impl Fn(u32, u32, Rgb<u8>) -> Rgb<u8> + Sync {
+        let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+        let max_distance = (cx * cx + cy * cy).sqrt();
+        move |x, y, p| {
+            let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+            let scale = 1.0 - (dx * dx + dy * dy).sqrt() / max_distance;
+            Rgb(p.0.map(|c| (c as f32 * scale).round() as u8))
+        }
+    }
+
+    #[test]
+    fn test_map_enumerated_pixels_passes_coordinates() {
+        let image = RgbImage::from_pixel(5, 3, Rgb([7, 0, 0]));
+        let mapped = map_enumerated_pixels(&image, |x, y, p| Luma([p[0] + (10 * y + x) as u8]));
+        assert_eq!(
+            mapped.into_raw(),
+            (0..15)
+                .map(|i| 7 + (i / 5) * 10 + i % 5)
+                .collect::<Vec<u8>>()
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_map_enumerated_pixels_parallel_matches_serial() {
+        for (width, height) in [(97, 61), (1, 40), (40, 1), (0, 5), (5, 0)] {
+            let image = RgbImage::from_fn(width, height, |x, y| {
+                Rgb([(x * 3) as u8, (y * 5) as u8, ((x + y) * 7) as u8])
+            });
+            let f = vignette(width, height);
+            let serial = map_enumerated_pixels(&image, &f);
+            let parallel = map_enumerated_pixels_parallel(&image, &f);
+            assert_eq!(parallel.dimensions(), (width, height));
+            assert_pixels_eq!(parallel, serial);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_map_enumerated_pixels_parallel_changes_channel_count() {
+        let image = RgbImage::from_fn(30, 20, |x, y| Rgb([x as u8, y as u8, 100]));
+        let to_gray = |x: u32, y: u32, p: Rgb<u8>| Luma([p[0] / 2 + p[1] / 2 + (x * y % 3) as u8]);
+        assert_pixels_eq!(
+            map_enumerated_pixels_parallel(&image, to_gray),
+            map_enumerated_pixels(&image, to_gray)
+        );
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use image::{Rgb, RgbImage};
+    use test::{black_box, Bencher};
+
+    fn bench_image() -> RgbImage {
+        RgbImage::from_fn(1000, 1000, |x, y| {
+            Rgb([(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8])
+        })
+    }
+
+    fn vignette(x: u32, y: u32, p: Rgb<u8>) -> Rgb<u8> {
+        let (dx, dy) = (x as f32 - 500.0, y as f32 - 500.0);
+        let scale = 1.0 - (dx * dx + dy * dy).sqrt() / 710.0;
+        Rgb(p.0.map(|c| (c as f32 * scale) as u8))
+    }
+
+    #[bench]
+    fn bench_map_enumerated_pixels(b: &mut Bencher) {
+        let image = bench_image();
+        b.iter(|| {
+            let mapped = map_enumerated_pixels(&image, vignette);
+            black_box(mapped);
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[bench]
+    fn bench_map_enumerated_pixels_parallel(b: &mut Bencher) {
+        let image = bench_image();
+        b.iter(|| {
+            let mapped = map_enumerated_pixels_parallel(&image, vignette);
+            black_box(mapped);
+        });
+    }
+}
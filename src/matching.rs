@@ -0,0 +1,224 @@
+//! Functions for matching feature descriptors between images.
+//!
+//! The matchers in this module work with descriptors of any type, given a function
+//! computing the distance between two descriptors. For binary descriptors, see also
+//! [`match_binary_descriptors`](crate::binary_descriptors::match_binary_descriptors).
+
+/// A match between a descriptor in a query set and a descriptor in a train set.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DMatch {
+    /// The index of the descriptor in the query set.
+    pub query_idx: usize,
+    /// The index of the matched descriptor in the train set.
+    pub train_idx: usize,
+    /// The distance between the two descriptors.
+    pub distance: f32,
+}
+
+/// Matches each descriptor in `query` to its nearest neighbour in `train`, comparing
+/// every pair of descriptors using `distance_fn`.
+///
+/// Returns one match for each descriptor in `query`, in order, or no matches if `train` is
+/// empty. If several descriptors in `train` are equally close the first of them is chosen.
+/// Descriptors in `train` may be matched to more than one descriptor in `query`.
+///
+/// # Examples
+/// ```
+/// use imageproc::matching::{match_descriptors_bruteforce, DMatch};
+///
+/// let query = [[0.0f32, 0.0], [5.0, 5.0]];
+/// let train = [[4.0f32, 5.0], [0.0, 1.0], [9.0, 9.0]];
+/// let distance = |a: &[f32; 2], b: &[f32; 2]| ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt();
+///
+/// let matches = match_descriptors_bruteforce(&query, &train, distance);
+/// assert_eq!(
+///     matches,
+///     vec![
+///         DMatch { query_idx: 0, train_idx: 1, distance: 1.0 },
+///         DMatch { query_idx: 1, train_idx: 0, distance: 1.0 },
+///     ]
+/// );
+/// ```
+pub fn match_descriptors_bruteforce<D, F>(query: &[D], train: &[D], distance_fn: F) -> Vec<DMatch>
+where
+    F: Fn(&D, &D) -> f32,
+{
+    query
+        .iter()
+        .enumerate()
+        .filter_map(|(query_idx, q)| {
+            let mut best: Option<DMatch> = None;
+            for (train_idx, t) in train.iter().enumerate() {
+                let distance = distance_fn(q, t);
+                if best.map_or(true, |b| distance < b.distance) {
+                    best = Some(DMatch {
+                        query_idx,
+                        train_idx,
+                        distance,
+                    });
+                }
+            }
+            best
+        })
+        .collect()
+}
+
+/// Matches each descriptor in `query` to its nearest neighbour in `train`, discarding
+/// ambiguous matches using the ratio test from Lowe's "Distinctive Image Features from
+/// Scale-Invariant Keypoints".
+///
+/// A match is kept only if its distance is less than `ratio` times the distance from the
+/// query descriptor to its second nearest neighbour in `train`. This rejects descriptors
+/// which are similar to several descriptors in `train`, and so are likely to be mismatched.
+/// Lowe suggests a ratio of 0.8. If `train` contains a single descriptor then every query
+/// descriptor is matched to it.
+///
+/// Distances are computed using `distance_fn`, and matches are returned in order of
+/// query index.
+///
+/// # Panics
+///
+/// If `ratio` is not in `(0, 1]`.
+///
+/// # Examples
+/// ```
+/// use imageproc::matching::match_with_ratio_test;
+///
+/// let query = [0.0f32, 10.0];
+/// // 10.0 is almost as close to 11.2 as it is to 9.0, so its match is ambiguous
+/// let train = [0.5f32, 9.0, 11.2, 20.0];
+/// let distance = |a: &f32, b: &f32| (a - b).abs();
+///
+/// let matches = match_with_ratio_test(&query, &train, 0.8, distance);
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!((matches[0].query_idx, matches[0].train_idx), (0, 0));
+/// ```
+pub fn match_with_ratio_test<D, F>(
+    query: &[D],
+    train: &[D],
+    ratio: f32,
+    distance_fn: F,
+) -> Vec<DMatch>
+where
+    F: Fn(&D, &D) -> f32,
+{
+    assert!(
+        ratio > 0.0 && ratio <= 1.0,
+        "ratio must be in (0, 1] (found {})",
+        ratio
+    );
+
+    query
+        .iter()
+        .enumerate()
+        .filter_map(|(query_idx, q)| {
+            let mut best: Option<DMatch> = None;
+            let mut second_distance = f32::INFINITY;
+            for (train_idx, t) in train.iter().enumerate() {
+                let distance = distance_fn(q, t);
+                match best {
+                    Some(b) if distance >= b.distance => {
+                        second_distance = second_distance.min(distance);
+                    }
+                    _ => {
+                        if let Some(b) = best {
+                            second_distance = b.distance;
+                        }
+                        best = Some(DMatch {
+                            query_idx,
+                            train_idx,
+                            distance,
+                        });
+                    }
+                }
+            }
+            best.filter(|b| b.distance < ratio * second_distance)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn euclidean(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+        a.iter()
+            .zip(b)
+            .map(|(x, y)| (x - y) * (x - y))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    // Returns descriptors in which features 0, 1 and 2 are distinct, and feature 3 is
+    // almost identical to feature 4.
+    fn train_descriptors() -> Vec<[f32; 4]> {
+        vec![
+            [0.0, 0.0, 0.0, 10.0],
+            [10.0, 0.0, 0.0, 0.0],
+            [0.0, 10.0, 0.0, 0.0],
+            [0.0, 0.0, 10.0, 0.0],
+            [0.2, 0.0, 10.2, 0.2],
+        ]
+    }
+
+    fn query_descriptors() -> Vec<[f32; 4]> {
+        vec![
+            [0.0, 10.2, 0.1, 0.0],
+            [0.1, 0.0, 10.1, 0.12],
+            [9.8, 0.0, 0.3, 0.0],
+            [0.0, 0.1, 0.0, 9.9],
+        ]
+    }
+
+    #[test]
+    fn test_match_descriptors_bruteforce_finds_nearest_neighbours() {
+        let matches =
+            match_descriptors_bruteforce(&query_descriptors(), &train_descriptors(), euclidean);
+        let pairs: Vec<(usize, usize)> =
+            matches.iter().map(|m| (m.query_idx, m.train_idx)).collect();
+        assert_eq!(pairs, vec![(0, 2), (1, 4), (2, 1), (3, 0)]);
+        for m in &matches {
+            let expected = euclidean(
+                &query_descriptors()[m.query_idx],
+                &train_descriptors()[m.train_idx],
+            );
+            assert_eq!(m.distance, expected);
+        }
+    }
+
+    #[test]
+    fn test_match_with_ratio_test_filters_ambiguous_matches() {
+        let matches =
+            match_with_ratio_test(&query_descriptors(), &train_descriptors(), 0.8, euclidean);
+        let pairs: Vec<(usize, usize)> =
+            matches.iter().map(|m| (m.query_idx, m.train_idx)).collect();
+        // Query 1 is close to both train 3 and train 4, so is discarded
+        assert_eq!(pairs, vec![(0, 2), (2, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn test_match_with_ratio_test_finds_second_nearest_before_nearest() {
+        // The nearest neighbour is last, so the second nearest is found before it
+        let train = [5.0f32, 3.0, 1.0];
+        let distance = |a: &f32, b: &f32| (a - b).abs();
+        assert!(match_with_ratio_test(&[1.5], &train, 0.5, distance)[0].train_idx == 2);
+        assert!(match_with_ratio_test(&[2.2], &train, 0.6, distance).is_empty());
+    }
+
+    #[test]
+    fn test_matchers_handle_small_train_sets() {
+        let distance = |a: &f32, b: &f32| (a - b).abs();
+        assert!(match_descriptors_bruteforce(&[1.0], &[], distance).is_empty());
+        assert!(match_with_ratio_test(&[1.0], &[], 0.8, distance).is_empty());
+        let matches = match_with_ratio_test(&[1.0, 4.0], &[2.0], 0.8, distance);
+        assert_eq!(matches.len(), 2);
+        // Exact duplicates in the train set are ambiguous
+        assert!(match_with_ratio_test(&[1.0], &[2.0, 2.0], 0.8, distance).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_match_with_ratio_test_rejects_invalid_ratio() {
+        let _ = match_with_ratio_test(&[1.0f32], &[2.0], 1.5, |a, b| (a - b).abs());
+    }
+}
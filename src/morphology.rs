@@ -4,7 +4,9 @@
 
 use crate::{
     distance_transform::{distance_transform_impl, distance_transform_mut, DistanceFrom, Norm},
+    map::map_subpixels,
     point::Point,
+    region_labelling::{connected_components_with_stats, Connectivity},
 };
 use image::{GrayImage, Luma};
 use itertools::Itertools;
@@ -334,6 +336,513 @@ pub fn close_mut(image: &mut GrayImage, norm: Norm, k: u8) {
     erode_mut(image, norm, k);
 }
 
+/// The white top-hat transform: the difference between `image` and its [`open`]ing.
+///
+/// Keeps the foreground structures that are removed by opening, i.e. those which are too
+/// small to contain the structuring element. Subtraction saturates at zero.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::white_tophat;
+/// use imageproc::distance_transform::Norm;
+///
+/// let image = gray_image!(
+///     255,   0,   0,   0,   0;
+///       0,   0, 255, 255, 255;
+///       0,   0, 255, 255, 255;
+///       0,   0, 255, 255, 255
+/// );
+///
+/// // The isolated pixel is too small to survive opening
+/// let tophat = gray_image!(
+///     255,   0,   0,   0,   0;
+///       0,   0,   0,   0,   0;
+///       0,   0,   0,   0,   0;
+///       0,   0,   0,   0,   0
+/// );
+///
+/// assert_pixels_eq!(white_tophat(&image, Norm::LInf, 1), tophat);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn white_tophat(image: &GrayImage, norm: Norm, k: u8) -> GrayImage {
+    saturating_difference(image, &open(image, norm, k))
+}
+
+/// The black top-hat transform: the difference between the [`close`]d `image` and `image`.
+///
+/// Keeps the background structures that are filled in by closing, i.e. holes and gaps
+/// which are too small to contain the structuring element. Subtraction saturates at zero.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::black_tophat;
+/// use imageproc::distance_transform::Norm;
+///
+/// let image = gray_image!(
+///     255, 255, 255, 255;
+///     255,   0, 255, 255;
+///     255, 255, 255, 255;
+///     255, 255, 255, 255
+/// );
+///
+/// // The small hole is filled by closing
+/// let tophat = gray_image!(
+///       0,   0,   0,   0;
+///       0, 255,   0,   0;
+///       0,   0,   0,   0;
+///       0,   0,   0,   0
+/// );
+///
+/// assert_pixels_eq!(black_tophat(&image, Norm::LInf, 1), tophat);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn black_tophat(image: &GrayImage, norm: Norm, k: u8) -> GrayImage {
+    saturating_difference(&close(image, norm, k), image)
+}
+
+/// The morphological gradient: the difference between the [`dilate`]d and [`erode`]d `image`.
+///
+/// The result is white at pixels within distance `k` of the boundary between foreground
+/// and background, where foreground pixels within distance `k` of a background pixel are on
+/// the inside of the boundary and background pixels within distance `k` of a foreground pixel
+/// are on the outside. The boundary of each foreground region therefore becomes a band of
+/// width `2 * k`.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::morphological_gradient;
+/// use imageproc::distance_transform::Norm;
+///
+/// let image = gray_image!(
+///       0,   0,   0,   0,   0,   0;
+///       0, 255, 255, 255, 255,   0;
+///       0, 255, 255, 255, 255,   0;
+///       0, 255, 255, 255, 255,   0;
+///       0,   0,   0,   0,   0,   0
+/// );
+///
+/// let gradient = gray_image!(
+///     255, 255, 255, 255, 255, 255;
+///     255, 255, 255, 255, 255, 255;
+///     255, 255,   0,   0, 255, 255;
+///     255, 255, 255, 255, 255, 255;
+///     255, 255, 255, 255, 255, 255
+/// );
+///
+/// assert_pixels_eq!(morphological_gradient(&image, Norm::LInf, 1), gradient);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn morphological_gradient(image: &GrayImage, norm: Norm, k: u8) -> GrayImage {
+    saturating_difference(&dilate(image, norm, k), &erode(image, norm, k))
+}
+
+/// Reduces the foreground regions of a binary image to skeletons one pixel wide, using the
+/// [Zhang-Suen thinning algorithm].
+///
+/// A pixel is treated as belonging to the foreground if it has non-zero intensity.
+/// Foreground pixels are set to white in the output and all other pixels are set to black.
+///
+/// Boundary pixels of each region are removed in alternating sub-passes from the
+/// south-east and north-west, until a pass removes nothing. Pixels are never removed
+/// if this would disconnect their region or shorten the end of a line, so each
+/// region's skeleton remains 8-connected. The sub-passes are not symmetric, so skeletons
+/// may not be exactly centred within their regions. Pixels outside the image are treated
+/// as background.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::thin;
+///
+/// let bar = gray_image!(
+///       0,   0,   0,   0,   0,   0,   0;
+///       0, 255, 255, 255, 255, 255,   0;
+///       0, 255, 255, 255, 255, 255,   0;
+///       0, 255, 255, 255, 255, 255,   0;
+///       0,   0,   0,   0,   0,   0,   0
+/// );
+///
+/// let skeleton = gray_image!(
+///       0,   0,   0,   0,   0,   0,   0;
+///       0,   0,   0,   0,   0,   0,   0;
+///       0,   0, 255, 255,   0,   0,   0;
+///       0,   0,   0,   0,   0,   0,   0;
+///       0,   0,   0,   0,   0,   0,   0
+/// );
+///
+/// assert_pixels_eq!(thin(&bar), skeleton);
+/// # }
+/// ```
+///
+/// [Zhang-Suen thinning algorithm]: https://doi.org/10.1145/357994.358023
+#[must_use = "the function does not modify the original image"]
+pub fn thin(image: &GrayImage) -> GrayImage {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut foreground: Vec<bool> = image.iter().map(|&p| p != 0).collect();
+
+    // The neighbours of (x, y) in the order north, north-east, east, ..., north-west
+    let neighbours = |foreground: &[bool], x: usize, y: usize| -> [bool; 8] {
+        let at = |dx: isize, dy: isize| {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            nx >= 0
+                && ny >= 0
+                && (nx as usize) < width
+                && (ny as usize) < height
+                && foreground[ny as usize * width + nx as usize]
+        };
+        [
+            at(0, -1),
+            at(1, -1),
+            at(1, 0),
+            at(1, 1),
+            at(0, 1),
+            at(-1, 1),
+            at(-1, 0),
+            at(-1, -1),
+        ]
+    };
+
+    let mut to_remove = Vec::new();
+    loop {
+        let mut changed = false;
+        for first_pass in [true, false] {
+            to_remove.clear();
+            for y in 0..height {
+                for x in 0..width {
+                    if !foreground[y * width + x] {
+                        continue;
+                    }
+                    let ring = neighbours(&foreground, x, y);
+                    let (n, e, s, w) = (ring[0], ring[2], ring[4], ring[6]);
+                    let count = ring.iter().filter(|&&b| b).count();
+                    let transitions = (0..8).filter(|&i| !ring[i] && ring[(i + 1) % 8]).count();
+                    let removable = if first_pass {
+                        !(e && s && (n || w))
+                    } else {
+                        !(n && w && (e || s))
+                    };
+                    if (2..=6).contains(&count) && transitions == 1 && removable {
+                        to_remove.push(y * width + x);
+                    }
+                }
+            }
+            for &i in &to_remove {
+                foreground[i] = false;
+            }
+            changed |= !to_remove.is_empty();
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    GrayImage::from_fn(width as u32, height as u32, |x, y| {
+        if foreground[y as usize * width + x as usize] {
+            Luma([255])
+        } else {
+            Luma([0])
+        }
+    })
+}
+
+/// Fills the holes in the foreground regions of a binary image.
+///
+/// A pixel is treated as belonging to the foreground if it has non-zero intensity. A hole
+/// is a region of background pixels which is not connected to the border of the image,
+/// where background pixels are connected to their neighbors according to `conn`. With
+/// [`Connectivity::Four`] a hole may be enclosed by a diagonally connected boundary, whereas
+/// with [`Connectivity::Eight`] background can leak through diagonal gaps.
+///
+/// Foreground pixels and holes are set to white in the output and all other pixels are set
+/// to black.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::fill_holes;
+/// use imageproc::region_labelling::Connectivity;
+///
+/// let image = gray_image!(
+///       0, 255, 255, 255,   0;
+///       0, 255,   0, 255,   0;
+///       0, 255, 255, 255,   0;
+///       0, 255,   0, 255,   0
+/// );
+///
+/// // The lower gap is open to the border, so is not a hole
+/// let filled = gray_image!(
+///       0, 255, 255, 255,   0;
+///       0, 255, 255, 255,   0;
+///       0, 255, 255, 255,   0;
+///       0, 255,   0, 255,   0
+/// );
+///
+/// assert_pixels_eq!(fill_holes(&image, Connectivity::Four), filled);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn fill_holes(image: &GrayImage, conn: Connectivity) -> GrayImage {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    if width == 0 || height == 0 {
+        return GrayImage::new(image.width(), image.height());
+    }
+    let background: Vec<bool> = image.iter().map(|&p| p == 0).collect();
+
+    // Flood fill the background from the border. Unreached background pixels are holes.
+    let mut reached = vec![false; width * height];
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    let visit = |x: usize, y: usize, reached: &mut [bool], stack: &mut Vec<(usize, usize)>| {
+        let i = y * width + x;
+        if background[i] && !reached[i] {
+            reached[i] = true;
+            stack.push((x, y));
+        }
+    };
+    for x in 0..width {
+        visit(x, 0, &mut reached, &mut stack);
+        visit(x, height - 1, &mut reached, &mut stack);
+    }
+    for y in 0..height {
+        visit(0, y, &mut reached, &mut stack);
+        visit(width - 1, y, &mut reached, &mut stack);
+    }
+
+    let offsets: &[(isize, isize)] = match conn {
+        Connectivity::Four => &[(0, -1), (1, 0), (0, 1), (-1, 0)],
+        Connectivity::Eight => &[
+            (0, -1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 1),
+            (-1, 0),
+            (-1, -1),
+        ],
+    };
+    while let Some((x, y)) = stack.pop() {
+        for &(dx, dy) in offsets {
+            let (nx, ny) = (x as isize + dx, y as isize + dy);
+            if nx >= 0 && ny >= 0 && (nx as usize) < width && (ny as usize) < height {
+                visit(nx as usize, ny as usize, &mut reached, &mut stack);
+            }
+        }
+    }
+
+    let mut out = GrayImage::new(image.width(), image.height());
+    for (p, &r) in out.iter_mut().zip(reached.iter()) {
+        if !r {
+            *p = 255;
+        }
+    }
+    out
+}
+
+/// Removes the foreground regions of a binary image with fewer than `min_area` pixels.
+///
+/// A pixel is treated as belonging to the foreground if it has non-zero intensity, and
+/// foreground pixels are connected to their neighbors according to `conn`. Pixels of
+/// foreground regions with at least `min_area` pixels are set to white in the output and
+/// all other pixels are set to black.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::remove_small_objects;
+/// use imageproc::region_labelling::Connectivity;
+///
+/// let image = gray_image!(
+///     255, 255,   0,   0;
+///     255, 255,   0, 255;
+///       0,   0,   0,   0
+/// );
+///
+/// let cleaned = gray_image!(
+///     255, 255,   0,   0;
+///     255, 255,   0,   0;
+///       0,   0,   0,   0
+/// );
+///
+/// assert_pixels_eq!(remove_small_objects(&image, 2, Connectivity::Four), cleaned);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn remove_small_objects(image: &GrayImage, min_area: u32, conn: Connectivity) -> GrayImage {
+    let foreground = map_subpixels(image, |p| if p == 0 { 0u8 } else { 255 });
+    let (labels, stats) = connected_components_with_stats(&foreground, conn, Luma([0]));
+    let keep: Vec<bool> = stats.iter().map(|s| s.area >= min_area).collect();
+    map_subpixels(&labels, |l| {
+        if l > 0 && keep[l as usize - 1] {
+            255
+        } else {
+            0
+        }
+    })
+}
+
+/// Fills the holes in the foreground regions of a binary image with at most
+/// `max_hole_area` pixels.
+///
+/// A pixel is treated as belonging to the foreground if it has non-zero intensity. As for
+/// [`fill_holes`], a hole is a region of background pixels, connected according to `conn`,
+/// which does not touch the border of the image. Foreground pixels and pixels of holes with
+/// at most `max_hole_area` pixels are set to white in the output and all other pixels are
+/// set to black.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::remove_small_holes;
+/// use imageproc::region_labelling::Connectivity;
+///
+/// let image = gray_image!(
+///     255, 255, 255, 255, 255, 255, 255;
+///     255,   0, 255,   0,   0,   0, 255;
+///     255, 255, 255,   0,   0,   0, 255;
+///     255, 255, 255, 255, 255, 255, 255
+/// );
+///
+/// // Only the smaller hole is filled
+/// let filled = gray_image!(
+///     255, 255, 255, 255, 255, 255, 255;
+///     255, 255, 255,   0,   0,   0, 255;
+///     255, 255, 255,   0,   0,   0, 255;
+///     255, 255, 255, 255, 255, 255, 255
+/// );
+///
+/// assert_pixels_eq!(remove_small_holes(&image, 3, Connectivity::Four), filled);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn remove_small_holes(image: &GrayImage, max_hole_area: u32, conn: Connectivity) -> GrayImage {
+    let (width, height) = image.dimensions();
+    let background = map_subpixels(image, |p| if p == 0 { 255u8 } else { 0 });
+    let (labels, stats) = connected_components_with_stats(&background, conn, Luma([0]));
+    let fill: Vec<bool> = stats
+        .iter()
+        .map(|s| {
+            let touches_border = s.bbox.left() == 0
+                || s.bbox.top() == 0
+                || s.bbox.right() == width as i32 - 1
+                || s.bbox.bottom() == height as i32 - 1;
+            !touches_border && s.area <= max_hole_area
+        })
+        .collect();
+    map_subpixels(&labels, |l| {
+        if l == 0 || fill[l as usize - 1] {
+            255
+        } else {
+            0
+        }
+    })
+}
+
+/// The hit-or-miss transform, which finds the pixels at which a pattern of foreground and
+/// background pixels appears.
+///
+/// A pixel is treated as belonging to the foreground if it has non-zero intensity, and
+/// pixels outside the image are treated as background. A pixel is set to white in the
+/// output if every offset from it in `foreground` is a foreground pixel and every offset
+/// from it in `background` is a background pixel. All other pixels are set to black.
+/// Offsets in neither mask may have any value.
+///
+/// # Panics
+///
+/// If `foreground` and `background` share any offset, as no pixel could then match.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::{hit_or_miss, Mask};
+/// use imageproc::point::Point;
+///
+/// let image = gray_image!(
+///       0,   0,   0,   0,   0;
+///       0, 255, 255, 255,   0;
+///       0, 255, 255, 255,   0;
+///       0,   0,   0,   0,   0
+/// );
+///
+/// // Foreground pixels with background above them
+/// let foreground = Mask::from_points(&[Point::new(0, 0)]);
+/// let background = Mask::from_points(&[Point::new(0, -1)]);
+///
+/// let top_edge = gray_image!(
+///       0,   0,   0,   0,   0;
+///       0, 255, 255, 255,   0;
+///       0,   0,   0,   0,   0;
+///       0,   0,   0,   0,   0
+/// );
+///
+/// assert_pixels_eq!(hit_or_miss(&image, &foreground, &background), top_edge);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn hit_or_miss(image: &GrayImage, foreground: &Mask, background: &Mask) -> GrayImage {
+    assert!(
+        foreground
+            .elements
+            .iter()
+            .all(|p| !background.elements.contains(p)),
+        "foreground and background masks must not overlap"
+    );
+    let (width, height) = (i64::from(image.width()), i64::from(image.height()));
+    let is_foreground = |x: i64, y: i64, offset: &Point<i16>| {
+        let (x, y) = (x + i64::from(offset.x), y + i64::from(offset.y));
+        x >= 0 && y >= 0 && x < width && y < height && image.get_pixel(x as u32, y as u32)[0] != 0
+    };
+
+    GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        let (x, y) = (i64::from(x), i64::from(y));
+        let hit = foreground.elements.iter().all(|p| is_foreground(x, y, p));
+        let miss = background.elements.iter().all(|p| !is_foreground(x, y, p));
+        if hit && miss {
+            Luma([255])
+        } else {
+            Luma([0])
+        }
+    })
+}
+
+// Computes `minuend - subtrahend` pixelwise, saturating at zero.
+fn saturating_difference(minuend: &GrayImage, subtrahend: &GrayImage) -> GrayImage {
+    let mut out = minuend.clone();
+    for (o, &s) in out.iter_mut().zip(subtrahend.iter()) {
+        *o = o.saturating_sub(s);
+    }
+    out
+}
+
 /// A mask used in grayscale morphological operations.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Mask {
@@ -382,6 +891,41 @@ impl Mask {
         Self::new(elements)
     }
 
+    /// Creates a mask containing the given offsets from the origin. Duplicate offsets are ignored.
+    ///
+    /// # Panics
+    /// If any coordinate of any offset is not strictly between -512 and 512.
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate image;
+    /// # #[macro_use]
+    /// # extern crate imageproc;
+    /// # fn main() {
+    /// use imageproc::morphology::Mask;
+    /// use imageproc::point::Point;
+    ///
+    /// let horizontal_line = Mask::from_points(&[
+    ///     Point::new(1, 0),
+    ///     Point::new(-1, 0),
+    ///     Point::new(0, 0),
+    /// ]);
+    /// assert_eq!(horizontal_line, Mask::from_image(&gray_image!(255, 255, 255), 1, 0));
+    /// # }
+    /// ```
+    pub fn from_points(points: &[Point<i16>]) -> Self {
+        assert!(
+            points
+                .iter()
+                .all(|p| p.x.unsigned_abs() < 512 && p.y.unsigned_abs() < 512),
+            "mask offsets must be strictly between -512 and 512"
+        );
+        let mut elements = points.to_vec();
+        elements.sort_by_key(|p| (p.y, p.x));
+        elements.dedup();
+        Self::new(elements)
+    }
+
     fn new(elements: Vec<Point<i16>>) -> Self {
         assert!(elements.len() <= (511 * 511) as usize);
         debug_assert!(elements.iter().tuple_windows().all(|(a, b)| {
@@ -802,9 +1346,118 @@ pub fn grayscale_close(image: &GrayImage, mask: &Mask) -> GrayImage {
     grayscale_erode(&grayscale_dilate(image, mask), mask)
 }
 
+/// The grayscale white top-hat transform: the difference between `image`
+/// and its [`grayscale_open`]ing.
+///
+/// Extracts bright structures which are smaller than the mask, while removing
+/// background variations which are smooth at the scale of the mask. This makes it
+/// useful for correcting uneven illumination. Subtraction saturates at zero.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::{Mask, grayscale_white_tophat};
+///
+/// let image = gray_image!(
+///    10,  20,  30,  30,  30;
+///    10,  20, 130,  30,  30;
+///    10,  20,  30,  30,  30
+/// );
+///
+/// let tophat = gray_image!(
+///     0,   0,   0,   0,   0;
+///     0,   0, 100,   0,   0;
+///     0,   0,   0,   0,   0
+/// );
+///
+/// assert_pixels_eq!(grayscale_white_tophat(&image, &Mask::square(1)), tophat);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn grayscale_white_tophat(image: &GrayImage, mask: &Mask) -> GrayImage {
+    saturating_difference(image, &grayscale_open(image, mask))
+}
+
+/// The grayscale black top-hat transform: the difference between the
+/// [`grayscale_close`]d `image` and `image`.
+///
+/// Extracts dark structures which are smaller than the mask, while removing
+/// background variations which are smooth at the scale of the mask.
+/// Subtraction saturates at zero.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::{Mask, grayscale_black_tophat};
+///
+/// let image = gray_image!(
+///   200, 190, 180, 180, 180;
+///   200, 190,  80, 180, 180;
+///   200, 190, 180, 180, 180
+/// );
+///
+/// let tophat = gray_image!(
+///     0,   0,   0,   0,   0;
+///     0,   0, 100,   0,   0;
+///     0,   0,   0,   0,   0
+/// );
+///
+/// assert_pixels_eq!(grayscale_black_tophat(&image, &Mask::square(1)), tophat);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn grayscale_black_tophat(image: &GrayImage, mask: &Mask) -> GrayImage {
+    saturating_difference(&grayscale_close(image, mask), image)
+}
+
+/// The grayscale morphological gradient: the difference between the [`grayscale_dilate`]d
+/// and [`grayscale_erode`]d `image`.
+///
+/// Each output pixel is the range of the input intensities within the mask centred on
+/// that pixel, so edges are highlighted with a thickness determined by the mask.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::morphology::{Mask, grayscale_morphological_gradient};
+///
+/// let image = gray_image!(
+///    10,  10,  10,  90,  90,  90;
+///    10,  10,  10,  90,  90,  90;
+///    10,  10,  10,  90,  90,  90
+/// );
+///
+/// let gradient = gray_image!(
+///     0,   0,  80,  80,   0,   0;
+///     0,   0,  80,  80,   0,   0;
+///     0,   0,  80,  80,   0,   0
+/// );
+///
+/// assert_pixels_eq!(grayscale_morphological_gradient(&image, &Mask::square(1)), gradient);
+/// # }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn grayscale_morphological_gradient(image: &GrayImage, mask: &Mask) -> GrayImage {
+    saturating_difference(
+        &grayscale_dilate(image, mask),
+        &grayscale_erode(image, mask),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::drawing::draw_filled_circle_mut;
+    use crate::region_labelling::connected_components;
 
     #[test]
     fn test_dilate_point_l1_0() {
@@ -1311,6 +1964,37 @@ mod tests {
         Mask::from_image(&mask_base, 5, 5);
     }
 
+    #[test]
+    fn test_mask_from_points_matches_from_image() {
+        let mask_base = gray_image!(
+              0, 255,   0;
+            255,   0,   0;
+              0,   0, 255
+        );
+        let points = [
+            Point::new(1, 1),
+            Point::new(0, -1),
+            Point::new(-1, 0),
+            Point::new(0, -1),
+        ];
+        assert_eq!(
+            Mask::from_points(&points),
+            Mask::from_image(&mask_base, 1, 1)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mask_from_points_out_of_bounds() {
+        let _ = Mask::from_points(&[Point::new(0, 512)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "mask offsets must be strictly between -512 and 512")]
+    fn test_mask_from_points_minimum_offset() {
+        let _ = Mask::from_points(&[Point::new(i16::MIN, 0)]);
+    }
+
     #[test]
     fn test_masks_0() {
         let mask_base = gray_image!(72);
@@ -1844,6 +2528,407 @@ mod tests {
         );
         assert_eq!(grayscale_erode(&image, &mask), dilated);
     }
+
+    #[test]
+    fn test_grayscale_top_hat_highlights_small_bright_features() {
+        // A smooth background with a small bright spot and a bright region much
+        // larger than the mask
+        let image = GrayImage::from_fn(30, 20, |x, y| {
+            let background = 40 + 2 * x as u8;
+            let in_spot = (6..8).contains(&x) && (9..11).contains(&y);
+            let in_region = x >= 18 && (4..16).contains(&y);
+            if in_spot || in_region {
+                Luma([background + 100])
+            } else {
+                Luma([background])
+            }
+        });
+
+        let top_hat = grayscale_white_tophat(&image, &Mask::square(2));
+
+        for y in 9..11 {
+            for x in 6..8 {
+                assert!(top_hat.get_pixel(x, y)[0] >= 90, "{:?}", top_hat);
+            }
+        }
+        for (x, y, p) in top_hat.enumerate_pixels() {
+            let in_spot = (5..9).contains(&x) && (8..12).contains(&y);
+            if !in_spot {
+                assert!(p[0] <= 8, "({}, {}): {}", x, y, p[0]);
+            }
+        }
+    }
+
+    // A slow illumination gradient with small bright spots at the given positions
+    // and small dark spots at the same positions mirrored horizontally
+    fn unevenly_lit_spots(spots: &[(u32, u32)]) -> GrayImage {
+        GrayImage::from_fn(60, 40, |x, y| {
+            let background = 60 + (x + y) as u8;
+            let bright = spots
+                .iter()
+                .any(|&(sx, sy)| x.abs_diff(sx) <= 1 && y.abs_diff(sy) <= 1);
+            let dark = spots
+                .iter()
+                .any(|&(sx, sy)| x.abs_diff(59 - sx) <= 1 && y.abs_diff(sy) <= 1);
+            if bright {
+                Luma([background + 80])
+            } else if dark {
+                Luma([background - 50])
+            } else {
+                Luma([background])
+            }
+        })
+    }
+
+    #[test]
+    fn test_grayscale_tophats_remove_illumination_gradient() {
+        let spots = [(8, 8), (20, 30), (12, 20)];
+        let image = unevenly_lit_spots(&spots);
+        let white = grayscale_white_tophat(&image, &Mask::square(3));
+        let black = grayscale_black_tophat(&image, &Mask::square(3));
+
+        for (x, y, p) in white.enumerate_pixels() {
+            let in_spot = spots
+                .iter()
+                .any(|&(sx, sy)| x.abs_diff(sx) <= 1 && y.abs_diff(sy) <= 1);
+            if in_spot {
+                assert!(p[0] >= 75, "({}, {}): {}", x, y, p[0]);
+            } else {
+                assert!(p[0] <= 6, "({}, {}): {}", x, y, p[0]);
+            }
+        }
+        for (x, y, p) in black.enumerate_pixels() {
+            let in_spot = spots
+                .iter()
+                .any(|&(sx, sy)| x.abs_diff(59 - sx) <= 1 && y.abs_diff(sy) <= 1);
+            if in_spot {
+                assert!(p[0] >= 45, "({}, {}): {}", x, y, p[0]);
+            } else {
+                assert!(p[0] <= 6, "({}, {}): {}", x, y, p[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_morphological_gradient_of_rectangle_is_ring() {
+        let (left, top, right, bottom) = (10i32, 8i32, 30i32, 22i32);
+        let image = GrayImage::from_fn(40, 30, |x, y| {
+            if (left..=right).contains(&(x as i32)) && (top..=bottom).contains(&(y as i32)) {
+                Luma([255])
+            } else {
+                Luma([0])
+            }
+        });
+
+        for k in 1..4 {
+            let gradient = morphological_gradient(&image, Norm::LInf, k);
+            let k = k as i32;
+            for (x, y, p) in gradient.enumerate_pixels() {
+                let (x, y) = (x as i32, y as i32);
+                // Distance outside the rectangle, or negative distance inside it
+                let outside = (left - x).max(x - right).max(top - y).max(y - bottom);
+                let in_ring = outside > -k && outside <= k;
+                assert_eq!(p[0] == 255, in_ring, "k = {}, ({}, {})", k, x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_grayscale_morphological_gradient_is_local_range() {
+        let image = gray_image!(
+             5,  9, 12;
+            30,  7, 40;
+             1, 17, 22
+        );
+        let gradient = grayscale_morphological_gradient(&image, &Mask::square(1));
+        assert_eq!(gradient.get_pixel(1, 1)[0], 39);
+        assert_eq!(gradient.get_pixel(0, 0)[0], 25);
+        assert_eq!(gradient.get_pixel(2, 2)[0], 33);
+    }
+
+    #[test]
+    fn test_thin_thick_bar_becomes_line() {
+        let image = GrayImage::from_fn(40, 15, |x, y| {
+            if (5..35).contains(&x) && (4..11).contains(&y) {
+                Luma([255])
+            } else {
+                Luma([0])
+            }
+        });
+        let thinned = thin(&image);
+
+        // Every column away from the ends of the bar contains exactly one pixel,
+        // all in the same row
+        let mut rows = Vec::new();
+        for x in 9..31 {
+            let column: Vec<u32> = (0..15)
+                .filter(|&y| thinned.get_pixel(x, y)[0] == 255)
+                .collect();
+            assert_eq!(column.len(), 1, "column {}: {:?}", x, column);
+            rows.push(column[0]);
+        }
+        assert!(rows.iter().all(|&y| y == rows[0]));
+        assert!(thinned.iter().all(|&p| p == 0 || p == 255));
+    }
+
+    #[test]
+    fn test_thin_ring_remains_connected() {
+        let image = GrayImage::from_fn(40, 40, |x, y| {
+            let (dx, dy) = (x as f32 - 19.5, y as f32 - 19.5);
+            let r = (dx * dx + dy * dy).sqrt();
+            if (9.0..15.0).contains(&r) {
+                Luma([255])
+            } else {
+                Luma([0])
+            }
+        });
+        let thinned = thin(&image);
+
+        let count_labels = |image: &GrayImage, conn, background| {
+            let labels = connected_components(image, conn, Luma([background]));
+            labels.iter().copied().max().unwrap_or(0)
+        };
+        // A single 8-connected foreground component surrounding a hole
+        assert_eq!(count_labels(&thinned, Connectivity::Eight, 0), 1);
+        assert_eq!(count_labels(&thinned, Connectivity::Four, 255), 2);
+
+        // The skeleton is much thinner than the ring
+        let ring_pixels = image.iter().filter(|&&p| p > 0).count();
+        let skeleton_pixels = thinned.iter().filter(|&&p| p > 0).count();
+        assert!(skeleton_pixels * 4 < ring_pixels);
+
+        // The ring is left unchanged by further thinning
+        assert_pixels_eq!(thin(&thinned), thinned);
+    }
+
+    #[test]
+    fn test_fill_holes_ignores_hole_opened_by_notch() {
+        let mut image = GrayImage::new(20, 20);
+        draw_filled_circle_mut(&mut image, (8, 10), 6, Luma([255]));
+        draw_filled_circle_mut(&mut image, (8, 10), 3, Luma([0]));
+        // A notch cut into the ring from the border
+        for y in 9..12 {
+            for x in 0..5 {
+                image.put_pixel(x, y, Luma([0]));
+            }
+        }
+        // A second ring, whose hole is not open to the border
+        for y in 2..9 {
+            for x in 14..19 {
+                let edge = x == 14 || x == 18 || y == 2 || y == 8;
+                image.put_pixel(x, y, Luma([if edge { 255 } else { 0 }]));
+            }
+        }
+
+        let filled = fill_holes(&image, Connectivity::Four);
+        // The hole in the second ring is filled
+        for y in 3..8 {
+            for x in 15..18 {
+                assert_eq!(filled.get_pixel(x, y)[0], 255);
+            }
+        }
+        // The hole in the first ring is connected to the notch, so remains open
+        assert_eq!(filled.get_pixel(8, 10)[0], 0);
+        assert_eq!(filled.get_pixel(2, 10)[0], 0);
+        // Foreground is unchanged
+        for (x, y, p) in image.enumerate_pixels() {
+            if p[0] != 0 {
+                assert_eq!(filled.get_pixel(x, y)[0], 255);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill_holes_fills_closed_ring() {
+        let mut image = GrayImage::new(20, 20);
+        draw_filled_circle_mut(&mut image, (10, 10), 6, Luma([255]));
+        draw_filled_circle_mut(&mut image, (10, 10), 3, Luma([0]));
+        let mut disk = GrayImage::new(20, 20);
+        draw_filled_circle_mut(&mut disk, (10, 10), 6, Luma([255]));
+
+        assert_pixels_eq!(fill_holes(&image, Connectivity::Four), disk);
+        assert_pixels_eq!(fill_holes(&image, Connectivity::Eight), disk);
+    }
+
+    #[test]
+    fn test_fill_holes_leaves_solid_blob_unchanged() {
+        let mut image = GrayImage::new(15, 12);
+        draw_filled_circle_mut(&mut image, (7, 6), 4, Luma([255]));
+        assert_pixels_eq!(fill_holes(&image, Connectivity::Four), image);
+        assert_pixels_eq!(fill_holes(&image, Connectivity::Eight), image);
+    }
+
+    #[test]
+    fn test_fill_holes_connectivity_of_diagonal_gaps() {
+        // The centre pixel is enclosed by a diamond which only 4-connected
+        // background cannot pass through
+        let image = gray_image!(
+              0,   0,   0,   0,   0;
+              0,   0, 255,   0,   0;
+              0, 255,   0, 255,   0;
+              0,   0, 255,   0,   0;
+              0,   0,   0,   0,   0
+        );
+        assert_eq!(
+            fill_holes(&image, Connectivity::Four).get_pixel(2, 2)[0],
+            255
+        );
+        assert_eq!(
+            fill_holes(&image, Connectivity::Eight).get_pixel(2, 2)[0],
+            0
+        );
+        assert_eq!(
+            fill_holes(&GrayImage::new(0, 3), Connectivity::Four).dimensions(),
+            (0, 3)
+        );
+    }
+
+    #[test]
+    fn test_remove_small_objects_removes_only_small_blobs() {
+        let mut image = GrayImage::new(40, 30);
+        // Squares with areas 4, 9, 16 and 25, and a single pixel
+        let squares = [(2, 2, 2), (8, 2, 3), (15, 2, 4), (22, 2, 5)];
+        for &(x0, y0, side) in &squares {
+            for y in y0..y0 + side {
+                for x in x0..x0 + side {
+                    image.put_pixel(x, y, Luma([200]));
+                }
+            }
+        }
+        image.put_pixel(35, 20, Luma([17]));
+
+        let cleaned = remove_small_objects(&image, 9, Connectivity::Eight);
+        let (_, stats) = connected_components_with_stats(&cleaned, Connectivity::Eight, Luma([0]));
+        let areas: Vec<u32> = stats.iter().map(|s| s.area).collect();
+        assert_eq!(areas, vec![9, 16, 25]);
+        for &(x0, y0, side) in &squares[1..] {
+            assert_eq!(cleaned.get_pixel(x0, y0)[0], 255);
+            assert_eq!(cleaned.get_pixel(x0 + side - 1, y0 + side - 1)[0], 255);
+        }
+        assert_eq!(cleaned.get_pixel(2, 2)[0], 0);
+        assert_eq!(cleaned.get_pixel(35, 20)[0], 0);
+
+        assert!(remove_small_objects(&image, 26, Connectivity::Eight)
+            .iter()
+            .all(|&p| p == 0));
+    }
+
+    #[test]
+    fn test_remove_small_objects_respects_connectivity() {
+        // Two diagonally adjacent pixels form one object only with 8-connectivity
+        let image = gray_image!(
+            255,   0,   0;
+              0, 255,   0;
+              0,   0,   0
+        );
+        assert_pixels_eq!(remove_small_objects(&image, 2, Connectivity::Eight), image);
+        assert!(remove_small_objects(&image, 2, Connectivity::Four)
+            .iter()
+            .all(|&p| p == 0));
+    }
+
+    #[test]
+    fn test_remove_small_holes_fills_only_small_enclosed_holes() {
+        let mut image = GrayImage::from_pixel(30, 20, Luma([255]));
+        // Holes with areas 1, 4 and 12, and a background region touching the border
+        image.put_pixel(3, 3, Luma([0]));
+        for (x0, y0, w, h) in [(8, 3, 2, 2), (14, 3, 4, 3), (25, 0, 2, 2)] {
+            for y in y0..y0 + h {
+                for x in x0..x0 + w {
+                    image.put_pixel(x, y, Luma([0]));
+                }
+            }
+        }
+
+        let filled = remove_small_holes(&image, 4, Connectivity::Four);
+        assert_eq!(filled.get_pixel(3, 3)[0], 255);
+        assert_eq!(filled.get_pixel(9, 4)[0], 255);
+        assert_eq!(filled.get_pixel(15, 4)[0], 0);
+        assert_eq!(filled.get_pixel(25, 0)[0], 0);
+
+        assert_pixels_eq!(
+            remove_small_holes(&image, 100, Connectivity::Four),
+            fill_holes(&image, Connectivity::Four)
+        );
+    }
+
+    #[test]
+    fn test_hit_or_miss_finds_convex_corners_of_square() {
+        let mut image = GrayImage::new(12, 10);
+        for y in 2..8 {
+            for x in 3..9 {
+                image.put_pixel(x, y, Luma([200]));
+            }
+        }
+
+        // A top-left corner has foreground to its right and below, and background
+        // on the other three sides. Other corners are rotations of this pattern.
+        let corner_patterns = [(1, 1), (-1, 1), (1, -1), (-1, -1)];
+        let mut corners = Vec::new();
+        for &(sx, sy) in &corner_patterns {
+            let foreground =
+                Mask::from_points(&[Point::new(0, 0), Point::new(sx, 0), Point::new(0, sy)]);
+            let background =
+                Mask::from_points(&[Point::new(-sx, 0), Point::new(0, -sy), Point::new(-sx, -sy)]);
+            let matches = hit_or_miss(&image, &foreground, &background);
+            for (x, y, p) in matches.enumerate_pixels() {
+                if p[0] == 255 {
+                    corners.push((x, y));
+                }
+            }
+        }
+        corners.sort();
+        assert_eq!(corners, vec![(3, 2), (3, 7), (8, 2), (8, 7)]);
+    }
+
+    #[test]
+    fn test_hit_or_miss_treats_outside_as_background() {
+        let image = GrayImage::from_pixel(3, 3, Luma([255]));
+        let foreground = Mask::from_points(&[Point::new(0, 0)]);
+        let background = Mask::from_points(&[Point::new(-1, 0)]);
+        let expected = gray_image!(
+            255, 0, 0;
+            255, 0, 0;
+            255, 0, 0
+        );
+        assert_pixels_eq!(hit_or_miss(&image, &foreground, &background), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hit_or_miss_rejects_overlapping_masks() {
+        let image = GrayImage::new(3, 3);
+        let _ = hit_or_miss(&image, &Mask::square(1), &Mask::diamond(1));
+    }
+
+    #[test]
+    fn test_binary_tophats() {
+        let spot_and_block = gray_image!(
+              0,   0,   0,   0,   0,   0,   0;
+              0, 255,   0,   0,   0,   0,   0;
+              0,   0,   0, 255, 255, 255, 255;
+              0,   0,   0, 255, 255, 255, 255;
+              0,   0,   0, 255, 255, 255, 255;
+              0,   0,   0, 255, 255, 255, 255
+        );
+        let spot = gray_image!(
+              0,   0,   0,   0,   0,   0,   0;
+              0, 255,   0,   0,   0,   0,   0;
+              0,   0,   0,   0,   0,   0,   0;
+              0,   0,   0,   0,   0,   0,   0;
+              0,   0,   0,   0,   0,   0,   0;
+              0,   0,   0,   0,   0,   0,   0
+        );
+        assert_pixels_eq!(white_tophat(&spot_and_block, Norm::LInf, 1), spot);
+
+        let mut holey = GrayImage::from_pixel(7, 6, Luma([255]));
+        holey.put_pixel(1, 1, Luma([0]));
+        holey.put_pixel(4, 3, Luma([0]));
+        let mut holes = GrayImage::new(7, 6);
+        holes.put_pixel(1, 1, Luma([255]));
+        holes.put_pixel(4, 3, Luma([255]));
+        assert_pixels_eq!(black_tophat(&holey, Norm::LInf, 1), holes);
+    }
 }
 
 #[cfg(not(miri))]
@@ -3,7 +3,7 @@
 use crate::definitions::{Clamp, HasBlack, HasWhite, Image};
 use image::Pixel;
 use rand::{rngs::StdRng, SeedableRng};
-use rand_distr::{Distribution, Normal, Uniform};
+use rand_distr::{Distribution, Normal, Poisson, Uniform};
 
 /// Adds independent additive Gaussian noise to all channels
 /// of an image, with the given mean and standard deviation.
@@ -60,11 +60,238 @@ where
     }
 }
 
+/// Adds independent multiplicative noise to all channels of an image, replacing each
+/// subpixel `p` by `p * (1 + n)`, where `n` is drawn from a Gaussian distribution with
+/// zero mean and the given `variance`.
+///
+/// The strength of speckle noise is proportional to intensity, so black pixels are
+/// left unchanged.
+///
+/// # Panics
+///
+/// If `variance` is negative.
+pub fn speckle_noise<P>(image: &Image<P>, variance: f64, seed: u64) -> Image<P>
+where
+    P: Pixel,
+    P::Subpixel: Into<f64> + Clamp<f64>,
+{
+    let mut out = image.clone();
+    speckle_noise_mut(&mut out, variance, seed);
+    out
+}
+#[doc=generate_mut_doc_comment!("speckle_noise")]
+pub fn speckle_noise_mut<P>(image: &mut Image<P>, variance: f64, seed: u64)
+where
+    P: Pixel,
+    P::Subpixel: Into<f64> + Clamp<f64>,
+{
+    assert!(variance >= 0.0, "variance must be non-negative");
+    let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+    let normal = Normal::new(0.0, variance.sqrt()).unwrap();
+
+    for p in image.pixels_mut() {
+        for c in p.channels_mut() {
+            let noise = normal.sample(&mut rng);
+            *c = P::Subpixel::clamp((*c).into() * (1.0 + noise));
+        }
+    }
+}
+
+/// Adds Poisson-distributed shot noise to all channels of an image.
+///
+/// Each subpixel `p` is treated as the expected photon count `p * scale` of a Poisson
+/// process, and replaced by a sample from this distribution divided by `scale`. The
+/// variance of the result is therefore `p / scale`, growing with intensity, and
+/// smaller values of `scale` simulate images captured in lower light.
+///
+/// # Panics
+///
+/// If `scale` is not positive.
+pub fn poisson_noise<P>(image: &Image<P>, scale: f64, seed: u64) -> Image<P>
+where
+    P: Pixel,
+    P::Subpixel: Into<f64> + Clamp<f64>,
+{
+    let mut out = image.clone();
+    poisson_noise_mut(&mut out, scale, seed);
+    out
+}
+#[doc=generate_mut_doc_comment!("poisson_noise")]
+pub fn poisson_noise_mut<P>(image: &mut Image<P>, scale: f64, seed: u64)
+where
+    P: Pixel,
+    P::Subpixel: Into<f64> + Clamp<f64>,
+{
+    assert!(scale > 0.0, "scale must be positive");
+    let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+
+    for p in image.pixels_mut() {
+        for c in p.channels_mut() {
+            let rate = (*c).into() * scale;
+            if rate > 0.0 {
+                let count: f64 = Poisson::new(rate).unwrap().sample(&mut rng);
+                *c = P::Subpixel::clamp(count / scale);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::median_filter;
+    use image::{GrayImage, Luma};
+
+    fn count_differences(a: &GrayImage, b: &GrayImage) -> usize {
+        a.iter().zip(b.iter()).filter(|(p, q)| p != q).count()
+    }
+
+    #[test]
+    fn test_salt_and_pepper_noise_corrupts_expected_fraction() {
+        let image = GrayImage::from_pixel(100, 100, Luma([128]));
+        for &rate in &[0.05, 0.2, 0.5] {
+            let noisy = salt_and_pepper_noise(&image, rate, 7);
+            let corrupted = count_differences(&image, &noisy) as f64 / 10_000.0;
+            assert!((corrupted - rate).abs() < 0.02, "{} vs {}", corrupted, rate);
+            assert!(noisy.iter().all(|&p| p == 0 || p == 128 || p == 255));
+
+            let salt = noisy.iter().filter(|&&p| p == 255).count() as f64;
+            let pepper = noisy.iter().filter(|&&p| p == 0).count() as f64;
+            assert!((salt - pepper).abs() / (salt + pepper) < 0.15);
+        }
+    }
+
+    #[test]
+    fn test_salt_and_pepper_noise_is_removed_by_median_filter() {
+        let image = GrayImage::from_fn(60, 60, |x, y| {
+            Luma([if x < 30 { 60 } else { 180 } + (y % 5) as u8])
+        });
+        let noisy = salt_and_pepper_noise(&image, 0.1, 3);
+        let filtered = median_filter(&noisy, 1, 1);
+
+        let before = count_differences(&image, &noisy);
+        let remaining = filtered
+            .iter()
+            .zip(image.iter())
+            .filter(|(&p, &q)| (p as i32 - q as i32).abs() > 5)
+            .count();
+        assert!(
+            remaining * 20 < before,
+            "{} of {} remain",
+            remaining,
+            before
+        );
+    }
+
+    #[test]
+    fn test_noise_is_deterministic_given_seed() {
+        let image = GrayImage::from_fn(30, 20, |x, y| Luma([(x * 5 + y * 3) as u8]));
+        assert_pixels_eq!(
+            salt_and_pepper_noise(&image, 0.3, 11),
+            salt_and_pepper_noise(&image, 0.3, 11)
+        );
+        assert_pixels_eq!(
+            speckle_noise(&image, 0.05, 11),
+            speckle_noise(&image, 0.05, 11)
+        );
+        assert!(
+            count_differences(
+                &speckle_noise(&image, 0.05, 11),
+                &speckle_noise(&image, 0.05, 12)
+            ) > 0
+        );
+    }
+
+    #[test]
+    fn test_speckle_noise_with_zero_variance_is_identity() {
+        let image = GrayImage::from_fn(30, 20, |x, y| Luma([(x * 5 + y * 3) as u8]));
+        assert_pixels_eq!(speckle_noise(&image, 0.0, 1), image);
+    }
+
+    #[test]
+    fn test_speckle_noise_scales_with_intensity() {
+        let mut image = GrayImage::from_pixel(100, 100, Luma([0]));
+        for y in 50..100 {
+            for x in 0..100 {
+                image.put_pixel(x, y, Luma([100]));
+            }
+        }
+        let noisy = speckle_noise(&image, 0.01, 5);
+        assert!((0..50).all(|y| (0..100).all(|x| noisy.get_pixel(x, y)[0] == 0)));
+
+        let bright: Vec<f64> = (50..100)
+            .flat_map(|y| (0..100).map(move |x| (x, y)))
+            .map(|(x, y)| noisy.get_pixel(x, y)[0] as f64)
+            .collect();
+        let mean = bright.iter().sum::<f64>() / bright.len() as f64;
+        let variance = bright.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / bright.len() as f64;
+        // A variance of 0.01 gives a standard deviation of 10% of the intensity
+        assert!((mean - 100.0).abs() < 1.0, "mean {}", mean);
+        assert!(
+            (variance.sqrt() - 10.0).abs() < 1.0,
+            "stddev {}",
+            variance.sqrt()
+        );
+    }
+
+    #[test]
+    fn test_poisson_noise_variance_grows_with_intensity() {
+        let image = GrayImage::from_fn(100, 100, |x, _| Luma([if x < 50 { 10 } else { 200 }]));
+        let noisy = poisson_noise(&image, 1.0, 9);
+
+        let variance_of_half = |right: bool| {
+            let values: Vec<f64> = noisy
+                .enumerate_pixels()
+                .filter(|(x, _, _)| (*x >= 50) == right)
+                .map(|(_, _, p)| p[0] as f64)
+                .collect();
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+        };
+        let dark = variance_of_half(false);
+        let bright = variance_of_half(true);
+        // With a scale of 1 the variance equals the intensity
+        assert!((dark - 10.0).abs() < 2.0, "dark variance {}", dark);
+        assert!((bright - 200.0).abs() < 30.0, "bright variance {}", bright);
+    }
+
+    #[test]
+    fn test_poisson_noise_decreases_with_scale() {
+        let image = GrayImage::from_pixel(50, 50, Luma([100]));
+        let error = |scale: f64| {
+            poisson_noise(&image, scale, 4)
+                .iter()
+                .map(|&p| (p as f64 - 100.0).powi(2))
+                .sum::<f64>()
+        };
+        assert!(error(10.0) * 5.0 < error(1.0));
+    }
+
+    #[test]
+    fn test_poisson_noise_is_deterministic_given_seed() {
+        let image = GrayImage::from_fn(30, 20, |x, y| Luma([(x * 5 + y * 3) as u8]));
+        assert_pixels_eq!(poisson_noise(&image, 0.5, 2), poisson_noise(&image, 0.5, 2));
+        assert_eq!(poisson_noise(&image, 0.5, 2).get_pixel(0, 0)[0], 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_poisson_noise_rejects_non_positive_scale() {
+        let _ = poisson_noise(&GrayImage::new(2, 2), 0.0, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_speckle_noise_rejects_negative_variance() {
+        let _ = speckle_noise(&GrayImage::new(2, 2), -1.0, 1);
+    }
+}
+
 #[cfg(not(miri))]
 #[cfg(test)]
 mod benches {
     use super::*;
-    use image::GrayImage;
+    use image::{GrayImage, Luma};
     use test::{black_box, Bencher};
 
     #[bench]
@@ -84,4 +311,22 @@ mod benches {
         });
         black_box(image);
     }
+
+    #[bench]
+    fn bench_speckle_noise_mut(b: &mut Bencher) {
+        let mut image = GrayImage::from_pixel(100, 100, Luma([128]));
+        b.iter(|| {
+            speckle_noise_mut(&mut image, 0.05, 1);
+        });
+        black_box(image);
+    }
+
+    #[bench]
+    fn bench_poisson_noise_mut(b: &mut Bencher) {
+        let mut image = GrayImage::from_pixel(100, 100, Luma([128]));
+        b.iter(|| {
+            poisson_noise_mut(&mut image, 1.0, 1);
+        });
+        black_box(image);
+    }
 }
@@ -0,0 +1,865 @@
+//! Functions for estimating the motion of image content between two frames.
+//!
+//! [`lucas_kanade_flow`] tracks a sparse set of points, and [`farneback_flow`] estimates
+//! the motion of every pixel. Dense flow fields can be visualised using [`colorize_flow`] or
+//! [`draw_flow_field`](crate::drawing::draw_flow_field).
+
+use crate::colorspace::vector_field_to_rgb;
+use crate::definitions::Image;
+use crate::filter::box_mean;
+use crate::map::map_subpixels;
+use crate::point::Point;
+use crate::pyramid::gaussian_pyramid;
+use image::{GrayImage, Luma, RgbImage};
+use nalgebra::{SMatrix, SVector};
+
+/// The number of pyramid levels used by [`lucas_kanade_flow`], including the original image.
+const LUCAS_KANADE_PYRAMID_LEVELS: u32 = 4;
+
+/// The smallest mean squared gradient over a tracking window, in squared intensity units, for
+/// which the window is considered to contain enough texture to be tracked.
+const LUCAS_KANADE_MIN_EIGENVALUE: f32 = 1e-2;
+
+/// The result of tracking a single point with [`lucas_kanade_flow`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FlowResult {
+    /// The estimated position of the point in the second frame. If the point was not found
+    /// then this is the best estimate reached before tracking failed, or the original
+    /// position if tracking could not be started.
+    pub point: Point<f32>,
+    /// Whether the point was successfully tracked.
+    pub found: bool,
+}
+
+/// Tracks points from `prev` to `next` using the pyramidal iterative Lucas-Kanade method.
+///
+/// Each point is tracked by finding the displacement that best aligns a square window of side
+/// length `2 * window_radius + 1` centred on the point in `prev` with the corresponding window
+/// in `next`. The displacement is first estimated on heavily downsampled copies of the images
+/// computed by [`gaussian_pyramid`] and then refined on each finer level in turn, which allows
+/// displacements larger than the window to be recovered. On each level at most `max_iters`
+/// Gauss-Newton steps are taken, stopping early once a step is shorter than `epsilon` pixels.
+///
+/// Pixels outside the images are treated as having the intensity of the nearest pixel inside.
+///
+/// A point is reported as not found if it does not lie within `prev`, if the window around it
+/// in the original image has too little texture for its motion to be determined, or if its
+/// estimated position does not lie within `next`.
+///
+/// Returns one result per input point, in the same order.
+///
+/// # Panics
+///
+/// If `prev` and `next` do not have the same dimensions.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::optical_flow::lucas_kanade_flow;
+/// use imageproc::point::Point;
+///
+/// let texture = |x: f32, y: f32| 128.0 + 60.0 * (x / 5.0).sin() * (y / 7.0).cos();
+/// let prev = GrayImage::from_fn(64, 64, |x, y| Luma([texture(x as f32, y as f32) as u8]));
+/// // The content of next is moved 2 pixels right and 1 pixel down
+/// let next = GrayImage::from_fn(64, 64, |x, y| {
+///     Luma([texture(x as f32 - 2.0, y as f32 - 1.0) as u8])
+/// });
+///
+/// let flow = lucas_kanade_flow(&prev, &next, &[Point::new(30.0, 35.0)], 7, 20, 0.01);
+///
+/// assert!(flow[0].found);
+/// assert!((flow[0].point.x - 32.0).abs() < 0.25);
+/// assert!((flow[0].point.y - 36.0).abs() < 0.25);
+/// ```
+pub fn lucas_kanade_flow(
+    prev: &GrayImage,
+    next: &GrayImage,
+    points: &[Point<f32>],
+    window_radius: u32,
+    max_iters: u32,
+    epsilon: f32,
+) -> Vec<FlowResult> {
+    assert_eq!(
+        prev.dimensions(),
+        next.dimensions(),
+        "prev and next must have the same dimensions"
+    );
+
+    let to_f32_pyramid = |image: &GrayImage| -> Vec<Image<Luma<f32>>> {
+        gaussian_pyramid(image, LUCAS_KANADE_PYRAMID_LEVELS)
+            .iter()
+            .map(|level| map_subpixels(level, f32::from))
+            .collect()
+    };
+    let prev_pyramid = to_f32_pyramid(prev);
+    let next_pyramid = to_f32_pyramid(next);
+
+    points
+        .iter()
+        .map(|&point| {
+            track_point(
+                &prev_pyramid,
+                &next_pyramid,
+                point,
+                window_radius,
+                max_iters,
+                epsilon,
+            )
+        })
+        .collect()
+}
+
+fn track_point(
+    prev_pyramid: &[Image<Luma<f32>>],
+    next_pyramid: &[Image<Luma<f32>>],
+    point: Point<f32>,
+    window_radius: u32,
+    max_iters: u32,
+    epsilon: f32,
+) -> FlowResult {
+    let (width, height) = prev_pyramid[0].dimensions();
+    let in_bounds = |p: Point<f32>| {
+        p.x >= 0.0 && p.y >= 0.0 && p.x <= width as f32 - 1.0 && p.y <= height as f32 - 1.0
+    };
+    if !in_bounds(point) {
+        return FlowResult {
+            point,
+            found: false,
+        };
+    }
+
+    let r = window_radius as i32;
+    let mut window = Vec::with_capacity(((2 * r + 1) * (2 * r + 1)) as usize);
+    // Displacement estimate at the current level
+    let mut guess = (0.0f32, 0.0f32);
+
+    for level in (0..prev_pyramid.len()).rev() {
+        let (prev_level, next_level) = (&prev_pyramid[level], &next_pyramid[level]);
+        // Pixel i of a pyramid level lies at pixel 2i of the level below it
+        let scale = 0.5f32.powi(level as i32);
+        let (px, py) = (point.x * scale, point.y * scale);
+
+        // The window in prev and its spatial gradients, and the matrix of the normal equations
+        window.clear();
+        let (mut gxx, mut gxy, mut gyy) = (0.0f32, 0.0f32, 0.0f32);
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let (x, y) = (px + dx as f32, py + dy as f32);
+                let ix = 0.5 * (sample(prev_level, x + 1.0, y) - sample(prev_level, x - 1.0, y));
+                let iy = 0.5 * (sample(prev_level, x, y + 1.0) - sample(prev_level, x, y - 1.0));
+                window.push((x, y, sample(prev_level, x, y), ix, iy));
+                gxx += ix * ix;
+                gxy += ix * iy;
+                gyy += iy * iy;
+            }
+        }
+
+        let min_eigenvalue = 0.5 * (gxx + gyy - ((gxx - gyy).powi(2) + 4.0 * gxy * gxy).sqrt());
+        let det = gxx * gyy - gxy * gxy;
+        let trackable = min_eigenvalue / window.len() as f32 >= LUCAS_KANADE_MIN_EIGENVALUE
+            && det > f32::EPSILON;
+
+        let mut flow = (0.0f32, 0.0f32);
+        if trackable {
+            for _ in 0..max_iters {
+                let (mut bx, mut by) = (0.0f32, 0.0f32);
+                for &(x, y, i, ix, iy) in &window {
+                    let j = sample(next_level, x + guess.0 + flow.0, y + guess.1 + flow.1);
+                    bx += (i - j) * ix;
+                    by += (i - j) * iy;
+                }
+                let step = ((gyy * bx - gxy * by) / det, (gxx * by - gxy * bx) / det);
+                flow = (flow.0 + step.0, flow.1 + step.1);
+                if step.0 * step.0 + step.1 * step.1 < epsilon * epsilon {
+                    break;
+                }
+            }
+        } else if level == 0 {
+            // Coarser levels may lose the texture of small features, but if the original
+            // image has none then the motion of this point cannot be determined
+            return FlowResult {
+                point: Point::new(point.x + guess.0, point.y + guess.1),
+                found: false,
+            };
+        }
+
+        guess = (guess.0 + flow.0, guess.1 + flow.1);
+        if level > 0 {
+            guess = (2.0 * guess.0, 2.0 * guess.1);
+        }
+    }
+
+    let tracked = Point::new(point.x + guess.0, point.y + guess.1);
+    FlowResult {
+        point: tracked,
+        found: tracked.x.is_finite() && tracked.y.is_finite() && in_bounds(tracked),
+    }
+}
+
+// Bilinearly interpolates image at (x, y), clamping coordinates to the image.
+fn sample(image: &Image<Luma<f32>>, x: f32, y: f32) -> f32 {
+    let (width, height) = image.dimensions();
+    let x = x.clamp(0.0, (width - 1) as f32);
+    let y = y.clamp(0.0, (height - 1) as f32);
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+    let at = |x, y| image.get_pixel(x, y)[0];
+    let top = at(x0, y0) + fx * (at(x1, y0) - at(x0, y0));
+    let bottom = at(x0, y1) + fx * (at(x1, y1) - at(x0, y1));
+    top + fy * (bottom - top)
+}
+
+/// Options controlling [`farneback_flow`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FarnebackOptions {
+    /// The number of pyramid levels to use, including the original images. Defaults to 3.
+    ///
+    /// Each level halves the size of the images, and so roughly doubles the largest
+    /// displacement that can be recovered.
+    pub pyramid_levels: u32,
+    /// The radius of the square window over which the displacement of each pixel is
+    /// estimated. Larger windows are more robust to noise but blur the flow field.
+    /// Defaults to 7.
+    pub window_radius: u32,
+    /// The number of refinement iterations performed on each pyramid level. Defaults to 3.
+    pub iterations: u32,
+    /// The radius of the neighbourhood used to fit a quadratic polynomial to the image
+    /// around each pixel. Defaults to 2.
+    pub poly_radius: u32,
+    /// The standard deviation of the Gaussian weighting used when fitting polynomials.
+    /// Must be at least 0.5, as smaller values give almost no weight to any pixel other
+    /// than the centre of the neighbourhood. Defaults to 1.1.
+    pub poly_sigma: f32,
+}
+
+impl Default for FarnebackOptions {
+    fn default() -> Self {
+        FarnebackOptions {
+            pyramid_levels: 3,
+            window_radius: 7,
+            iterations: 3,
+            poly_radius: 2,
+            poly_sigma: 1.1,
+        }
+    }
+}
+
+/// Computes the dense optical flow from `prev` to `next` using the method described in
+/// "Two-Frame Motion Estimation Based on Polynomial Expansion" by Gunnar Farnebäck.
+///
+/// The neighbourhood of each pixel in each image is approximated by a quadratic polynomial.
+/// If `next` is `prev` displaced by `d` then the polynomial coefficients of the two images
+/// are related by a linear equation in `d`, which is solved in the least squares sense over
+/// a window around each pixel. Estimates are computed from coarse to fine over a
+/// [`gaussian_pyramid`] of each image, with the flow from each level used as the initial
+/// estimate for the next.
+///
+/// Returns the horizontal and vertical components of the flow field. The pixel at `(x, y)`
+/// in `prev` is estimated to be at `(x + flow_x(x, y), y + flow_y(x, y))` in `next`.
+///
+/// # Panics
+///
+/// If `prev` and `next` do not have the same dimensions, `options.poly_sigma < 0.5`, or
+/// `options.poly_radius` is 0.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::optical_flow::{farneback_flow, FarnebackOptions};
+///
+/// let texture = |x: f32, y: f32| 128.0 + 60.0 * (x / 5.0).sin() * (y / 7.0).cos();
+/// let prev = GrayImage::from_fn(64, 64, |x, y| Luma([texture(x as f32, y as f32) as u8]));
+/// // The content of next is moved 1 pixel right and 2 pixels up
+/// let next = GrayImage::from_fn(64, 64, |x, y| {
+///     Luma([texture(x as f32 - 1.0, y as f32 + 2.0) as u8])
+/// });
+///
+/// let (flow_x, flow_y) = farneback_flow(&prev, &next, &FarnebackOptions::default());
+///
+/// assert!((flow_x.get_pixel(32, 32)[0] - 1.0).abs() < 0.25);
+/// assert!((flow_y.get_pixel(32, 32)[0] + 2.0).abs() < 0.25);
+/// ```
+pub fn farneback_flow(
+    prev: &GrayImage,
+    next: &GrayImage,
+    options: &FarnebackOptions,
+) -> (Image<Luma<f32>>, Image<Luma<f32>>) {
+    assert_eq!(
+        prev.dimensions(),
+        next.dimensions(),
+        "prev and next must have the same dimensions"
+    );
+    assert!(
+        options.poly_sigma >= MIN_POLY_SIGMA,
+        "poly_sigma must be >= {}",
+        MIN_POLY_SIGMA
+    );
+    assert!(options.poly_radius > 0, "poly_radius must be > 0");
+
+    let (width, height) = prev.dimensions();
+    if width == 0 || height == 0 {
+        return (Image::new(width, height), Image::new(width, height));
+    }
+
+    let expansion = PolynomialExpansion::new(options.poly_radius, options.poly_sigma);
+    let prev_pyramid = gaussian_pyramid(prev, options.pyramid_levels.max(1));
+    let next_pyramid = gaussian_pyramid(next, options.pyramid_levels.max(1));
+
+    let coarsest = prev_pyramid.len() - 1;
+    let (mut flow_x, mut flow_y) = (Image::new(0, 0), Image::new(0, 0));
+    for level in (0..=coarsest).rev() {
+        let (prev_level, next_level) = (&prev_pyramid[level], &next_pyramid[level]);
+        let (w, h) = prev_level.dimensions();
+        // The initial estimate is the flow from the coarser level, doubled. Pixel i of the
+        // coarser level lies at pixel 2i of this level
+        let mut estimate: Vec<(f64, f64)> = if level == coarsest {
+            vec![(0.0, 0.0); (w * h) as usize]
+        } else {
+            let (coarse_x, coarse_y) = (&flow_x, &flow_y);
+            (0..h)
+                .flat_map(|y| {
+                    (0..w).map(move |x| {
+                        let (cx, cy) = (0.5 * x as f32, 0.5 * y as f32);
+                        (
+                            2.0 * sample(coarse_x, cx, cy) as f64,
+                            2.0 * sample(coarse_y, cx, cy) as f64,
+                        )
+                    })
+                })
+                .collect()
+        };
+
+        let prev_coefficients = expansion.expand(prev_level);
+        let next_coefficients = expansion.expand(next_level);
+        for _ in 0..options.iterations {
+            estimate = refine_flow(
+                &prev_coefficients,
+                &next_coefficients,
+                &estimate,
+                w as usize,
+                h as usize,
+                options.window_radius as usize,
+            );
+        }
+
+        flow_x = Image::from_fn(w, h, |x, y| Luma([estimate[(y * w + x) as usize].0 as f32]));
+        flow_y = Image::from_fn(w, h, |x, y| Luma([estimate[(y * w + x) as usize].1 as f32]));
+    }
+    (flow_x, flow_y)
+}
+
+// The coefficients of the quadratic polynomial fitted around a pixel:
+// f(x, y) ~ c + b[0] x + b[1] y + a[0] x^2 + a[1] y^2 + a[2] xy.
+#[derive(Copy, Clone)]
+struct Quadratic {
+    b: [f64; 2],
+    a: [f64; 3],
+}
+
+// The smallest supported value of FarnebackOptions::poly_sigma. Below this the weights of
+// all but the central pixel vanish and the polynomial fit is numerically degenerate.
+const MIN_POLY_SIGMA: f32 = 0.5;
+
+// Fits quadratic polynomials to image neighbourhoods by weighted least squares.
+struct PolynomialExpansion {
+    // For each offset in the neighbourhood, the contribution of its intensity to each
+    // polynomial coefficient
+    filters: Vec<(i32, i32, [f64; 6])>,
+}
+
+impl PolynomialExpansion {
+    // Requires radius > 0 and sigma >= MIN_POLY_SIGMA.
+    fn new(radius: u32, sigma: f32) -> PolynomialExpansion {
+        let radius = radius as i32;
+        let sigma = sigma as f64;
+        let basis = |dx: i32, dy: i32| {
+            let (x, y) = (dx as f64, dy as f64);
+            SVector::<f64, 6>::from([1.0, x, y, x * x, y * y, x * y])
+        };
+        let weight =
+            |dx: i32, dy: i32| (-((dx * dx + dy * dy) as f64) / (2.0 * sigma * sigma)).exp();
+
+        let mut gram = SMatrix::<f64, 6, 6>::zeros();
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let b = basis(dx, dy);
+                gram += weight(dx, dy) * b * b.transpose();
+            }
+        }
+        // The basis functions are linearly independent on any neighbourhood of radius 1 or
+        // more, and sigma is large enough that every pixel in the 3x3 centre of the
+        // neighbourhood has a significant weight, so the Gram matrix is well conditioned
+        let inverse = gram
+            .try_inverse()
+            .expect("the Gram matrix of the polynomial basis is invertible");
+
+        let mut filters = vec![];
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let f = inverse * (weight(dx, dy) * basis(dx, dy));
+                filters.push((dx, dy, [f[0], f[1], f[2], f[3], f[4], f[5]]));
+            }
+        }
+        PolynomialExpansion { filters }
+    }
+
+    // Returns the polynomial coefficients at each pixel, in row-major order, padding
+    // the image by continuity.
+    fn expand(&self, image: &GrayImage) -> Vec<Quadratic> {
+        let (width, height) = (image.width() as i32, image.height() as i32);
+        let mut coefficients = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let mut r = [0f64; 6];
+                for &(dx, dy, ref filter) in &self.filters {
+                    let px = (x + dx).clamp(0, width - 1) as u32;
+                    let py = (y + dy).clamp(0, height - 1) as u32;
+                    let value = image.get_pixel(px, py)[0] as f64;
+                    for (r, f) in r.iter_mut().zip(filter) {
+                        *r += f * value;
+                    }
+                }
+                coefficients.push(Quadratic {
+                    b: [r[1], r[2]],
+                    a: [r[3], r[4], r[5]],
+                });
+            }
+        }
+        coefficients
+    }
+}
+
+// Performs one Farnebäck update of the flow estimate for every pixel.
+fn refine_flow(
+    prev: &[Quadratic],
+    next: &[Quadratic],
+    estimate: &[(f64, f64)],
+    width: usize,
+    height: usize,
+    window_radius: usize,
+) -> Vec<(f64, f64)> {
+    let len = width * height;
+    let (mut g11, mut g12, mut g22) = (vec![0f64; len], vec![0f64; len], vec![0f64; len]);
+    let (mut h1, mut h2) = (vec![0f64; len], vec![0f64; len]);
+
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let (dx, dy) = estimate[i];
+            let p = &prev[i];
+            let q = interpolate_quadratic(next, width, height, x as f64 + dx, y as f64 + dy);
+
+            // f(x) = x^T A x + b^T x + c, so the off-diagonal entry of A is half of a[2]
+            let a11 = 0.5 * (p.a[0] + q.a[0]);
+            let a22 = 0.5 * (p.a[1] + q.a[1]);
+            let a12 = 0.25 * (p.a[2] + q.a[2]);
+            let db1 = -0.5 * (q.b[0] - p.b[0]) + a11 * dx + a12 * dy;
+            let db2 = -0.5 * (q.b[1] - p.b[1]) + a12 * dx + a22 * dy;
+
+            // Terms of the normal equations (A^T A) d = A^T db
+            g11[i] = a11 * a11 + a12 * a12;
+            g12[i] = a12 * (a11 + a22);
+            g22[i] = a12 * a12 + a22 * a22;
+            h1[i] = a11 * db1 + a12 * db2;
+            h2[i] = a12 * db1 + a22 * db2;
+        }
+    }
+
+    let g11 = box_mean(&g11, width, height, window_radius);
+    let g12 = box_mean(&g12, width, height, window_radius);
+    let g22 = box_mean(&g22, width, height, window_radius);
+    let h1 = box_mean(&h1, width, height, window_radius);
+    let h2 = box_mean(&h2, width, height, window_radius);
+
+    (0..len)
+        .map(|i| {
+            let det = g11[i] * g22[i] - g12[i] * g12[i];
+            if det.abs() < 1e-12 {
+                // The motion of an untextured region cannot be determined
+                estimate[i]
+            } else {
+                (
+                    (g22[i] * h1[i] - g12[i] * h2[i]) / det,
+                    (g11[i] * h2[i] - g12[i] * h1[i]) / det,
+                )
+            }
+        })
+        .collect()
+}
+
+// Bilinearly interpolates polynomial coefficients at (x, y), clamping coordinates to the image.
+fn interpolate_quadratic(
+    coefficients: &[Quadratic],
+    width: usize,
+    height: usize,
+    x: f64,
+    y: f64,
+) -> Quadratic {
+    let x = x.clamp(0.0, (width - 1) as f64);
+    let y = y.clamp(0.0, (height - 1) as f64);
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+    let (fx, fy) = (x - x0 as f64, y - y0 as f64);
+
+    let corners = [
+        (x0, y0, (1.0 - fx) * (1.0 - fy)),
+        (x1, y0, fx * (1.0 - fy)),
+        (x0, y1, (1.0 - fx) * fy),
+        (x1, y1, fx * fy),
+    ];
+    let mut result = Quadratic {
+        b: [0.0; 2],
+        a: [0.0; 3],
+    };
+    for (x, y, w) in corners {
+        let c = &coefficients[y * width + x];
+        for k in 0..2 {
+            result.b[k] += w * c.b[k];
+        }
+        for k in 0..3 {
+            result.a[k] += w * c.a[k];
+        }
+    }
+    result
+}
+
+/// Renders a flow field as a color image, with the hue of each pixel giving the direction
+/// of its flow vector and the value giving its magnitude.
+///
+/// Colors are assigned as by [`vector_field_to_rgb`], so vectors pointing right are red and
+/// the fastest moving pixel has full brightness. Pixels with no motion are black.
+///
+/// # Panics
+///
+/// If `flow_x` and `flow_y` have different dimensions.
+///
+/// # Examples
+/// ```
+/// use image::{Luma, Rgb};
+/// use imageproc::definitions::Image;
+/// use imageproc::optical_flow::colorize_flow;
+///
+/// let flow_x = Image::from_fn(2, 1, |x, _| Luma([x as f32]));
+/// let flow_y = Image::new(2, 1);
+/// let colors = colorize_flow(&flow_x, &flow_y);
+///
+/// assert_eq!(colors.get_pixel(0, 0), &Rgb([0, 0, 0]));
+/// assert_eq!(colors.get_pixel(1, 0), &Rgb([255, 0, 0]));
+/// ```
+pub fn colorize_flow(flow_x: &Image<Luma<f32>>, flow_y: &Image<Luma<f32>>) -> RgbImage {
+    vector_field_to_rgb(flow_x, flow_y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colorspace::{hsv_to_rgb, Hsv};
+
+    fn texture(x: f32, y: f32) -> f32 {
+        128.0
+            + 45.0 * (x / 4.0).sin() * (y / 5.0).cos()
+            + 30.0 * ((x + 2.0 * y) / 7.0).sin()
+            + 40.0 * (x / 13.0 + y / 17.0).sin()
+    }
+
+    // Returns images of texture, magnified by zoom, whose content is moved by (tx, ty) from
+    // the first to the second.
+    fn translated_pair(
+        width: u32,
+        height: u32,
+        zoom: f32,
+        tx: f32,
+        ty: f32,
+    ) -> (GrayImage, GrayImage) {
+        let render = |tx: f32, ty: f32| {
+            GrayImage::from_fn(width, height, |x, y| {
+                let value = texture((x as f32 - tx) / zoom, (y as f32 - ty) / zoom);
+                Luma([value.round().clamp(0.0, 255.0) as u8])
+            })
+        };
+        (render(0.0, 0.0), render(tx, ty))
+    }
+
+    fn interior_points(width: u32, height: u32, margin: u32, step: u32) -> Vec<Point<f32>> {
+        let mut points = vec![];
+        for y in (margin..height - margin).step_by(step as usize) {
+            for x in (margin..width - margin).step_by(step as usize) {
+                points.push(Point::new(x as f32, y as f32));
+            }
+        }
+        points
+    }
+
+    fn assert_tracked_by(
+        points: &[Point<f32>],
+        flow: &[FlowResult],
+        tx: f32,
+        ty: f32,
+        tolerance: f32,
+    ) {
+        assert_eq!(points.len(), flow.len());
+        for (p, result) in points.iter().zip(flow) {
+            assert!(result.found, "point {:?} was not found", p);
+            let (dx, dy) = (result.point.x - p.x, result.point.y - p.y);
+            assert!(
+                (dx - tx).abs() < tolerance && (dy - ty).abs() < tolerance,
+                "point {:?} moved by ({}, {}), expected ({}, {})",
+                p,
+                dx,
+                dy,
+                tx,
+                ty
+            );
+        }
+    }
+
+    #[test]
+    fn test_lucas_kanade_recovers_integer_translation() {
+        let (prev, next) = translated_pair(96, 96, 1.0, 3.0, -2.0);
+        let points = interior_points(96, 96, 15, 11);
+        let flow = lucas_kanade_flow(&prev, &next, &points, 7, 20, 0.01);
+        assert_tracked_by(&points, &flow, 3.0, -2.0, 0.1);
+    }
+
+    #[test]
+    fn test_lucas_kanade_recovers_subpixel_translation() {
+        let (prev, next) = translated_pair(96, 96, 1.0, 1.5, 0.75);
+        let points = interior_points(96, 96, 15, 11);
+        let flow = lucas_kanade_flow(&prev, &next, &points, 7, 20, 0.01);
+        assert_tracked_by(&points, &flow, 1.5, 0.75, 0.1);
+    }
+
+    #[test]
+    fn test_lucas_kanade_uses_pyramid_for_large_translation() {
+        // The shift is larger than the tracking window. The texture is magnified so that
+        // it is not aliased on the coarsest pyramid level
+        let (prev, next) = translated_pair(128, 128, 2.0, 9.0, -6.0);
+        let points = interior_points(128, 128, 25, 13);
+        let flow = lucas_kanade_flow(&prev, &next, &points, 4, 30, 0.01);
+        assert_tracked_by(&points, &flow, 9.0, -6.0, 0.2);
+    }
+
+    #[test]
+    fn test_lucas_kanade_identical_frames_give_zero_flow() {
+        let (prev, _) = translated_pair(64, 64, 1.0, 0.0, 0.0);
+        let points = interior_points(64, 64, 8, 9);
+        let flow = lucas_kanade_flow(&prev, &prev, &points, 5, 10, 0.01);
+        assert_tracked_by(&points, &flow, 0.0, 0.0, 1e-3);
+    }
+
+    #[test]
+    fn test_lucas_kanade_rejects_untextured_and_outside_points() {
+        let flat = GrayImage::from_pixel(40, 40, Luma([90]));
+        let points = [
+            Point::new(20.0, 20.0),
+            Point::new(-1.0, 5.0),
+            Point::new(5.0, 40.5),
+        ];
+        let flow = lucas_kanade_flow(&flat, &flat, &points, 5, 10, 0.01);
+        assert!(flow.iter().all(|result| !result.found));
+        assert_eq!(flow[1].point, points[1]);
+    }
+
+    #[test]
+    fn test_lucas_kanade_empty_inputs() {
+        let image = GrayImage::new(0, 0);
+        let flow = lucas_kanade_flow(&image, &image, &[Point::new(0.0, 0.0)], 3, 10, 0.01);
+        assert!(!flow[0].found);
+
+        let (prev, next) = translated_pair(20, 20, 1.0, 1.0, 1.0);
+        assert!(lucas_kanade_flow(&prev, &next, &[], 3, 10, 0.01).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lucas_kanade_rejects_mismatched_dimensions() {
+        let _ = lucas_kanade_flow(
+            &GrayImage::new(10, 10),
+            &GrayImage::new(10, 11),
+            &[],
+            3,
+            10,
+            0.01,
+        );
+    }
+
+    // Checks that the flow at every pixel at least margin pixels from the border is (tx, ty).
+    fn assert_constant_flow(
+        flow: &(Image<Luma<f32>>, Image<Luma<f32>>),
+        margin: u32,
+        tx: f32,
+        ty: f32,
+        tolerance: f32,
+    ) {
+        let (flow_x, flow_y) = flow;
+        let (width, height) = flow_x.dimensions();
+        for y in margin..height - margin {
+            for x in margin..width - margin {
+                let (dx, dy) = (flow_x.get_pixel(x, y)[0], flow_y.get_pixel(x, y)[0]);
+                assert!(
+                    (dx - tx).abs() < tolerance && (dy - ty).abs() < tolerance,
+                    "flow at ({}, {}) is ({}, {}), expected ({}, {})",
+                    x,
+                    y,
+                    dx,
+                    dy,
+                    tx,
+                    ty
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "slow")]
+    fn test_farneback_recovers_translation() {
+        let (prev, next) = translated_pair(96, 96, 1.0, 2.0, -1.0);
+        let flow = farneback_flow(&prev, &next, &FarnebackOptions::default());
+        assert_constant_flow(&flow, 16, 2.0, -1.0, 0.2);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "slow")]
+    fn test_farneback_recovers_subpixel_translation() {
+        let (prev, next) = translated_pair(96, 96, 1.0, -0.5, 1.25);
+        let flow = farneback_flow(&prev, &next, &FarnebackOptions::default());
+        assert_constant_flow(&flow, 16, -0.5, 1.25, 0.2);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore = "slow")]
+    fn test_farneback_uses_pyramid_for_large_translation() {
+        let (prev, next) = translated_pair(128, 128, 2.0, 6.0, 5.0);
+        let options = FarnebackOptions {
+            pyramid_levels: 4,
+            ..FarnebackOptions::default()
+        };
+        let flow = farneback_flow(&prev, &next, &options);
+        assert_constant_flow(&flow, 24, 6.0, 5.0, 0.3);
+    }
+
+    #[test]
+    fn test_farneback_identical_frames_give_zero_flow() {
+        let (prev, _) = translated_pair(40, 30, 1.0, 0.0, 0.0);
+        let flow = farneback_flow(&prev, &prev, &FarnebackOptions::default());
+        assert_constant_flow(&flow, 0, 0.0, 0.0, 1e-6);
+
+        let flat = GrayImage::from_pixel(20, 20, Luma([50]));
+        let flow = farneback_flow(&flat, &flat, &FarnebackOptions::default());
+        assert_constant_flow(&flow, 0, 0.0, 0.0, 1e-6);
+    }
+
+    #[test]
+    fn test_farneback_empty_image() {
+        let image = GrayImage::new(0, 7);
+        let (flow_x, flow_y) = farneback_flow(&image, &image, &FarnebackOptions::default());
+        assert_eq!(flow_x.dimensions(), (0, 7));
+        assert_eq!(flow_y.dimensions(), (0, 7));
+    }
+
+    #[test]
+    #[should_panic(expected = "poly_sigma must be >= 0.5")]
+    fn test_farneback_rejects_tiny_poly_sigma() {
+        let options = FarnebackOptions {
+            poly_sigma: 0.01,
+            ..Default::default()
+        };
+        let _ = farneback_flow(&GrayImage::new(10, 10), &GrayImage::new(10, 10), &options);
+    }
+
+    #[test]
+    fn test_farneback_with_minimum_poly_sigma() {
+        let options = FarnebackOptions {
+            poly_sigma: 0.5,
+            ..Default::default()
+        };
+        let image = GrayImage::from_fn(16, 16, |x, y| Luma([(x * 7 + y * 13) as u8]));
+        let (flow_x, flow_y) = farneback_flow(&image, &image, &options);
+        assert!(flow_x.iter().chain(flow_y.iter()).all(|f| f.is_finite()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_farneback_rejects_mismatched_dimensions() {
+        let _ = farneback_flow(
+            &GrayImage::new(10, 10),
+            &GrayImage::new(11, 10),
+            &FarnebackOptions::default(),
+        );
+    }
+
+    #[test]
+    fn test_colorize_flow_encodes_direction_and_magnitude() {
+        let components = [(2.0, 0.0), (0.0, 2.0), (-2.0, 0.0), (0.0, -1.0), (0.0, 0.0)];
+        let flow_x = Image::from_fn(5, 1, |x, _| Luma([components[x as usize].0]));
+        let flow_y = Image::from_fn(5, 1, |x, _| Luma([components[x as usize].1]));
+        let colors = colorize_flow(&flow_x, &flow_y);
+
+        let expected = [
+            hsv_to_rgb(Hsv {
+                hue: 0.0,
+                saturation: 1.0,
+                value: 1.0,
+            }),
+            hsv_to_rgb(Hsv {
+                hue: 90.0,
+                saturation: 1.0,
+                value: 1.0,
+            }),
+            hsv_to_rgb(Hsv {
+                hue: 180.0,
+                saturation: 1.0,
+                value: 1.0,
+            }),
+            hsv_to_rgb(Hsv {
+                hue: 270.0,
+                saturation: 1.0,
+                value: 0.5,
+            }),
+            image::Rgb([0, 0, 0]),
+        ];
+        for (x, expected) in expected.iter().enumerate() {
+            assert_eq!(colors.get_pixel(x as u32, 0), expected);
+        }
+    }
+
+    #[test]
+    fn test_colorize_zero_flow_is_black() {
+        let zero = Image::new(3, 4);
+        let colors = colorize_flow(&zero, &zero);
+        assert!(colors.iter().all(|&c| c == 0));
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::gray_bench_image;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_lucas_kanade_flow(b: &mut Bencher) {
+        let prev = gray_bench_image(320, 240);
+        let next = gray_bench_image(320, 240);
+        let points: Vec<_> = (0..100)
+            .map(|i| Point::new((20 + 28 * (i % 10)) as f32, (20 + 20 * (i / 10)) as f32))
+            .collect();
+        b.iter(|| {
+            let flow = lucas_kanade_flow(&prev, &next, &points, 7, 20, 0.01);
+            black_box(flow);
+        });
+    }
+
+    #[bench]
+    fn bench_farneback_flow(b: &mut Bencher) {
+        let prev = gray_bench_image(160, 120);
+        let next = gray_bench_image(160, 120);
+        b.iter(|| {
+            let flow = farneback_flow(&prev, &next, &FarnebackOptions::default());
+            black_box(flow);
+        });
+    }
+}
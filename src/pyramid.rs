@@ -0,0 +1,306 @@
+//! Image pyramids, for coarse-to-fine and multi-scale processing.
+//!
+//! Each level of a pyramid is formed by halving the dimensions of the previous level,
+//! rounding down. [`gaussian_pyramid`] smooths each level before downsampling it,
+//! [`mean_pyramid`] averages blocks of 2x2 pixels, and [`laplacian_pyramid`] stores the
+//! detail lost at each level of a Gaussian pyramid, so that the original image can be
+//! recovered by [`reconstruct_from_laplacian`].
+
+use crate::definitions::Image;
+use crate::filter::separable_filter_equal;
+use crate::map::map_subpixels;
+use image::{GrayImage, Luma};
+
+/// The five-tap binomial approximation to a Gaussian used by Burt and Adelson.
+const PYRAMID_KERNEL: [f32; 5] = [0.0625, 0.25, 0.375, 0.25, 0.0625];
+
+/// Returns a Gaussian pyramid of an image, starting with the image itself.
+///
+/// Each subsequent level is computed by smoothing the previous level with the five-tap
+/// binomial kernel `[1, 4, 6, 4, 1] / 16` in each direction, padding by continuity, and then
+/// keeping every second pixel in each direction. A level of size `(w, h)` is therefore
+/// followed by one of size `(w / 2, h / 2)`.
+///
+/// Returns `levels` images, or fewer if a dimension of the next level would be zero.
+///
+/// # Examples
+/// ```
+/// use image::GrayImage;
+/// use imageproc::pyramid::gaussian_pyramid;
+///
+/// let image = GrayImage::new(100, 41);
+/// let pyramid = gaussian_pyramid(&image, 4);
+///
+/// let sizes: Vec<_> = pyramid.iter().map(|level| level.dimensions()).collect();
+/// assert_eq!(sizes, vec![(100, 41), (50, 20), (25, 10), (12, 5)]);
+/// ```
+pub fn gaussian_pyramid(image: &GrayImage, levels: u32) -> Vec<GrayImage> {
+    build_pyramid(image, levels, |level| {
+        let smoothed = separable_filter_equal(&map_subpixels(level, f32::from), &PYRAMID_KERNEL);
+        GrayImage::from_fn(level.width() / 2, level.height() / 2, |x, y| {
+            Luma([to_u8(smoothed.get_pixel(2 * x, 2 * y)[0])])
+        })
+    })
+}
+
+/// Returns a pyramid of an image in which each level is formed by averaging the 2x2 blocks
+/// of pixels of the previous level, starting with the image itself.
+///
+/// A level of size `(w, h)` is followed by one of size `(w / 2, h / 2)`. If a dimension of
+/// a level is odd then its final row or column is ignored when computing the next level.
+///
+/// Returns `levels` images, or fewer if a dimension of the next level would be zero.
+pub fn mean_pyramid(image: &GrayImage, levels: u32) -> Vec<GrayImage> {
+    build_pyramid(image, levels, |level| {
+        GrayImage::from_fn(level.width() / 2, level.height() / 2, |x, y| {
+            let sum: u32 = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                .iter()
+                .map(|&(dx, dy)| level.get_pixel(2 * x + dx, 2 * y + dy)[0] as u32)
+                .sum();
+            Luma([((sum + 2) / 4) as u8])
+        })
+    })
+}
+
+fn build_pyramid<F>(image: &GrayImage, levels: u32, downsample: F) -> Vec<GrayImage>
+where
+    F: Fn(&GrayImage) -> GrayImage,
+{
+    let mut pyramid: Vec<GrayImage> = Vec::with_capacity(levels as usize);
+    if levels == 0 {
+        return pyramid;
+    }
+    pyramid.push(image.clone());
+    while pyramid.len() < levels as usize {
+        let last = pyramid.last().unwrap();
+        if last.width() < 2 || last.height() < 2 {
+            break;
+        }
+        let next = downsample(last);
+        pyramid.push(next);
+    }
+    pyramid
+}
+
+/// Returns a Laplacian pyramid of an image.
+///
+/// The Gaussian pyramid `g` of the image is computed using [`gaussian_pyramid`]. Every level
+/// except the last is the difference `g[i] - expand(g[i + 1])`, where `expand` upsamples an
+/// image to twice its size by interpolating with the same kernel as is used for smoothing.
+/// These levels hold the detail lost when downsampling. The last level is the smallest level
+/// of the Gaussian pyramid.
+///
+/// The image can be recovered from its Laplacian pyramid using [`reconstruct_from_laplacian`].
+///
+/// Returns `levels` images, or fewer if a dimension of the next level would be zero.
+///
+/// # Examples
+/// ```
+/// use imageproc::pyramid::{laplacian_pyramid, reconstruct_from_laplacian};
+/// use imageproc::utils::gray_bench_image;
+///
+/// let image = gray_bench_image(64, 48);
+/// let pyramid = laplacian_pyramid(&image, 4);
+///
+/// assert_eq!(pyramid.len(), 4);
+/// assert_eq!(reconstruct_from_laplacian(&pyramid), image);
+/// ```
+pub fn laplacian_pyramid(image: &GrayImage, levels: u32) -> Vec<Image<Luma<f32>>> {
+    let gaussian: Vec<Image<Luma<f32>>> = gaussian_pyramid(image, levels)
+        .iter()
+        .map(|level| map_subpixels(level, f32::from))
+        .collect();
+
+    let mut pyramid = Vec::with_capacity(gaussian.len());
+    for pair in gaussian.windows(2) {
+        let (fine, coarse) = (&pair[0], &pair[1]);
+        let mut detail = expand(coarse, fine.width(), fine.height());
+        for (d, f) in detail.iter_mut().zip(fine.iter()) {
+            *d = f - *d;
+        }
+        pyramid.push(detail);
+    }
+    if let Some(last) = gaussian.last() {
+        pyramid.push(last.clone());
+    }
+    pyramid
+}
+
+/// Reconstructs an image from its Laplacian pyramid, as computed by [`laplacian_pyramid`].
+///
+/// Starting from the last level, each level is expanded to the size of the one before it
+/// and added to it. For a pyramid returned by [`laplacian_pyramid`] this recovers the
+/// original image exactly, up to floating point rounding which is removed when converting
+/// the result to 8bpp.
+///
+/// # Panics
+///
+/// If `pyramid` is empty, or if any level is larger than twice the size of the level after
+/// it plus one in either dimension.
+pub fn reconstruct_from_laplacian(pyramid: &[Image<Luma<f32>>]) -> GrayImage {
+    let (last, rest) = pyramid
+        .split_last()
+        .expect("pyramid must contain at least one level");
+
+    let mut current = last.clone();
+    for level in rest.iter().rev() {
+        assert!(
+            level.width() <= 2 * current.width() + 1 && level.height() <= 2 * current.height() + 1,
+            "each pyramid level must be at most twice the size of the next level, plus one"
+        );
+        let mut expanded = expand(&current, level.width(), level.height());
+        for (e, l) in expanded.iter_mut().zip(level.iter()) {
+            *e += l;
+        }
+        current = expanded;
+    }
+
+    GrayImage::from_fn(current.width(), current.height(), |x, y| {
+        Luma([to_u8(current.get_pixel(x, y)[0])])
+    })
+}
+
+// Upsamples image to the given size, which is approximately twice the size of image.
+// Output pixel 2i coincides with input pixel i, and output pixel 2i + 1 lies halfway
+// between input pixels i and i + 1. Interpolates with the pyramid kernel, padding by
+// continuity.
+fn expand(image: &Image<Luma<f32>>, width: u32, height: u32) -> Image<Luma<f32>> {
+    let (in_width, in_height) = image.dimensions();
+    let rows: Vec<f32> = (0..in_height)
+        .flat_map(|y| {
+            let row: Vec<f32> = (0..in_width).map(|x| image.get_pixel(x, y)[0]).collect();
+            expand_line(&row, width as usize)
+        })
+        .collect();
+
+    let mut out = Image::new(width, height);
+    for x in 0..width as usize {
+        let column: Vec<f32> = (0..in_height as usize)
+            .map(|y| rows[y * width as usize + x])
+            .collect();
+        for (y, value) in expand_line(&column, height as usize)
+            .into_iter()
+            .enumerate()
+        {
+            out.put_pixel(x as u32, y as u32, Luma([value]));
+        }
+    }
+    out
+}
+
+fn expand_line(line: &[f32], len: usize) -> Vec<f32> {
+    let last = line.len() - 1;
+    let at = |i: usize| line[i.min(last)];
+    (0..len)
+        .map(|i| {
+            let centre = i / 2;
+            if i % 2 == 0 {
+                let left = at(centre.saturating_sub(1));
+                0.125 * left + 0.75 * at(centre) + 0.125 * at(centre + 1)
+            } else {
+                0.5 * at(centre) + 0.5 * at(centre + 1)
+            }
+        })
+        .collect()
+}
+
+fn to_u8(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::gray_bench_image;
+
+    #[test]
+    fn test_pyramid_levels_halve_dimensions() {
+        let image = gray_bench_image(97, 60);
+        let expected = vec![(97, 60), (48, 30), (24, 15), (12, 7), (6, 3)];
+        for pyramid in [gaussian_pyramid(&image, 5), mean_pyramid(&image, 5)] {
+            let sizes: Vec<_> = pyramid.iter().map(|level| level.dimensions()).collect();
+            assert_eq!(sizes, expected);
+        }
+        let sizes: Vec<_> = laplacian_pyramid(&image, 5)
+            .iter()
+            .map(|level| level.dimensions())
+            .collect();
+        assert_eq!(sizes, expected);
+    }
+
+    #[test]
+    fn test_pyramid_stops_before_empty_levels() {
+        let image = gray_bench_image(9, 3);
+        assert_eq!(gaussian_pyramid(&image, 10).len(), 2);
+        assert_eq!(mean_pyramid(&image, 10).len(), 2);
+        assert_eq!(laplacian_pyramid(&image, 10).len(), 2);
+        assert!(gaussian_pyramid(&image, 0).is_empty());
+    }
+
+    #[test]
+    fn test_gaussian_pyramid_preserves_constant_image() {
+        let image = GrayImage::from_pixel(40, 30, Luma([77]));
+        for level in gaussian_pyramid(&image, 4) {
+            assert!(level.iter().all(|&p| p == 77));
+        }
+    }
+
+    #[test]
+    fn test_mean_pyramid_averages_blocks() {
+        let image = gray_image!(
+            0, 4, 10, 10, 7;
+            8, 4, 10, 11, 7;
+            1, 1, 1, 1, 1);
+        let pyramid = mean_pyramid(&image, 2);
+        assert_pixels_eq!(pyramid[1], gray_image!(4, 10));
+    }
+
+    #[test]
+    fn test_laplacian_reconstruction_round_trips() {
+        for (width, height) in [(64, 64), (75, 33), (20, 157)] {
+            let image = gray_bench_image(width, height);
+            for levels in 1..6 {
+                let pyramid = laplacian_pyramid(&image, levels);
+                assert_pixels_eq!(reconstruct_from_laplacian(&pyramid), image);
+            }
+        }
+    }
+
+    #[test]
+    fn test_laplacian_detail_vanishes_for_smooth_image() {
+        let image = GrayImage::from_fn(64, 64, |x, _| Luma([(2 * x) as u8]));
+        let pyramid = laplacian_pyramid(&image, 3);
+        // The expansion of a linear ramp is exact away from the image boundary
+        for y in 0..64 {
+            for x in 4..60 {
+                assert!(pyramid[0].get_pixel(x, y)[0].abs() <= 1.0);
+            }
+        }
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::gray_bench_image;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_gaussian_pyramid(b: &mut Bencher) {
+        let image = gray_bench_image(400, 400);
+        b.iter(|| {
+            let pyramid = gaussian_pyramid(&image, 5);
+            black_box(pyramid);
+        });
+    }
+
+    #[bench]
+    fn bench_laplacian_pyramid(b: &mut Bencher) {
+        let image = gray_bench_image(400, 400);
+        b.iter(|| {
+            let pyramid = laplacian_pyramid(&image, 5);
+            black_box(pyramid);
+        });
+    }
+}
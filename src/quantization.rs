@@ -0,0 +1,744 @@
+//! Functions for reducing the number of colors in an image.
+//!
+//! [`quantize_median_cut`] and [`quantize_kmeans`] choose a palette for an RGB image and
+//! return an index image giving the palette entry used for each pixel. The quantized image
+//! can be recovered from these using [`apply_palette`].
+//!
+//! [`dither_floyd_steinberg`] and [`dither_ordered`] reduce the number of intensity levels
+//! in a grayscale image while preserving its local average intensity.
+
+use crate::definitions::Image;
+use image::{GrayImage, Luma, Rgb, RgbImage};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+/// Chooses a palette of at most `num_colors` colors for an image using the median cut
+/// algorithm, and returns the palette along with an image of the palette index of each pixel.
+///
+/// All colors in the image start in a single box in RGB space. The box whose colors span the
+/// widest range in any channel is repeatedly split in two along that channel, at the median
+/// of its pixels, until there are `num_colors` boxes or no box contains more than one color.
+/// The palette entry for each box is the mean color of its pixels, and each pixel is assigned
+/// the entry for its box.
+///
+/// Boxes are never split between pixels of the same color, so an image with at most
+/// `num_colors` distinct colors is reproduced exactly. Returns an empty palette for an empty
+/// image.
+///
+/// # Panics
+///
+/// If `num_colors` is 0 or greater than 256.
+///
+/// # Examples
+/// ```
+/// use image::{Rgb, RgbImage};
+/// use imageproc::quantization::{apply_palette, quantize_median_cut};
+///
+/// let colors = [Rgb([255, 0, 0]), Rgb([0, 255, 0]), Rgb([0, 0, 255])];
+/// let image = RgbImage::from_fn(6, 4, |x, y| colors[((x + y) % 3) as usize]);
+///
+/// let (palette, indices) = quantize_median_cut(&image, 3);
+///
+/// assert_eq!(palette.len(), 3);
+/// assert_eq!(apply_palette(&palette, &indices), image);
+/// ```
+pub fn quantize_median_cut(image: &RgbImage, num_colors: usize) -> (Vec<Rgb<u8>>, GrayImage) {
+    assert_num_colors(num_colors);
+
+    let mut boxes = vec![color_histogram(image)];
+    if boxes[0].is_empty() {
+        return (vec![], GrayImage::new(image.width(), image.height()));
+    }
+
+    while boxes.len() < num_colors {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .map(|(i, colors)| {
+                let (channel, range) = widest_channel(colors);
+                (range, colors.len(), i, channel)
+            })
+            .filter(|&(range, ..)| range > 0)
+            .max();
+        let Some((_, _, i, channel)) = widest else {
+            break;
+        };
+
+        let mut lower = boxes.swap_remove(i);
+        lower.sort_by_key(|(color, _)| color[channel]);
+        let upper = lower.split_off(median_split(&lower, channel));
+        boxes.push(lower);
+        boxes.push(upper);
+    }
+
+    let palette: Vec<Rgb<u8>> = boxes.iter().map(|colors| mean_color(colors)).collect();
+    let index: HashMap<[u8; 3], u8> = boxes
+        .iter()
+        .enumerate()
+        .flat_map(|(i, colors)| colors.iter().map(move |&(color, _)| (color, i as u8)))
+        .collect();
+
+    (palette, index_image(image, |color| index[&color]))
+}
+
+/// Chooses a palette of at most `num_colors` colors for an image using k-means clustering,
+/// and returns the palette along with an image of the palette index of each pixel.
+///
+/// The initial cluster centres are chosen from the colors in the image by k-means++ seeding
+/// using a random number generator seeded with `seed`, so the output is deterministic for a
+/// given seed. Lloyd's algorithm is then run for at most `max_iterations` iterations, or until
+/// no color changes cluster. The palette consists of the rounded cluster centres, and each
+/// pixel is assigned the index of the nearest palette entry.
+///
+/// If the image has at most `num_colors` distinct colors then each is its own cluster and the
+/// image is reproduced exactly. Returns an empty palette for an empty image.
+///
+/// # Panics
+///
+/// If `num_colors` is 0 or greater than 256.
+///
+/// # Examples
+/// ```
+/// use imageproc::quantization::{apply_palette, quantize_kmeans};
+/// use imageproc::utils::rgb_bench_image;
+///
+/// let image = rgb_bench_image(40, 30);
+///
+/// let (palette, indices) = quantize_kmeans(&image, 8, 20, 1);
+/// let quantized = apply_palette(&palette, &indices);
+///
+/// assert!(palette.len() <= 8);
+/// assert_eq!(quantized.dimensions(), image.dimensions());
+/// assert_eq!((palette, indices), quantize_kmeans(&image, 8, 20, 1));
+/// ```
+pub fn quantize_kmeans(
+    image: &RgbImage,
+    num_colors: usize,
+    max_iterations: u32,
+    seed: u64,
+) -> (Vec<Rgb<u8>>, GrayImage) {
+    assert_num_colors(num_colors);
+
+    let colors = color_histogram(image);
+    if colors.is_empty() {
+        return (vec![], GrayImage::new(image.width(), image.height()));
+    }
+
+    let to_f64 = |c: [u8; 3]| c.map(f64::from);
+    let mut centres = kmeans_plus_plus(&colors, num_colors, seed);
+    let mut assignments: Vec<usize> = vec![usize::MAX; colors.len()];
+
+    for _ in 0..max_iterations {
+        let mut changed = false;
+        for (assignment, &(color, _)) in assignments.iter_mut().zip(&colors) {
+            let nearest = nearest(&centres, to_f64(color));
+            changed |= *assignment != nearest;
+            *assignment = nearest;
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![([0.0; 3], 0.0); centres.len()];
+        for (&assignment, &(color, count)) in assignments.iter().zip(&colors) {
+            let (sum, total) = &mut sums[assignment];
+            for c in 0..3 {
+                sum[c] += color[c] as f64 * count as f64;
+            }
+            *total += count as f64;
+        }
+        for (centre, (sum, total)) in centres.iter_mut().zip(sums) {
+            // Empty clusters keep their previous centre
+            if total > 0.0 {
+                *centre = sum.map(|s| s / total);
+            }
+        }
+    }
+
+    let palette: Vec<Rgb<u8>> = centres
+        .iter()
+        .map(|centre| Rgb(centre.map(|c| c.round() as u8)))
+        .collect();
+    let palette_f64: Vec<[f64; 3]> = palette.iter().map(|p| to_f64(p.0)).collect();
+    let index: HashMap<[u8; 3], u8> = colors
+        .iter()
+        .map(|&(color, _)| (color, nearest(&palette_f64, to_f64(color)) as u8))
+        .collect();
+
+    (palette, index_image(image, |color| index[&color]))
+}
+
+/// Returns the image whose pixel at each location is the entry of `palette` given by the
+/// corresponding pixel of `indices`.
+///
+/// This recovers the quantized image from the output of [`quantize_median_cut`] or
+/// [`quantize_kmeans`].
+///
+/// # Panics
+///
+/// If any index is not a valid index into `palette`.
+pub fn apply_palette(palette: &[Rgb<u8>], indices: &GrayImage) -> RgbImage {
+    RgbImage::from_fn(indices.width(), indices.height(), |x, y| {
+        palette[indices.get_pixel(x, y)[0] as usize]
+    })
+}
+
+/// Reduces an image to `levels` evenly spaced intensities using Floyd-Steinberg error
+/// diffusion.
+///
+/// The output intensities are `round(k * 255 / (levels - 1))` for `k` in `0..levels`. Pixels
+/// are processed in raster order and each is set to the nearest output intensity. The
+/// difference between the intensity before and after quantization is then distributed to
+/// the pixels yet to be processed: `7/16` to the right neighbor and `3/16`, `5/16` and
+/// `1/16` to the bottom left, bottom and bottom right neighbors respectively. Error
+/// which would be passed outside the image is discarded.
+///
+/// # Panics
+///
+/// If `levels < 2`.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::quantization::dither_floyd_steinberg;
+///
+/// let image = GrayImage::from_pixel(8, 8, Luma([64]));
+/// let dithered = dither_floyd_steinberg(&image, 2);
+///
+/// assert!(dithered.iter().all(|&p| p == 0 || p == 255));
+/// // Roughly a quarter of the pixels are white
+/// let white = dithered.iter().filter(|&&p| p == 255).count();
+/// assert!((15..=17).contains(&white));
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn dither_floyd_steinberg(image: &GrayImage, levels: u8) -> GrayImage {
+    assert!(levels >= 2, "levels must be at least 2");
+
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut values: Vec<f32> = image.iter().map(|&p| p as f32).collect();
+    let mut out = GrayImage::new(image.width(), image.height());
+    let step = 255.0 / (levels - 1) as f32;
+
+    for (i, o) in out.iter_mut().enumerate() {
+        let (x, y) = (i % width, i / width);
+        let value = values[i];
+        let level = (value / step).round().clamp(0.0, (levels - 1) as f32);
+        let quantized = (level * step).round();
+        *o = quantized as u8;
+
+        let error = value - quantized;
+        let mut diffuse = |dx: isize, dy: usize, weight: f32| {
+            let nx = x as isize + dx;
+            if nx >= 0 && (nx as usize) < width && y + dy < height {
+                values[(y + dy) * width + nx as usize] += error * weight;
+            }
+        };
+        diffuse(1, 0, 7.0 / 16.0);
+        diffuse(-1, 1, 3.0 / 16.0);
+        diffuse(0, 1, 5.0 / 16.0);
+        diffuse(1, 1, 1.0 / 16.0);
+    }
+    out
+}
+
+/// Reduces an image to `levels` evenly spaced intensities using ordered dithering with a
+/// Bayer matrix of size `matrix_size x matrix_size`.
+///
+/// The output intensities are `round(k * 255 / (levels - 1))` for `k` in `0..levels`. The
+/// Bayer matrix is tiled over the image and gives each pixel a threshold in `(0, 1)`. A pixel
+/// whose intensity lies a fraction `f` of the way from output level `k` to level `k + 1` is
+/// set to level `k + 1` if `f` is at least its threshold, and to level `k` otherwise.
+///
+/// Unlike [`dither_floyd_steinberg`], each output pixel depends only on the corresponding
+/// input pixel and its position, which gives a regular cross-hatched pattern.
+///
+/// # Panics
+///
+/// If `levels < 2`, or if `matrix_size` is not a power of two between 2 and 256.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::quantization::dither_ordered;
+///
+/// let image = GrayImage::from_pixel(8, 8, Luma([128]));
+/// let dithered = dither_ordered(&image, 2, 4);
+///
+/// // Alternate pixels are black and white
+/// for (x, y, p) in dithered.enumerate_pixels() {
+///     assert_eq!(p[0], if (x + y) % 2 == 0 { 255 } else { 0 });
+/// }
+/// ```
+#[must_use = "the function does not modify the original image"]
+pub fn dither_ordered(image: &GrayImage, levels: u8, matrix_size: u32) -> GrayImage {
+    assert!(levels >= 2, "levels must be at least 2");
+    assert!(
+        (2..=256).contains(&matrix_size) && matrix_size.is_power_of_two(),
+        "matrix_size must be a power of two between 2 and 256"
+    );
+
+    let matrix = bayer_matrix(matrix_size);
+    let cells = (matrix_size * matrix_size) as f32;
+    let max_level = (levels - 1) as f32;
+    let step = 255.0 / max_level;
+
+    GrayImage::from_fn(image.width(), image.height(), |x, y| {
+        let index = (y % matrix_size) * matrix_size + x % matrix_size;
+        let threshold = (matrix[index as usize] as f32 + 0.5) / cells;
+        let scaled = image.get_pixel(x, y)[0] as f32 / step;
+        let level = (scaled + 1.0 - threshold).floor().min(max_level);
+        Luma([(level * step).round() as u8])
+    })
+}
+
+// Returns the Bayer matrix of the given size in row major order, with entries 0..size².
+// Each matrix is built from the one of half its size M as [4M, 4M + 2; 4M + 3, 4M + 1].
+fn bayer_matrix(size: u32) -> Vec<u32> {
+    let mut matrix = vec![0];
+    let mut n = 1;
+    while n < size {
+        let mut next = vec![0; (4 * n * n) as usize];
+        for y in 0..n {
+            for x in 0..n {
+                let m = 4 * matrix[(y * n + x) as usize];
+                let at = |dx: u32, dy: u32| ((y + dy * n) * 2 * n + x + dx * n) as usize;
+                next[at(0, 0)] = m;
+                next[at(1, 0)] = m + 2;
+                next[at(0, 1)] = m + 3;
+                next[at(1, 1)] = m + 1;
+            }
+        }
+        matrix = next;
+        n *= 2;
+    }
+    matrix
+}
+
+fn assert_num_colors(num_colors: usize) {
+    assert!(
+        (1..=256).contains(&num_colors),
+        "num_colors must be between 1 and 256"
+    );
+}
+
+// Returns the distinct colors in an image and the number of pixels of each, ordered by color.
+fn color_histogram(image: &RgbImage) -> Vec<([u8; 3], u32)> {
+    let mut counts: HashMap<[u8; 3], u32> = HashMap::new();
+    for p in image.pixels() {
+        *counts.entry(p.0).or_insert(0) += 1;
+    }
+    let mut colors: Vec<([u8; 3], u32)> = counts.into_iter().collect();
+    colors.sort_unstable();
+    colors
+}
+
+fn index_image<F>(image: &RgbImage, index: F) -> GrayImage
+where
+    F: Fn([u8; 3]) -> u8,
+{
+    let mut out: Image<Luma<u8>> = GrayImage::new(image.width(), image.height());
+    for (o, p) in out.iter_mut().zip(image.pixels()) {
+        *o = index(p.0);
+    }
+    out
+}
+
+// Returns the channel with the widest range of values, and that range.
+fn widest_channel(colors: &[([u8; 3], u32)]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let min = colors.iter().map(|(color, _)| color[c]).min().unwrap();
+            let max = colors.iter().map(|(color, _)| color[c]).max().unwrap();
+            (c, max - min)
+        })
+        .max_by_key(|&(c, range)| (range, std::cmp::Reverse(c)))
+        .unwrap()
+}
+
+// Returns the index at which to split colors, which are sorted by the given channel and
+// contain at least two values of it. The split is as close as possible to the median pixel
+// while never separating two colors with the same value in this channel.
+fn median_split(colors: &[([u8; 3], u32)], channel: usize) -> usize {
+    let total: u64 = colors.iter().map(|&(_, count)| count as u64).sum();
+    let mut seen = 0u64;
+    let mut median = colors.len() - 1;
+    for (i, &(_, count)) in colors.iter().enumerate() {
+        seen += count as u64;
+        if 2 * seen >= total {
+            median = i + 1;
+            break;
+        }
+    }
+    let median = median.clamp(1, colors.len() - 1);
+
+    let value = colors[median].0[channel];
+    let first = colors.partition_point(|(color, _)| color[channel] < value);
+    if first > 0 {
+        first
+    } else {
+        colors.partition_point(|(color, _)| color[channel] <= value)
+    }
+}
+
+fn mean_color(colors: &[([u8; 3], u32)]) -> Rgb<u8> {
+    let mut sum = [0u64; 3];
+    let mut total = 0u64;
+    for &(color, count) in colors {
+        for c in 0..3 {
+            sum[c] += color[c] as u64 * count as u64;
+        }
+        total += count as u64;
+    }
+    Rgb(sum.map(|s| ((s + total / 2) / total) as u8))
+}
+
+// Chooses up to k distinct colors as initial cluster centres. Each centre after the first is
+// chosen with probability proportional to its pixel count times its squared distance from
+// the nearest centre already chosen.
+fn kmeans_plus_plus(colors: &[([u8; 3], u32)], k: usize, seed: u64) -> Vec<[f64; 3]> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let to_f64 = |c: [u8; 3]| c.map(f64::from);
+
+    // Squared distance of each color from the nearest centre chosen so far
+    let mut distances = vec![f64::INFINITY; colors.len()];
+    let mut centres: Vec<[f64; 3]> = Vec::with_capacity(k);
+    while centres.len() < k {
+        let weights: Vec<f64> = colors
+            .iter()
+            .zip(&distances)
+            .map(|(&(_, count), &d)| {
+                if centres.is_empty() {
+                    count as f64
+                } else {
+                    count as f64 * d
+                }
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+        if total <= 0.0 {
+            break;
+        }
+
+        let target = rng.gen::<f64>() * total;
+        let mut seen = 0.0;
+        let mut chosen = weights.iter().rposition(|&w| w > 0.0).unwrap();
+        for (i, &w) in weights.iter().enumerate() {
+            seen += w;
+            if w > 0.0 && seen > target {
+                chosen = i;
+                break;
+            }
+        }
+
+        let centre = to_f64(colors[chosen].0);
+        centres.push(centre);
+        for (d, &(color, _)) in distances.iter_mut().zip(colors) {
+            *d = d.min(squared_distance(centre, to_f64(color)));
+        }
+    }
+    centres
+}
+
+fn nearest(centres: &[[f64; 3]], color: [f64; 3]) -> usize {
+    let mut best = (0, f64::INFINITY);
+    for (i, &centre) in centres.iter().enumerate() {
+        let d = squared_distance(centre, color);
+        if d < best.1 {
+            best = (i, d);
+        }
+    }
+    best.0
+}
+
+fn squared_distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (0..3).map(|c| (a[c] - b[c]) * (a[c] - b[c])).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn distinct_colors(image: &RgbImage) -> HashSet<Rgb<u8>> {
+        image.pixels().copied().collect()
+    }
+
+    fn mean_squared_error(a: &RgbImage, b: &RgbImage) -> f64 {
+        let sum: f64 = a
+            .iter()
+            .zip(b.iter())
+            .map(|(&p, &q)| (p as f64 - q as f64).powi(2))
+            .sum();
+        sum / a.len() as f64
+    }
+
+    fn gradient_image(width: u32, height: u32) -> RgbImage {
+        RgbImage::from_fn(width, height, |x, y| {
+            Rgb([(4 * x) as u8, (5 * y) as u8, (2 * (x + y)) as u8])
+        })
+    }
+
+    fn five_color_image() -> RgbImage {
+        let colors = [
+            Rgb([250, 10, 10]),
+            Rgb([20, 200, 40]),
+            Rgb([20, 200, 41]),
+            Rgb([0, 0, 0]),
+            Rgb([128, 128, 255]),
+        ];
+        let mut rng = StdRng::seed_from_u64(7);
+        // Colors with very different numbers of pixels
+        RgbImage::from_fn(30, 20, |_, _| {
+            let r: f64 = rng.gen();
+            colors[(r * r * colors.len() as f64) as usize]
+        })
+    }
+
+    #[test]
+    fn test_quantize_reproduces_image_with_few_colors() {
+        let image = five_color_image();
+        let colors = distinct_colors(&image);
+        assert_eq!(colors.len(), 5);
+
+        let mut results = vec![
+            quantize_median_cut(&image, 5),
+            quantize_median_cut(&image, 9),
+        ];
+        for seed in 0..5 {
+            results.push(quantize_kmeans(&image, 5, 10, seed));
+        }
+        for (palette, indices) in results {
+            assert_eq!(palette.len(), 5);
+            assert_eq!(palette.iter().copied().collect::<HashSet<_>>(), colors);
+            assert_pixels_eq!(apply_palette(&palette, &indices), image);
+        }
+    }
+
+    #[test]
+    fn test_quantize_reduces_colors_and_error_falls_with_palette_size() {
+        let image = gradient_image(60, 50);
+        let mut previous_errors = [f64::INFINITY; 2];
+        for num_colors in [1, 4, 16, 64] {
+            let results = [
+                quantize_median_cut(&image, num_colors),
+                quantize_kmeans(&image, num_colors, 30, 3),
+            ];
+            for ((palette, indices), previous_error) in results.iter().zip(&mut previous_errors) {
+                assert!(palette.len() <= num_colors);
+                assert!(indices.iter().all(|&i| (i as usize) < palette.len()));
+
+                let quantized = apply_palette(palette, indices);
+                assert!(distinct_colors(&quantized).len() <= num_colors);
+                let error = mean_squared_error(&image, &quantized);
+                assert!(error < *previous_error, "{} colors", num_colors);
+                *previous_error = error;
+            }
+        }
+    }
+
+    #[test]
+    fn test_quantize_to_one_color_gives_mean() {
+        let image = rgb_image!(
+            [0, 10, 200], [10, 20, 100];
+            [20, 30, 0], [30, 40, 100]);
+        for (palette, indices) in [
+            quantize_median_cut(&image, 1),
+            quantize_kmeans(&image, 1, 5, 0),
+        ] {
+            assert_eq!(palette, vec![Rgb([15, 25, 100])]);
+            assert!(indices.iter().all(|&i| i == 0));
+        }
+    }
+
+    #[test]
+    fn test_quantize_kmeans_is_deterministic_for_seed() {
+        let image = gradient_image(40, 40);
+        assert_eq!(
+            quantize_kmeans(&image, 10, 15, 42),
+            quantize_kmeans(&image, 10, 15, 42)
+        );
+    }
+
+    #[test]
+    fn test_quantize_empty_image() {
+        let image = RgbImage::new(0, 4);
+        for (palette, indices) in [
+            quantize_median_cut(&image, 4),
+            quantize_kmeans(&image, 4, 5, 0),
+        ] {
+            assert!(palette.is_empty());
+            assert_eq!(indices.dimensions(), (0, 4));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_quantize_median_cut_rejects_too_many_colors() {
+        let _ = quantize_median_cut(&RgbImage::new(2, 2), 257);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_quantize_kmeans_rejects_zero_colors() {
+        let _ = quantize_kmeans(&RgbImage::new(2, 2), 0, 5, 0);
+    }
+
+    fn horizontal_gradient() -> GrayImage {
+        GrayImage::from_fn(256, 32, |x, _| Luma([x as u8]))
+    }
+
+    // Asserts that the mean intensity over each non-overlapping block of dithered is close
+    // to that over the same block of image.
+    fn assert_local_means_close(image: &GrayImage, dithered: &GrayImage, block: u32, tol: f32) {
+        for by in 0..image.height() / block {
+            for bx in 0..image.width() / block {
+                let mean = |im: &GrayImage| {
+                    let mut sum = 0.0;
+                    for y in by * block..(by + 1) * block {
+                        for x in bx * block..(bx + 1) * block {
+                            sum += im.get_pixel(x, y)[0] as f32;
+                        }
+                    }
+                    sum / (block * block) as f32
+                };
+                let (expected, actual) = (mean(image), mean(dithered));
+                assert!(
+                    (expected - actual).abs() <= tol,
+                    "block ({}, {}): {} vs {}",
+                    bx,
+                    by,
+                    actual,
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_dithered_gradient_preserves_local_mean() {
+        let image = horizontal_gradient();
+        let floyd_steinberg = dither_floyd_steinberg(&image, 2);
+        let ordered = dither_ordered(&image, 2, 8);
+        for dithered in [&floyd_steinberg, &ordered] {
+            assert!(dithered.iter().all(|&p| p == 0 || p == 255));
+            assert_local_means_close(&image, dithered, 16, 12.0);
+        }
+    }
+
+    #[test]
+    fn test_dither_to_several_levels() {
+        let image = horizontal_gradient();
+        for dithered in [
+            dither_floyd_steinberg(&image, 4),
+            dither_ordered(&image, 4, 4),
+        ] {
+            assert!(dithered.iter().all(|p| [0, 85, 170, 255].contains(p)));
+            assert_local_means_close(&image, &dithered, 16, 6.0);
+        }
+
+        // Images containing only output levels are unchanged
+        let levels = GrayImage::from_fn(20, 20, |x, y| {
+            Luma([[0, 85, 170, 255][((x + y) % 4) as usize]])
+        });
+        assert_pixels_eq!(dither_floyd_steinberg(&levels, 4), levels);
+        assert_pixels_eq!(dither_ordered(&levels, 4, 8), levels);
+    }
+
+    #[test]
+    fn test_dither_ordered_is_deterministic_and_periodic() {
+        let image = GrayImage::from_pixel(40, 24, Luma([100]));
+        let dithered = dither_ordered(&image, 2, 8);
+        assert_pixels_eq!(dithered, dither_ordered(&image, 2, 8));
+        for (x, y, p) in dithered.enumerate_pixels() {
+            assert_eq!(p, dithered.get_pixel(x % 8, y % 8));
+        }
+        // 100 / 255 of the 64 thresholds lie below the intensity
+        assert_eq!(dithered.iter().filter(|&&p| p == 255).count(), 25 * 15);
+    }
+
+    #[test]
+    fn test_bayer_matrix() {
+        assert_eq!(bayer_matrix(2), vec![0, 2, 3, 1]);
+        assert_eq!(
+            bayer_matrix(4),
+            vec![0, 8, 2, 10, 12, 4, 14, 6, 3, 11, 1, 9, 15, 7, 13, 5]
+        );
+        let mut entries = bayer_matrix(16);
+        entries.sort_unstable();
+        assert_eq!(entries, (0..256).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dither_floyd_steinberg_rejects_single_level() {
+        let _ = dither_floyd_steinberg(&GrayImage::new(2, 2), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dither_ordered_rejects_invalid_matrix_size() {
+        let _ = dither_ordered(&GrayImage::new(2, 2), 2, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix_size must be a power of two between 2 and 256")]
+    fn test_dither_ordered_rejects_oversized_matrix() {
+        let _ = dither_ordered(&GrayImage::new(2, 2), 2, 1 << 20);
+    }
+
+    #[test]
+    fn test_dither_ordered_accepts_largest_matrix() {
+        let image = GrayImage::from_pixel(300, 3, Luma([128]));
+        let dithered = dither_ordered(&image, 2, 256);
+        assert!(dithered.iter().all(|&p| p == 0 || p == 255));
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::gray_bench_image;
+    use test::{black_box, Bencher};
+
+    fn bench_image() -> RgbImage {
+        RgbImage::from_fn(200, 200, |x, y| {
+            Rgb([x as u8, y as u8, ((x + y) / 2) as u8])
+        })
+    }
+
+    #[bench]
+    fn bench_quantize_median_cut(b: &mut Bencher) {
+        let image = bench_image();
+        b.iter(|| {
+            let quantized = quantize_median_cut(&image, 16);
+            black_box(quantized);
+        });
+    }
+
+    #[bench]
+    fn bench_quantize_kmeans(b: &mut Bencher) {
+        let image = bench_image();
+        b.iter(|| {
+            let quantized = quantize_kmeans(&image, 16, 10, 1);
+            black_box(quantized);
+        });
+    }
+    #[bench]
+    fn bench_dither_floyd_steinberg(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let dithered = dither_floyd_steinberg(&image, 2);
+            black_box(dithered);
+        });
+    }
+
+    #[bench]
+    fn bench_dither_ordered(b: &mut Bencher) {
+        let image = gray_bench_image(200, 200);
+        b.iter(|| {
+            let dithered = dither_ordered(&image, 2, 8);
+            black_box(dithered);
+        });
+    }
+}
@@ -1,10 +1,12 @@
 //! Functions for finding and labelling connected components of an image.
 
-use std::cmp;
+use std::cmp::{self, Reverse};
+use std::collections::BinaryHeap;
 
-use image::{GenericImage, GenericImageView, Luma};
+use image::{GenericImage, GenericImageView, GrayImage, Luma};
 
 use crate::definitions::Image;
+use crate::rect::Rect;
 use crate::union_find::DisjointSetForest;
 
 /// Determines which neighbors of a pixel we consider
@@ -243,6 +245,230 @@ where
     out
 }
 
+/// Summary statistics for a connected component, as computed by [`connected_components_with_stats`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ComponentStats {
+    /// The label of the component in the output of [`connected_components`].
+    pub label: u32,
+    /// The number of pixels in the component.
+    pub area: u32,
+    /// The smallest rectangle containing every pixel of the component.
+    pub bbox: Rect,
+    /// The mean `(x, y)` coordinates of the pixels in the component.
+    pub centroid: (f32, f32),
+}
+
+/// Labels the connected foreground components of an image as in [`connected_components`],
+/// and also returns statistics for each component.
+///
+/// The statistics for the component with label `l` are at index `l - 1` of the returned `Vec`.
+///
+/// # Panics
+/// Panics if the image contains 2<sup>32</sup> or more pixels.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use image::Luma;
+/// use imageproc::rect::Rect;
+/// use imageproc::region_labelling::{connected_components_with_stats, Connectivity};
+///
+/// let image = gray_image!(
+///     1, 1, 0, 0;
+///     1, 1, 0, 1;
+///     0, 0, 0, 1);
+///
+/// let (labels, stats) = connected_components_with_stats(&image, Connectivity::Four, Luma([0]));
+/// assert_eq!(labels.get_pixel(3, 2)[0], 2);
+/// assert_eq!(stats.len(), 2);
+///
+/// assert_eq!(stats[0].area, 4);
+/// assert_eq!(stats[0].bbox, Rect::at(0, 0).of_size(2, 2));
+/// assert_eq!(stats[0].centroid, (0.5, 0.5));
+///
+/// assert_eq!(stats[1].label, 2);
+/// assert_eq!(stats[1].area, 2);
+/// assert_eq!(stats[1].bbox, Rect::at(3, 1).of_size(1, 2));
+/// assert_eq!(stats[1].centroid, (3.0, 1.5));
+/// # }
+/// ```
+pub fn connected_components_with_stats<I>(
+    image: &I,
+    conn: Connectivity,
+    background: I::Pixel,
+) -> (Image<Luma<u32>>, Vec<ComponentStats>)
+where
+    I: GenericImage,
+    I::Pixel: Eq,
+{
+    let labels = connected_components(image, conn, background);
+
+    struct Accumulator {
+        area: u32,
+        min: (u32, u32),
+        max: (u32, u32),
+        sum: (u64, u64),
+    }
+
+    let mut accumulators: Vec<Accumulator> = Vec::new();
+    for (x, y, p) in labels.enumerate_pixels() {
+        let label = p[0] as usize;
+        if label == 0 {
+            continue;
+        }
+        // Labels are assigned in raster order, so each new label is one more than the last
+        if label > accumulators.len() {
+            accumulators.push(Accumulator {
+                area: 0,
+                min: (x, y),
+                max: (x, y),
+                sum: (0, 0),
+            });
+        }
+        let acc = &mut accumulators[label - 1];
+        acc.area += 1;
+        acc.min = (acc.min.0.min(x), acc.min.1.min(y));
+        acc.max = (acc.max.0.max(x), acc.max.1.max(y));
+        acc.sum = (acc.sum.0 + x as u64, acc.sum.1 + y as u64);
+    }
+
+    let stats = accumulators
+        .iter()
+        .enumerate()
+        .map(|(i, acc)| ComponentStats {
+            label: i as u32 + 1,
+            area: acc.area,
+            bbox: Rect::at(acc.min.0 as i32, acc.min.1 as i32)
+                .of_size(acc.max.0 - acc.min.0 + 1, acc.max.1 - acc.min.1 + 1),
+            centroid: (
+                (acc.sum.0 as f64 / acc.area as f64) as f32,
+                (acc.sum.1 as f64 / acc.area as f64) as f32,
+            ),
+        })
+        .collect();
+
+    (labels, stats)
+}
+
+/// The label given by [`watershed`] to pixels on the boundaries between basins.
+pub const WATERSHED_LINE: i32 = -1;
+
+/// Segments an image using marker-controlled watershed flooding, as described by Meyer in
+/// "Topographic distance and watershed lines".
+///
+/// `image` is treated as a topographic relief, and is typically a gradient magnitude image so
+/// that the boundaries between regions lie along ridges. Each pixel with a positive value in
+/// `markers` is a seed for the basin with that label, and all other pixels are unlabelled.
+///
+/// Basins are flooded from their seeds in order of increasing intensity of `image`, using
+/// 4-connectivity. An unlabelled pixel reached by only one basin receives that basin's label,
+/// and a pixel reached by two or more basins at once is labelled [`WATERSHED_LINE`] and stops
+/// the flood. Pixels which cannot be reached from any seed keep a label of 0.
+///
+/// # Panics
+///
+/// If `image` and `markers` have different dimensions.
+///
+/// # Examples
+/// ```
+/// # extern crate image;
+/// # #[macro_use]
+/// # extern crate imageproc;
+/// # fn main() {
+/// use imageproc::region_labelling::{watershed, WATERSHED_LINE};
+///
+/// // Two valleys separated by a ridge
+/// let relief = gray_image!(
+///     0, 10, 90, 10, 0;
+///     0, 10, 90, 10, 0;
+///     0, 10, 90, 10, 0);
+///
+/// let markers = gray_image!(type: i32,
+///     1, 0, 0, 0, 2;
+///     0, 0, 0, 0, 0;
+///     0, 0, 0, 0, 0);
+///
+/// let l = WATERSHED_LINE;
+/// let expected = gray_image!(type: i32,
+///     1, 1, l, 2, 2;
+///     1, 1, l, 2, 2;
+///     1, 1, l, 2, 2);
+///
+/// assert_pixels_eq!(watershed(&relief, &markers), expected);
+/// # }
+/// ```
+pub fn watershed(image: &GrayImage, markers: &Image<Luma<i32>>) -> Image<Luma<i32>> {
+    assert_eq!(
+        image.dimensions(),
+        markers.dimensions(),
+        "image and markers must have the same dimensions"
+    );
+    let (width, height) = image.dimensions();
+    let mut labels = Image::from_fn(width, height, |x, y| {
+        Luma([cmp::max(markers.get_pixel(x, y)[0], 0)])
+    });
+
+    let neighbours = move |x: u32, y: u32| {
+        [
+            (x > 0).then(|| (x - 1, y)),
+            (x + 1 < width).then(|| (x + 1, y)),
+            (y > 0).then(|| (x, y - 1)),
+            (y + 1 < height).then(|| (x, y + 1)),
+        ]
+        .into_iter()
+        .flatten()
+    };
+
+    // Pixels are flooded in order of intensity, and in the order they were
+    // queued among pixels of equal intensity.
+    let mut queue = BinaryHeap::new();
+    let mut queued = vec![false; width as usize * height as usize];
+    let mut order = 0u64;
+    let mut enqueue = |queue: &mut BinaryHeap<_>, labels: &Image<Luma<i32>>, x: u32, y: u32| {
+        for (nx, ny) in neighbours(x, y) {
+            let index = ny as usize * width as usize + nx as usize;
+            if labels.get_pixel(nx, ny)[0] == 0 && !queued[index] {
+                queued[index] = true;
+                queue.push(Reverse((image.get_pixel(nx, ny)[0], order, nx, ny)));
+                order += 1;
+            }
+        }
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            if labels.get_pixel(x, y)[0] > 0 {
+                enqueue(&mut queue, &labels, x, y);
+            }
+        }
+    }
+
+    while let Some(Reverse((_, _, x, y))) = queue.pop() {
+        let mut label = 0;
+        for (nx, ny) in neighbours(x, y) {
+            let neighbour = labels.get_pixel(nx, ny)[0];
+            if neighbour > 0 {
+                if label == 0 {
+                    label = neighbour;
+                } else if label != neighbour {
+                    label = WATERSHED_LINE;
+                    break;
+                }
+            }
+        }
+        labels.put_pixel(x, y, Luma([label]));
+        if label > 0 {
+            enqueue(&mut queue, &labels, x, y);
+        }
+    }
+
+    labels
+}
+
 #[cfg(test)]
 mod tests {
     extern crate wasm_bindgen_test;
@@ -253,8 +479,12 @@ mod tests {
 
     use crate::definitions::{HasBlack, HasWhite};
 
-    use super::connected_components;
     use super::Connectivity::{Eight, Four};
+    use super::{connected_components, connected_components_with_stats, watershed, WATERSHED_LINE};
+    use crate::definitions::Image;
+    use crate::distance_transform::euclidean_distance_transform;
+    use crate::drawing::draw_filled_circle_mut;
+    use crate::rect::Rect;
 
     #[cfg_attr(not(target_arch = "wasm32"), test)]
     #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
@@ -275,6 +505,105 @@ mod tests {
         assert_pixels_eq!(labelled, expected);
     }
 
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_connected_components_with_stats_two_rectangles() {
+        let mut image = GrayImage::new(30, 20);
+        for y in 2..7 {
+            for x in 3..13 {
+                image.put_pixel(x, y, Luma([255]));
+            }
+        }
+        for y in 9..18 {
+            for x in 20..24 {
+                image.put_pixel(x, y, Luma([128]));
+            }
+        }
+
+        let (labels, stats) = connected_components_with_stats(&image, Eight, Luma::black());
+        assert_pixels_eq!(labels, connected_components(&image, Eight, Luma::black()));
+        assert_eq!(stats.len(), 2);
+
+        assert_eq!(stats[0].label, 1);
+        assert_eq!(stats[0].area, 50);
+        assert_eq!(stats[0].bbox, Rect::at(3, 2).of_size(10, 5));
+        assert_eq!(stats[0].centroid, (7.5, 4.0));
+
+        assert_eq!(stats[1].label, 2);
+        assert_eq!(stats[1].area, 36);
+        assert_eq!(stats[1].bbox, Rect::at(20, 9).of_size(4, 9));
+        assert_eq!(stats[1].centroid, (21.5, 13.0));
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_connected_components_with_stats_matches_label_counts() {
+        let image = chessboard(9, 7);
+        let (labels, stats) = connected_components_with_stats(&image, Four, Luma::black());
+        assert_eq!(stats.len(), 32);
+        for s in &stats {
+            let count = labels.pixels().filter(|p| p[0] == s.label).count();
+            assert_eq!(s.area as usize, count);
+            assert_eq!(s.bbox.width(), 1);
+            assert_eq!(s.bbox.left() as f32, s.centroid.0);
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_watershed_separates_overlapping_disks() {
+        let (left, right, radius) = ((20, 25), (42, 25), 14);
+        let mut mask = GrayImage::new(62, 50);
+        draw_filled_circle_mut(&mut mask, left, radius, Luma([255]));
+        draw_filled_circle_mut(&mut mask, right, radius, Luma([255]));
+
+        // The relief is lowest at the centres of the disks and highest at the neck
+        // between them, and outside the disks
+        let background = GrayImage::from_fn(62, 50, |x, y| {
+            Luma([if mask.get_pixel(x, y)[0] == 0 { 255 } else { 0 }])
+        });
+        let distances = euclidean_distance_transform(&background);
+        let relief = GrayImage::from_fn(62, 50, |x, y| {
+            Luma([(255.0 - 12.0 * distances.get_pixel(x, y)[0]).max(0.0) as u8])
+        });
+
+        let mut markers = Image::new(62, 50);
+        markers.put_pixel(left.0 as u32, left.1 as u32, Luma([1]));
+        markers.put_pixel(right.0 as u32, right.1 as u32, Luma([2]));
+        let labels = watershed(&relief, &markers);
+
+        let neck = 31;
+        for (x, y, p) in labels.enumerate_pixels() {
+            if mask.get_pixel(x, y)[0] == 0 {
+                continue;
+            }
+            if x + 2 < neck {
+                assert_eq!(p[0], 1, "({}, {})", x, y);
+            } else if x > neck + 2 {
+                assert_eq!(p[0], 2, "({}, {})", x, y);
+            } else {
+                assert_ne!(p[0], 0, "({}, {})", x, y);
+            }
+        }
+
+        // The dividing line crosses every row of the neck
+        for y in 0..50 {
+            if mask.get_pixel(neck, y)[0] > 0 {
+                let crosses =
+                    (neck - 2..=neck + 2).any(|x| labels.get_pixel(x, y)[0] == WATERSHED_LINE);
+                assert!(crosses, "row {}", y);
+            }
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_watershed_without_markers_labels_nothing() {
+        let image = chessboard(5, 5);
+        let labels = watershed(&image, &Image::new(5, 5));
+        assert!(labels.iter().all(|&l| l == 0));
+    }
+
     // One huge component with eight-way connectivity, loads of
     // isolated components with four-way connectivity.
     pub(super) fn chessboard(width: u32, height: u32) -> GrayImage {
@@ -309,10 +638,11 @@ mod tests {
 #[cfg(not(miri))]
 #[cfg(test)]
 mod benches {
-    use super::connected_components;
     use super::tests::chessboard;
     use super::Connectivity::{Eight, Four};
-    use crate::definitions::HasBlack;
+    use super::{connected_components, watershed};
+    use crate::definitions::{HasBlack, Image};
+    use crate::utils::gray_bench_image;
     use ::test;
     use image::Luma;
 
@@ -333,4 +663,20 @@ mod benches {
             test::black_box(components);
         });
     }
+
+    #[bench]
+    fn bench_watershed(b: &mut test::Bencher) {
+        let image = gray_bench_image(300, 300);
+        let markers = Image::from_fn(300, 300, |x, y| {
+            if x % 50 == 25 && y % 50 == 25 {
+                Luma([(x / 50 + 6 * (y / 50) + 1) as i32])
+            } else {
+                Luma([0])
+            }
+        });
+        b.iter(|| {
+            let labels = watershed(&image, &markers);
+            test::black_box(labels);
+        });
+    }
 }
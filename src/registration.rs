@@ -0,0 +1,259 @@
+//! Functions for estimating the alignment between images.
+//!
+//! This module requires the `fft` feature.
+
+use image::GrayImage;
+use rustfft::{num_complex::Complex, FftDirection, FftPlanner};
+
+/// Estimates the translation between two images of the same size using phase correlation.
+///
+/// Returns `(dx, dy, confidence)`, where `b` is approximately `a` shifted right by `dx`
+/// and down by `dy`, i.e. `b(x, y) ≈ a(x - dx, y - dy)`.
+///
+/// The mean intensity of each image is subtracted and the images are multiplied by a
+/// two-dimensional Hann window, to reduce the effects of the discontinuities at their
+/// borders. The normalised cross-power spectrum of their Fourier transforms is then
+/// inverted to give a correlation surface, whose peak is at the translation between the
+/// images. The peak is refined to subpixel accuracy by fitting a parabola along each axis.
+///
+/// Shifts are found modulo the image size, and reported in the range
+/// `[-width / 2, width / 2)` horizontally and similarly vertically. Shifts are most reliably
+/// recovered when they are small compared to the image size, as the overlap between the
+/// images shrinks as the shift grows.
+///
+/// The confidence is the height of the peak of the correlation surface. This is 1 for
+/// images which are exact circular shifts of each other, and close to 0 for unrelated
+/// images.
+///
+/// # Panics
+///
+/// If `a` and `b` have different dimensions, or are empty.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::registration::phase_correlation;
+///
+/// let pattern = |x: u32, y: u32| Luma([((x * x + 3 * y * y + x * y) % 251) as u8]);
+/// let a = GrayImage::from_fn(64, 64, |x, y| pattern(x + 10, y + 10));
+/// // b shows the same scene as a, shifted 4 pixels right and 3 pixels up
+/// let b = GrayImage::from_fn(64, 64, |x, y| pattern(x + 6, y + 13));
+///
+/// let (dx, dy, confidence) = phase_correlation(&a, &b);
+/// assert!((dx - 4.0).abs() < 0.5);
+/// assert!((dy + 3.0).abs() < 0.5);
+/// assert!(confidence > 0.1);
+/// ```
+pub fn phase_correlation(a: &GrayImage, b: &GrayImage) -> (f32, f32, f32) {
+    assert_eq!(
+        a.dimensions(),
+        b.dimensions(),
+        "images must have the same dimensions"
+    );
+    let (width, height) = a.dimensions();
+    assert!(width > 0 && height > 0, "images must not be empty");
+    let (w, h) = (width as usize, height as usize);
+
+    let mut planner = FftPlanner::<f32>::new();
+    let mut spectrum_a = windowed(a);
+    let mut spectrum_b = windowed(b);
+    fft_2d(&mut planner, &mut spectrum_a, w, h, FftDirection::Forward);
+    fft_2d(&mut planner, &mut spectrum_b, w, h, FftDirection::Forward);
+
+    // The normalised cross-power spectrum. A peak at (dx, dy) in its inverse transform
+    // corresponds to b(x, y) = a(x - dx, y - dy).
+    let mut correlation: Vec<Complex<f32>> = spectrum_a
+        .iter()
+        .zip(&spectrum_b)
+        .map(|(sa, sb)| {
+            let product = sa.conj() * sb;
+            let norm = product.norm();
+            if norm > 1e-12 {
+                product / norm
+            } else {
+                Complex::new(0.0, 0.0)
+            }
+        })
+        .collect();
+    fft_2d(&mut planner, &mut correlation, w, h, FftDirection::Inverse);
+
+    let scale = 1.0 / (w * h) as f32;
+    let surface: Vec<f32> = correlation.iter().map(|c| c.re * scale).collect();
+    let (peak_index, &peak) = surface
+        .iter()
+        .enumerate()
+        .max_by(|(_, p), (_, q)| p.total_cmp(q))
+        .unwrap();
+    let (px, py) = (peak_index % w, peak_index / w);
+
+    let at = |x: usize, y: usize| surface[(y % h) * w + x % w];
+    let offset_x = parabolic_peak_offset(at(px + w - 1, py), peak, at(px + 1, py));
+    let offset_y = parabolic_peak_offset(at(px, py + h - 1), peak, at(px, py + 1));
+
+    let wrap = |p: usize, n: usize| {
+        if p >= (n + 1) / 2 {
+            p as f32 - n as f32
+        } else {
+            p as f32
+        }
+    };
+    (wrap(px, w) + offset_x, wrap(py, h) + offset_y, peak)
+}
+
+// Returns the image with its mean subtracted, multiplied by a Hann window.
+fn windowed(image: &GrayImage) -> Vec<Complex<f32>> {
+    let (width, height) = image.dimensions();
+    let mean = image.iter().map(|&p| p as f32).sum::<f32>() / (width * height) as f32;
+    let hann = |i: u32, n: u32| {
+        if n == 1 {
+            1.0
+        } else {
+            0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / (n - 1) as f32).cos()
+        }
+    };
+    let window_x: Vec<f32> = (0..width).map(|x| hann(x, width)).collect();
+    let window_y: Vec<f32> = (0..height).map(|y| hann(y, height)).collect();
+
+    image
+        .enumerate_pixels()
+        .map(|(x, y, p)| {
+            let value = (p[0] as f32 - mean) * window_x[x as usize] * window_y[y as usize];
+            Complex::new(value, 0.0)
+        })
+        .collect()
+}
+
+// Computes the unnormalised two-dimensional FFT of the row-major width x height data in place.
+fn fft_2d(
+    planner: &mut FftPlanner<f32>,
+    data: &mut [Complex<f32>],
+    width: usize,
+    height: usize,
+    direction: FftDirection,
+) {
+    planner.plan_fft(width, direction).process(data);
+
+    let column_fft = planner.plan_fft(height, direction);
+    let mut column = vec![Complex::new(0.0, 0.0); height];
+    for x in 0..width {
+        for (y, c) in column.iter_mut().enumerate() {
+            *c = data[y * width + x];
+        }
+        column_fft.process(&mut column);
+        for (y, c) in column.iter().enumerate() {
+            data[y * width + x] = *c;
+        }
+    }
+}
+
+// Returns the offset from the centre sample of the vertex of the parabola through
+// three equally spaced samples, clamped to [-0.5, 0.5].
+fn parabolic_peak_offset(left: f32, centre: f32, right: f32) -> f32 {
+    let denominator = left - 2.0 * centre + right;
+    if denominator.abs() < 1e-12 {
+        return 0.0;
+    }
+    (0.5 * (left - right) / denominator).clamp(-0.5, 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::gaussian_blur_f32;
+    use crate::noise::gaussian_noise;
+    use image::{GenericImageView, Luma};
+
+    // A smooth random texture, larger than the crops taken from it
+    fn scene() -> GrayImage {
+        let noise = gaussian_noise(&GrayImage::from_pixel(120, 100, Luma([128])), 0.0, 60.0, 5);
+        gaussian_blur_f32(&noise, 1.5)
+    }
+
+    // The width x height region of the scene with top left corner (x, y)
+    fn crop(scene: &GrayImage, x: u32, y: u32, width: u32, height: u32) -> GrayImage {
+        scene.view(x, y, width, height).to_image()
+    }
+
+    #[test]
+    fn test_phase_correlation_recovers_integer_shifts() {
+        let scene = scene();
+        let a = crop(&scene, 20, 20, 64, 48);
+        for (dx, dy) in [(0i32, 0i32), (5, 0), (0, -7), (-9, 4), (12, 10)] {
+            // b(x, y) = a(x - dx, y - dy)
+            let b = crop(&scene, (20 - dx) as u32, (20 - dy) as u32, 64, 48);
+            let (ex, ey, confidence) = phase_correlation(&a, &b);
+            assert!(
+                (ex - dx as f32).abs() < 1.0 && (ey - dy as f32).abs() < 1.0,
+                "expected ({}, {}), found ({}, {})",
+                dx,
+                dy,
+                ex,
+                ey
+            );
+            assert!(confidence > 0.2, "confidence {}", confidence);
+        }
+    }
+
+    #[test]
+    fn test_phase_correlation_recovers_circular_shifts_exactly() {
+        let a = crop(&scene(), 0, 0, 32, 32);
+        let b = GrayImage::from_fn(32, 32, |x, y| *a.get_pixel((x + 29) % 32, (y + 5) % 32));
+        let (dx, dy, _) = phase_correlation(&a, &b);
+        assert!(
+            (dx - 3.0).abs() < 0.25 && (dy + 5.0).abs() < 0.25,
+            "({}, {})",
+            dx,
+            dy
+        );
+    }
+
+    #[test]
+    fn test_phase_correlation_confidence_is_low_for_unrelated_images() {
+        let scene = scene();
+        let a = crop(&scene, 0, 0, 48, 48);
+        let b = crop(&scene, 5, 2, 48, 48);
+        let unrelated = crop(&gaussian_noise(&scene, 0.0, 50.0, 99), 60, 40, 48, 48);
+        let unrelated = gaussian_blur_f32(&unrelated, 1.5);
+
+        let (_, _, related_confidence) = phase_correlation(&a, &b);
+        let (_, _, unrelated_confidence) = phase_correlation(&a, &unrelated);
+        assert!(
+            unrelated_confidence < 0.5 * related_confidence,
+            "{} vs {}",
+            unrelated_confidence,
+            related_confidence
+        );
+    }
+
+    #[test]
+    fn test_parabolic_peak_offset() {
+        assert_eq!(parabolic_peak_offset(1.0, 2.0, 1.0), 0.0);
+        // Samples of -(x - 0.25)^2 at x = -1, 0, 1
+        let f = |x: f32| -(x - 0.25) * (x - 0.25);
+        assert!((parabolic_peak_offset(f(-1.0), f(0.0), f(1.0)) - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_phase_correlation_rejects_mismatched_sizes() {
+        let _ = phase_correlation(&GrayImage::new(8, 8), &GrayImage::new(8, 9));
+    }
+}
+
+#[cfg(not(miri))]
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::utils::gray_bench_image;
+    use test::{black_box, Bencher};
+
+    #[bench]
+    fn bench_phase_correlation(b: &mut Bencher) {
+        let image = gray_bench_image(256, 256);
+        let shifted = GrayImage::from_fn(256, 256, |x, y| *image.get_pixel((x + 3) % 256, y));
+        b.iter(|| {
+            let estimate = phase_correlation(&image, &shifted);
+            black_box(estimate);
+        });
+    }
+}
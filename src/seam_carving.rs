@@ -26,20 +26,7 @@ where
     P: Pixel<Subpixel = u8> + WithChannel<u16> + WithChannel<i16>,
     <P as WithChannel<u16>>::Pixel: HasBlack,
 {
-    assert!(
-        target_width <= image.width(),
-        "target_width must be <= input image width"
-    );
-
-    let iterations = image.width() - target_width;
-    let mut result = image.clone();
-
-    for _ in 0..iterations {
-        let seam = find_vertical_seam(&result);
-        result = remove_vertical_seam(&result, &seam);
-    }
-
-    result
+    shrink_width_with_options(image, target_width, &SeamCarveOptions::default())
 }
 
 /// Computes an 8-connected path from the bottom of the image to the top whose sum of
@@ -49,114 +36,389 @@ where
     P: Pixel<Subpixel = u8> + WithChannel<u16> + WithChannel<i16>,
     <P as WithChannel<u16>>::Pixel: HasBlack,
 {
-    let (width, height) = image.dimensions();
+    find_vertical_seam_with_options(image, &SeamCarveOptions::default())
+}
+
+/// Returns the result of removing `seam` from `image`.
+// This should just mutate an image in place. The problem is that we don't have a
+// way of talking about views of ImageBuffer without devolving into supporting
+// arbitrary GenericImages. And a lot of other functions don't support those because
+// it would make them a lot slower.
+pub fn remove_vertical_seam<P>(image: &Image<P>, seam: &VerticalSeam) -> Image<P>
+where
+    P: Pixel,
+{
     assert!(
-        image.width() >= 2,
-        "Cannot find seams if image width is < 2"
+        seam.0.len() as u32 == image.height(),
+        "seam length does not match image height"
     );
 
-    let mut gradients = gradients(
-        image,
-        kernel::SOBEL_HORIZONTAL_3X3,
-        kernel::SOBEL_VERTICAL_3X3,
-        |p| {
-            let gradient_sum: u16 = p.channels().iter().sum();
-            let gradient_mean: u16 = gradient_sum / P::CHANNEL_COUNT as u16;
-            Luma([gradient_mean as u32])
-        },
-    );
+    let (width, height) = image.dimensions();
+    let mut out = Image::new(width - 1, height);
 
-    // Find the least energy path through the gradient image.
-    for y in 1..height {
-        for x in 0..width {
-            set_path_energy(&mut gradients, x, y);
+    for y in 0..height {
+        let x_seam = seam.0[(height - y - 1) as usize];
+        for x in 0..x_seam {
+            out.put_pixel(x, y, *image.get_pixel(x, y));
+        }
+        for x in (x_seam + 1)..width {
+            out.put_pixel(x - 1, y, *image.get_pixel(x, y));
         }
     }
 
-    // Retrace our steps to find the vertical seam.
-    let mut min_x = 0;
-    let mut min_energy = gradients.get_pixel(0, height - 1)[0];
+    out
+}
 
-    for x in 1..width {
-        let c = gradients.get_pixel(x, height - 1)[0];
-        if c < min_energy {
-            min_x = x;
-            min_energy = c;
-        }
-    }
+/// The energy used to choose which seams to remove or insert.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum SeamEnergy {
+    /// Seams minimise the sum of the gradient magnitudes of the pixels they pass through.
+    #[default]
+    Backward,
+    /// Seams minimise the intensity differences introduced between pixels that become
+    /// adjacent once the seam is removed, as described in "Improved Seam Carving for Video
+    /// Retargeting" by Rubinstein, Shamir and Avidan. This produces fewer artifacts than
+    /// [`SeamEnergy::Backward`] along straight edges crossed by many seams.
+    Forward,
+}
 
-    let mut seam = Vec::with_capacity(height as usize);
+/// Options controlling [`find_vertical_seam_with_options`], [`shrink_width_with_options`]
+/// and [`insert_seams`].
+#[derive(Clone, Debug, Default)]
+pub struct SeamCarveOptions {
+    /// The energy used to choose seams.
+    pub energy: SeamEnergy,
+    /// If present, seams avoid the pixels at which this mask is non-zero whenever an
+    /// alternative path exists. Must have the same dimensions as the image being carved.
+    pub protect_mask: Option<GrayImage>,
+    /// If present, seams pass through the pixels at which this mask is non-zero whenever
+    /// possible, so that shrinking an image by the width of a masked object removes it.
+    /// Must have the same dimensions as the image being carved.
+    pub remove_mask: Option<GrayImage>,
+}
 
-    seam.push(min_x);
+// Energy added to each protected pixel and subtracted from each removed pixel. Large
+// enough to dominate any path energy, but small enough not to overflow an i64 when summed
+// along a seam.
+const MASK_ENERGY: i64 = 1 << 32;
 
-    let mut last_x = min_x;
+/// Computes an 8-connected path from the bottom of the image to the top with minimal
+/// energy, as configured by `options`.
+///
+/// With default options this is equivalent to [`find_vertical_seam`].
+///
+/// # Panics
+///
+/// If `image` has width less than 2, or either mask in `options` has different
+/// dimensions to `image`.
+pub fn find_vertical_seam_with_options<P>(
+    image: &Image<P>,
+    options: &SeamCarveOptions,
+) -> VerticalSeam
+where
+    P: Pixel<Subpixel = u8> + WithChannel<u16> + WithChannel<i16>,
+    <P as WithChannel<u16>>::Pixel: HasBlack,
+{
+    let (width, height) = image.dimensions();
+    assert!(width >= 2, "Cannot find seams if image width is < 2");
+    for mask in options.protect_mask.iter().chain(&options.remove_mask) {
+        assert_eq!(
+            mask.dimensions(),
+            image.dimensions(),
+            "masks must have the same dimensions as the image"
+        );
+    }
+
+    let (w, h) = (width as usize, height as usize);
+    if h == 0 {
+        return VerticalSeam(vec![]);
+    }
+    let mask_energy = |x: usize, y: usize| {
+        let masked = |mask: &Option<GrayImage>| {
+            mask.as_ref()
+                .is_some_and(|m| m.get_pixel(x as u32, y as u32)[0] > 0)
+        };
+        let mut energy = 0;
+        if masked(&options.protect_mask) {
+            energy += MASK_ENERGY;
+        }
+        if masked(&options.remove_mask) {
+            energy -= MASK_ENERGY;
+        }
+        energy
+    };
+
+    // For each pixel, the energy of the cheapest path to it from the top of the image and
+    // the x-coordinate of the previous pixel on that path.
+    let mut path_energies = vec![0i64; w * h];
+    let mut parents = vec![0usize; w * h];
 
-    for y in (1..height).rev() {
-        let above = gradients.get_pixel(last_x, y - 1)[0];
-        if last_x > 0 {
-            let left = gradients.get_pixel(last_x - 1, y - 1)[0];
-            if left < above {
-                min_x = last_x - 1;
-                min_energy = left;
+    match options.energy {
+        SeamEnergy::Backward => {
+            let gradients = gradients(
+                image,
+                kernel::SOBEL_HORIZONTAL_3X3,
+                kernel::SOBEL_VERTICAL_3X3,
+                |p| {
+                    let gradient_sum: u16 = p.channels().iter().sum();
+                    let gradient_mean: u16 = gradient_sum / P::CHANNEL_COUNT as u16;
+                    Luma([gradient_mean as u32])
+                },
+            );
+            for y in 0..h {
+                for x in 0..w {
+                    let mut energy = gradients.get_pixel(x as u32, y as u32)[0] as i64;
+                    energy += mask_energy(x, y);
+                    if y > 0 {
+                        let row = &path_energies[(y - 1) * w..y * w];
+                        let parent = (x.saturating_sub(1)..(x + 2).min(w))
+                            .min_by_key(|&px| row[px])
+                            .unwrap();
+                        energy += row[parent];
+                        parents[y * w + x] = parent;
+                    }
+                    path_energies[y * w + x] = energy;
+                }
             }
         }
-        if last_x < width - 1 {
-            let right = gradients.get_pixel(last_x + 1, y - 1)[0];
-            if right < min_energy {
-                min_x = last_x + 1;
-                min_energy = right;
+        SeamEnergy::Forward => {
+            let intensities: Vec<i64> = image
+                .pixels()
+                .map(|p| {
+                    let sum: u32 = p.channels().iter().map(|&c| c as u32).sum();
+                    (sum / P::CHANNEL_COUNT as u32) as i64
+                })
+                .collect();
+            let intensity = |x: usize, y: usize| intensities[y * w + x];
+            for y in 0..h {
+                for x in 0..w {
+                    let left = x.saturating_sub(1);
+                    let right = (x + 1).min(w - 1);
+                    let cost_up = (intensity(right, y) - intensity(left, y)).abs();
+                    let mut energy = mask_energy(x, y);
+                    if y == 0 {
+                        energy += cost_up;
+                    } else {
+                        let above = intensity(x, y - 1);
+                        let row = &path_energies[(y - 1) * w..y * w];
+                        let (mut parent, mut path_energy) = (x, row[x] + cost_up);
+                        if x > 0 {
+                            let cost_left = cost_up + (above - intensity(left, y)).abs();
+                            if row[x - 1] + cost_left < path_energy {
+                                (parent, path_energy) = (x - 1, row[x - 1] + cost_left);
+                            }
+                        }
+                        if x < w - 1 {
+                            let cost_right = cost_up + (above - intensity(right, y)).abs();
+                            if row[x + 1] + cost_right < path_energy {
+                                (parent, path_energy) = (x + 1, row[x + 1] + cost_right);
+                            }
+                        }
+                        energy += path_energy;
+                        parents[y * w + x] = parent;
+                    }
+                    path_energies[y * w + x] = energy;
+                }
             }
         }
+    }
 
-        last_x = min_x;
-        seam.push(min_x);
+    let mut x = (0..w)
+        .min_by_key(|&x| path_energies[(h - 1) * w + x])
+        .unwrap();
+    let mut seam = Vec::with_capacity(h);
+    for y in (0..h).rev() {
+        seam.push(x as u32);
+        x = parents[y * w + x];
     }
 
     VerticalSeam(seam)
 }
 
-/// Assumes that the previous rows have all been processed.
-fn set_path_energy(path_energies: &mut Image<Luma<u32>>, x: u32, y: u32) {
-    let above = path_energies.get_pixel(x, y - 1)[0];
-    let mut min_energy = above;
+/// Reduces the width of an image using seam carving, as configured by `options`.
+///
+/// Any masks in `options` are carved along with the image, so that they continue to
+/// apply to the same image content as seams are removed.
+///
+/// # Panics
+///
+/// If `target_width` is greater than the width of `image`, or either mask in `options`
+/// has different dimensions to `image`.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::seam_carving::{shrink_width_with_options, SeamCarveOptions};
+///
+/// // Remove a bright object, even though it has strong edges.
+/// let mut image = GrayImage::from_fn(20, 10, |x, y| Luma([(x * 3 + y * 7) as u8 % 20]));
+/// let mut mask = GrayImage::new(20, 10);
+/// for y in 2..8 {
+///     for x in 8..11 {
+///         image.put_pixel(x, y, Luma([255]));
+///         mask.put_pixel(x, y, Luma([1]));
+///     }
+/// }
+///
+/// let options = SeamCarveOptions {
+///     remove_mask: Some(mask),
+///     ..Default::default()
+/// };
+/// let carved = shrink_width_with_options(&image, 17, &options);
+/// assert_eq!(carved.dimensions(), (17, 10));
+/// assert!(carved.iter().all(|&p| p != 255));
+/// ```
+pub fn shrink_width_with_options<P>(
+    image: &Image<P>,
+    target_width: u32,
+    options: &SeamCarveOptions,
+) -> Image<P>
+where
+    P: Pixel<Subpixel = u8> + WithChannel<u16> + WithChannel<i16>,
+    <P as WithChannel<u16>>::Pixel: HasBlack,
+{
+    assert!(
+        target_width <= image.width(),
+        "target_width must be <= input image width"
+    );
 
-    if x > 0 {
-        let above_left = path_energies.get_pixel(x - 1, y - 1)[0];
-        min_energy = min(above, above_left);
-    }
-    if x < path_energies.width() - 1 {
-        let above_right = path_energies.get_pixel(x + 1, y - 1)[0];
-        min_energy = min(min_energy, above_right);
+    let mut result = image.clone();
+    let mut options = options.clone();
+
+    while result.width() > target_width {
+        let seam = find_vertical_seam_with_options(&result, &options);
+        result = remove_vertical_seam(&result, &seam);
+        for mask in options
+            .protect_mask
+            .iter_mut()
+            .chain(options.remove_mask.iter_mut())
+        {
+            *mask = remove_vertical_seam(mask, &seam);
+        }
     }
 
-    let current = path_energies.get_pixel(x, y)[0];
-    path_energies.put_pixel(x, y, Luma([min_energy + current]));
+    result
 }
 
-/// Returns the result of removing `seam` from `image`.
-// This should just mutate an image in place. The problem is that we don't have a
-// way of talking about views of ImageBuffer without devolving into supporting
-// arbitrary GenericImages. And a lot of other functions don't support those because
-// it would make them a lot slower.
-pub fn remove_vertical_seam<P>(image: &Image<P>, seam: &VerticalSeam) -> Image<P>
+/// Increases the width of an image to `target_width` by duplicating its lowest-energy
+/// seams, as configured by `options`.
+///
+/// The seams to duplicate are chosen by repeatedly removing seams from a copy of the
+/// image, so that the same region of the image is not stretched over and over again.
+/// Each inserted pixel is the average of the seam pixel and its right neighbour. At most
+/// half the current width is inserted at a time, so large enlargements are performed in
+/// several rounds.
+///
+/// Protected pixels are not duplicated unless no alternative exists. The `remove_mask`
+/// in `options` marks pixels that should preferentially be duplicated.
+///
+/// # Panics
+///
+/// If `target_width` is less than the width of `image`, `image` has width less than 2,
+/// or either mask in `options` has different dimensions to `image`.
+///
+/// # Examples
+/// ```
+/// use image::{GrayImage, Luma};
+/// use imageproc::seam_carving::{insert_seams, SeamCarveOptions};
+///
+/// let image = GrayImage::from_fn(10, 5, |x, _| Luma([if x < 5 { 0 } else { 200 }]));
+/// let wider = insert_seams(&image, 14, &SeamCarveOptions::default());
+/// assert_eq!(wider.dimensions(), (14, 5));
+/// ```
+pub fn insert_seams<P>(image: &Image<P>, target_width: u32, options: &SeamCarveOptions) -> Image<P>
 where
-    P: Pixel,
+    P: Pixel<Subpixel = u8> + WithChannel<u16> + WithChannel<i16>,
+    <P as WithChannel<u16>>::Pixel: HasBlack,
 {
     assert!(
-        seam.0.len() as u32 == image.height(),
-        "seam length does not match image height"
+        target_width >= image.width(),
+        "target_width must be >= input image width"
+    );
+    assert!(
+        image.width() >= 2,
+        "Cannot insert seams if image width is < 2"
     );
 
+    let mut result = image.clone();
+    let mut options = options.clone();
+
+    while result.width() < target_width {
+        let count = min(target_width - result.width(), result.width() / 2);
+        let seams = lowest_energy_seams(&result, count, &options);
+        result = duplicate_seams(&result, &seams);
+        for mask in options
+            .protect_mask
+            .iter_mut()
+            .chain(options.remove_mask.iter_mut())
+        {
+            *mask = duplicate_seams(mask, &seams);
+        }
+    }
+
+    result
+}
+
+// Finds `count` seams by repeatedly removing seams from a copy of `image`, returning for
+// each row the x-coordinates in `image` of the pixels removed from that row.
+fn lowest_energy_seams<P>(image: &Image<P>, count: u32, options: &SeamCarveOptions) -> Vec<Vec<u32>>
+where
+    P: Pixel<Subpixel = u8> + WithChannel<u16> + WithChannel<i16>,
+    <P as WithChannel<u16>>::Pixel: HasBlack,
+{
     let (width, height) = image.dimensions();
-    let mut out = Image::new(width - 1, height);
+    let mut positions: Vec<Vec<u32>> = vec![(0..width).collect(); height as usize];
+    let mut removed = vec![Vec::with_capacity(count as usize); height as usize];
+    let mut carved = image.clone();
+    let mut options = options.clone();
 
-    for y in 0..height {
-        let x_seam = seam.0[(height - y - 1) as usize];
-        for x in 0..x_seam {
-            out.put_pixel(x, y, *image.get_pixel(x, y));
+    for _ in 0..count {
+        let seam = find_vertical_seam_with_options(&carved, &options);
+        for (y, &x) in (0..height as usize).rev().zip(&seam.0) {
+            removed[y].push(positions[y].remove(x as usize));
         }
-        for x in (x_seam + 1)..width {
-            out.put_pixel(x - 1, y, *image.get_pixel(x, y));
+        carved = remove_vertical_seam(&carved, &seam);
+        for mask in options
+            .protect_mask
+            .iter_mut()
+            .chain(options.remove_mask.iter_mut())
+        {
+            *mask = remove_vertical_seam(mask, &seam);
+        }
+    }
+
+    for row in &mut removed {
+        row.sort_unstable();
+    }
+    removed
+}
+
+// Inserts a new pixel to the right of each of the given pixels in each row, whose value
+// is the average of that pixel and its right neighbour.
+fn duplicate_seams<P>(image: &Image<P>, seams: &[Vec<u32>]) -> Image<P>
+where
+    P: Pixel<Subpixel = u8>,
+{
+    let (width, height) = image.dimensions();
+    let count = seams.first().map_or(0, |row| row.len() as u32);
+    let mut out = Image::new(width + count, height);
+
+    for y in 0..height {
+        let mut duplicated = seams[y as usize].iter().peekable();
+        let mut out_x = 0;
+        for x in 0..width {
+            let p = *image.get_pixel(x, y);
+            out.put_pixel(out_x, y, p);
+            out_x += 1;
+            if duplicated.next_if_eq(&&x).is_some() {
+                let q = *image.get_pixel(min(x + 1, width - 1), y);
+                out.put_pixel(
+                    out_x,
+                    y,
+                    p.map2(&q, |a, b| ((a as u16 + b as u16 + 1) / 2) as u8),
+                );
+                out_x += 1;
+            }
         }
     }
 
@@ -187,6 +449,207 @@ pub fn draw_vertical_seams(image: &GrayImage, seams: &[VerticalSeam]) -> Image<R
     out
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // A noisy image containing a flat square, which seams with backward energy prefer
+    // to pass through.
+    fn image_with_flat_square() -> GrayImage {
+        let mut rng = StdRng::seed_from_u64(17);
+        let mut image = GrayImage::from_fn(30, 20, |_, _| Luma([rng.gen_range(0..200)]));
+        for y in 5..15 {
+            for x in 10..20 {
+                image.put_pixel(x, y, Luma([230]));
+            }
+        }
+        image
+    }
+
+    fn square_mask() -> GrayImage {
+        GrayImage::from_fn(30, 20, |x, y| {
+            Luma([((10..20).contains(&x) && (5..15).contains(&y)) as u8])
+        })
+    }
+
+    fn count_value(image: &GrayImage, value: u8) -> usize {
+        image.iter().filter(|&&p| p == value).count()
+    }
+
+    #[test]
+    fn test_backward_energy_seam_is_minimal() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let image = GrayImage::from_fn(6, 5, |_, _| Luma([rng.gen_range(0..255)]));
+        let gradients = gradients(
+            &image,
+            kernel::SOBEL_HORIZONTAL_3X3,
+            kernel::SOBEL_VERTICAL_3X3,
+            |p| Luma([p[0] as u32]),
+        );
+
+        // Exhaustively search all connected paths from the top row.
+        fn min_energy(gradients: &Image<Luma<u32>>, x: u32, y: u32) -> u32 {
+            let energy = gradients.get_pixel(x, y)[0];
+            if y + 1 == gradients.height() {
+                return energy;
+            }
+            let below = (x.saturating_sub(1)..min(x + 2, gradients.width()))
+                .map(|nx| min_energy(gradients, nx, y + 1))
+                .min()
+                .unwrap();
+            energy + below
+        }
+        let expected = (0..6).map(|x| min_energy(&gradients, x, 0)).min().unwrap();
+
+        let seam = find_vertical_seam_with_options(&image, &SeamCarveOptions::default());
+        let energy: u32 = (0..5)
+            .rev()
+            .zip(&seam.0)
+            .map(|(y, &x)| gradients.get_pixel(x, y)[0])
+            .sum();
+        assert_eq!(energy, expected);
+    }
+
+    #[test]
+    fn test_seams_are_connected() {
+        let image = image_with_flat_square();
+        for energy in [SeamEnergy::Backward, SeamEnergy::Forward] {
+            let options = SeamCarveOptions {
+                energy,
+                ..Default::default()
+            };
+            let seam = find_vertical_seam_with_options(&image, &options);
+            assert_eq!(seam.0.len(), 20);
+            assert!(seam.0.windows(2).all(|w| w[0].abs_diff(w[1]) <= 1));
+            assert!(seam.0.iter().all(|&x| x < 30));
+        }
+    }
+
+    #[test]
+    fn test_forward_energy_prefers_seams_that_join_similar_pixels() {
+        // Every column has the same gradient energy, but removing a column from the
+        // left half joins pixels of equal intensity while the right half alternates.
+        let image = GrayImage::from_fn(20, 10, |x, y| {
+            if x < 10 {
+                Luma([100])
+            } else {
+                Luma([if (x + y) % 2 == 0 { 0 } else { 200 }])
+            }
+        });
+        let options = SeamCarveOptions {
+            energy: SeamEnergy::Forward,
+            ..Default::default()
+        };
+        let seam = find_vertical_seam_with_options(&image, &options);
+        assert!(seam.0.iter().all(|&x| x < 9), "{:?}", seam.0);
+    }
+
+    #[test]
+    fn test_protect_mask_keeps_pixel_count() {
+        let image = image_with_flat_square();
+        let unprotected = shrink_width_with_options(&image, 22, &SeamCarveOptions::default());
+        assert!(count_value(&unprotected, 230) < 100);
+
+        for energy in [SeamEnergy::Backward, SeamEnergy::Forward] {
+            let options = SeamCarveOptions {
+                energy,
+                protect_mask: Some(square_mask()),
+                ..Default::default()
+            };
+            let carved = shrink_width_with_options(&image, 22, &options);
+            assert_eq!(carved.dimensions(), (22, 20));
+            assert_eq!(count_value(&carved, 230), 100);
+
+            let enlarged = insert_seams(&image, 38, &options);
+            assert_eq!(enlarged.dimensions(), (38, 20));
+            assert_eq!(count_value(&enlarged, 230), 100);
+        }
+    }
+
+    #[test]
+    fn test_remove_mask_removes_object() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut image = GrayImage::from_fn(30, 20, |_, _| Luma([rng.gen_range(0..100)]));
+        let mut mask = GrayImage::new(30, 20);
+        for y in 4..16 {
+            for x in 12..16 {
+                image.put_pixel(x, y, Luma([255]));
+                mask.put_pixel(x, y, Luma([1]));
+            }
+        }
+        for energy in [SeamEnergy::Backward, SeamEnergy::Forward] {
+            let options = SeamCarveOptions {
+                energy,
+                remove_mask: Some(mask.clone()),
+                ..Default::default()
+            };
+            let carved = shrink_width_with_options(&image, 26, &options);
+            assert_eq!(count_value(&carved, 255), 0);
+        }
+    }
+
+    #[test]
+    fn test_insert_seams_then_shrink_restores_width() {
+        let image = RgbImage::from_fn(24, 12, |x, y| {
+            Rgb([(x * 10) as u8, (y * 20) as u8, ((x + y) * 5) as u8])
+        });
+        for energy in [SeamEnergy::Backward, SeamEnergy::Forward] {
+            let options = SeamCarveOptions {
+                energy,
+                ..Default::default()
+            };
+            let enlarged = insert_seams(&image, 40, &options);
+            assert_eq!(enlarged.dimensions(), (40, 12));
+            let restored = shrink_width_with_options(&enlarged, 24, &options);
+            assert_eq!(restored.dimensions(), image.dimensions());
+        }
+    }
+
+    #[test]
+    fn test_insert_seams_interpolates_new_pixels() {
+        let image = GrayImage::from_pixel(6, 4, Luma([80]));
+        let enlarged = insert_seams(&image, 9, &SeamCarveOptions::default());
+        assert_pixels_eq!(enlarged, GrayImage::from_pixel(9, 4, Luma([80])));
+
+        let seams = vec![vec![0], vec![2]];
+        let image = gray_image!(
+            10, 20, 30;
+            10, 20, 30);
+        let expected = gray_image!(
+            10, 15, 20, 30;
+            10, 20, 30, 30);
+        assert_pixels_eq!(duplicate_seams(&image, &seams), expected);
+    }
+
+    #[test]
+    fn test_shrink_width_of_empty_height_image() {
+        let image = GrayImage::new(5, 0);
+        for energy in [SeamEnergy::Backward, SeamEnergy::Forward] {
+            let options = SeamCarveOptions {
+                energy,
+                ..Default::default()
+            };
+            assert!(find_vertical_seam_with_options(&image, &options)
+                .0
+                .is_empty());
+            let carved = shrink_width_with_options(&image, 3, &options);
+            assert_eq!(carved.dimensions(), (3, 0));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mismatched_mask_panics() {
+        let options = SeamCarveOptions {
+            protect_mask: Some(GrayImage::new(5, 5)),
+            ..Default::default()
+        };
+        let _ = find_vertical_seam_with_options(&GrayImage::new(6, 5), &options);
+    }
+}
+
 #[cfg(not(miri))]
 #[cfg(test)]
 mod benches {
@@ -1,8 +1,10 @@
 //! Functions for suppressing non-maximal values.
 
 use crate::definitions::{Image, Position, Score};
+use crate::point::Point;
 use image::{GenericImage, Luma, Primitive};
 use std::cmp;
+use std::collections::HashMap;
 
 /// Returned image has zeroes for all inputs pixels which do not have the greatest
 /// intensity in the (2 * radius + 1) square block centred on them.
@@ -46,12 +48,12 @@ where
                 }
             }
 
-            let x0 = if radius >= best_x { 0 } else { best_x - radius };
+            let x0 = best_x.saturating_sub(radius);
             let x1 = x;
             let x2 = cmp::min(width, x + radius + 1);
             let x3 = cmp::min(width, best_x + radius + 1);
 
-            let y0 = if radius >= best_y { 0 } else { best_y - radius };
+            let y0 = best_y.saturating_sub(radius);
             let y1 = y;
             let y2 = cmp::min(height, y + radius + 1);
             let y3 = cmp::min(height, best_y + radius + 1);
@@ -131,7 +133,7 @@ where
         let cs = t.score();
 
         let mut is_max = true;
-        let row_lower = if radius > cy { 0 } else { cy - radius };
+        let row_lower = cy.saturating_sub(radius);
         let row_upper = if cy + radius + 1 > height {
             height
         } else {
@@ -171,15 +173,135 @@ where
     max_ts
 }
 
+/// Returns the locations of the pixels of `responses` whose value is at least `threshold`
+/// and is the greatest value in the `window x window` square centred on them, in raster
+/// order. Ties are resolved lexicographically, in favour of the pixel with the smallest
+/// `(y, x)`. NaN values are ignored.
+///
+/// This is the non-maximum suppression step of many keypoint detectors, applied to their
+/// response maps.
+///
+/// # Panics
+///
+/// If `window` is not odd.
+///
+/// # Examples
+/// ```
+/// use image::Luma;
+/// use imageproc::definitions::Image;
+/// use imageproc::point::Point;
+/// use imageproc::suppress::non_max_suppression;
+///
+/// let mut responses: Image<Luma<f32>> = Image::new(10, 10);
+/// responses.put_pixel(2, 2, Luma([5.0]));
+/// responses.put_pixel(3, 3, Luma([4.0]));
+/// responses.put_pixel(7, 6, Luma([1.0]));
+///
+/// assert_eq!(non_max_suppression(&responses, 3, 0.5), vec![Point::new(2, 2), Point::new(7, 6)]);
+/// assert_eq!(non_max_suppression(&responses, 3, 2.0), vec![Point::new(2, 2)]);
+/// ```
+pub fn non_max_suppression(
+    responses: &Image<Luma<f32>>,
+    window: u32,
+    threshold: f32,
+) -> Vec<Point<u32>> {
+    assert!(window % 2 == 1, "window must be odd");
+    let candidates: Vec<Response> = responses
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p[0] >= threshold)
+        .map(|(x, y, p)| Response { x, y, score: p[0] })
+        .collect();
+    local_maxima(&candidates, window / 2)
+        .iter()
+        .map(|r| Point::new(r.x, r.y))
+        .collect()
+}
+
+#[derive(Copy, Clone)]
+struct Response {
+    x: u32,
+    y: u32,
+    score: f32,
+}
+
+impl Position for Response {
+    fn x(&self) -> u32 {
+        self.x
+    }
+
+    fn y(&self) -> u32 {
+        self.y
+    }
+}
+
+impl Score for Response {
+    fn score(&self) -> f32 {
+        self.score
+    }
+}
+
+/// Greedily suppresses keypoints which are close to keypoints with higher scores.
+///
+/// Keypoints are considered in order of decreasing score, and a keypoint is kept if it is
+/// further than `radius` from every keypoint kept so far. Keypoints with equal scores are
+/// considered in the order in which they are given. Returns the kept keypoints in order
+/// of decreasing score.
+///
+/// # Panics
+///
+/// If `radius` is not strictly positive.
+///
+/// # Examples
+/// ```
+/// use imageproc::point::Point;
+/// use imageproc::suppress::suppress_keypoints;
+///
+/// let keypoints = [
+///     (Point::new(10.0, 10.0), 1.0),
+///     (Point::new(12.5, 11.0), 3.0),
+///     (Point::new(30.0, 10.0), 2.0),
+/// ];
+/// let kept = suppress_keypoints(&keypoints, 5.0);
+/// assert_eq!(kept, vec![keypoints[1], keypoints[2]]);
+/// ```
+pub fn suppress_keypoints(points: &[(Point<f32>, f32)], radius: f32) -> Vec<(Point<f32>, f32)> {
+    assert!(radius > 0.0, "radius must be > 0.0");
+
+    let mut ordered = points.to_vec();
+    ordered.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    // Kept points, bucketed into a grid of cells of side radius, so that only
+    // neighbouring cells need to be searched
+    let cell = |p: &Point<f32>| ((p.x / radius).floor() as i64, (p.y / radius).floor() as i64);
+    let mut grid: HashMap<(i64, i64), Vec<Point<f32>>> = HashMap::new();
+    let mut kept = Vec::new();
+    for (point, score) in ordered {
+        let (cx, cy) = cell(&point);
+        let is_isolated = (cy - 1..=cy + 1).all(|y| {
+            (cx - 1..=cx + 1).all(|x| {
+                grid.get(&(x, y)).map_or(true, |others| {
+                    others.iter().all(|o| {
+                        let (dx, dy) = (o.x - point.x, o.y - point.y);
+                        dx * dx + dy * dy > radius * radius
+                    })
+                })
+            })
+        });
+        if is_isolated {
+            grid.entry((cx, cy)).or_default().push(point);
+            kept.push((point, score));
+        }
+    }
+    kept
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{local_maxima, suppress_non_maximum};
-    use crate::definitions::{Image, Position, Score};
+    use super::*;
     use crate::property_testing::GrayTestImage;
     use crate::utils::pixel_diff_summary;
-    use image::{GenericImage, GrayImage, Luma, Primitive};
+    use image::GrayImage;
     use quickcheck::{quickcheck, TestResult};
-    use std::cmp;
 
     #[derive(PartialEq, Debug, Copy, Clone)]
     pub(super) struct T {
@@ -333,11 +455,93 @@ mod tests {
         quickcheck(prop as fn(GrayTestImage) -> TestResult);
     }
 
+    #[test]
+    fn test_non_max_suppression_keeps_stronger_of_close_peaks() {
+        // Two Gaussian peaks of different heights, four pixels apart
+        let responses: Image<Luma<f32>> = Image::from_fn(30, 20, |x, y| {
+            let peak = |cx: f32, cy: f32, height: f32| {
+                let d2 = (x as f32 - cx).powi(2) + (y as f32 - cy).powi(2);
+                height * (-d2 / 2.0).exp()
+            };
+            Luma([peak(10.0, 10.0, 5.0) + peak(14.0, 10.0, 3.0)])
+        });
+
+        assert_eq!(
+            non_max_suppression(&responses, 11, 0.5),
+            vec![Point::new(10, 10)]
+        );
+        // A window too small to contain both peaks keeps both
+        assert_eq!(
+            non_max_suppression(&responses, 5, 0.5),
+            vec![Point::new(10, 10), Point::new(14, 10)]
+        );
+        // The weaker peak is also removed by a threshold above its height
+        assert_eq!(
+            non_max_suppression(&responses, 5, 4.0),
+            vec![Point::new(10, 10)]
+        );
+    }
+
+    #[test]
+    fn test_non_max_suppression_ignores_nan_and_resolves_ties() {
+        let mut responses: Image<Luma<f32>> = Image::new(6, 6);
+        responses.put_pixel(0, 0, Luma([f32::NAN]));
+        responses.put_pixel(3, 2, Luma([2.0]));
+        responses.put_pixel(2, 3, Luma([2.0]));
+        assert_eq!(
+            non_max_suppression(&responses, 3, 1.0),
+            vec![Point::new(3, 2)]
+        );
+        assert!(non_max_suppression(&Image::new(0, 0), 3, 0.0).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_max_suppression_rejects_even_window() {
+        let _ = non_max_suppression(&Image::new(5, 5), 4, 0.0);
+    }
+
+    #[test]
+    fn test_suppress_keypoints() {
+        let keypoints = vec![
+            (Point::new(0.0, 0.0), 1.0),
+            (Point::new(3.0, 4.0), 2.0),
+            (Point::new(6.1, 8.0), 1.5),
+            (Point::new(20.0, 20.0), 0.5),
+            (Point::new(20.0, 21.0), 0.5),
+        ];
+        let kept = suppress_keypoints(&keypoints, 5.0);
+        // (0, 0) is exactly 5 from the strongest point, so is suppressed
+        assert_eq!(
+            kept,
+            vec![keypoints[1], keypoints[2], keypoints[3]],
+            "{:?}",
+            kept
+        );
+
+        // Every point is kept if the radius is small enough
+        assert_eq!(suppress_keypoints(&keypoints, 0.5).len(), keypoints.len());
+    }
+
+    #[test]
+    fn test_suppress_keypoints_handles_negative_coordinates() {
+        let keypoints = [
+            (Point::new(-0.5, -0.5), 1.0),
+            (Point::new(0.5, 0.5), 2.0),
+            (Point::new(-9.0, 0.0), 3.0),
+        ];
+        let kept = suppress_keypoints(&keypoints, 2.0);
+        assert_eq!(kept, vec![keypoints[2], keypoints[1]]);
+    }
+
     #[test]
     fn test_step() {
         assert_eq!((0u32..5).step_by(4).collect::<Vec<u32>>(), vec![0, 4]);
         assert_eq!((0u32..4).step_by(4).collect::<Vec<u32>>(), vec![0]);
-        assert_eq!((4u32..4).step_by(4).collect::<Vec<u32>>(), vec![]);
+        assert_eq!(
+            (4u32..4).step_by(4).collect::<Vec<u32>>(),
+            Vec::<u32>::new()
+        );
     }
 }
 
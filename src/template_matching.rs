@@ -1,5 +1,7 @@
 //! Functions for performing template matching.
 use crate::definitions::Image;
+use crate::point::Point;
+use image::imageops::{resize, FilterType};
 use image::{GenericImageView, GrayImage, Luma, Primitive};
 
 #[cfg_attr(feature = "katexit", katexit::katexit)]
@@ -60,7 +62,29 @@ pub enum MatchTemplateMethod {
     ///         {\sqrt{ \sum_{x', y'}{(\text{template}(x', y') \cdot \text{mask}(x', y'))}^2 \cdot \sum_{x', y'}{(\text{image}(x+x', y+y') \cdot \text{mask}(x', y'))}^2 }}
     /// $$
     ///
+    /// For non-negative intensities the output lies in `[0, 1]`, and is unchanged by scaling
+    /// the intensities of the image, but not by adding a constant offset to them.
     CrossCorrelationNormalized,
+    /// Normalized cross correlation after subtracting the mean of both the template and the
+    /// image region from each of their pixels. Larger values indicate a better match.
+    ///
+    /// Without a mask:
+    /// $$
+    /// \text{output}(x, y) = \frac{\sum_{x', y'} \left( T'(x', y') \cdot I'(x+x', y+y') \right)}
+    ///                     {\sqrt{ \sum_{x', y'} {T'(x', y')}^2 \cdot \sum_{x', y'} {I'(x+x', y+y')}^2 }}
+    /// $$
+    ///
+    /// where $T'(x', y') = \text{template}(x', y') - \overline{\text{template}}$ and
+    /// $I'(x+x', y+y') = \text{image}(x+x', y+y') - \overline{\text{image}}_{x, y}$,
+    /// with $\overline{\text{image}}_{x, y}$ the mean of the image region at $(x, y)$.
+    ///
+    /// With a mask the means and sums are weighted by ${\text{mask}(x', y')}^2$.
+    ///
+    /// The output lies in `[-1, 1]` and is unchanged by any positive scaling of the image
+    /// intensities followed by adding a constant offset, so is robust to changes in
+    /// brightness and contrast. The output is `0` where either the template or the image
+    /// region is constant.
+    CorrelationCoefficientNormalized,
 }
 
 /// Slides a `template` over an `image` and scores the match at each point using
@@ -88,6 +112,7 @@ pub fn match_template(
         M::SumOfSquaredErrorsNormalized => methods::SseNormalized::match_template(input),
         M::CrossCorrelation => methods::Ccorr::match_template(input),
         M::CrossCorrelationNormalized => methods::CcorrNormalized::match_template(input),
+        M::CorrelationCoefficientNormalized => methods::CcoeffNormalized::match_template(input),
     }
 }
 
@@ -106,6 +131,9 @@ pub fn match_template_parallel(
         M::SumOfSquaredErrorsNormalized => methods::SseNormalized::match_template_parallel(input),
         M::CrossCorrelation => methods::Ccorr::match_template_parallel(input),
         M::CrossCorrelationNormalized => methods::CcorrNormalized::match_template_parallel(input),
+        M::CorrelationCoefficientNormalized => {
+            methods::CcoeffNormalized::match_template_parallel(input)
+        }
     }
 }
 
@@ -136,6 +164,9 @@ pub fn match_template_with_mask(
         M::SumOfSquaredErrorsNormalized => methods::SseNormalizedWithMask::match_template(input),
         M::CrossCorrelation => methods::CcorrWithMask::match_template(input),
         M::CrossCorrelationNormalized => methods::CcorrNormalizedWithMask::match_template(input),
+        M::CorrelationCoefficientNormalized => {
+            methods::CcoeffNormalizedWithMask::match_template(input)
+        }
     }
 }
 
@@ -159,9 +190,110 @@ pub fn match_template_with_mask_parallel(
         M::CrossCorrelationNormalized => {
             methods::CcorrNormalizedWithMask::match_template_parallel(input)
         }
+        M::CorrelationCoefficientNormalized => {
+            methods::CcoeffNormalizedWithMask::match_template_parallel(input)
+        }
     }
 }
 
+impl MatchTemplateMethod {
+    // Whether smaller scores indicate a better match for this method.
+    fn smaller_is_better(self) -> bool {
+        matches!(
+            self,
+            MatchTemplateMethod::SumOfSquaredErrors
+                | MatchTemplateMethod::SumOfSquaredErrorsNormalized
+        )
+    }
+}
+
+/// The best match of a resized template, as found by [`match_template_multiscale`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScaledMatch {
+    /// The top left corner of the best match of the resized template in the image.
+    pub location: Point<u32>,
+    /// The factor by which the template was resized.
+    pub scale: f32,
+    /// The score of the match using the requested [`MatchTemplateMethod`].
+    pub score: f32,
+}
+
+/// Matches `template` against `image` after resizing the template by each factor
+/// in `scales`, to find objects whose size in `image` is unknown.
+///
+/// For each scale the template is resized using bilinear interpolation and matched
+/// using [`match_template`]. The best match at each scale is returned, and matches are
+/// sorted so that the first is the best overall. Scales for which the resized template
+/// is empty or larger than `image` are skipped.
+///
+/// Scores are only comparable between scales for the normalized methods, so
+/// [`MatchTemplateMethod::SumOfSquaredErrors`] and [`MatchTemplateMethod::CrossCorrelation`]
+/// will favour small and large scales respectively.
+///
+/// # Panics
+///
+/// If any of `scales` is not strictly positive.
+///
+/// # Examples
+/// ```
+/// use image::imageops::{replace, resize, FilterType};
+/// use image::{GrayImage, Luma};
+/// use imageproc::template_matching::{match_template_multiscale, MatchTemplateMethod};
+///
+/// let template = GrayImage::from_fn(12, 12, |x, y| Luma([((x * y) % 13 * 19) as u8]));
+/// let mut image = GrayImage::from_pixel(60, 60, Luma([50]));
+/// replace(&mut image, &resize(&template, 24, 24, FilterType::Triangle), 20, 10);
+///
+/// let matches = match_template_multiscale(
+///     &image,
+///     &template,
+///     &[1.0, 2.0, 3.0],
+///     MatchTemplateMethod::CorrelationCoefficientNormalized,
+/// );
+/// assert_eq!(matches[0].scale, 2.0);
+/// assert_eq!((matches[0].location.x, matches[0].location.y), (20, 10));
+/// ```
+pub fn match_template_multiscale(
+    image: &GrayImage,
+    template: &GrayImage,
+    scales: &[f32],
+    method: MatchTemplateMethod,
+) -> Vec<ScaledMatch> {
+    assert!(
+        scales.iter().all(|&s| s > 0.0),
+        "scales must all be strictly positive"
+    );
+
+    let mut matches = Vec::with_capacity(scales.len());
+    for &scale in scales {
+        let width = (template.width() as f32 * scale).round() as u32;
+        let height = (template.height() as f32 * scale).round() as u32;
+        if width == 0 || height == 0 || width > image.width() || height > image.height() {
+            continue;
+        }
+
+        let scaled = resize(template, width, height, FilterType::Triangle);
+        let extremes = find_extremes(&match_template(image, &scaled, method));
+        let (score, (x, y)) = if method.smaller_is_better() {
+            (extremes.min_value, extremes.min_value_location)
+        } else {
+            (extremes.max_value, extremes.max_value_location)
+        };
+        matches.push(ScaledMatch {
+            location: Point::new(x, y),
+            scale,
+            score,
+        });
+    }
+
+    if method.smaller_is_better() {
+        matches.sort_by(|a, b| a.score.total_cmp(&b.score));
+    } else {
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    }
+    matches
+}
+
 trait MatchTemplate<'a>
 where
     Self: Sync + Sized,
@@ -209,6 +341,7 @@ trait OutputDims {
 
 mod methods {
     use super::*;
+    use crate::integral_image::{integral_image, integral_squared_image, sum_image_pixels};
 
     pub struct Sse;
     impl<'a> MatchTemplate<'a> for Sse {
@@ -391,8 +524,129 @@ mod methods {
         }
     }
 
+    pub struct CcoeffNormalized {
+        template_mean: f64,
+        template_variance_sum: f64,
+        integral: Image<Luma<u64>>,
+        integral_squared: Image<Luma<u64>>,
+    }
+    impl<'a> MatchTemplate<'a> for CcoeffNormalized {
+        type Input = ImageTemplate<'a>;
+        fn init(input: &Self::Input) -> Self {
+            let n = input.template.len() as f64;
+            let sum: f64 = input.template.iter().map(|&x| x as f64).sum();
+            let template_mean = sum / n;
+            let template_variance_sum = input
+                .template
+                .iter()
+                .map(|&x| (x as f64 - template_mean).powi(2))
+                .sum();
+            Self {
+                template_mean,
+                template_variance_sum,
+                integral: integral_image(input.image),
+                integral_squared: integral_squared_image(input.image),
+            }
+        }
+        fn score_at(&self, at: (u32, u32), input: &Self::Input) -> f32 {
+            let mut it = 0f64;
+            unsafe {
+                input.slide_window_at(at, |i, t| {
+                    it += (i * t) as f64;
+                })
+            };
+            let (width, height) = input.template.dimensions();
+            let (right, bottom) = (at.0 + width - 1, at.1 + height - 1);
+            let n = (width * height) as f64;
+            let sum = sum_image_pixels(&self.integral, at.0, at.1, right, bottom)[0] as f64;
+            let squared_sum =
+                sum_image_pixels(&self.integral_squared, at.0, at.1, right, bottom)[0] as f64;
+
+            let image_variance_sum = squared_sum - sum * sum / n;
+            correlation_coefficient(
+                it - self.template_mean * sum,
+                self.template_variance_sum,
+                image_variance_sum,
+            )
+        }
+    }
+
+    pub struct CcoeffNormalizedWithMask {
+        weights: Vec<f32>,
+        zero_mean_template: Vec<f32>,
+        template_variance_sum: f64,
+        weight_sum: f64,
+    }
+    impl<'a> MatchTemplate<'a> for CcoeffNormalizedWithMask {
+        type Input = ImageTemplateMask<'a>;
+        fn init(input: &Self::Input) -> Self {
+            let weights: Vec<f32> = input.mask.iter().map(|&m| (m as f32).powi(2)).collect();
+            let weight_sum: f64 = weights.iter().map(|&w| w as f64).sum();
+            let weighted_sum: f64 = input
+                .inner
+                .template
+                .iter()
+                .zip(&weights)
+                .map(|(&t, &w)| t as f64 * w as f64)
+                .sum();
+            let template_mean = if weight_sum > 0.0 {
+                weighted_sum / weight_sum
+            } else {
+                0.0
+            };
+            let zero_mean_template: Vec<f32> = input
+                .inner
+                .template
+                .iter()
+                .map(|&t| (t as f64 - template_mean) as f32)
+                .collect();
+            let template_variance_sum = zero_mean_template
+                .iter()
+                .zip(&weights)
+                .map(|(&t, &w)| (t as f64).powi(2) * w as f64)
+                .sum();
+            Self {
+                weights,
+                zero_mean_template,
+                template_variance_sum,
+                weight_sum,
+            }
+        }
+        fn score_at(&self, at: (u32, u32), input: &Self::Input) -> f32 {
+            let (mut it, mut sum, mut squared_sum) = (0f64, 0f64, 0f64);
+            let mut k = 0;
+            unsafe {
+                input.slide_window_at(at, |i, _, _| {
+                    let w = self.weights[k] as f64;
+                    let i = i as f64;
+                    it += w * i * self.zero_mean_template[k] as f64;
+                    sum += w * i;
+                    squared_sum += w * i * i;
+                    k += 1;
+                })
+            };
+            if self.weight_sum == 0.0 {
+                return 0.0;
+            }
+            let image_variance_sum = squared_sum - sum * sum / self.weight_sum;
+            correlation_coefficient(it, self.template_variance_sum, image_variance_sum)
+        }
+    }
+
+    // Divides a covariance by the product of standard deviations, returning 0 if either
+    // variance is zero. Variances computed from running sums can be slightly negative
+    // for constant regions, so these are treated as zero too.
+    fn correlation_coefficient(covariance: f64, variance_a: f64, variance_b: f64) -> f32 {
+        let norm = (variance_a * variance_b).sqrt();
+        if variance_a > 1e-9 && variance_b > 1e-9 && norm > 0.0 {
+            (covariance / norm).clamp(-1.0, 1.0) as f32
+        } else {
+            0.0
+        }
+    }
+
     fn square_sum(input: &GrayImage) -> f32 {
-        input.iter().map(|&x| (x as f32 * x as f32)).sum()
+        input.iter().map(|&x| x as f32 * x as f32).sum()
     }
     fn mult_square_sum(a: &GrayImage, b: &GrayImage) -> f32 {
         a.iter()
@@ -825,6 +1079,195 @@ mod tests {
         }
     }
 
+    fn random_image(width: u32, height: u32, seed: u64) -> GrayImage {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        let mut rng = StdRng::seed_from_u64(seed);
+        GrayImage::from_fn(width, height, |_, _| Luma([rng.gen()]))
+    }
+
+    #[test]
+    fn match_template_correlation_coefficient_normalized() {
+        let image = gray_image!(
+            1, 4, 2;
+            2, 1, 3;
+            3, 3, 4
+        );
+        let template = gray_image!(
+            1, 2;
+            3, 4
+        );
+        let expected = gray_image!(type: f32,
+            -0.18257418, -0.4;
+            0.6741999, 0.9233805
+        );
+        let actual = match_template(
+            &image,
+            &template,
+            MatchTemplateMethod::CorrelationCoefficientNormalized,
+        );
+        assert_pixels_eq_within!(actual, expected, 1e-6);
+
+        #[cfg(feature = "rayon")]
+        {
+            let actual_parallel = match_template_parallel(
+                &image,
+                &template,
+                MatchTemplateMethod::CorrelationCoefficientNormalized,
+            );
+            assert_pixels_eq_within!(actual_parallel, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn match_template_correlation_coefficient_normalized_is_zero_for_constant_regions() {
+        let image = GrayImage::from_pixel(6, 5, Luma([70]));
+        let template = random_image(3, 3, 1);
+        let result = match_template(
+            &image,
+            &template,
+            MatchTemplateMethod::CorrelationCoefficientNormalized,
+        );
+        assert!(result.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn match_template_correlation_coefficient_normalized_with_full_mask_matches_unmasked() {
+        let image = random_image(20, 15, 2);
+        let template = random_image(5, 4, 3);
+        let mask = GrayImage::from_pixel(5, 4, Luma([1]));
+        let method = MatchTemplateMethod::CorrelationCoefficientNormalized;
+        let unmasked = match_template(&image, &template, method);
+        let masked = match_template_with_mask(&image, &template, method, &mask);
+        assert_pixels_eq_within!(masked, unmasked, 1e-5);
+        assert!(unmasked.iter().all(|&s| (-1.0..=1.0).contains(&s)));
+    }
+
+    #[test]
+    fn match_template_correlation_coefficient_normalized_with_mask_ignores_masked_pixels() {
+        let image = random_image(20, 15, 4);
+        let mut template = image.view(6, 5, 5, 5).to_image();
+        let mut mask = GrayImage::from_pixel(5, 5, Luma([2]));
+        for x in 0..5 {
+            template.put_pixel(x, 2, Luma([255 - template.get_pixel(x, 2)[0]]));
+            mask.put_pixel(x, 2, Luma([0]));
+        }
+        let result = match_template_with_mask(
+            &image,
+            &template,
+            MatchTemplateMethod::CorrelationCoefficientNormalized,
+            &mask,
+        );
+        let extremes = find_extremes(&result);
+        assert_eq!(extremes.max_value_location, (6, 5));
+        assert!((extremes.max_value - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn normalized_methods_find_template_in_brightened_image() {
+        // Low contrast texture with a dark flat patch in one corner
+        let image = GrayImage::from_fn(40, 30, {
+            let texture = random_image(40, 30, 5);
+            move |x, y| {
+                if x < 10 && y < 10 {
+                    Luma([0])
+                } else {
+                    Luma([40 + texture.get_pixel(x, y)[0] / 5])
+                }
+            }
+        });
+        let (x, y) = (21, 13);
+        let template = image.view(x, y, 8, 8).to_image();
+        let brightened = GrayImage::from_fn(40, 30, |x, y| Luma([image.get_pixel(x, y)[0] + 100]));
+
+        // The brightened dark patch is closer to the template than its true location
+        let sse = match_template(
+            &brightened,
+            &template,
+            MatchTemplateMethod::SumOfSquaredErrors,
+        );
+        let (sse_x, sse_y) = find_extremes(&sse).min_value_location;
+        assert!(sse_x < 10 && sse_y < 10);
+
+        for method in [
+            MatchTemplateMethod::CorrelationCoefficientNormalized,
+            MatchTemplateMethod::CrossCorrelationNormalized,
+        ] {
+            let result = match_template(&brightened, &template, method);
+            assert_eq!(
+                find_extremes(&result).max_value_location,
+                (x, y),
+                "{:?}",
+                method
+            );
+        }
+
+        let ccoeff = match_template(
+            &brightened,
+            &template,
+            MatchTemplateMethod::CorrelationCoefficientNormalized,
+        );
+        assert!(find_extremes(&ccoeff).max_value > 0.99);
+        assert!(ccoeff.iter().all(|&s| (-1.0..=1.0).contains(&s)));
+    }
+
+    #[test]
+    fn match_template_multiscale_finds_scaled_copy() {
+        use image::imageops::replace;
+
+        // A smooth pattern, so that resizing it up and back down changes it little
+        let template = GrayImage::from_fn(16, 16, |x, y| {
+            let (x, y) = (x as f32, y as f32);
+            Luma([(127.0 + 60.0 * (x / 2.5).sin() + 60.0 * (y / 3.5).cos()) as u8])
+        });
+        let mut image = random_image(80, 70, 6);
+        let scaled = resize(&template, 24, 24, FilterType::Triangle);
+        replace(&mut image, &scaled, 37, 29);
+
+        let scales = [0.5, 0.75, 1.0, 1.25, 1.4, 1.5, 1.6, 1.75, 2.0];
+        for method in [
+            MatchTemplateMethod::SumOfSquaredErrorsNormalized,
+            MatchTemplateMethod::CorrelationCoefficientNormalized,
+        ] {
+            let matches = match_template_multiscale(&image, &template, &scales, method);
+            assert_eq!(matches.len(), scales.len());
+            let best = matches[0];
+            assert!((best.scale - 1.5).abs() < 0.11, "{:?}: {:?}", method, best);
+            assert!(
+                (best.location.x as i32 - 37).abs() <= 1
+                    && (best.location.y as i32 - 29).abs() <= 1,
+                "{:?}: {:?}",
+                method,
+                best
+            );
+        }
+    }
+
+    #[test]
+    fn match_template_multiscale_skips_scales_too_large_for_image() {
+        let image = random_image(20, 20, 7);
+        let template = random_image(8, 8, 8);
+        let matches = match_template_multiscale(
+            &image,
+            &template,
+            &[0.01, 1.0, 2.0, 3.0],
+            MatchTemplateMethod::CrossCorrelationNormalized,
+        );
+        let mut scales: Vec<f32> = matches.iter().map(|m| m.scale).collect();
+        scales.sort_by(f32::total_cmp);
+        assert_eq!(scales, vec![1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn match_template_multiscale_rejects_non_positive_scales() {
+        let _ = match_template_multiscale(
+            &GrayImage::new(10, 10),
+            &GrayImage::new(3, 3),
+            &[1.0, 0.0],
+            MatchTemplateMethod::SumOfSquaredErrors,
+        );
+    }
+
     #[test]
     fn test_find_extremes() {
         let image = gray_image!(
@@ -956,4 +1399,18 @@ mod benches {
         image_size: 100,
         template_size: 16,
         method: MatchTemplateMethod::SumOfSquaredErrorsNormalized);
+
+    #[bench]
+    fn bench_match_template_s100_t16_ccoeff_norm(b: &mut Bencher) {
+        let image = gray_bench_image(100, 100);
+        let template = gray_bench_image(16, 16);
+        b.iter(|| {
+            let result = match_template(
+                &image,
+                &template,
+                MatchTemplateMethod::CorrelationCoefficientNormalized,
+            );
+            black_box(result);
+        })
+    }
 }
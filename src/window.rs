@@ -3,72 +3,509 @@
 use image::{
     buffer::ConvertBuffer,
     imageops::{resize, FilterType},
-    GenericImageView, RgbaImage,
+    DynamicImage, GenericImageView, Pixel, Primitive, Rgba, RgbaImage,
 };
+use num::cast;
 use sdl2::{
     event::{Event, WindowEvent},
     keyboard::Keycode,
+    mouse::{MouseButton, MouseWheelDirection},
     pixels::{Color, PixelFormatEnum},
     rect::Rect,
-    render::{Canvas, TextureCreator, WindowCanvas},
+    render::{BlendMode, Canvas, Texture, TextureCreator, WindowCanvas},
     surface::Surface,
     video::{Window, WindowContext},
+    EventPump, Sdl, VideoSubsystem,
 };
+use std::{
+    fmt,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// The minimum window width or height
+const MIN_WINDOW_DIMENSION: u32 = 150;
+
+/// An error encountered while creating or rendering to a window.
+///
+/// Each variant holds the error message reported by sdl2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowError {
+    /// Initialising the sdl2 context, video subsystem or event pump failed.
+    /// This is typically the case when no display is available.
+    Init(String),
+    /// Creating a window or its canvas failed.
+    Window(String),
+    /// Creating a surface from the image data failed.
+    Surface(String),
+    /// Creating a texture from the surface failed.
+    Texture(String),
+    /// Copying the texture to the window canvas failed.
+    Canvas(String),
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowError::Init(e) => write!(f, "couldn't initialise sdl2: {}", e),
+            WindowError::Window(e) => write!(f, "couldn't create window: {}", e),
+            WindowError::Surface(e) => write!(f, "couldn't create surface: {}", e),
+            WindowError::Texture(e) => write!(f, "couldn't create texture from surface: {}", e),
+            WindowError::Canvas(e) => write!(f, "couldn't copy texture to canvas: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WindowError {}
+
+/// Options controlling how images are displayed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// The filter used when shrinking images to fit the window. Defaults to
+    /// [`FilterType::Triangle`].
+    ///
+    /// Use [`FilterType::Nearest`] to see the exact pixel values of e.g. thresholded
+    /// images. Images enlarged by zooming in are always displayed using nearest
+    /// neighbour sampling.
+    pub filter: FilterType,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        DisplayOptions {
+            filter: FilterType::Triangle,
+        }
+    }
+}
 
 /// Displays the provided RGBA image in a new window.
 ///
 /// The minimum window width or height is 150 pixels - input values less than this
 /// will be rounded up to the minimum.
+///
+/// This function blocks until the window is closed. Use [`display_image_async`]
+/// to keep processing while the window is open.
+///
+/// The image is initially scaled to fit the window. Use the mouse wheel to
+/// zoom in or out around the cursor, drag with the left or middle mouse button
+/// to pan, and press `0` to reset to the fit-to-window view. Press `S` to save
+/// the displayed image at its original resolution to a PNG file in the current
+/// working directory, named after the window title and the current time.
+/// Press `I` to toggle a pixel inspector showing the coordinates and RGBA value
+/// of the image pixel under the cursor.
+///
+/// # Panics
+///
+/// If the window cannot be created or rendered to. See [`try_display_image`]
+/// for a non-panicking version of this function.
 pub fn display_image<I>(title: &str, image: &I, window_width: u32, window_height: u32)
 where
     I: GenericImageView + ConvertBuffer<RgbaImage>,
 {
-    display_multiple_images(title, &[image], window_width, window_height);
+    try_display_image(title, image, window_width, window_height).expect("couldn't display image");
+}
+
+/// Displays the provided image in a new window.
+///
+/// Unlike [`display_image`], this accepts images of any pixel type, e.g. `GrayImage`,
+/// `RgbImage` or `ImageBuffer<Rgb<u16>, _>`. Images are converted to 8-bit RGBA for display,
+/// with subpixel values rescaled from the full range of their type. Grayscale
+/// intensities are copied to all three colour channels and images without an alpha
+/// channel are displayed fully opaque.
+///
+/// See [`display_image`] for more details.
+///
+/// # Examples
+/// ```no_run
+/// use image::GrayImage;
+/// use imageproc::filter::gaussian_blur_f32;
+/// use imageproc::window::display;
+///
+/// let image = GrayImage::new(100, 100);
+/// display("blurred", &gaussian_blur_f32(&image, 2.0), 500, 500);
+/// ```
+pub fn display<I>(title: &str, image: &I, window_width: u32, window_height: u32)
+where
+    I: GenericImageView,
+{
+    display_image(title, &to_rgba8(image), window_width, window_height);
+}
+
+/// Displays the provided [`DynamicImage`] in a new window.
+///
+/// See [`display`] for details of how non-RGBA images are converted for display.
+pub fn display_dynamic(title: &str, image: &DynamicImage, window_width: u32, window_height: u32) {
+    display_image(title, &image.to_rgba8(), window_width, window_height);
+}
+
+/// Displays the provided RGBA image in a new window using the given display options.
+///
+/// See [`display_image`] for more details.
+///
+/// # Examples
+/// ```no_run
+/// use image::{imageops::FilterType, RgbaImage};
+/// use imageproc::window::{display_image_with, DisplayOptions};
+///
+/// let image = RgbaImage::new(1000, 1000);
+/// let options = DisplayOptions {
+///     filter: FilterType::Nearest,
+/// };
+/// display_image_with("pixels", &image, 500, 500, options);
+/// ```
+pub fn display_image_with<I>(
+    title: &str,
+    image: &I,
+    window_width: u32,
+    window_height: u32,
+    options: DisplayOptions,
+) where
+    I: GenericImageView + ConvertBuffer<RgbaImage>,
+{
+    try_display_image_with(title, image, window_width, window_height, options)
+        .expect("couldn't display image");
+}
+
+/// Displays the provided RGBA image in a new window, returning an error instead of
+/// panicking if the window cannot be created or rendered to.
+///
+/// This is useful when running in environments where no display may be available,
+/// e.g. in headless CI or over SSH.
+///
+/// See [`display_image`] for more details.
+pub fn try_display_image<I>(
+    title: &str,
+    image: &I,
+    window_width: u32,
+    window_height: u32,
+) -> Result<(), WindowError>
+where
+    I: GenericImageView + ConvertBuffer<RgbaImage>,
+{
+    try_display_image_with(
+        title,
+        image,
+        window_width,
+        window_height,
+        DisplayOptions::default(),
+    )
+}
+
+/// Displays the provided RGBA image in a new window using the given display options,
+/// returning an error instead of panicking if the window cannot be created or
+/// rendered to.
+///
+/// See [`display_image_with`] for more details.
+pub fn try_display_image_with<I>(
+    title: &str,
+    image: &I,
+    window_width: u32,
+    window_height: u32,
+    options: DisplayOptions,
+) -> Result<(), WindowError>
+where
+    I: GenericImageView + ConvertBuffer<RgbaImage>,
+{
+    let mut window = ImageWindow::new(title, image, window_width, window_height, options)?;
+    while window.wait()? {}
+    Ok(())
+}
+
+/// Displays the provided RGBA image in a new window and returns immediately.
+///
+/// The returned [`ImageWindow`] does not process any window events on its own - call
+/// [`ImageWindow::poll`] regularly (e.g. once per iteration of your algorithm) to keep
+/// the window responsive, and [`ImageWindow::update`] to change the displayed image.
+///
+/// The minimum window width or height is 150 pixels - input values less than this
+/// will be rounded up to the minimum.
+///
+/// # Panics
+///
+/// If the window cannot be created. See [`try_display_image_async`]
+/// for a non-panicking version of this function.
+///
+/// # Examples
+/// ```no_run
+/// use image::RgbaImage;
+/// use imageproc::window::display_image_async;
+///
+/// let mut image = RgbaImage::new(100, 100);
+/// let mut window = display_image_async("progress", &image, 500, 500);
+/// for i in 0..100 {
+///     image.put_pixel(i, i, image::Rgba([255, 0, 0, 255]));
+///     window.update(&image).unwrap();
+///     if !window.poll().unwrap() {
+///         break;
+///     }
+/// }
+/// ```
+pub fn display_image_async<I>(
+    title: &str,
+    image: &I,
+    window_width: u32,
+    window_height: u32,
+) -> ImageWindow
+where
+    I: GenericImageView + ConvertBuffer<RgbaImage>,
+{
+    try_display_image_async(title, image, window_width, window_height)
+        .expect("couldn't display image")
+}
+
+/// Displays the provided RGBA image in a new window and returns immediately,
+/// returning an error instead of panicking if the window cannot be created.
+///
+/// See [`display_image_async`] for more details.
+pub fn try_display_image_async<I>(
+    title: &str,
+    image: &I,
+    window_width: u32,
+    window_height: u32,
+) -> Result<ImageWindow, WindowError>
+where
+    I: GenericImageView + ConvertBuffer<RgbaImage>,
+{
+    ImageWindow::new(
+        title,
+        image,
+        window_width,
+        window_height,
+        DisplayOptions::default(),
+    )
+}
+
+/// A handle to a window displaying an image, created by [`display_image_async`].
+///
+/// The window is closed when the handle is dropped.
+pub struct ImageWindow {
+    view: ImageView,
+    event_pump: EventPump,
+    open: bool,
+    // Kept alive for as long as the window exists
+    _sdl: Sdl,
+}
+
+impl ImageWindow {
+    fn new<I>(
+        title: &str,
+        image: &I,
+        window_width: u32,
+        window_height: u32,
+        options: DisplayOptions,
+    ) -> Result<ImageWindow, WindowError>
+    where
+        I: GenericImageView + ConvertBuffer<RgbaImage>,
+    {
+        let sdl = sdl2::init().map_err(WindowError::Init)?;
+        let video_subsystem = sdl.video().map_err(WindowError::Init)?;
+        let window = create_window(&video_subsystem, title, window_width, window_height)?;
+
+        let mut view = ImageView::new(window, image.convert())?;
+        view.options = options;
+        view.render()?;
+
+        let mut event_pump = sdl.event_pump().map_err(WindowError::Init)?;
+        event_pump.enable_event(sdl2::event::EventType::Window);
+
+        Ok(ImageWindow {
+            view,
+            event_pump,
+            open: true,
+            _sdl: sdl,
+        })
+    }
+
+    /// Processes all pending window events without blocking.
+    ///
+    /// Returns `Ok(true)` if the window is still open.
+    pub fn poll(&mut self) -> Result<bool, WindowError> {
+        let events: Vec<Event> = self.event_pump.poll_iter().collect();
+        for event in events {
+            self.handle_event(event)?;
+        }
+        Ok(self.open)
+    }
+
+    /// Blocks until the next window event arrives and processes it.
+    ///
+    /// Returns `Ok(true)` if the window is still open.
+    pub fn wait(&mut self) -> Result<bool, WindowError> {
+        let event = self.event_pump.wait_event();
+        self.handle_event(event)?;
+        self.poll()
+    }
+
+    /// Replaces the displayed image.
+    pub fn update<I>(&mut self, image: &I) -> Result<(), WindowError>
+    where
+        I: GenericImageView + ConvertBuffer<RgbaImage>,
+    {
+        if !self.open {
+            return Ok(());
+        }
+        self.view.image = image.convert();
+        self.view.display_buffer = None;
+        self.view.render()
+    }
+
+    /// Changes the options used to display the image.
+    pub fn set_options(&mut self, options: DisplayOptions) -> Result<(), WindowError> {
+        self.view.options = options;
+        if !self.open {
+            return Ok(());
+        }
+        self.view.render()
+    }
+
+    /// Returns `true` until the window is closed, either by the user
+    /// or by calling [`close`](ImageWindow::close).
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Closes the window.
+    pub fn close(&mut self) {
+        self.view.canvas.window_mut().hide();
+        self.open = false;
+    }
+
+    fn handle_event(&mut self, event: Event) -> Result<(), WindowError> {
+        match event {
+            Event::Quit { .. }
+            | Event::KeyDown {
+                keycode: Some(Keycode::Escape) | Some(Keycode::Q),
+                ..
+            }
+            | Event::Window {
+                win_event: WindowEvent::Close,
+                ..
+            } => self.close(),
+            Event::Window {
+                win_event: WindowEvent::Resized(..),
+                ..
+            } if self.open => self.view.render()?,
+            Event::KeyDown {
+                keycode: Some(Keycode::S),
+                ..
+            } if self.open => self.view.save_screenshot(),
+            Event::KeyDown {
+                keycode: Some(Keycode::Num0) | Some(Keycode::Kp0),
+                ..
+            } if self.open => {
+                self.view.reset_zoom();
+                self.view.render()?;
+            }
+            Event::MouseWheel { y, direction, .. } if self.open && y != 0 => {
+                let steps = match direction {
+                    MouseWheelDirection::Flipped => -y,
+                    _ => y,
+                };
+                let mouse = self.event_pump.mouse_state();
+                self.view
+                    .zoom_at((mouse.x(), mouse.y()), ZOOM_STEP.powi(steps));
+                self.view.render()?;
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left | MouseButton::Middle,
+                ..
+            } => self.view.dragging = true,
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left | MouseButton::Middle,
+                ..
+            } => self.view.dragging = false,
+            Event::KeyDown {
+                keycode: Some(Keycode::I),
+                ..
+            } if self.open => {
+                self.view.inspector = !self.view.inspector;
+                self.view.render()?;
+            }
+            Event::MouseMotion {
+                x, y, xrel, yrel, ..
+            } if self.open => {
+                self.view.cursor = Some((x, y));
+                if self.view.dragging {
+                    self.view.offset.0 += xrel;
+                    self.view.offset.1 += yrel;
+                }
+                if self.view.dragging {
+                    self.view.render()?;
+                } else if self.view.inspector {
+                    self.view.redraw_pixel_inspector()?;
+                }
+            }
+            Event::Window {
+                win_event: WindowEvent::Leave,
+                ..
+            } if self.open => {
+                self.view.cursor = None;
+                if self.view.inspector {
+                    self.view.redraw_pixel_inspector()?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 }
 
 /// Displays the provided RGBA images in new windows.
 ///
 /// The minimum window width or height is 150 pixels - input values less than this
 /// will be rounded up to the minimum.
+///
+/// # Panics
+///
+/// If the windows cannot be created or rendered to. See [`try_display_multiple_images`]
+/// for a non-panicking version of this function.
 pub fn display_multiple_images<I>(title: &str, images: &[&I], window_width: u32, window_height: u32)
+where
+    I: GenericImageView + ConvertBuffer<RgbaImage>,
+{
+    try_display_multiple_images(title, images, window_width, window_height)
+        .expect("couldn't display images");
+}
+
+/// Displays the provided RGBA images in new windows, returning an error instead of
+/// panicking if the windows cannot be created or rendered to.
+///
+/// See [`display_multiple_images`] for more details.
+pub fn try_display_multiple_images<I>(
+    title: &str,
+    images: &[&I],
+    window_width: u32,
+    window_height: u32,
+) -> Result<(), WindowError>
 where
     I: GenericImageView + ConvertBuffer<RgbaImage>,
 {
     if images.is_empty() {
-        return;
+        return Ok(());
     }
 
-    // Enforce minimum window size
-    const MIN_WINDOW_DIMENSION: u32 = 150;
-    let window_width = window_width.max(MIN_WINDOW_DIMENSION);
-    let window_height = window_height.max(MIN_WINDOW_DIMENSION);
-
     // Initialise sdl2 window
-    let sdl = sdl2::init().expect("couldn't create sdl2 context");
-    let video_subsystem = sdl.video().expect("couldn't create video subsystem");
+    let sdl = sdl2::init().map_err(WindowError::Init)?;
+    let video_subsystem = sdl.video().map_err(WindowError::Init)?;
 
     let mut windows: Vec<Window> = Vec::with_capacity(images.len());
-    let mut window_visibility: Vec<bool> = Vec::with_capacity(images.len());
     for _ in 0..images.len() {
-        let mut window = video_subsystem
-            .window(title, window_width, window_height)
-            .resizable()
-            .allow_highdpi()
-            .build()
-            .expect("couldn't create window");
-
-        window
-            .set_minimum_size(MIN_WINDOW_DIMENSION, MIN_WINDOW_DIMENSION)
-            .expect("invalid minimum size for window");
-
-        windows.push(window);
-        window_visibility.push(true);
+        windows.push(create_window(
+            &video_subsystem,
+            title,
+            window_width,
+            window_height,
+        )?);
     }
 
     {
         use sdl2::video::WindowPos::Positioned;
 
+        let (window_width, _) = windows[0].size();
         let (base_position_x, base_position_y) = windows[0].position();
+        let display_bounds = video_subsystem
+            .display_bounds(0)
+            .map_err(WindowError::Window)?;
         for (i, window) in windows.iter_mut().enumerate() {
             let multiplier = 1.0 + i as f32 / 20.0;
             window.set_position(
@@ -77,9 +514,6 @@ where
             );
 
             let (window_pos_x, _window_pos_y) = window.position();
-            let display_bounds = video_subsystem
-                .display_bounds(0)
-                .expect("No bounds found for that display.");
             let screen_width = display_bounds.w;
             if window_pos_x + window_width as i32 > screen_width {
                 window.set_position(
@@ -90,40 +524,18 @@ where
         }
     }
 
-    let mut canvases: Vec<WindowCanvas> = Vec::with_capacity(images.len());
-    for window in windows.into_iter() {
-        let canvas = window
-            .into_canvas()
-            .software()
-            .build()
-            .expect("couldn't create canvas");
-        canvases.push(canvas);
-    }
-
-    let mut texture_creators: Vec<TextureCreator<WindowContext>> = Vec::with_capacity(images.len());
-    for canvas in canvases.iter() {
-        let texture_creator = canvas.texture_creator();
-        texture_creators.push(texture_creator);
-    }
-
     // Shrinks input image to fit if required and renders to the sdl canvas
-
-    for (i, (canvas, texture_creator)) in
-        canvases.iter_mut().zip(texture_creators.iter()).enumerate()
-    {
-        render_image_to_canvas(
-            images[i],
-            window_width,
-            window_height,
-            canvas,
-            texture_creator,
-        );
+    let mut views: Vec<ImageView> = Vec::with_capacity(images.len());
+    for (window, image) in windows.into_iter().zip(images.iter()) {
+        let mut view = ImageView::new(window, image.convert())?;
+        view.render()?;
+        views.push(view);
     }
 
     let mut hidden_count = 0;
 
     // Create and start event loop to keep window open until Esc
-    let mut event_pump = sdl.event_pump().unwrap();
+    let mut event_pump = sdl.event_pump().map_err(WindowError::Init)?;
     event_pump.enable_event(sdl2::event::EventType::Window);
     'running: loop {
         for event in event_pump.wait_iter() {
@@ -143,10 +555,10 @@ where
                     window_id,
                     ..
                 } => {
-                    for (i, canvas) in canvases.iter_mut().enumerate() {
-                        if window_id == canvas.window().id() {
-                            canvas.window_mut().hide();
-                            window_visibility[i] = false;
+                    for view in views.iter_mut() {
+                        if window_id == view.window_id() && view.visible {
+                            view.canvas.window_mut().hide();
+                            view.visible = false;
                             hidden_count += 1;
                         }
                         if hidden_count == images.len() {
@@ -155,21 +567,13 @@ where
                     }
                 }
                 Event::Window {
-                    win_event: WindowEvent::Resized(w, h),
+                    win_event: WindowEvent::Resized(..),
                     window_id,
                     ..
                 } => {
-                    for (i, (canvas, texture_creator)) in
-                        canvases.iter_mut().zip(texture_creators.iter()).enumerate()
-                    {
-                        if window_id == canvas.window().id() {
-                            render_image_to_canvas(
-                                images[i],
-                                w as u32,
-                                h as u32,
-                                canvas,
-                                texture_creator,
-                            );
+                    for view in views.iter_mut() {
+                        if window_id == view.window_id() {
+                            view.render()?;
                         }
                     }
                 }
@@ -177,68 +581,648 @@ where
             }
         }
     }
+
+    Ok(())
 }
 
-// Scale input image down if required so that it fits within a window of the given dimensions
-fn resize_to_fit<I>(image: &I, window_width: u32, window_height: u32) -> RgbaImage
+// Converts an image of any pixel type to 8-bit RGBA, rescaling subpixels from the
+// full range of their type
+fn to_rgba8<I>(image: &I) -> RgbaImage
 where
-    I: GenericImageView + ConvertBuffer<RgbaImage>,
+    I: GenericImageView,
 {
-    let image = image.convert();
-    if image.height() < window_height && image.width() < window_width {
-        return image;
+    let max: f32 = cast(<<I::Pixel as Pixel>::Subpixel as Primitive>::DEFAULT_MAX_VALUE).unwrap();
+    let to_u8 = |c: <I::Pixel as Pixel>::Subpixel| {
+        let c: f32 = cast(c).unwrap();
+        (255.0 * c / max).round().clamp(0.0, 255.0) as u8
+    };
+
+    let (width, height) = image.dimensions();
+    RgbaImage::from_fn(width, height, |x, y| {
+        let p = image.get_pixel(x, y).to_rgba();
+        Rgba([to_u8(p[0]), to_u8(p[1]), to_u8(p[2]), to_u8(p[3])])
+    })
+}
+
+/// Displays the provided RGBA images in a single window, laid out in a grid with
+/// the given number of columns and a caption below each image.
+///
+/// Each image is shrunk if required to fit its grid cell, preserving its aspect ratio.
+/// The window is initially sized to show all images at their original size, up to
+/// the size of the display, and the grid is re-flowed when the window is resized.
+///
+/// Captions are drawn using a small built-in font supporting digits, letters and
+/// common punctuation. Other characters are drawn as spaces.
+///
+/// # Panics
+///
+/// If the window cannot be created or rendered to. See [`try_display_images_grid`]
+/// for a non-panicking version of this function.
+///
+/// # Examples
+/// ```no_run
+/// use image::GrayImage;
+/// use imageproc::filter::gaussian_blur_f32;
+/// use imageproc::window::display_images_grid;
+///
+/// let image = GrayImage::new(100, 100);
+/// let blurred: Vec<_> = [1.0, 2.0, 4.0, 8.0]
+///     .iter()
+///     .map(|&sigma| {
+///         let caption = format!("sigma = {}", sigma);
+///         let blurred = image::DynamicImage::ImageLuma8(gaussian_blur_f32(&image, sigma));
+///         (caption, blurred.to_rgba8())
+///     })
+///     .collect();
+/// let cells: Vec<_> = blurred.iter().map(|(c, i)| (c.as_str(), i)).collect();
+/// display_images_grid("gaussian blur", &cells, 2);
+/// ```
+pub fn display_images_grid(title: &str, images: &[(&str, &RgbaImage)], cols: u32) {
+    try_display_images_grid(title, images, cols).expect("couldn't display images");
+}
+
+/// Displays the provided RGBA images in a grid in a single window, returning an error
+/// instead of panicking if the window cannot be created or rendered to.
+///
+/// See [`display_images_grid`] for more details.
+pub fn try_display_images_grid(
+    title: &str,
+    images: &[(&str, &RgbaImage)],
+    cols: u32,
+) -> Result<(), WindowError> {
+    if images.is_empty() {
+        return Ok(());
     }
 
-    let scale = {
-        let width_scale = window_width as f32 / image.width() as f32;
-        let height_scale = window_height as f32 / image.height() as f32;
-        width_scale.min(height_scale)
-    };
+    let layout = GridLayout::new(images.len(), cols);
+
+    // Size the window so that all images are displayed at their original size,
+    // if this fits on the screen
+    let cell_width = images.iter().map(|(_, image)| image.width()).max().unwrap();
+    let cell_height = images
+        .iter()
+        .map(|(_, image)| image.height())
+        .max()
+        .unwrap();
+    let (content_width, content_height) = layout.window_size_for_cell(cell_width, cell_height);
+
+    let sdl = sdl2::init().map_err(WindowError::Init)?;
+    let video_subsystem = sdl.video().map_err(WindowError::Init)?;
+    let display_bounds = video_subsystem
+        .display_bounds(0)
+        .map_err(WindowError::Window)?;
+    let window_width = content_width.min((display_bounds.w.max(0) as u32) * 9 / 10);
+    let window_height = content_height.min((display_bounds.h.max(0) as u32) * 9 / 10);
+
+    let window = create_window(&video_subsystem, title, window_width, window_height)?;
+    let mut view = ImageView::new(window, RgbaImage::new(0, 0))?;
+    render_grid(&mut view, images, &layout)?;
+
+    let mut event_pump = sdl.event_pump().map_err(WindowError::Init)?;
+    event_pump.enable_event(sdl2::event::EventType::Window);
+    for event in event_pump.wait_iter() {
+        match event {
+            Event::Quit { .. }
+            | Event::KeyDown {
+                keycode: Some(Keycode::Escape) | Some(Keycode::Q),
+                ..
+            }
+            | Event::Window {
+                win_event: WindowEvent::Close,
+                ..
+            } => break,
+            Event::Window {
+                win_event: WindowEvent::Resized(..),
+                ..
+            } => render_grid(&mut view, images, &layout)?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
 
-    let height = (scale * image.height() as f32) as u32;
-    let width = (scale * image.width() as f32) as u32;
+// Padding around each grid cell and its caption, in window pixels
+const GRID_PADDING: u32 = 4;
+// Scale at which captions are drawn
+const CAPTION_SCALE: u32 = 2;
+const CAPTION_HEIGHT: u32 = GLYPH_HEIGHT * CAPTION_SCALE;
 
-    resize(&image, width, height, FilterType::Triangle)
+// The arrangement of images in a grid
+struct GridLayout {
+    count: usize,
+    cols: u32,
+    rows: u32,
 }
 
-fn render_image_to_canvas<I>(
-    image: &I,
+impl GridLayout {
+    fn new(count: usize, cols: u32) -> GridLayout {
+        let cols = cols.clamp(1, count as u32);
+        let rows = (count as u32 + cols - 1) / cols;
+        GridLayout { count, cols, rows }
+    }
+
+    // The window size at which each cell can display an image of the given size
+    fn window_size_for_cell(&self, image_width: u32, image_height: u32) -> (u32, u32) {
+        let cell_width = image_width + 2 * GRID_PADDING;
+        let cell_height = image_height + CAPTION_HEIGHT + 3 * GRID_PADDING;
+        (self.cols * cell_width, self.rows * cell_height)
+    }
+
+    // The image and caption regions of each cell, for a window of the given size
+    fn cells(&self, window_width: u32, window_height: u32) -> impl Iterator<Item = (Rect, Rect)> {
+        let cols = self.cols;
+        let cell_width = window_width / self.cols;
+        let cell_height = window_height / self.rows;
+        (0..self.count as u32).map(move |i| {
+            let left = ((i % cols) * cell_width + GRID_PADDING) as i32;
+            let top = ((i / cols) * cell_height + GRID_PADDING) as i32;
+            let width = cell_width.saturating_sub(2 * GRID_PADDING).max(1);
+            let image_height = cell_height
+                .saturating_sub(CAPTION_HEIGHT + 3 * GRID_PADDING)
+                .max(1);
+            let caption_top = top + (image_height + GRID_PADDING) as i32;
+            (
+                Rect::new(left, top, width, image_height),
+                Rect::new(left, caption_top, width, CAPTION_HEIGHT),
+            )
+        })
+    }
+}
+
+// Renders each image, scaled to fit its grid cell, with its caption below it
+fn render_grid(
+    view: &mut ImageView,
+    images: &[(&str, &RgbaImage)],
+    layout: &GridLayout,
+) -> Result<(), WindowError> {
+    let (window_width, window_height) = view.canvas.window().size();
+    view.canvas.set_draw_color(Color::RGB(255, 255, 255));
+    view.canvas.clear();
+
+    for ((caption, image), (image_rect, caption_rect)) in
+        images.iter().zip(layout.cells(window_width, window_height))
+    {
+        // Centre the scaled image in its cell
+        let scale = fit_scale(image.dimensions(), image_rect.width(), image_rect.height());
+        let width = ((image.width() as f32 * scale) as u32).max(1);
+        let height = ((image.height() as f32 * scale) as u32).max(1);
+        let display_rect = Rect::new(
+            image_rect.x() + (image_rect.width() - width.min(image_rect.width())) as i32 / 2,
+            image_rect.y() + (image_rect.height() - height.min(image_rect.height())) as i32 / 2,
+            width,
+            height,
+        );
+        if image.width() > 0 && image.height() > 0 {
+            render_image_to_canvas(
+                image,
+                display_rect,
+                view.options.filter,
+                &mut view.canvas,
+                &view.texture_creator,
+            )?;
+        }
+
+        // Centre the caption below the image, dropping characters that don't fit
+        let glyph_advance = (GLYPH_WIDTH + 1) * CAPTION_SCALE;
+        let max_chars = (caption_rect.width() / glyph_advance) as usize;
+        let caption: String = caption.chars().take(max_chars).collect();
+        let caption_width = caption.chars().count() as u32 * glyph_advance;
+        let caption_left =
+            caption_rect.x() + (caption_rect.width().saturating_sub(caption_width) / 2) as i32;
+        let rects = text_rects(&caption, caption_left, caption_rect.y(), CAPTION_SCALE);
+        view.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        if !rects.is_empty() {
+            view.canvas
+                .fill_rects(&rects)
+                .map_err(WindowError::Canvas)?;
+        }
+    }
+
+    view.canvas.present();
+    Ok(())
+}
+
+// Creates a resizable window of at least the minimum window size
+fn create_window(
+    video_subsystem: &VideoSubsystem,
+    title: &str,
     window_width: u32,
     window_height: u32,
+) -> Result<Window, WindowError> {
+    // Textures are scaled using nearest neighbour sampling when zooming in, so that
+    // individual pixels remain visible. This must be set before textures are created.
+    sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "nearest");
+
+    // Enforce minimum window size
+    let window_width = window_width.max(MIN_WINDOW_DIMENSION);
+    let window_height = window_height.max(MIN_WINDOW_DIMENSION);
+
+    let mut window = video_subsystem
+        .window(title, window_width, window_height)
+        .resizable()
+        .allow_highdpi()
+        .build()
+        .map_err(|e| WindowError::Window(e.to_string()))?;
+
+    window
+        .set_minimum_size(MIN_WINDOW_DIMENSION, MIN_WINDOW_DIMENSION)
+        .map_err(|e| WindowError::Window(e.to_string()))?;
+
+    Ok(window)
+}
+
+// A window canvas together with the image it displays
+struct ImageView {
+    canvas: WindowCanvas,
+    texture_creator: TextureCreator<WindowContext>,
+    image: RgbaImage,
+    options: DisplayOptions,
+    visible: bool,
+    // Zoom relative to the scale at which the image fits the window
+    zoom: f32,
+    // Offset of the image centre from the window centre, in window pixels
+    offset: (i32, i32),
+    // Whether the image is currently being dragged by the mouse
+    dragging: bool,
+    // Whether to display the value of the pixel under the cursor
+    inspector: bool,
+    // The last known mouse position within the window
+    cursor: Option<(i32, i32)>,
+    // The image as last prepared for display, with the filter and display size used
+    display_buffer: Option<(FilterType, (u32, u32), RgbaImage)>,
+    // The window region covered by the pixel inspector, and the ABGR8888 pixels that
+    // it hides
+    inspector_background: Option<(Rect, Vec<u8>)>,
+}
+
+// The factor by which the zoom changes per mouse wheel step
+const ZOOM_STEP: f32 = 1.25;
+// Bounds for the zoom relative to the fit-to-window scale
+const MIN_ZOOM: f32 = 0.1;
+const MAX_ZOOM: f32 = 64.0;
+
+impl ImageView {
+    fn new(window: Window, image: RgbaImage) -> Result<ImageView, WindowError> {
+        let canvas = window
+            .into_canvas()
+            .software()
+            .build()
+            .map_err(|e| WindowError::Window(e.to_string()))?;
+        let texture_creator = canvas.texture_creator();
+        Ok(ImageView {
+            canvas,
+            texture_creator,
+            image,
+            options: DisplayOptions::default(),
+            visible: true,
+            zoom: 1.0,
+            offset: (0, 0),
+            dragging: false,
+            inspector: false,
+            cursor: None,
+            display_buffer: None,
+            inspector_background: None,
+        })
+    }
+
+    fn window_id(&self) -> u32 {
+        self.canvas.window().id()
+    }
+
+    // The ratio of displayed size to image size
+    fn scale(&self) -> f32 {
+        let (window_width, window_height) = self.canvas.window().size();
+        fit_scale(self.image.dimensions(), window_width, window_height) * self.zoom
+    }
+
+    // The window region that the image is drawn to
+    fn display_rect(&self) -> Rect {
+        let (window_width, window_height) = self.canvas.window().size();
+        let scale = self.scale();
+        let width = ((self.image.width() as f32 * scale) as u32).max(1);
+        let height = ((self.image.height() as f32 * scale) as u32).max(1);
+        let left = (window_width as i32 - width as i32) / 2 + self.offset.0;
+        let top = (window_height as i32 - height as i32) / 2 + self.offset.1;
+        Rect::new(left, top, width, height)
+    }
+
+    // Multiplies the zoom by the given factor, keeping the image point under the cursor fixed
+    fn zoom_at(&mut self, cursor: (i32, i32), factor: f32) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let factor = zoom / self.zoom;
+        let (window_width, window_height) = self.canvas.window().size();
+        // Cursor position relative to the image centre
+        let dx = (cursor.0 - window_width as i32 / 2 - self.offset.0) as f32;
+        let dy = (cursor.1 - window_height as i32 / 2 - self.offset.1) as f32;
+        self.offset.0 += (dx - dx * factor).round() as i32;
+        self.offset.1 += (dy - dy * factor).round() as i32;
+        self.zoom = zoom;
+    }
+
+    // Saves the image at its original resolution to a PNG in the current working directory.
+    // Failures are reported on stderr rather than interrupting the event loop.
+    fn save_screenshot(&self) {
+        let path = screenshot_path(self.canvas.window().title());
+        if let Err(e) = self.image.save(&path) {
+            eprintln!(
+                "warning: couldn't save screenshot to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    // Resets to the default fit-to-window view
+    fn reset_zoom(&mut self) {
+        self.zoom = 1.0;
+        self.offset = (0, 0);
+    }
+
+    // Renders the image to the canvas, taking into account the current zoom and offset.
+    // The resized image is cached, so panning does not resize the image again.
+    fn render(&mut self) -> Result<(), WindowError> {
+        let display_rect = self.display_rect();
+        let filter = self.options.filter;
+        let size = display_rect.size();
+        if !matches!(&self.display_buffer, Some((f, s, _)) if *f == filter && *s == size) {
+            let buffer = resize_for_display(&self.image, display_rect, filter);
+            self.display_buffer = Some((filter, size, buffer));
+        }
+
+        self.canvas.set_draw_color(Color::RGB(255, 255, 255));
+        self.canvas.clear();
+        if let Some((_, _, buffer)) = &mut self.display_buffer {
+            copy_image_to_canvas(
+                buffer,
+                display_rect,
+                &mut self.canvas,
+                &self.texture_creator,
+            )?;
+        }
+        self.inspector_background = None;
+        if self.inspector {
+            self.draw_pixel_inspector()?;
+        }
+        self.canvas.present();
+        Ok(())
+    }
+
+    // Replaces the pixel inspector with one for the current cursor position, leaving the
+    // rest of the window untouched
+    fn redraw_pixel_inspector(&mut self) -> Result<(), WindowError> {
+        if let Some((rect, mut pixels)) = self.inspector_background.take() {
+            let mut texture = texture_from_abgr(&mut pixels, rect.size(), &self.texture_creator)?;
+            texture.set_blend_mode(BlendMode::None);
+            self.canvas
+                .copy(&texture, None, rect)
+                .map_err(WindowError::Canvas)?;
+        }
+        self.draw_pixel_inspector()?;
+        self.canvas.present();
+        Ok(())
+    }
+
+    // Maps a window position to the image pixel displayed there, if any
+    fn image_coordinates(&self, position: (i32, i32)) -> Option<(u32, u32)> {
+        let rect = self.display_rect();
+        let (x, y) = (position.0 - rect.x(), position.1 - rect.y());
+        if x < 0 || y < 0 || x >= rect.width() as i32 || y >= rect.height() as i32 {
+            return None;
+        }
+        let image_x = (x as f32 * self.image.width() as f32 / rect.width() as f32) as u32;
+        let image_y = (y as f32 * self.image.height() as f32 / rect.height() as f32) as u32;
+        Some((
+            image_x.min(self.image.width() - 1),
+            image_y.min(self.image.height() - 1),
+        ))
+    }
+
+    // Draws the coordinates and value of the pixel under the cursor in the top left
+    // corner of the window
+    fn draw_pixel_inspector(&mut self) -> Result<(), WindowError> {
+        let (x, y) = match self.cursor.and_then(|c| self.image_coordinates(c)) {
+            Some(coordinates) => coordinates,
+            None => return Ok(()),
+        };
+        let [r, g, b, a] = self.image.get_pixel(x, y).0;
+        let lines = [
+            format!("({}, {})", x, y),
+            format!("({}, {}, {}, {})", r, g, b, a),
+        ];
+
+        const MARGIN: i32 = 4;
+        const SCALE: u32 = 2;
+        let line_height = (GLYPH_HEIGHT + 2) * SCALE;
+        let text_width = lines
+            .iter()
+            .map(|line| line.chars().count() as u32 * (GLYPH_WIDTH + 1) * SCALE)
+            .max()
+            .unwrap_or(0);
+
+        let background = Rect::new(
+            MARGIN,
+            MARGIN,
+            text_width + 2 * SCALE,
+            lines.len() as u32 * line_height + SCALE,
+        );
+        let (window_width, window_height) = self.canvas.window().size();
+        let window = Rect::new(0, 0, window_width, window_height);
+        if let Some(covered) = background.intersection(window) {
+            let pixels = self
+                .canvas
+                .read_pixels(covered, PixelFormatEnum::ABGR8888)
+                .map_err(WindowError::Canvas)?;
+            self.inspector_background = Some((covered, pixels));
+        }
+
+        self.canvas.set_blend_mode(BlendMode::Blend);
+        self.canvas.set_draw_color(Color::RGBA(0, 0, 0, 180));
+        self.canvas
+            .fill_rect(background)
+            .map_err(WindowError::Canvas)?;
+
+        self.canvas.set_draw_color(Color::RGB(255, 255, 255));
+        for (i, line) in lines.iter().enumerate() {
+            let top = MARGIN + ((i as u32 * line_height + 2 * SCALE) as i32);
+            let rects = text_rects(line, MARGIN + 2 * SCALE as i32, top, SCALE);
+            self.canvas
+                .fill_rects(&rects)
+                .map_err(WindowError::Canvas)?;
+        }
+        Ok(())
+    }
+}
+
+// Width and height of the glyphs used by the pixel inspector
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+
+// A minimal 3x5 bitmap font for digits, letters and common punctuation, used to
+// draw text in windows without requiring a font. Lowercase letters are drawn as
+// uppercase. Each entry is a row of the glyph, with the leftmost pixel in the
+// highest bit.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT as usize] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '(' => [0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => [0b100, 0b010, 0b010, 0b010, 0b100],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '_' => [0b000, 0b000, 0b000, 0b000, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '=' => [0b000, 0b111, 0b000, 0b111, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        _ => [0; GLYPH_HEIGHT as usize],
+    }
+}
+
+// The rectangles to fill to draw the given text with its top left corner at (left, top),
+// with each font pixel drawn as a square of side length scale
+fn text_rects(text: &str, left: i32, top: i32, scale: u32) -> Vec<Rect> {
+    let mut rects = Vec::new();
+    for (i, c) in text.chars().enumerate() {
+        let glyph_left = left + (i as u32 * (GLYPH_WIDTH + 1) * scale) as i32;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                    rects.push(Rect::new(
+                        glyph_left + (col * scale) as i32,
+                        top + (row as u32 * scale) as i32,
+                        scale,
+                        scale,
+                    ));
+                }
+            }
+        }
+    }
+    rects
+}
+
+// A file name for a screenshot, derived from the window title and the current time
+fn screenshot_path(title: &str) -> PathBuf {
+    let mut stem: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if stem.is_empty() {
+        stem.push_str("imageproc");
+    }
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    PathBuf::from(format!("{}_{}.png", stem, timestamp))
+}
+
+// The scale at which the image fits within a window of the given dimensions.
+// Images are shrunk where required but never enlarged.
+fn fit_scale(image_dimensions: (u32, u32), window_width: u32, window_height: u32) -> f32 {
+    let (image_width, image_height) = image_dimensions;
+    if image_height < window_height && image_width < window_width {
+        return 1.0;
+    }
+
+    let width_scale = window_width as f32 / image_width as f32;
+    let height_scale = window_height as f32 / image_height as f32;
+    width_scale.min(height_scale)
+}
+
+// Renders the image into the given region of the canvas. The caller is responsible
+// for presenting the canvas.
+//
+// Images displayed at less than their original size are resized using the given filter
+// before rendering. Enlarged images are scaled using nearest neighbour sampling so
+// that individual pixels remain visible.
+fn render_image_to_canvas(
+    image: &RgbaImage,
+    display_rect: Rect,
+    filter: FilterType,
     canvas: &mut Canvas<Window>,
     texture_creator: &TextureCreator<WindowContext>,
-) where
-    I: GenericImageView + ConvertBuffer<RgbaImage>,
-{
-    let scaled_image = resize_to_fit(image, window_width, window_height);
+) -> Result<(), WindowError> {
+    let mut buffer = resize_for_display(image, display_rect, filter);
+    copy_image_to_canvas(&mut buffer, display_rect, canvas, texture_creator)
+}
+
+// Shrinks the image to the size of display_rect if it is larger.
+fn resize_for_display(image: &RgbaImage, display_rect: Rect, filter: FilterType) -> RgbaImage {
+    if display_rect.width() < image.width() {
+        resize(image, display_rect.width(), display_rect.height(), filter)
+    } else {
+        image.clone()
+    }
+}
 
-    let (image_width, image_height) = scaled_image.dimensions();
-    let mut buffer = scaled_image.into_raw();
+// Copies the image to the given region of the canvas, scaling it to fit.
+fn copy_image_to_canvas(
+    image: &mut RgbaImage,
+    display_rect: Rect,
+    canvas: &mut Canvas<Window>,
+    texture_creator: &TextureCreator<WindowContext>,
+) -> Result<(), WindowError> {
+    let dimensions = image.dimensions();
+    let texture = texture_from_abgr(image, dimensions, texture_creator)?;
+    canvas
+        .copy(&texture, None, display_rect)
+        .map_err(WindowError::Canvas)
+}
+
+// Creates a texture from pixel data in ABGR8888 format, i.e. RGBA bytes.
+fn texture_from_abgr<'a>(
+    pixels: &mut [u8],
+    (width, height): (u32, u32),
+    texture_creator: &'a TextureCreator<WindowContext>,
+) -> Result<Texture<'a>, WindowError> {
     const CHANNEL_COUNT: u32 = 4;
     let surface = Surface::from_data(
-        &mut buffer,
-        image_width,
-        image_height,
-        image_width * CHANNEL_COUNT,
+        pixels,
+        width,
+        height,
+        width * CHANNEL_COUNT,
         PixelFormatEnum::ABGR8888, // sdl2 expects bits from highest to lowest
     )
-    .expect("couldn't create surface");
+    .map_err(WindowError::Surface)?;
 
-    let texture = texture_creator
+    texture_creator
         .create_texture_from_surface(surface)
-        .expect("couldn't create texture from surface");
-
-    canvas.set_draw_color(Color::RGB(255, 255, 255));
-    canvas.clear();
-
-    let left = ((window_width - image_width) as f32 / 2f32) as i32;
-    let top = ((window_height - image_height) as f32 / 2f32) as i32;
-    canvas
-        .copy(
-            &texture,
-            None,
-            Rect::new(left, top, image_width, image_height),
-        )
-        .unwrap();
-    canvas.present();
+        .map_err(|e| WindowError::Texture(e.to_string()))
 }